@@ -1,15 +1,19 @@
 use crate::auth_context::{AuthContext, SessionIdExtension, SessionInfoExtension};
+use crate::authorization_server::{AuthorizationServer, ACCESS_TOKEN_PREFIX};
 use crate::commands::app_info::get_mcp_server_info;
 use crate::mcp_client::McpClientManager;
+use crate::traffic::{
+    traffic_limit_middleware, AggregatorTraffic, ConnectionHandle, LimitedListener,
+};
 // Primary implementations
 pub use crate::mcp_manager::McpServerManager;
 pub use crate::token_manager::TokenManager;
 use crate::types::ServerConfig;
 use axum::{
     extract::Request,
-    http::StatusCode,
+    http::{header, StatusCode},
     middleware::{self, Next},
-    response::{Json, Response},
+    response::{IntoResponse, Json, Response},
 };
 use chrono;
 use rmcp::model::{
@@ -25,16 +29,22 @@ use rmcp::ErrorData as RmcpErrorData;
 use rmcp::{handler::server::ServerHandler, service::RequestContext, RoleServer};
 use serde_json::Value;
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 
+/// 重启时等待已有连接结束的最长时间，超时后强制关闭以释放端口
+const SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// Dynamic Bearer token authentication middleware using TokenManager
 /// Performs basic authentication and logs the token for auditing
 /// Stores token information in request extensions for later use in permission filtering
+/// OAuth access tokens are resolved to the token they were granted from
 async fn dynamic_bearer_auth_middleware(
     mut req: Request,
     next: Next,
     token_manager: Arc<TokenManager>,
+    authorization_server: Arc<AuthorizationServer>,
 ) -> Result<Response, StatusCode> {
     let uri = req.uri().path();
     let method = req.method();
@@ -86,8 +96,16 @@ async fn dynamic_bearer_auth_middleware(
 
     // Validate token using TokenManager
     if let Some(token_value) = token_value {
-        tracing::debug!("Validating token with TokenManager...");
-        match token_manager.validate_token(token_value).await {
+        let validated = if token_value.starts_with(ACCESS_TOKEN_PREFIX)
+            && authorization_server.is_enabled()
+        {
+            tracing::debug!("Validating OAuth access token...");
+            authorization_server.validate_access_token(token_value).await
+        } else {
+            tracing::debug!("Validating token with TokenManager...");
+            token_manager.validate_token(token_value).await
+        };
+        match validated {
             Ok(token_id) => {
                 tracing::info!("Authentication successful for token_id: {}", token_id);
 
@@ -178,8 +196,14 @@ pub struct McpAggregator {
     mcp_server_manager: Arc<McpServerManager>,
     mcp_client_manager: Arc<McpClientManager>,
     config: Arc<ServerConfig>,
+    /// 是否启用鉴权，配置热加载时可以在不重启监听的情况下切换
+    auth_enabled: Arc<AtomicBool>,
     token_manager: Arc<TokenManager>,
+    authorization_server: Arc<AuthorizationServer>,
     shutdown_signal: Arc<std::sync::Mutex<Option<CancellationToken>>>,
+    /// HTTP 监听任务，结束即表示端口已释放
+    server_task: Arc<std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>>,
+    traffic: Arc<AggregatorTraffic>,
     app: tauri::AppHandle,
 }
 
@@ -191,16 +215,59 @@ impl McpAggregator {
         token_manager: Arc<TokenManager>,
         app: tauri::AppHandle,
     ) -> Self {
+        let traffic = Arc::new(AggregatorTraffic::new(
+            config.max_connections,
+            config.max_body_size,
+            std::time::Duration::from_secs(config.timeout_seconds),
+        ));
+        let authorization_server = Arc::new(AuthorizationServer::new(
+            token_manager.clone(),
+            Some(app.clone()),
+            format!("{}:{}", config.host, config.port),
+            config.is_oauth_enabled(),
+        ));
         Self {
             mcp_server_manager,
             mcp_client_manager,
+            auth_enabled: Arc::new(AtomicBool::new(config.is_auth_enabled())),
             config,
             token_manager,
+            authorization_server,
             shutdown_signal: Arc::new(std::sync::Mutex::new(None)),
+            server_task: Arc::new(std::sync::Mutex::new(None)),
+            traffic,
             app,
         }
     }
 
+    /// Whether bearer token authentication is currently enforced
+    pub fn is_auth_enabled(&self) -> bool {
+        self.auth_enabled.load(Ordering::Relaxed)
+    }
+
+    /// Toggle authentication without restarting the listener
+    pub fn set_auth_enabled(&self, enabled: bool) {
+        self.auth_enabled.store(enabled, Ordering::Relaxed);
+        tracing::info!(
+            "Aggregator authentication {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+
+    /// Built-in OAuth authorization server for the aggregator endpoint
+    pub fn authorization_server(&self) -> Arc<AuthorizationServer> {
+        self.authorization_server.clone()
+    }
+
+    /// Toggle the OAuth authorization server without restarting the listener
+    pub fn set_oauth_enabled(&self, enabled: bool) {
+        self.authorization_server.set_enabled(enabled);
+        tracing::info!(
+            "Aggregator OAuth authorization {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+
     /// Apply pagination for tools
     async fn apply_pagination_tools(
         &self,
@@ -376,16 +443,43 @@ impl McpAggregator {
                 .route("/health", axum::routing::get(health_check))
                 .route("/debug/status", axum::routing::post(debug_status));
 
-            if self.config.is_auth_enabled() {
-                tracing::info!("Authentication enabled with dynamic token management");
-                let token_manager = self.token_manager.clone();
-                router = router.layer(middleware::from_fn(move |req, next| {
-                    let token_manager = token_manager.clone();
-                    async move { dynamic_bearer_auth_middleware(req, next, token_manager).await }
-                }));
-            } else {
-                tracing::info!("Authentication disabled - running without auth middleware");
-            }
+            // 鉴权中间件始终挂载，是否校验由 auth_enabled 决定，便于运行时切换
+            let token_manager = self.token_manager.clone();
+            let authorization_server = self.authorization_server.clone();
+            let auth_enabled = self.auth_enabled.clone();
+            router = router.layer(middleware::from_fn(move |req: Request, next: Next| {
+                let token_manager = token_manager.clone();
+                let authorization_server = authorization_server.clone();
+                let auth_enabled = auth_enabled.load(Ordering::Relaxed);
+                async move {
+                    if !auth_enabled {
+                        return Ok(next.run(req).await);
+                    }
+                    // 启用 OAuth 时在 401 中指明资源元数据地址，供客户端发起授权
+                    let challenge = authorization_server.bearer_challenge(req.headers());
+                    let result =
+                        dynamic_bearer_auth_middleware(req, next, token_manager, authorization_server)
+                            .await;
+                    match (result, challenge) {
+                        (Err(StatusCode::UNAUTHORIZED), Some(challenge)) => Ok((
+                            StatusCode::UNAUTHORIZED,
+                            [(header::WWW_AUTHENTICATE, challenge)],
+                        )
+                            .into_response()),
+                        (result, _) => result,
+                    }
+                }
+            }));
+
+            // OAuth 发现与授权端点不经过鉴权
+            router = router.merge(self.authorization_server.router());
+
+            // 最外层：请求体大小与在途请求数限制（在鉴权之前拒绝超限请求）
+            let traffic = self.traffic.clone();
+            router = router.layer(middleware::from_fn(move |req, next| {
+                let traffic = traffic.clone();
+                async move { traffic_limit_middleware(req, next, traffic).await }
+            }));
 
             router
         };
@@ -395,6 +489,7 @@ impl McpAggregator {
             tracing::error!("Failed to bind to {}: {}", addr, e);
             e
         })?;
+        let listener = LimitedListener::new(tcp_listener, self.traffic.clone());
 
         // Create cancellation token for graceful shutdown
         let ct = CancellationToken::new();
@@ -408,27 +503,40 @@ impl McpAggregator {
         }
 
         // Spawn server task
-        let _server_handle = tokio::spawn({
+        let server_handle = tokio::spawn({
             let ct = ct.clone();
             async move {
                 tracing::info!("MCP Aggregator HTTP server running on {}", addr);
-                let result = axum::serve(tcp_listener, router)
-                    .with_graceful_shutdown(async move {
-                        ct.cancelled_owned().await;
-                        tracing::info!("MCP Aggregator server shutting down...");
-                    })
-                    .await;
+                let result = axum::serve(
+                    listener,
+                    router.into_make_service_with_connect_info::<ConnectionHandle>(),
+                )
+                .with_graceful_shutdown(async move {
+                    ct.cancelled_owned().await;
+                    tracing::info!("MCP Aggregator server shutting down...");
+                })
+                .await;
 
                 if let Err(e) = result {
                     tracing::error!("Server error: {}", e);
                 }
             }
         });
+        *self
+            .server_task
+            .lock()
+            .expect("Failed to acquire server_task lock") = Some(server_handle);
 
         tracing::info!(
             "MCP Aggregator started successfully on {} (auth: {}, timeout: {}s, max_connections: {})",
             addr,
-            if self.config.is_auth_enabled() { "enabled with dynamic tokens" } else { "disabled" },
+            if !self.is_auth_enabled() {
+                "disabled"
+            } else if self.authorization_server.is_enabled() {
+                "enabled with dynamic tokens and OAuth"
+            } else {
+                "enabled with dynamic tokens"
+            },
             self.config.timeout_seconds,
             self.config.max_connections
         );
@@ -456,6 +564,10 @@ impl McpAggregator {
             "status": "running",
             "message": "Aggregator initialized",
             "server_stats": { "total": total_servers, "connected": connected_servers },
+            "active_connections": self.traffic.active_connections(),
+            "in_flight_requests": self.traffic.in_flight_requests(),
+            "rejected_requests": self.traffic.rejected_requests(),
+            "max_connections": self.traffic.max_connections(),
             "tool_cache": { "enabled": true, "entries": total, "ttl_seconds": ttl, "tools_total": updated_count, "last_updated": latest.duration_since(std::time::UNIX_EPOCH).ok().map(|d| format!("{}", d.as_secs())) }
         })
     }
@@ -591,6 +703,24 @@ impl McpAggregator {
         }
     }
 
+    /// Start a lazy server on demand, failures are reported by the following status check
+    async fn start_lazy_server(&self, server_name: &str) {
+        // 先记录使用，避免运行中的服务在调用开始前被当作空闲服务停止
+        self.mcp_client_manager.touch(server_name);
+        if self.mcp_client_manager.is_connected(server_name).await {
+            return;
+        }
+
+        let config = match self.mcp_server_manager.get_server_config(server_name).await {
+            Ok(Some(config)) if config.enabled && config.is_lazy() => config,
+            _ => return,
+        };
+
+        if let Err(e) = self.mcp_client_manager.start_on_demand(&config).await {
+            tracing::warn!("Failed to start lazy server '{}': {}", server_name, e);
+        }
+    }
+
     pub async fn trigger_shutdown(&self) {
         tracing::info!("Triggering aggregator shutdown...");
 
@@ -606,6 +736,30 @@ impl McpAggregator {
         }
     }
 
+    /// 发送关闭信号并等待监听任务退出，连接迟迟不结束时强制终止
+    pub async fn shutdown(&self) {
+        self.trigger_shutdown().await;
+        let handle = self
+            .server_task
+            .lock()
+            .expect("Failed to acquire server_task lock")
+            .take();
+        let Some(mut handle) = handle else {
+            return;
+        };
+        if tokio::time::timeout(SHUTDOWN_GRACE, &mut handle)
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                "MCP Aggregator did not shut down within {:?}, aborting open connections",
+                SHUTDOWN_GRACE
+            );
+            handle.abort();
+            let _ = handle.await;
+        }
+    }
+
     /// Fetch tools from database (merged logic from get_tools_direct)
     async fn fetch_tools_from_database(&self) -> Result<Vec<McpTool>, RmcpErrorData> {
         tracing::info!("🔍 Getting tools directly from database");
@@ -860,8 +1014,8 @@ impl ServerHandler for McpAggregator {
         tracing::info!("Request parameters: {:?}", request);
 
         // If authentication is disabled, return all tools without pagination
-        tracing::info!("Authentication enabled: {}", self.config.is_auth_enabled());
-        if !self.config.is_auth_enabled() {
+        tracing::info!("Authentication enabled: {}", self.is_auth_enabled());
+        if !self.is_auth_enabled() {
             tracing::info!("Authentication disabled, returning all tools");
             // Direct data retrieval - return all tools when auth is disabled
             let tools = self.fetch_tools_from_database().await?;
@@ -960,7 +1114,7 @@ impl ServerHandler for McpAggregator {
         tracing::debug!("Call tool request received for name: {}", request.name);
 
         // If authentication is disabled, allow all tool calls
-        if !self.config.is_auth_enabled() {
+        if !self.is_auth_enabled() {
             tracing::debug!(
                 "Authentication disabled, allowing tool call: {}",
                 request.name
@@ -1027,6 +1181,9 @@ impl ServerHandler for McpAggregator {
             original_name
         );
 
+        // lazy 服务在首次调用时启动
+        self.start_lazy_server(&server_name).await;
+
         // Check if the server is connected first
         let (connection_status, error_message) = self
             .mcp_client_manager
@@ -1072,7 +1229,9 @@ impl ServerHandler for McpAggregator {
                 );
                 let error_code = if e.to_string().contains("Service not found") {
                     ErrorCode(404)
-                } else if e.to_string().contains("Service not connected") {
+                } else if e.to_string().contains("Service not connected")
+                    || matches!(e, crate::error::McpError::ServiceUnavailable(_))
+                {
                     ErrorCode(503)
                 } else {
                     ErrorCode(500)
@@ -1094,7 +1253,7 @@ impl ServerHandler for McpAggregator {
         tracing::debug!("List prompts request received");
 
         // If authentication is disabled, return all prompts without pagination
-        if !self.config.is_auth_enabled() {
+        if !self.is_auth_enabled() {
             tracing::info!("Authentication disabled, returning all prompts");
             // Direct data retrieval - return all prompts when auth is disabled
             let prompts = self.fetch_prompts_from_database().await?;
@@ -1180,7 +1339,7 @@ impl ServerHandler for McpAggregator {
         tracing::debug!("Get prompt request received for name: {}", request.name);
 
         // If authentication is disabled, allow all prompt access
-        if !self.config.is_auth_enabled() {
+        if !self.is_auth_enabled() {
             tracing::debug!(
                 "Authentication disabled, allowing prompt access: {}",
                 request.name
@@ -1247,6 +1406,9 @@ impl ServerHandler for McpAggregator {
             original_name
         );
 
+        // lazy 服务在首次调用时启动
+        self.start_lazy_server(&server_name).await;
+
         // Check if the server is connected first
         let (connection_status, error_message) = self
             .mcp_client_manager
@@ -1312,7 +1474,9 @@ impl ServerHandler for McpAggregator {
                 );
                 let error_code = if e.to_string().contains("Service not found") {
                     ErrorCode(404)
-                } else if e.to_string().contains("Service not connected") {
+                } else if e.to_string().contains("Service not connected")
+                    || matches!(e, crate::error::McpError::ServiceUnavailable(_))
+                {
                     ErrorCode(503)
                 } else {
                     ErrorCode(500)
@@ -1334,7 +1498,7 @@ impl ServerHandler for McpAggregator {
         tracing::debug!("List resources request received");
 
         // If authentication is disabled, return all resources without pagination
-        if !self.config.is_auth_enabled() {
+        if !self.is_auth_enabled() {
             tracing::info!("Authentication disabled, returning all resources");
             // Direct data retrieval - return all resources when auth is disabled
             let resources = self.fetch_resources_from_database().await?;
@@ -1420,7 +1584,7 @@ impl ServerHandler for McpAggregator {
         tracing::debug!("Read resource request received for URI: {}", request.uri);
 
         // If authentication is disabled, allow all resource reads
-        if !self.config.is_auth_enabled() {
+        if !self.is_auth_enabled() {
             tracing::debug!(
                 "Authentication disabled, allowing resource read: {}",
                 request.uri
@@ -1489,6 +1653,9 @@ impl ServerHandler for McpAggregator {
             original_uri
         );
 
+        // lazy 服务在首次调用时启动
+        self.start_lazy_server(&server_name).await;
+
         // Check if the server is connected first
         let (connection_status, error_message) = self
             .mcp_client_manager
@@ -1533,7 +1700,9 @@ impl ServerHandler for McpAggregator {
                 );
                 let error_code = if e.to_string().contains("Service not found") {
                     ErrorCode(404)
-                } else if e.to_string().contains("Service not connected") {
+                } else if e.to_string().contains("Service not connected")
+                    || matches!(e, crate::error::McpError::ServiceUnavailable(_))
+                {
                     ErrorCode(503)
                 } else {
                     ErrorCode(500)
@@ -0,0 +1,1467 @@
+// 聚合接口的内置 OAuth 2.1 授权服务器
+
+use crate::entities::oauth_grant;
+use crate::error::{McpError, Result};
+use crate::oauth_client::{lock, now_secs, pkce_challenge, random_token};
+use crate::storage::orm_storage::Storage;
+use crate::storage::StorageError;
+use crate::token_manager::TokenManager;
+use axum::extract::{Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use axum::routing::{get, post};
+use axum::{Form, Json};
+use base64::Engine;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager};
+
+/// 新的授权请求等待用户确认时发送给前端的事件
+pub const CONSENT_REQUEST_EVENT: &str = "oauth-consent-request";
+
+/// 访问令牌前缀，用于和静态 Token 区分
+pub const ACCESS_TOKEN_PREFIX: &str = "mcpat_";
+
+const REFRESH_TOKEN_PREFIX: &str = "mcprt_";
+
+const ACCESS_TOKEN_TTL_SECS: u64 = 3600;
+
+/// 授权码只能在短时间内兑换一次
+const AUTHORIZATION_CODE_TTL: Duration = Duration::from_secs(60);
+
+/// 用户确认授权的最长等待时间
+const CONSENT_TTL: Duration = Duration::from_secs(600);
+
+/// 同时等待确认的授权请求上限
+const MAX_PENDING_CONSENTS: usize = 10;
+
+/// 每个限流窗口内允许的动态注册次数
+const MAX_REGISTRATIONS_PER_WINDOW: usize = 10;
+const REGISTRATION_WINDOW: Duration = Duration::from_secs(60);
+
+/// 已注册客户端的上限
+const MAX_CLIENTS: u64 = 100;
+
+/// 注册后超过此时间仍未获得授权的客户端会被清理
+const UNUSED_CLIENT_TTL_SECS: i64 = 24 * 3600;
+
+/// 授权等待页 nonce Cookie 的名称前缀，后接请求 ID
+const CONSENT_COOKIE_PREFIX: &str = "mcp_router_consent_";
+
+const PROTECTED_RESOURCE_WELL_KNOWN: &str = "/.well-known/oauth-protected-resource";
+const AUTHORIZATION_SERVER_WELL_KNOWN: &str = "/.well-known/oauth-authorization-server";
+
+/// 等待用户确认的授权请求，推送给前端展示
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsentRequest {
+    pub id: String,
+    pub client_id: String,
+    pub client_name: String,
+    pub redirect_uri: String,
+    pub created_at: u64,
+}
+
+/// 已授予客户端的访问权限
+#[derive(Debug, Clone, Serialize)]
+pub struct OAuthGrantInfo {
+    pub id: String,
+    pub client_id: String,
+    pub client_name: String,
+    pub token_id: String,
+    pub token_name: Option<String>,
+    pub created_at: u64,
+    /// 最近一次签发访问令牌的时间
+    pub updated_at: u64,
+}
+
+struct PendingConsent {
+    request: ConsentRequest,
+    issuer: String,
+    state: Option<String>,
+    code_challenge: String,
+    /// 授权请求是否显式携带了 redirect_uri
+    redirect_uri_supplied: bool,
+    /// 发起请求的浏览器所持有 nonce 的摘要，轮询结果时校验
+    nonce_digest: String,
+    /// 用户处理后浏览器应跳转到的地址
+    redirect: Option<String>,
+    created: Instant,
+}
+
+struct AuthorizationCode {
+    client_id: String,
+    /// 授权请求携带的 redirect_uri，换取令牌时必须一致；未携带时为 None
+    redirect_uri: Option<String>,
+    code_challenge: String,
+    token_id: String,
+    created: Instant,
+}
+
+/// 聚合器的内置授权服务器
+pub struct AuthorizationServer {
+    token_manager: Arc<TokenManager>,
+    /// 用于推送授权请求，没有桌面端（测试）时为 None
+    app: Option<tauri::AppHandle>,
+    /// 监听地址，Host 头不可信时用于拼接 issuer
+    default_authority: String,
+    enabled: AtomicBool,
+    consents: std::sync::Mutex<HashMap<String, PendingConsent>>,
+    codes: std::sync::Mutex<HashMap<String, AuthorizationCode>>,
+    /// 限流窗口内的动态注册时间
+    registrations: std::sync::Mutex<VecDeque<Instant>>,
+}
+
+impl AuthorizationServer {
+    pub fn new(
+        token_manager: Arc<TokenManager>,
+        app: Option<tauri::AppHandle>,
+        default_authority: String,
+        enabled: bool,
+    ) -> Self {
+        Self {
+            token_manager,
+            app,
+            default_authority,
+            enabled: AtomicBool::new(enabled),
+            consents: std::sync::Mutex::new(HashMap::new()),
+            codes: std::sync::Mutex::new(HashMap::new()),
+            registrations: std::sync::Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    fn storage(&self) -> Arc<Storage> {
+        self.token_manager.orm_storage()
+    }
+
+    /// 发现、注册和授权端点，关闭时全部返回 404
+    pub fn router(self: &Arc<Self>) -> axum::Router {
+        let server = self.clone();
+        axum::Router::new()
+            .route(
+                PROTECTED_RESOURCE_WELL_KNOWN,
+                get(protected_resource_metadata),
+            )
+            .route(
+                &format!("{}/mcp", PROTECTED_RESOURCE_WELL_KNOWN),
+                get(protected_resource_metadata),
+            )
+            .route(
+                AUTHORIZATION_SERVER_WELL_KNOWN,
+                get(authorization_server_metadata),
+            )
+            .route("/oauth/register", post(register))
+            .route("/oauth/authorize", get(authorize))
+            .route("/oauth/authorize/status", get(authorize_status))
+            .route("/oauth/token", post(token))
+            .route("/oauth/revoke", post(revoke))
+            .with_state(self.clone())
+            .layer(middleware::from_fn(move |req: Request, next: Next| {
+                let enabled = server.is_enabled();
+                async move {
+                    if !enabled {
+                        return StatusCode::NOT_FOUND.into_response();
+                    }
+                    next.run(req).await
+                }
+            }))
+    }
+
+    /// 未认证请求的 WWW-Authenticate 值，关闭时返回 None
+    pub fn bearer_challenge(&self, headers: &HeaderMap) -> Option<String> {
+        if !self.is_enabled() {
+            return None;
+        }
+        Some(format!(
+            "Bearer resource_metadata=\"{}{}/mcp\"",
+            self.issuer(headers),
+            PROTECTED_RESOURCE_WELL_KNOWN
+        ))
+    }
+
+    /// 校验访问令牌，返回其映射的 Token ID
+    pub async fn validate_access_token(&self, access_token: &str) -> Result<String> {
+        let grant = self
+            .storage()
+            .get_oauth_grant_by_access_hash(&token_digest(access_token))
+            .await?
+            .ok_or_else(|| McpError::AuthError("Unknown access token".to_string()))?;
+        if grant.access_expires_at < chrono::Utc::now() {
+            return Err(McpError::AuthError("Access token has expired".to_string()));
+        }
+        self.ensure_token_usable(&grant.token_id).await?;
+
+        if let Err(e) = self.token_manager.record_usage(&grant.token_id).await {
+            tracing::warn!("Failed to update token usage: {}", e);
+        }
+        Ok(grant.token_id)
+    }
+
+    /// 等待用户确认的授权请求
+    pub fn pending_requests(&self) -> Vec<ConsentRequest> {
+        let mut consents = lock(&self.consents);
+        consents.retain(|_, consent| consent.created.elapsed() < CONSENT_TTL);
+        let mut requests: Vec<ConsentRequest> = consents
+            .values()
+            .filter(|consent| consent.redirect.is_none())
+            .map(|consent| consent.request.clone())
+            .collect();
+        requests.sort_by_key(|request| request.created_at);
+        requests
+    }
+
+    /// 处理授权请求：批准时签发绑定到 token_id 的授权码，拒绝时返回 access_denied
+    pub async fn respond(
+        &self,
+        request_id: &str,
+        approve: bool,
+        token_id: Option<String>,
+    ) -> Result<()> {
+        let token_id = match (approve, token_id) {
+            (true, Some(token_id)) => {
+                self.ensure_token_usable(&token_id).await?;
+                Some(token_id)
+            }
+            (true, None) => {
+                return Err(McpError::InvalidInput(
+                    "A token must be selected to approve the request".to_string(),
+                ))
+            }
+            (false, _) => None,
+        };
+
+        let mut consents = lock(&self.consents);
+        let consent = consents
+            .get_mut(request_id)
+            .filter(|consent| consent.redirect.is_none() && consent.created.elapsed() < CONSENT_TTL)
+            .ok_or_else(|| {
+                McpError::NotFound(format!("Authorization request {} not found", request_id))
+            })?;
+
+        let mut params = Vec::new();
+        match token_id {
+            Some(token_id) => {
+                let code = random_token::<32>();
+                lock(&self.codes).insert(
+                    code.clone(),
+                    AuthorizationCode {
+                        client_id: consent.request.client_id.clone(),
+                        redirect_uri: consent
+                            .redirect_uri_supplied
+                            .then(|| consent.request.redirect_uri.clone()),
+                        code_challenge: consent.code_challenge.clone(),
+                        token_id,
+                        created: Instant::now(),
+                    },
+                );
+                params.push(("code", code));
+            }
+            None => params.push(("error", "access_denied".to_string())),
+        }
+        consent.redirect = Some(redirect_with(
+            &consent.request.redirect_uri,
+            params,
+            consent.state.as_deref(),
+            &consent.issuer,
+        ));
+        tracing::info!(
+            "OAuth authorization for client '{}' {}",
+            consent.request.client_name,
+            if approve { "approved" } else { "denied" }
+        );
+        Ok(())
+    }
+
+    /// 已授予的访问权限列表
+    pub async fn list_grants(&self) -> Result<Vec<OAuthGrantInfo>> {
+        let storage = self.storage();
+        let clients: HashMap<String, String> = storage
+            .list_oauth_clients()
+            .await?
+            .into_iter()
+            .map(|client| (client.client_id, client.client_name))
+            .collect();
+        let tokens: HashMap<String, String> = self
+            .token_manager
+            .list()
+            .await?
+            .into_iter()
+            .map(|token| (token.id, token.name))
+            .collect();
+
+        Ok(storage
+            .list_oauth_grants()
+            .await?
+            .into_iter()
+            .map(|grant| OAuthGrantInfo {
+                client_name: clients
+                    .get(&grant.client_id)
+                    .cloned()
+                    .unwrap_or_else(|| grant.client_id.clone()),
+                token_name: tokens.get(&grant.token_id).cloned(),
+                created_at: grant.created_at.timestamp() as u64,
+                updated_at: grant.updated_at.timestamp() as u64,
+                id: grant.id,
+                client_id: grant.client_id,
+                token_id: grant.token_id,
+            })
+            .collect())
+    }
+
+    /// 撤销授权，客户端的访问令牌和刷新令牌立即失效
+    pub async fn revoke_grant(&self, grant_id: &str) -> Result<()> {
+        self.storage().delete_oauth_grant(grant_id).await?;
+        Ok(())
+    }
+
+    /// Token 必须存在、启用且未过期
+    async fn ensure_token_usable(&self, token_id: &str) -> Result<()> {
+        match self.token_manager.get_by_id(token_id).await? {
+            Some(token) if token.enabled && !token.is_expired => Ok(()),
+            Some(_) => Err(McpError::AuthError(format!(
+                "Token {} is disabled or expired",
+                token_id
+            ))),
+            None => Err(McpError::AuthError(format!("Token {} not found", token_id))),
+        }
+    }
+
+    /// 为兑换的授权码创建授权，签发访问令牌和刷新令牌
+    async fn issue_tokens(&self, client_id: &str, token_id: &str) -> Result<TokenResponse> {
+        let (response, expires_at) = TokenResponse::generate();
+        self.storage()
+            .create_oauth_grant(
+                client_id,
+                token_id,
+                &token_digest(&response.access_token),
+                expires_at,
+                &token_digest(&response.refresh_token),
+            )
+            .await?;
+        Ok(response)
+    }
+
+    /// 轮换授权的令牌，刷新令牌已被其他请求用掉时返回 None
+    async fn rotate_tokens(
+        &self,
+        grant_id: &str,
+        refresh_hash: &str,
+    ) -> Result<Option<TokenResponse>> {
+        let (response, expires_at) = TokenResponse::generate();
+        let rotated = self
+            .storage()
+            .rotate_oauth_grant(
+                grant_id,
+                refresh_hash,
+                &token_digest(&response.access_token),
+                expires_at,
+                &token_digest(&response.refresh_token),
+            )
+            .await?;
+        Ok(rotated.then_some(response))
+    }
+
+    /// 清理长期未获得授权的客户端，返回是否还能注册新的客户端
+    async fn has_client_capacity(&self) -> Result<bool> {
+        let storage = self.storage();
+        let registered_before =
+            chrono::Utc::now() - chrono::Duration::seconds(UNUSED_CLIENT_TTL_SECS);
+        let removed = storage
+            .delete_unused_oauth_clients(registered_before)
+            .await?;
+        if removed > 0 {
+            tracing::info!("Removed {} unused OAuth clients", removed);
+        }
+        Ok(storage.count_oauth_clients().await? < MAX_CLIENTS)
+    }
+
+    /// 按请求的 Host 头拼接 issuer，与客户端实际访问的地址保持一致；
+    /// 只信任回环地址和配置的监听地址，其他 Host 头使用监听地址
+    fn issuer(&self, headers: &HeaderMap) -> String {
+        let authority = headers
+            .get(header::HOST)
+            .and_then(|value| value.to_str().ok())
+            .filter(|host| is_trusted_authority(host, &self.default_authority))
+            .unwrap_or(&self.default_authority);
+        format!("http://{}", authority)
+    }
+
+    fn take_code(&self, code: &str) -> Option<AuthorizationCode> {
+        let mut codes = lock(&self.codes);
+        codes.retain(|_, code| code.created.elapsed() < AUTHORIZATION_CODE_TTL);
+        codes.remove(code)
+    }
+
+    /// 推送授权请求，只提示用户注意窗口，不抢占焦点
+    fn notify_consent(&self, request: &ConsentRequest) {
+        let Some(app) = &self.app else {
+            return;
+        };
+        if let Err(e) = app.emit(CONSENT_REQUEST_EVENT, request) {
+            tracing::error!("Failed to emit OAuth consent request: {}", e);
+        }
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.request_user_attention(Some(tauri::UserAttentionType::Informational));
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct TokenResponse {
+    access_token: String,
+    token_type: &'static str,
+    expires_in: u64,
+    refresh_token: String,
+}
+
+impl TokenResponse {
+    /// 生成新的访问令牌和刷新令牌，同时返回访问令牌的过期时间
+    fn generate() -> (Self, chrono::DateTime<chrono::Utc>) {
+        let expires_at =
+            chrono::Utc::now() + chrono::Duration::seconds(ACCESS_TOKEN_TTL_SECS as i64);
+        let response = Self {
+            access_token: format!("{}{}", ACCESS_TOKEN_PREFIX, random_token::<32>()),
+            token_type: "Bearer",
+            expires_in: ACCESS_TOKEN_TTL_SECS,
+            refresh_token: format!("{}{}", REFRESH_TOKEN_PREFIX, random_token::<32>()),
+        };
+        (response, expires_at)
+    }
+}
+
+async fn protected_resource_metadata(
+    State(server): State<Arc<AuthorizationServer>>,
+    headers: HeaderMap,
+) -> Json<serde_json::Value> {
+    let issuer = server.issuer(&headers);
+    Json(serde_json::json!({
+        "resource": format!("{}/mcp", issuer),
+        "authorization_servers": [issuer],
+        "bearer_methods_supported": ["header"],
+        "resource_name": "MCP Router",
+    }))
+}
+
+async fn authorization_server_metadata(
+    State(server): State<Arc<AuthorizationServer>>,
+    headers: HeaderMap,
+) -> Json<serde_json::Value> {
+    let issuer = server.issuer(&headers);
+    Json(serde_json::json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{}/oauth/authorize", issuer),
+        "token_endpoint": format!("{}/oauth/token", issuer),
+        "registration_endpoint": format!("{}/oauth/register", issuer),
+        "revocation_endpoint": format!("{}/oauth/revoke", issuer),
+        "response_types_supported": ["code"],
+        "grant_types_supported": ["authorization_code", "refresh_token"],
+        "code_challenge_methods_supported": ["S256"],
+        "token_endpoint_auth_methods_supported": ["none"],
+        "revocation_endpoint_auth_methods_supported": ["none"],
+        "authorization_response_iss_parameter_supported": true,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct RegistrationRequest {
+    #[serde(default)]
+    redirect_uris: Vec<String>,
+    client_name: Option<String>,
+    token_endpoint_auth_method: Option<String>,
+    #[serde(default)]
+    grant_types: Vec<String>,
+    #[serde(default)]
+    response_types: Vec<String>,
+}
+
+async fn register(
+    State(server): State<Arc<AuthorizationServer>>,
+    Json(request): Json<RegistrationRequest>,
+) -> Response {
+    if request.redirect_uris.is_empty() {
+        return oauth_error(
+            StatusCode::BAD_REQUEST,
+            "invalid_redirect_uri",
+            "redirect_uris is required",
+        );
+    }
+    if let Some(uri) = request
+        .redirect_uris
+        .iter()
+        .find(|uri| !is_allowed_redirect_uri(uri))
+    {
+        return oauth_error(
+            StatusCode::BAD_REQUEST,
+            "invalid_redirect_uri",
+            format!("Redirect URI {} is not allowed", uri),
+        );
+    }
+    if request
+        .token_endpoint_auth_method
+        .as_deref()
+        .is_some_and(|method| method != "none")
+    {
+        return oauth_error(
+            StatusCode::BAD_REQUEST,
+            "invalid_client_metadata",
+            "Only public clients (token_endpoint_auth_method \"none\") are supported",
+        );
+    }
+    if request
+        .grant_types
+        .iter()
+        .any(|grant| grant != "authorization_code" && grant != "refresh_token")
+        || request
+            .response_types
+            .iter()
+            .any(|response| response != "code")
+    {
+        return oauth_error(
+            StatusCode::BAD_REQUEST,
+            "invalid_client_metadata",
+            "Only the authorization_code and refresh_token grants are supported",
+        );
+    }
+
+    if !within_rate_limit(
+        &mut lock(&server.registrations),
+        MAX_REGISTRATIONS_PER_WINDOW,
+        REGISTRATION_WINDOW,
+    ) {
+        return oauth_error(
+            StatusCode::TOO_MANY_REQUESTS,
+            "temporarily_unavailable",
+            "Too many client registrations, try again later",
+        );
+    }
+    match server.has_client_capacity().await {
+        Ok(true) => {}
+        Ok(false) => {
+            return oauth_error(
+                StatusCode::TOO_MANY_REQUESTS,
+                "temporarily_unavailable",
+                "Too many registered clients",
+            )
+        }
+        Err(e) => return server_error(e),
+    }
+
+    let client_id = random_token::<16>();
+    let client_name = request
+        .client_name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "Unnamed client".to_string());
+    if let Err(e) = server
+        .storage()
+        .insert_oauth_client(&client_id, &client_name, &request.redirect_uris)
+        .await
+    {
+        return server_error(e.into());
+    }
+    tracing::info!("Registered OAuth client '{}' ({})", client_name, client_id);
+
+    (
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "client_id": client_id,
+            "client_id_issued_at": now_secs(),
+            "client_name": client_name,
+            "redirect_uris": request.redirect_uris,
+            "grant_types": ["authorization_code", "refresh_token"],
+            "response_types": ["code"],
+            "token_endpoint_auth_method": "none",
+        })),
+    )
+        .into_response()
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorizeParams {
+    response_type: Option<String>,
+    client_id: Option<String>,
+    redirect_uri: Option<String>,
+    code_challenge: Option<String>,
+    code_challenge_method: Option<String>,
+    state: Option<String>,
+}
+
+async fn authorize(
+    State(server): State<Arc<AuthorizationServer>>,
+    headers: HeaderMap,
+    Query(params): Query<AuthorizeParams>,
+) -> Response {
+    let client = match params.client_id.as_deref() {
+        Some(client_id) => match server.storage().get_oauth_client(client_id).await {
+            Ok(client) => client,
+            Err(e) => return server_error(e.into()),
+        },
+        None => None,
+    };
+    let Some(client) = client else {
+        return error_page(StatusCode::BAD_REQUEST, "Unknown client_id");
+    };
+    let registered: Vec<String> = serde_json::from_str(&client.redirect_uris).unwrap_or_default();
+
+    // 客户端或回调地址无效时不能重定向，只能直接展示错误
+    let redirect_uri_supplied = params.redirect_uri.is_some();
+    let redirect_uri = match params.redirect_uri {
+        Some(uri) if registered.iter().any(|r| redirect_uri_matches(r, &uri)) => uri,
+        None if registered.len() == 1 => registered[0].clone(),
+        _ => return error_page(StatusCode::BAD_REQUEST, "Invalid redirect_uri"),
+    };
+
+    let issuer = server.issuer(&headers);
+    let state = params.state;
+    let reject = |error: &str, description: &str| {
+        Redirect::to(&redirect_with(
+            &redirect_uri,
+            vec![
+                ("error", error.to_string()),
+                ("error_description", description.to_string()),
+            ],
+            state.as_deref(),
+            &issuer,
+        ))
+        .into_response()
+    };
+    if params.response_type.as_deref() != Some("code") {
+        return reject(
+            "unsupported_response_type",
+            "Only the code response type is supported",
+        );
+    }
+    let code_challenge = match (
+        params.code_challenge,
+        params.code_challenge_method.as_deref(),
+    ) {
+        (Some(challenge), Some("S256")) if !challenge.is_empty() => challenge,
+        _ => return reject("invalid_request", "PKCE with S256 is required"),
+    };
+
+    let request = ConsentRequest {
+        id: uuid::Uuid::now_v7().to_string(),
+        client_id: client.client_id,
+        client_name: client.client_name,
+        redirect_uri: redirect_uri.clone(),
+        created_at: now_secs(),
+    };
+    let nonce = random_token::<32>();
+    {
+        let mut consents = lock(&server.consents);
+        consents.retain(|_, consent| consent.created.elapsed() < CONSENT_TTL);
+        if consents.len() >= MAX_PENDING_CONSENTS {
+            return error_page(
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many pending authorization requests, try again later",
+            );
+        }
+        consents.insert(
+            request.id.clone(),
+            PendingConsent {
+                request: request.clone(),
+                issuer: issuer.clone(),
+                state: state.clone(),
+                code_challenge,
+                redirect_uri_supplied,
+                nonce_digest: token_digest(&nonce),
+                redirect: None,
+                created: Instant::now(),
+            },
+        );
+    }
+    server.notify_consent(&request);
+
+    (
+        [
+            (
+                header::SET_COOKIE,
+                consent_cookie(&request.id, &nonce, CONSENT_TTL.as_secs()),
+            ),
+            (header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+        Html(consent_page(&request)),
+    )
+        .into_response()
+}
+
+#[derive(Debug, Deserialize)]
+struct StatusParams {
+    request_id: String,
+}
+
+/// 授权等待页轮询此端点，用户处理后返回跳转地址；
+/// 只响应持有该请求 nonce Cookie 的浏览器，其他请求一律视为已过期
+async fn authorize_status(
+    State(server): State<Arc<AuthorizationServer>>,
+    headers: HeaderMap,
+    Query(params): Query<StatusParams>,
+) -> Response {
+    let nonce_digest = consent_nonce(&headers, &params.request_id).map(token_digest);
+    let mut consents = lock(&server.consents);
+    match consents.get(&params.request_id) {
+        Some(consent)
+            if consent.created.elapsed() < CONSENT_TTL
+                && nonce_digest.as_deref() == Some(consent.nonce_digest.as_str()) =>
+        {
+            match consent.redirect.clone() {
+                Some(redirect) => {
+                    consents.remove(&params.request_id);
+                    (
+                        [(
+                            header::SET_COOKIE,
+                            consent_cookie(&params.request_id, "", 0),
+                        )],
+                        Json(serde_json::json!({ "status": "done", "redirect": redirect })),
+                    )
+                        .into_response()
+                }
+                None => Json(serde_json::json!({ "status": "pending" })).into_response(),
+            }
+        }
+        _ => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "status": "expired" })),
+        )
+            .into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenRequest {
+    grant_type: String,
+    code: Option<String>,
+    redirect_uri: Option<String>,
+    client_id: Option<String>,
+    code_verifier: Option<String>,
+    refresh_token: Option<String>,
+}
+
+async fn token(
+    State(server): State<Arc<AuthorizationServer>>,
+    Form(request): Form<TokenRequest>,
+) -> Response {
+    let Some(client_id) = request.client_id else {
+        return oauth_error(
+            StatusCode::UNAUTHORIZED,
+            "invalid_client",
+            "client_id is required",
+        );
+    };
+
+    let result = match request.grant_type.as_str() {
+        "authorization_code" => {
+            let Some(code) = request.code.and_then(|code| server.take_code(&code)) else {
+                return invalid_grant("Invalid or expired authorization code");
+            };
+            if code.client_id != client_id {
+                return invalid_grant("Authorization code was issued to another client");
+            }
+            if code
+                .redirect_uri
+                .as_deref()
+                .is_some_and(|uri| request.redirect_uri.as_deref() != Some(uri))
+            {
+                return invalid_grant("redirect_uri does not match the authorization request");
+            }
+            let verifier = request.code_verifier.unwrap_or_default();
+            if pkce_challenge(&verifier) != code.code_challenge {
+                return invalid_grant("PKCE verification failed");
+            }
+            if let Err(e) = server.ensure_token_usable(&code.token_id).await {
+                return invalid_grant(&e.to_string());
+            }
+            server.issue_tokens(&client_id, &code.token_id).await
+        }
+        "refresh_token" => {
+            let refresh_hash = token_digest(&request.refresh_token.unwrap_or_default());
+            let storage = server.storage();
+            let grant = match storage.get_oauth_grant_by_refresh_hash(&refresh_hash).await {
+                Ok(Some(grant)) if grant.client_id == client_id => grant,
+                Ok(Some(_)) => return invalid_grant("Invalid refresh token"),
+                Ok(None) => {
+                    return match storage
+                        .get_oauth_grant_by_previous_refresh_hash(&refresh_hash)
+                        .await
+                    {
+                        Ok(Some(grant)) => revoke_reused_grant(&server, &grant).await,
+                        Ok(None) => invalid_grant("Invalid refresh token"),
+                        Err(e) => server_error(e.into()),
+                    }
+                }
+                Err(e) => return server_error(e.into()),
+            };
+            if let Err(e) = server.ensure_token_usable(&grant.token_id).await {
+                return invalid_grant(&e.to_string());
+            }
+            match server.rotate_tokens(&grant.id, &refresh_hash).await {
+                Ok(Some(response)) => Ok(response),
+                // 同一个刷新令牌已被并发请求用掉
+                Ok(None) => return revoke_reused_grant(&server, &grant).await,
+                Err(e) => Err(e),
+            }
+        }
+        _ => {
+            return oauth_error(
+                StatusCode::BAD_REQUEST,
+                "unsupported_grant_type",
+                format!("Grant type {} is not supported", request.grant_type),
+            )
+        }
+    };
+
+    match result {
+        Ok(response) => ([(header::CACHE_CONTROL, "no-store")], Json(response)).into_response(),
+        Err(e) => server_error(e),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RevokeRequest {
+    token: String,
+}
+
+/// RFC 7009：未知令牌同样返回 200
+async fn revoke(
+    State(server): State<Arc<AuthorizationServer>>,
+    Form(request): Form<RevokeRequest>,
+) -> Response {
+    let storage = server.storage();
+    let digest = token_digest(&request.token);
+    let grant = match storage.get_oauth_grant_by_access_hash(&digest).await {
+        Ok(None) => storage.get_oauth_grant_by_refresh_hash(&digest).await,
+        other => other,
+    };
+    match grant {
+        Ok(Some(grant)) => match storage.delete_oauth_grant(&grant.id).await {
+            Ok(()) => StatusCode::OK.into_response(),
+            Err(e) => server_error(e.into()),
+        },
+        Ok(None) => StatusCode::OK.into_response(),
+        Err(e) => server_error(e.into()),
+    }
+}
+
+/// 已轮换的刷新令牌被再次使用，说明令牌可能已泄露，撤销整个授权（RFC 9700 §4.14.2）
+async fn revoke_reused_grant(server: &AuthorizationServer, grant: &oauth_grant::Model) -> Response {
+    tracing::warn!(
+        "Refresh token of OAuth grant {} (client {}) was reused, revoking the grant",
+        grant.id,
+        grant.client_id
+    );
+    match server.storage().delete_oauth_grant(&grant.id).await {
+        Ok(()) | Err(StorageError::NotFound(_)) => {
+            invalid_grant("Refresh token was already used, the grant has been revoked")
+        }
+        Err(e) => server_error(e.into()),
+    }
+}
+
+fn oauth_error(status: StatusCode, error: &str, description: impl Into<String>) -> Response {
+    (
+        status,
+        [(header::CACHE_CONTROL, "no-store")],
+        Json(serde_json::json!({
+            "error": error,
+            "error_description": description.into(),
+        })),
+    )
+        .into_response()
+}
+
+fn invalid_grant(description: &str) -> Response {
+    oauth_error(StatusCode::BAD_REQUEST, "invalid_grant", description)
+}
+
+fn server_error(error: McpError) -> Response {
+    tracing::error!("OAuth request failed: {}", error);
+    oauth_error(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "server_error",
+        error.to_string(),
+    )
+}
+
+fn error_page(status: StatusCode, message: &str) -> Response {
+    (
+        status,
+        Html(format!(
+            "<!doctype html><html><body style=\"font-family: sans-serif\">\
+<h3>Authorization failed</h3><p>{}</p></body></html>",
+            html_escape(message)
+        )),
+    )
+        .into_response()
+}
+
+/// 等待用户在桌面端确认的页面，确认后跳转回客户端
+fn consent_page(request: &ConsentRequest) -> String {
+    format!(
+        r#"<!doctype html><html><head><meta charset="utf-8"><title>MCP Router</title></head>
+<body style="font-family: sans-serif">
+<h3>Authorize {client}</h3>
+<p id="message">Approve or deny this request in the MCP Router window.</p>
+<script>
+const poll = async () => {{
+  try {{
+    const res = await fetch('/oauth/authorize/status?request_id={id}')
+    const body = await res.json()
+    if (body.status === 'done') {{
+      window.location.replace(body.redirect)
+      return
+    }}
+    if (body.status === 'expired') {{
+      document.getElementById('message').textContent =
+        'This request has expired. Start the sign-in again from your client.'
+      return
+    }}
+  }} catch (e) {{}}
+  setTimeout(poll, 1000)
+}}
+poll()
+</script>
+</body></html>"#,
+        client = html_escape(&request.client_name),
+        id = request.id
+    )
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// 授权等待页的 nonce Cookie：只随同源的状态轮询请求发送，页面脚本无法读取
+fn consent_cookie(request_id: &str, nonce: &str, max_age: u64) -> String {
+    format!(
+        "{}{}={}; Path=/oauth/authorize/status; Max-Age={}; HttpOnly; SameSite=Strict",
+        CONSENT_COOKIE_PREFIX, request_id, nonce, max_age
+    )
+}
+
+/// 从 Cookie 头中读取授权请求的 nonce
+fn consent_nonce<'a>(headers: &'a HeaderMap, request_id: &str) -> Option<&'a str> {
+    let name = format!("{}{}", CONSENT_COOKIE_PREFIX, request_id);
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, value)| *key == name && !value.is_empty())
+        .map(|(_, value)| value)
+}
+
+/// 滑动窗口限流：窗口内的次数未达上限时记录本次并返回 true
+fn within_rate_limit(events: &mut VecDeque<Instant>, limit: usize, window: Duration) -> bool {
+    while events.front().is_some_and(|time| time.elapsed() >= window) {
+        events.pop_front();
+    }
+    if events.len() >= limit {
+        return false;
+    }
+    events.push_back(Instant::now());
+    true
+}
+
+/// 令牌只保存摘要，数据库泄露时无法直接使用
+fn token_digest(token: &str) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(token.as_bytes()))
+}
+
+fn redirect_with(
+    redirect_uri: &str,
+    params: Vec<(&str, String)>,
+    state: Option<&str>,
+    issuer: &str,
+) -> String {
+    let Ok(mut url) = Url::parse(redirect_uri) else {
+        return redirect_uri.to_string();
+    };
+    {
+        let mut query = url.query_pairs_mut();
+        for (key, value) in &params {
+            query.append_pair(key, value);
+        }
+        if let Some(state) = state {
+            query.append_pair("state", state);
+        }
+        query.append_pair("iss", issuer);
+    }
+    url.to_string()
+}
+
+/// 只接受主机名或 IP 加可选端口，避免把任意 Host 头写进元数据
+fn is_valid_authority(host: &str) -> bool {
+    !host.is_empty()
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '[' | ']'))
+}
+
+/// Host 头是否可以用于 issuer：端口与监听端口一致，主机为回环地址或配置的监听地址
+fn is_trusted_authority(authority: &str, configured: &str) -> bool {
+    if !is_valid_authority(authority) {
+        return false;
+    }
+    let (Ok(url), Ok(configured)) = (
+        Url::parse(&format!("http://{}", authority)),
+        Url::parse(&format!("http://{}", configured)),
+    ) else {
+        return false;
+    };
+    url.port_or_known_default() == configured.port_or_known_default()
+        && (is_loopback(&url) || url.host_str() == configured.host_str())
+}
+
+fn is_loopback(url: &Url) -> bool {
+    matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"))
+}
+
+/// 允许 HTTPS、回环 HTTP 和原生应用的私有 scheme
+fn is_allowed_redirect_uri(uri: &str) -> bool {
+    let Ok(url) = Url::parse(uri) else {
+        return false;
+    };
+    if url.fragment().is_some() {
+        return false;
+    }
+    match url.scheme() {
+        "https" => true,
+        "http" => is_loopback(&url),
+        "javascript" | "data" | "file" | "vbscript" => false,
+        _ => true,
+    }
+}
+
+/// RFC 8252 §7.3：回环地址的端口由客户端运行时决定，比较时忽略端口
+fn redirect_uri_matches(registered: &str, requested: &str) -> bool {
+    if registered == requested {
+        return true;
+    }
+    match (Url::parse(registered), Url::parse(requested)) {
+        (Ok(mut registered), Ok(mut requested))
+            if registered.scheme() == "http" && is_loopback(&registered) =>
+        {
+            let _ = registered.set_port(None);
+            let _ = requested.set_port(None);
+            registered == requested
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token_manager::CreateTokenParams;
+
+    const CLIENT_ID: &str = "client";
+    const REDIRECT_URI: &str = "http://127.0.0.1:1234/callback";
+
+    /// 内存数据库上的授权服务器，注册一个客户端并创建一个可授予的 Token
+    async fn test_server() -> (Arc<AuthorizationServer>, String) {
+        let storage = Arc::new(
+            Storage::new("sqlite::memory:", false, log::LevelFilter::Off)
+                .await
+                .unwrap(),
+        );
+        storage
+            .insert_oauth_client(CLIENT_ID, "Client", &[REDIRECT_URI.to_string()])
+            .await
+            .unwrap();
+        let token_manager = Arc::new(TokenManager::new(storage).await.unwrap());
+        let token = token_manager
+            .create(CreateTokenParams {
+                name: "test".to_string(),
+                description: None,
+                allowed_tools: None,
+                allowed_resources: None,
+                allowed_prompts: None,
+                allowed_prompt_templates: None,
+                expires_in: None,
+            })
+            .await
+            .unwrap();
+        let server = Arc::new(AuthorizationServer::new(
+            token_manager,
+            None,
+            "127.0.0.1:8000".to_string(),
+            true,
+        ));
+        (server, token.id)
+    }
+
+    /// 跳过桌面端确认，直接签发授权码
+    fn insert_code(server: &AuthorizationServer, token_id: &str, verifier: &str) -> String {
+        let code = random_token::<32>();
+        lock(&server.codes).insert(
+            code.clone(),
+            AuthorizationCode {
+                client_id: CLIENT_ID.to_string(),
+                redirect_uri: Some(REDIRECT_URI.to_string()),
+                code_challenge: pkce_challenge(verifier),
+                token_id: token_id.to_string(),
+                created: Instant::now(),
+            },
+        );
+        code
+    }
+
+    fn code_request(code: &str, redirect_uri: &str, verifier: &str) -> TokenRequest {
+        TokenRequest {
+            grant_type: "authorization_code".to_string(),
+            code: Some(code.to_string()),
+            redirect_uri: Some(redirect_uri.to_string()),
+            client_id: Some(CLIENT_ID.to_string()),
+            code_verifier: Some(verifier.to_string()),
+            refresh_token: None,
+        }
+    }
+
+    fn refresh_request(refresh_token: &str) -> TokenRequest {
+        TokenRequest {
+            grant_type: "refresh_token".to_string(),
+            code: None,
+            redirect_uri: None,
+            client_id: Some(CLIENT_ID.to_string()),
+            code_verifier: None,
+            refresh_token: Some(refresh_token.to_string()),
+        }
+    }
+
+    async fn read_json(response: Response) -> (StatusCode, serde_json::Value) {
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn call_token(
+        server: &Arc<AuthorizationServer>,
+        request: TokenRequest,
+    ) -> (StatusCode, serde_json::Value) {
+        read_json(token(State(server.clone()), Form(request)).await).await
+    }
+
+    fn authorize_params(redirect_uri: &str) -> AuthorizeParams {
+        AuthorizeParams {
+            response_type: Some("code".to_string()),
+            client_id: Some(CLIENT_ID.to_string()),
+            redirect_uri: Some(redirect_uri.to_string()),
+            code_challenge: Some(pkce_challenge("verifier")),
+            code_challenge_method: Some("S256".to_string()),
+            state: Some("s1".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_validates_redirect_uris() {
+        assert!(is_allowed_redirect_uri("https://app.example.com/callback"));
+        assert!(is_allowed_redirect_uri("http://127.0.0.1:33418/callback"));
+        assert!(is_allowed_redirect_uri("http://localhost/callback"));
+        assert!(is_allowed_redirect_uri(
+            "cursor://anysphere.cursor-mcp/oauth"
+        ));
+        assert!(!is_allowed_redirect_uri("http://evil.example.com/callback"));
+        assert!(!is_allowed_redirect_uri("https://app.example.com/cb#frag"));
+        assert!(!is_allowed_redirect_uri("javascript:alert(1)"));
+        assert!(!is_allowed_redirect_uri("not a url"));
+    }
+
+    #[test]
+    fn test_matches_loopback_redirects_on_any_port() {
+        assert!(redirect_uri_matches(
+            "http://127.0.0.1:1234/callback",
+            "http://127.0.0.1:5678/callback"
+        ));
+        assert!(!redirect_uri_matches(
+            "http://127.0.0.1:1234/callback",
+            "http://127.0.0.1:1234/other"
+        ));
+        assert!(!redirect_uri_matches(
+            "https://app.example.com:443/callback",
+            "https://app.example.com:8443/callback"
+        ));
+    }
+
+    #[test]
+    fn test_builds_authorization_response() {
+        let redirect = redirect_with(
+            "http://127.0.0.1:1234/callback?x=1",
+            vec![("code", "abc".to_string())],
+            Some("s 1"),
+            "http://127.0.0.1:8000",
+        );
+        assert_eq!(
+            redirect,
+            "http://127.0.0.1:1234/callback?x=1&code=abc&state=s+1&iss=http%3A%2F%2F127.0.0.1%3A8000"
+        );
+        assert!(is_valid_authority("127.0.0.1:8000"));
+        assert!(!is_valid_authority("evil\"host"));
+    }
+
+    #[test]
+    fn test_trusts_only_loopback_or_configured_authority() {
+        assert!(is_trusted_authority("127.0.0.1:8000", "127.0.0.1:8000"));
+        assert!(is_trusted_authority("localhost:8000", "127.0.0.1:8000"));
+        assert!(is_trusted_authority("[::1]:8000", "127.0.0.1:8000"));
+        assert!(is_trusted_authority("router.lan:8000", "router.lan:8000"));
+        assert!(!is_trusted_authority(
+            "evil.example.com:8000",
+            "127.0.0.1:8000"
+        ));
+        assert!(!is_trusted_authority("127.0.0.1:9000", "127.0.0.1:8000"));
+        assert!(!is_trusted_authority("127.0.0.1", "127.0.0.1:8000"));
+        assert!(!is_trusted_authority(
+            "user@127.0.0.1:8000",
+            "127.0.0.1:8000"
+        ));
+    }
+
+    #[test]
+    fn test_limits_rate_within_window() {
+        let mut events = VecDeque::new();
+        assert!(within_rate_limit(&mut events, 2, Duration::from_secs(60)));
+        assert!(within_rate_limit(&mut events, 2, Duration::from_secs(60)));
+        assert!(!within_rate_limit(&mut events, 2, Duration::from_secs(60)));
+        assert_eq!(events.len(), 2);
+        assert!(within_rate_limit(&mut events, 2, Duration::ZERO));
+        assert_eq!(events.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_token_rejects_pkce_mismatch_and_code_reuse() {
+        let (server, token_id) = test_server().await;
+
+        let code = insert_code(&server, &token_id, "verifier");
+        let (status, body) = call_token(&server, code_request(&code, REDIRECT_URI, "wrong")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error_description"], "PKCE verification failed");
+        // 校验失败的授权码同样作废
+        let (status, body) =
+            call_token(&server, code_request(&code, REDIRECT_URI, "verifier")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid_grant");
+
+        let code = insert_code(&server, &token_id, "verifier");
+        let (status, body) =
+            call_token(&server, code_request(&code, REDIRECT_URI, "verifier")).await;
+        assert_eq!(status, StatusCode::OK);
+        let access_token = body["access_token"].as_str().unwrap();
+        assert_eq!(
+            server.validate_access_token(access_token).await.unwrap(),
+            token_id
+        );
+        let (status, body) =
+            call_token(&server, code_request(&code, REDIRECT_URI, "verifier")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body["error_description"],
+            "Invalid or expired authorization code"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_token_rejects_expired_code_and_redirect_mismatch() {
+        let (server, token_id) = test_server().await;
+
+        let code = insert_code(&server, &token_id, "verifier");
+        lock(&server.codes).get_mut(&code).unwrap().created = Instant::now()
+            .checked_sub(AUTHORIZATION_CODE_TTL + Duration::from_secs(1))
+            .unwrap();
+        let (status, body) =
+            call_token(&server, code_request(&code, REDIRECT_URI, "verifier")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid_grant");
+
+        let code = insert_code(&server, &token_id, "verifier");
+        let (status, body) = call_token(
+            &server,
+            code_request(&code, "http://127.0.0.1:1234/other", "verifier"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid_grant");
+
+        // 授权请求携带了 redirect_uri 时换取令牌必须同样携带
+        let code = insert_code(&server, &token_id, "verifier");
+        let mut request = code_request(&code, REDIRECT_URI, "verifier");
+        request.redirect_uri = None;
+        let (status, body) = call_token(&server, request).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid_grant");
+
+        let response = authorize(
+            State(server.clone()),
+            HeaderMap::new(),
+            Query(authorize_params("http://127.0.0.1:1234/other")),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(server.pending_requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_token_accepts_omitted_redirect_uri() {
+        let (server, token_id) = test_server().await;
+        let mut params = authorize_params(REDIRECT_URI);
+        params.redirect_uri = None;
+        let response = authorize(State(server.clone()), HeaderMap::new(), Query(params)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let request_id = server.pending_requests()[0].id.clone();
+        server
+            .respond(&request_id, true, Some(token_id))
+            .await
+            .unwrap();
+
+        let redirect = lock(&server.consents)[&request_id]
+            .redirect
+            .clone()
+            .unwrap();
+        let code = Url::parse(&redirect)
+            .unwrap()
+            .query_pairs()
+            .find(|(key, _)| key == "code")
+            .map(|(_, value)| value.into_owned())
+            .unwrap();
+        let mut request = code_request(&code, REDIRECT_URI, "verifier");
+        request.redirect_uri = None;
+        let (status, body) = call_token(&server, request).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["access_token"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rotation_revokes_grant_on_reuse() {
+        let (server, token_id) = test_server().await;
+        let code = insert_code(&server, &token_id, "verifier");
+        let (_, first) = call_token(&server, code_request(&code, REDIRECT_URI, "verifier")).await;
+        let first_refresh = first["refresh_token"].as_str().unwrap();
+
+        let (status, second) = call_token(&server, refresh_request(first_refresh)).await;
+        assert_eq!(status, StatusCode::OK);
+        let second_access = second["access_token"].as_str().unwrap();
+        let second_refresh = second["refresh_token"].as_str().unwrap();
+        assert!(server
+            .validate_access_token(first["access_token"].as_str().unwrap())
+            .await
+            .is_err());
+        assert!(server.validate_access_token(second_access).await.is_ok());
+
+        // 旧的刷新令牌再次出现：撤销授权，新签发的令牌一并失效
+        let (status, body) = call_token(&server, refresh_request(first_refresh)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "invalid_grant");
+        assert!(server.validate_access_token(second_access).await.is_err());
+        let (status, _) = call_token(&server, refresh_request(second_refresh)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(server.list_grants().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_status_requires_consent_cookie() {
+        let (server, token_id) = test_server().await;
+        let response = authorize(
+            State(server.clone()),
+            HeaderMap::new(),
+            Query(authorize_params(REDIRECT_URI)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let set_cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+        assert!(set_cookie.contains("HttpOnly") && set_cookie.contains("SameSite=Strict"));
+        let cookie = set_cookie.split(';').next().unwrap().to_string();
+        let request_id = server.pending_requests()[0].id.clone();
+
+        let status = |cookie: Option<&str>| {
+            let mut headers = HeaderMap::new();
+            if let Some(cookie) = cookie {
+                headers.insert(header::COOKIE, cookie.parse().unwrap());
+            }
+            authorize_status(
+                State(server.clone()),
+                headers,
+                Query(StatusParams {
+                    request_id: request_id.clone(),
+                }),
+            )
+        };
+        let (code, body) = read_json(status(Some(&cookie)).await).await;
+        assert_eq!(
+            (code, body["status"].as_str()),
+            (StatusCode::OK, Some("pending"))
+        );
+
+        server
+            .respond(&request_id, true, Some(token_id))
+            .await
+            .unwrap();
+        let forged = format!("{}{}=forged", CONSENT_COOKIE_PREFIX, request_id);
+        for cookie in [None, Some(forged.as_str())] {
+            let (code, body) = read_json(status(cookie).await).await;
+            assert_eq!(code, StatusCode::NOT_FOUND);
+            assert!(body.get("redirect").is_none());
+        }
+        let (code, body) = read_json(status(Some(&cookie)).await).await;
+        assert_eq!(code, StatusCode::OK);
+        let redirect = Url::parse(body["redirect"].as_str().unwrap()).unwrap();
+        assert!(redirect.query_pairs().any(|(key, _)| key == "code"));
+        assert!(redirect
+            .query_pairs()
+            .any(|(key, value)| key == "state" && value == "s1"));
+    }
+
+    #[tokio::test]
+    async fn test_caps_pending_consents_and_registrations() {
+        let (server, _) = test_server().await;
+        for _ in 0..MAX_PENDING_CONSENTS {
+            let response = authorize(
+                State(server.clone()),
+                HeaderMap::new(),
+                Query(authorize_params(REDIRECT_URI)),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = authorize(
+            State(server.clone()),
+            HeaderMap::new(),
+            Query(authorize_params(REDIRECT_URI)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        let registration = || RegistrationRequest {
+            redirect_uris: vec![REDIRECT_URI.to_string()],
+            client_name: Some("Client".to_string()),
+            token_endpoint_auth_method: None,
+            grant_types: Vec::new(),
+            response_types: Vec::new(),
+        };
+        for _ in 0..MAX_REGISTRATIONS_PER_WINDOW {
+            let response = register(State(server.clone()), Json(registration())).await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+        let response = register(State(server.clone()), Json(registration())).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+}
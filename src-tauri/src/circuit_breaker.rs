@@ -0,0 +1,353 @@
+// 每个下游服务的熔断器
+
+use crate::error::McpError;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// 统计窗口内保留的最近调用数
+const WINDOW_SIZE: usize = 20;
+/// 窗口内至少有这么多调用才会评估失败率
+const MIN_CALLS: usize = 5;
+/// 失败率阈值（含慢调用），超过后熔断
+const FAILURE_RATE_THRESHOLD: f64 = 0.5;
+/// 耗时超过服务请求超时的这一比例视为慢调用，计入失败
+const SLOW_CALL_RATIO: f64 = 0.8;
+/// 熔断打开后等待多久进入半开状态
+const OPEN_DURATION: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl std::fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CircuitState::Closed => write!(f, "closed"),
+            CircuitState::Open => write!(f, "open"),
+            CircuitState::HalfOpen => write!(f, "half_open"),
+        }
+    }
+}
+
+/// 熔断器状态快照（用于前端展示）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerSnapshot {
+    pub state: CircuitState,
+    pub failure_rate: f64,
+    pub recent_calls: usize,
+    /// 熔断打开时距离进入半开状态的剩余秒数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_seconds: Option<u64>,
+}
+
+/// 熔断器放行调用时发放的许可，记录结果时交回
+///
+/// 只有半开状态下放行的试探请求携带试探编号，其余调用的结果不会决定半开状态的去向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallPermit {
+    probe: Option<u64>,
+}
+
+/// 熔断器：由失败率和调用延迟驱动的 closed / open / half-open 状态机
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    state: CircuitState,
+    /// 最近调用结果，true 表示失败（含慢调用）
+    window: VecDeque<bool>,
+    opened_at: Option<Instant>,
+    /// 半开状态下试探请求的开始时间（超时未返回则允许新的试探）
+    probe_started: Option<Instant>,
+    /// 当前试探请求的编号，超时后放行的新试探会使旧试探的结果失效
+    probe_id: u64,
+    /// 慢调用阈值，由服务的请求超时推导
+    slow_call_threshold: Duration,
+}
+
+impl CircuitBreaker {
+    /// 按服务的请求超时创建熔断器
+    pub fn new(request_timeout: Duration) -> Self {
+        Self {
+            state: CircuitState::Closed,
+            window: VecDeque::with_capacity(WINDOW_SIZE),
+            opened_at: None,
+            probe_started: None,
+            probe_id: 0,
+            slow_call_threshold: request_timeout.mul_f64(SLOW_CALL_RATIO),
+        }
+    }
+
+    /// 服务的请求超时变更后更新慢调用阈值
+    pub fn set_request_timeout(&mut self, request_timeout: Duration) {
+        self.slow_call_threshold = request_timeout.mul_f64(SLOW_CALL_RATIO);
+    }
+
+    /// 请求调用许可，熔断打开时返回剩余等待时间
+    pub fn try_acquire(&mut self) -> std::result::Result<CallPermit, Duration> {
+        match self.state {
+            CircuitState::Closed => Ok(CallPermit { probe: None }),
+            CircuitState::Open => {
+                let elapsed = self.opened_at.map(|t| t.elapsed()).unwrap_or(OPEN_DURATION);
+                if elapsed >= OPEN_DURATION {
+                    // 进入半开状态，放行一个试探请求
+                    self.state = CircuitState::HalfOpen;
+                    Ok(self.start_probe())
+                } else {
+                    Err(OPEN_DURATION - elapsed)
+                }
+            }
+            CircuitState::HalfOpen => match self.probe_started {
+                Some(started) if started.elapsed() < OPEN_DURATION => Err(Duration::from_secs(1)),
+                _ => Ok(self.start_probe()),
+            },
+        }
+    }
+
+    /// 记录一次调用结果，`failed` 只应包含下游故障（见 [`is_failure`]）
+    pub fn record(&mut self, permit: CallPermit, failed: bool, latency: Duration) {
+        let failed = failed || latency >= self.slow_call_threshold;
+
+        match self.state {
+            CircuitState::HalfOpen => {
+                // 熔断前放行的调用或已被替换的旧试探，结果不影响半开状态
+                if permit.probe != Some(self.probe_id) {
+                    return;
+                }
+                self.probe_started = None;
+                if failed {
+                    self.trip();
+                } else {
+                    self.reset();
+                }
+            }
+            CircuitState::Closed => {
+                if self.window.len() >= WINDOW_SIZE {
+                    self.window.pop_front();
+                }
+                self.window.push_back(failed);
+
+                if self.window.len() >= MIN_CALLS && self.failure_rate() >= FAILURE_RATE_THRESHOLD {
+                    self.trip();
+                }
+            }
+            // 熔断打开前已放行的请求，结果不再影响状态
+            CircuitState::Open => {}
+        }
+    }
+
+    /// 重置为关闭状态（例如服务重连成功后）
+    pub fn reset(&mut self) {
+        self.state = CircuitState::Closed;
+        self.window.clear();
+        self.opened_at = None;
+        self.probe_started = None;
+    }
+
+    pub fn snapshot(&self) -> CircuitBreakerSnapshot {
+        let retry_after_seconds = match self.state {
+            CircuitState::Open => self
+                .opened_at
+                .map(|t| OPEN_DURATION.saturating_sub(t.elapsed()).as_secs()),
+            _ => None,
+        };
+        CircuitBreakerSnapshot {
+            state: self.state,
+            failure_rate: self.failure_rate(),
+            recent_calls: self.window.len(),
+            retry_after_seconds,
+        }
+    }
+
+    fn start_probe(&mut self) -> CallPermit {
+        self.probe_id += 1;
+        self.probe_started = Some(Instant::now());
+        CallPermit {
+            probe: Some(self.probe_id),
+        }
+    }
+
+    fn failure_rate(&self) -> f64 {
+        if self.window.is_empty() {
+            return 0.0;
+        }
+        let failures = self.window.iter().filter(|failed| **failed).count();
+        failures as f64 / self.window.len() as f64
+    }
+
+    fn trip(&mut self) {
+        tracing::warn!(
+            "Circuit breaker opened (failure rate {:.0}%)",
+            self.failure_rate() * 100.0
+        );
+        self.state = CircuitState::Open;
+        self.opened_at = Some(Instant::now());
+        self.probe_started = None;
+    }
+}
+
+/// 调用错误是否属于下游故障（传输错误、超时、服务端 5xx / 内部错误）
+///
+/// 参数错误、未知工具等由调用方引起的错误不计入失败率，
+/// 否则一个客户端的错误请求会让所有客户端都被熔断
+pub fn is_failure(error: &McpError) -> bool {
+    match error {
+        McpError::ConnectionError(_) | McpError::TimeoutError(_) | McpError::ServiceError(_) => {
+            true
+        }
+        McpError::HttpStatusError(status, _) => *status >= 500,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAST: Duration = Duration::from_millis(10);
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    fn open_breaker() -> CircuitBreaker {
+        let mut breaker = CircuitBreaker::new(TIMEOUT);
+        for _ in 0..MIN_CALLS {
+            let permit = breaker.try_acquire().unwrap();
+            breaker.record(permit, true, FAST);
+        }
+        assert_eq!(breaker.snapshot().state, CircuitState::Open);
+        breaker
+    }
+
+    fn expire_open_duration(breaker: &mut CircuitBreaker) {
+        breaker.opened_at = Some(Instant::now() - OPEN_DURATION);
+    }
+
+    #[test]
+    fn test_opens_when_failure_rate_exceeded() {
+        let mut breaker = CircuitBreaker::new(TIMEOUT);
+        for _ in 0..MIN_CALLS - 1 {
+            let permit = breaker.try_acquire().unwrap();
+            breaker.record(permit, true, FAST);
+        }
+        // 调用数不足时不评估失败率
+        assert_eq!(breaker.snapshot().state, CircuitState::Closed);
+
+        let permit = breaker.try_acquire().unwrap();
+        breaker.record(permit, true, FAST);
+        assert_eq!(breaker.snapshot().state, CircuitState::Open);
+        assert!(breaker.try_acquire().is_err());
+    }
+
+    #[test]
+    fn test_slow_calls_count_as_failures() {
+        let mut breaker = CircuitBreaker::new(TIMEOUT);
+        for _ in 0..MIN_CALLS {
+            let permit = breaker.try_acquire().unwrap();
+            breaker.record(permit, false, TIMEOUT.mul_f64(SLOW_CALL_RATIO));
+        }
+        assert_eq!(breaker.snapshot().state, CircuitState::Open);
+    }
+
+    #[test]
+    fn test_slow_calls_under_timeout_keep_closed() {
+        // 请求超时较长的服务，正常的慢调用不应触发熔断
+        let mut breaker = CircuitBreaker::new(Duration::from_secs(300));
+        for _ in 0..WINDOW_SIZE {
+            let permit = breaker.try_acquire().unwrap();
+            breaker.record(permit, false, Duration::from_secs(45));
+        }
+        let snapshot = breaker.snapshot();
+        assert_eq!(snapshot.state, CircuitState::Closed);
+        assert_eq!(snapshot.failure_rate, 0.0);
+
+        // 缩短超时后同样的耗时计为慢调用
+        breaker.set_request_timeout(Duration::from_secs(30));
+        while let Ok(permit) = breaker.try_acquire() {
+            breaker.record(permit, false, Duration::from_secs(45));
+        }
+        assert_eq!(breaker.snapshot().state, CircuitState::Open);
+    }
+
+    #[test]
+    fn test_successful_probe_closes() {
+        let mut breaker = open_breaker();
+        expire_open_duration(&mut breaker);
+
+        let probe = breaker.try_acquire().unwrap();
+        assert_eq!(breaker.snapshot().state, CircuitState::HalfOpen);
+        // 半开状态下只放行一个试探请求
+        assert!(breaker.try_acquire().is_err());
+
+        breaker.record(probe, false, FAST);
+        let snapshot = breaker.snapshot();
+        assert_eq!(snapshot.state, CircuitState::Closed);
+        assert_eq!(snapshot.recent_calls, 0);
+    }
+
+    #[test]
+    fn test_failed_probe_reopens() {
+        let mut breaker = open_breaker();
+        expire_open_duration(&mut breaker);
+
+        let probe = breaker.try_acquire().unwrap();
+        breaker.record(probe, true, FAST);
+        assert_eq!(breaker.snapshot().state, CircuitState::Open);
+        assert!(breaker.try_acquire().is_err());
+    }
+
+    #[test]
+    fn test_calls_admitted_while_closed_do_not_decide_probe() {
+        let mut breaker = CircuitBreaker::new(TIMEOUT);
+        let late = breaker.try_acquire().unwrap();
+        for _ in 0..MIN_CALLS {
+            let permit = breaker.try_acquire().unwrap();
+            breaker.record(permit, true, FAST);
+        }
+        expire_open_duration(&mut breaker);
+        let probe = breaker.try_acquire().unwrap();
+
+        // 熔断前放行的调用此时才返回，不能当作试探结果
+        breaker.record(late, false, FAST);
+        assert_eq!(breaker.snapshot().state, CircuitState::HalfOpen);
+
+        breaker.record(probe, true, FAST);
+        assert_eq!(breaker.snapshot().state, CircuitState::Open);
+    }
+
+    #[test]
+    fn test_stale_probe_is_ignored() {
+        let mut breaker = open_breaker();
+        expire_open_duration(&mut breaker);
+        let stale = breaker.try_acquire().unwrap();
+
+        // 试探请求超时未返回，放行新的试探
+        breaker.probe_started = Some(Instant::now() - OPEN_DURATION);
+        let probe = breaker.try_acquire().unwrap();
+
+        breaker.record(stale, false, FAST);
+        assert_eq!(breaker.snapshot().state, CircuitState::HalfOpen);
+        breaker.record(probe, false, FAST);
+        assert_eq!(breaker.snapshot().state, CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_is_failure_ignores_caller_errors() {
+        assert!(is_failure(&McpError::ConnectionError("closed".into())));
+        assert!(is_failure(&McpError::TimeoutError("slow".into())));
+        assert!(is_failure(&McpError::ServiceError("internal".into())));
+        assert!(is_failure(&McpError::HttpStatusError(
+            502,
+            "bad gateway".into()
+        )));
+        assert!(!is_failure(&McpError::HttpStatusError(
+            400,
+            "bad request".into()
+        )));
+        assert!(!is_failure(&McpError::ProtocolError(
+            "invalid params".into()
+        )));
+        assert!(!is_failure(&McpError::ServiceNotFound("demo".into())));
+    }
+}
@@ -0,0 +1,340 @@
+// 生成指向 mcprouter 聚合接口的客户端配置，并可写入客户端配置文件
+
+use crate::client_import::{self, McpClient};
+use crate::error::{McpError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+/// 默认写入客户端配置中的服务名
+pub const DEFAULT_SERVER_NAME: &str = "mcprouter";
+
+/// stdio 桥接传递 Authorization 头的环境变量（避免参数中出现空格）
+const BRIDGE_AUTH_ENV: &str = "MCPROUTER_AUTH_HEADER";
+
+/// 配置导出的目标客户端
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClientTarget {
+    ClaudeDesktop,
+    Cursor,
+    VsCode,
+    Continue,
+    /// 通用 Streamable HTTP 客户端
+    StreamableHttp,
+    /// 只支持 stdio 的客户端，通过 mcp-remote 桥接到 HTTP
+    StdioBridge,
+}
+
+impl ClientTarget {
+    /// 配置写入的默认文件，通用格式没有固定位置
+    pub fn default_config_path(self, server_name: &str) -> Option<PathBuf> {
+        let client = match self {
+            Self::ClaudeDesktop => McpClient::ClaudeDesktop,
+            Self::Cursor => McpClient::Cursor,
+            Self::VsCode => McpClient::VsCode,
+            // Continue 读取 ~/.continue/mcpServers 下的独立配置文件
+            Self::Continue => {
+                return dirs::home_dir().map(|home| {
+                    home.join(".continue")
+                        .join("mcpServers")
+                        .join(format!("{}.json", file_stem(server_name)))
+                })
+            }
+            Self::StreamableHttp | Self::StdioBridge => return None,
+        };
+        client_import::known_config_paths()
+            .into_iter()
+            .find(|(c, _)| *c == client)
+            .map(|(_, path)| path)
+    }
+}
+
+/// 服务名对应的文件名（不含扩展名），经过编码，服务名中的路径分隔符不会写到目录之外
+fn file_stem(server_name: &str) -> String {
+    match server_name.trim() {
+        "" => DEFAULT_SERVER_NAME.to_string(),
+        name => crate::utils::encode_name(name),
+    }
+}
+
+/// 生成的配置片段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientConfigSnippet {
+    pub client: ClientTarget,
+    pub endpoint: String,
+    pub snippet: Value,
+    /// 格式化后的 JSON 文本，可直接复制
+    pub text: String,
+    /// 默认写入的配置文件
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+/// 写入配置文件的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WriteClientConfigResult {
+    pub path: String,
+    /// 原文件的备份，文件原本不存在时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup_path: Option<String>,
+}
+
+/// 聚合接口地址，监听所有地址时客户端使用本机回环地址连接
+pub fn router_endpoint(host: &str, port: u16) -> String {
+    let host = match host.trim() {
+        "" | "0.0.0.0" => "127.0.0.1".to_string(),
+        "::" | "[::]" => "[::1]".to_string(),
+        h if h.contains(':') && !h.starts_with('[') => format!("[{}]", h),
+        h => h.to_string(),
+    };
+    format!("http://{}:{}/mcp", host, port)
+}
+
+/// 生成目标客户端的配置片段
+pub fn build_snippet(
+    client: ClientTarget,
+    server_name: &str,
+    endpoint: &str,
+    token: Option<&str>,
+) -> Value {
+    let headers = token.map(|t| json!({ "Authorization": format!("Bearer {}", t) }));
+    let mut entry = Map::new();
+    match client {
+        // Claude Desktop 的配置文件只支持 stdio 服务
+        ClientTarget::ClaudeDesktop | ClientTarget::StdioBridge => {
+            let mut args = vec![json!("-y"), json!("mcp-remote"), json!(endpoint)];
+            if let Some(token) = token {
+                args.push(json!("--header"));
+                args.push(json!(format!("Authorization:${{{}}}", BRIDGE_AUTH_ENV)));
+                entry.insert(
+                    "env".to_string(),
+                    json!({ BRIDGE_AUTH_ENV: format!("Bearer {}", token) }),
+                );
+            }
+            entry.insert("command".to_string(), json!("npx"));
+            entry.insert("args".to_string(), Value::Array(args));
+        }
+        ClientTarget::Cursor => {
+            entry.insert("url".to_string(), json!(endpoint));
+        }
+        ClientTarget::VsCode | ClientTarget::StreamableHttp => {
+            entry.insert("type".to_string(), json!("http"));
+            entry.insert("url".to_string(), json!(endpoint));
+        }
+        ClientTarget::Continue => {
+            entry.insert("type".to_string(), json!("streamable-http"));
+            entry.insert("url".to_string(), json!(endpoint));
+        }
+    }
+    if let Some(headers) = headers.filter(|_| !entry.contains_key("command")) {
+        entry.insert("headers".to_string(), headers);
+    }
+
+    let key = servers_key(client);
+    json!({ key: { server_name: Value::Object(entry) } })
+}
+
+/// 服务表所在的顶层字段
+fn servers_key(client: ClientTarget) -> &'static str {
+    match client {
+        ClientTarget::VsCode => "servers",
+        _ => "mcpServers",
+    }
+}
+
+/// 将片段合并到已有配置中，只替换同名服务，保留其他字段
+pub fn merge_snippet(existing: Value, snippet: &Value) -> Result<Value> {
+    let mut existing = match existing {
+        Value::Object(map) => map,
+        Value::Null => Map::new(),
+        _ => {
+            return Err(McpError::InvalidConfiguration(
+                "Client config is not a JSON object".to_string(),
+            ))
+        }
+    };
+    for (key, servers) in snippet.as_object().into_iter().flatten() {
+        let target = existing
+            .entry(key.clone())
+            .or_insert_with(|| Value::Object(Map::new()));
+        let Value::Object(target) = target else {
+            return Err(McpError::InvalidConfiguration(format!(
+                "'{}' in client config is not an object",
+                key
+            )));
+        };
+        for (name, entry) in servers.as_object().into_iter().flatten() {
+            target.insert(name.clone(), entry.clone());
+        }
+    }
+    Ok(Value::Object(existing))
+}
+
+/// 合并写入客户端配置文件，写入前备份原文件
+///
+/// 原文件中的注释（JSONC）不会保留，备份文件中仍然保留原始内容。
+pub fn write_snippet(path: &Path, snippet: &Value) -> Result<Option<PathBuf>> {
+    let (value, original) = match std::fs::read_to_string(path) {
+        Ok(content) if content.trim().is_empty() => (Value::Null, Some(content)),
+        Ok(content) => {
+            let value =
+                serde_json::from_str(&client_import::strip_jsonc(&content)).map_err(|e| {
+                    McpError::InvalidConfiguration(format!("Invalid client config: {}", e))
+                })?;
+            (value, Some(content))
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (Value::Null, None),
+        Err(e) => {
+            return Err(McpError::ConfigError(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            )))
+        }
+    };
+    let merged = merge_snippet(value, snippet)?;
+
+    let backup = match original {
+        Some(content) => {
+            let timestamp = chrono::Local::now().format("%Y%m%d%H%M%S");
+            let mut backup = path.as_os_str().to_owned();
+            backup.push(format!(".{}.bak", timestamp));
+            let backup = PathBuf::from(backup);
+            write_private(&backup, content.as_bytes()).map_err(|e| {
+                McpError::ConfigError(format!("Failed to back up client config: {}", e))
+            })?;
+            Some(backup)
+        }
+        None => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| {
+                    McpError::ConfigError(format!("Failed to create config directory: {}", e))
+                })?;
+            }
+            None
+        }
+    };
+
+    let text = serde_json::to_string_pretty(&merged)
+        .map_err(|e| McpError::SerializationError(e.to_string()))?;
+    write_private(path, (text + "\n").as_bytes())
+        .map_err(|e| McpError::ConfigError(format!("Failed to write {}: {}", path.display(), e)))?;
+    tracing::info!("📝 Wrote mcprouter entry to {}", path.display());
+    Ok(backup)
+}
+
+/// 原子写入只有当前用户可读写的文件（配置中可能包含 Token）：
+/// 先在同一目录写入 0600 的临时文件并刷盘，再重命名覆盖目标，中途失败不会留下半个文件
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    // 上次中断留下的临时文件
+    if let Err(e) = std::fs::remove_file(&temp_path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            return Err(e);
+        }
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let result = options.open(&temp_path).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()?;
+        std::fs::rename(&temp_path, path)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_snippet() {
+        let endpoint = router_endpoint("0.0.0.0", 8850);
+        assert_eq!(endpoint, "http://127.0.0.1:8850/mcp");
+
+        let vscode = build_snippet(ClientTarget::VsCode, "mcprouter", &endpoint, Some("tok"));
+        assert_eq!(vscode["servers"]["mcprouter"]["type"], "http");
+        assert_eq!(
+            vscode["servers"]["mcprouter"]["headers"]["Authorization"],
+            "Bearer tok"
+        );
+
+        let bridge = build_snippet(
+            ClientTarget::ClaudeDesktop,
+            "mcprouter",
+            &endpoint,
+            Some("tok"),
+        );
+        let entry = &bridge["mcpServers"]["mcprouter"];
+        assert_eq!(entry["command"], "npx");
+        assert_eq!(entry["args"][2], endpoint);
+        assert_eq!(entry["env"][BRIDGE_AUTH_ENV], "Bearer tok");
+        assert!(entry.get("headers").is_none());
+
+        let cursor = build_snippet(ClientTarget::Cursor, "mcprouter", &endpoint, None);
+        assert!(cursor["mcpServers"]["mcprouter"].get("headers").is_none());
+    }
+
+    #[test]
+    fn test_merge_snippet_keeps_other_entries() {
+        let existing = json!({
+            "theme": "dark",
+            "mcpServers": { "other": { "command": "x" }, "mcprouter": { "url": "old" } }
+        });
+        let snippet = build_snippet(ClientTarget::Cursor, "mcprouter", "http://h/mcp", None);
+        let merged = merge_snippet(existing, &snippet).unwrap();
+        assert_eq!(merged["theme"], "dark");
+        assert_eq!(merged["mcpServers"]["other"]["command"], "x");
+        assert_eq!(merged["mcpServers"]["mcprouter"]["url"], "http://h/mcp");
+
+        assert!(merge_snippet(json!([]), &snippet).is_err());
+    }
+
+    #[test]
+    fn test_continue_file_name_is_sanitised() {
+        assert_eq!(file_stem("mcprouter"), "mcprouter");
+        assert_eq!(file_stem("../../.bashrc"), "_2e_2e_2f_2e_2e_2f_2ebashrc");
+        assert_eq!(file_stem("a\\b"), "a_5cb");
+        assert_eq!(file_stem("  "), DEFAULT_SERVER_NAME);
+        let path = ClientTarget::Continue.default_config_path("../x").unwrap();
+        assert_eq!(path.parent().unwrap().file_name().unwrap(), "mcpServers");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_snippet_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("mcprouter-export-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("mcp.json");
+        std::fs::write(&path, "{}").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let snippet = build_snippet(ClientTarget::Cursor, "mcprouter", "http://h/mcp", Some("t"));
+        let backup = write_snippet(&path, &snippet).unwrap().unwrap();
+        let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), 0o600);
+        assert_eq!(mode(&backup), 0o600);
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .contains("mcprouter"));
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
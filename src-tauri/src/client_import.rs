@@ -0,0 +1,738 @@
+// 从 Claude Desktop、Cursor、VS Code 等客户端的配置文件中发现并导入 MCP 服务
+
+use crate::error::{McpError, Result};
+use crate::types::{McpServerConfig, ServiceTransport};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// 配置文件所属的 MCP 客户端
+#[derive(
+    Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum McpClient {
+    ClaudeDesktop,
+    Cursor,
+    VsCode,
+    Windsurf,
+    Zed,
+    Continue,
+    /// 粘贴的配置或无法识别来源的文件
+    Other,
+}
+
+/// VS Code `inputs` 中声明的输入项，服务配置通过 `${input:<id>}` 引用
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClientInput {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub password: bool,
+}
+
+/// 与已有服务重复的原因
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateReason {
+    /// 同名服务已存在
+    Name,
+    /// 已有服务的命令或 URL 相同
+    Definition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Duplicate {
+    pub reason: DuplicateReason,
+    /// 已有服务的名称
+    pub existing: String,
+}
+
+/// 待导入的服务
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportCandidate {
+    pub name: String,
+    pub config: McpServerConfig,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate: Option<Duplicate>,
+    /// 配置中引用的 `${input:<id>}`，导入前需要提供取值
+    pub required_inputs: Vec<String>,
+    /// 无法映射的字段等提示
+    pub warnings: Vec<String>,
+    /// 无法导入的原因（例如沙箱配置无效），此时 config 只有名称
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 一个客户端配置文件的导入预览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientConfigPreview {
+    pub client: McpClient,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub servers: Vec<ImportCandidate>,
+    pub inputs: Vec<ClientInput>,
+    /// 文件无法读取或解析时的错误
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 单个服务的导入结果
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStatus {
+    Added,
+    Replaced,
+    /// 名称冲突，以新名称导入
+    Renamed,
+    Skipped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportResult {
+    pub name: String,
+    pub status: ImportStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// 已知客户端配置文件位置（Linux 下为 `~/.config`，其他平台使用对应的配置目录）
+pub fn known_config_paths() -> Vec<(McpClient, PathBuf)> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    let config = dirs::config_dir().unwrap_or_else(|| home.join(".config"));
+
+    let mut paths = vec![
+        (
+            McpClient::ClaudeDesktop,
+            config.join("Claude").join("claude_desktop_config.json"),
+        ),
+        (McpClient::Cursor, home.join(".cursor").join("mcp.json")),
+    ];
+    for app in ["Code", "Code - Insiders", "VSCodium"] {
+        paths.push((
+            McpClient::VsCode,
+            config.join(app).join("User").join("mcp.json"),
+        ));
+    }
+    paths.push((
+        McpClient::Windsurf,
+        home.join(".codeium")
+            .join("windsurf")
+            .join("mcp_config.json"),
+    ));
+    // Zed 在 Linux 和 macOS 上都使用 ~/.config/zed
+    let zed_dir = if cfg!(windows) {
+        config.join("Zed")
+    } else {
+        home.join(".config").join("zed")
+    };
+    paths.push((McpClient::Zed, zed_dir.join("settings.json")));
+
+    let continue_dir = home.join(".continue");
+    paths.push((McpClient::Continue, continue_dir.join("config.json")));
+    // Continue 也支持在 mcpServers 目录中放置独立的配置文件
+    let mut server_files: Vec<PathBuf> = std::fs::read_dir(continue_dir.join("mcpServers"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    server_files.sort();
+    paths.extend(
+        server_files
+            .into_iter()
+            .map(|path| (McpClient::Continue, path)),
+    );
+    paths
+}
+
+/// 读取所有存在的客户端配置文件并生成预览
+pub fn discover() -> Vec<ClientConfigPreview> {
+    known_config_paths()
+        .into_iter()
+        .filter(|(_, path)| path.is_file())
+        .map(|(client, path)| {
+            let result = std::fs::read_to_string(&path)
+                .map_err(|e| McpError::ConfigError(format!("Failed to read file: {}", e)))
+                .and_then(|content| parse_content(&content));
+            let path = Some(path.display().to_string());
+            match result {
+                Ok((servers, inputs)) => ClientConfigPreview {
+                    client,
+                    path,
+                    servers,
+                    inputs,
+                    error: None,
+                },
+                Err(e) => ClientConfigPreview {
+                    client,
+                    path,
+                    servers: Vec::new(),
+                    inputs: Vec::new(),
+                    error: Some(e.to_string()),
+                },
+            }
+        })
+        .collect()
+}
+
+/// 解析配置文件内容（允许 JSONC 注释和尾随逗号）
+pub fn parse_content(content: &str) -> Result<(Vec<ImportCandidate>, Vec<ClientInput>)> {
+    let value: Value = serde_json::from_str(&strip_jsonc(content))
+        .map_err(|e| McpError::InvalidConfiguration(format!("Invalid JSON: {}", e)))?;
+    parse_config(&value)
+}
+
+/// 解析客户端配置，自动识别 `mcpServers`、`servers`（VS Code）、`context_servers`（Zed）
+/// 和 `experimental.modelContextProtocolServers`（Continue）
+pub fn parse_config(value: &Value) -> Result<(Vec<ImportCandidate>, Vec<ClientInput>)> {
+    let entries: Vec<(String, &Value)> = if let Some(servers) = value.get("mcpServers") {
+        named_entries(servers)
+    } else if let Some(servers) = value.get("servers") {
+        named_entries(servers)
+    } else if let Some(servers) = value.get("context_servers") {
+        named_entries(servers)
+    } else if let Some(servers) = value
+        .get("experimental")
+        .and_then(|v| v.get("modelContextProtocolServers"))
+    {
+        named_entries(servers)
+    } else {
+        return Err(McpError::InvalidConfiguration(
+            "No MCP servers found. Expected 'mcpServers', 'servers', 'context_servers' or \
+             'experimental.modelContextProtocolServers'."
+                .to_string(),
+        ));
+    };
+
+    let inputs = value
+        .get("inputs")
+        .and_then(Value::as_array)
+        .map(|inputs| {
+            inputs
+                .iter()
+                .filter_map(|input| {
+                    Some(ClientInput {
+                        id: input.get("id")?.as_str()?.to_string(),
+                        description: input
+                            .get("description")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                        password: input
+                            .get("password")
+                            .and_then(Value::as_bool)
+                            .unwrap_or(false),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let servers = entries
+        .into_iter()
+        .filter_map(|(name, entry)| {
+            let obj = entry.as_object()?;
+            let candidate = match parse_server(&name, obj) {
+                Ok((config, warnings)) => ImportCandidate {
+                    name,
+                    required_inputs: referenced_inputs(&config),
+                    config,
+                    duplicate: None,
+                    warnings,
+                    error: None,
+                },
+                Err(e) => ImportCandidate {
+                    config: McpServerConfig::new(String::new(), name.clone()),
+                    name,
+                    duplicate: None,
+                    required_inputs: Vec::new(),
+                    warnings: Vec::new(),
+                    error: Some(e.to_string()),
+                },
+            };
+            Some(candidate)
+        })
+        .collect();
+    Ok((servers, inputs))
+}
+
+/// 服务表可以是以名称为键的对象，也可以是带 `name` 字段的数组
+fn named_entries(servers: &Value) -> Vec<(String, &Value)> {
+    match servers {
+        Value::Object(map) => map.iter().map(|(name, v)| (name.clone(), v)).collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, v)| {
+                let name = v
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("server-{}", i + 1));
+                (name, v)
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 字符串表（env / headers），数字和布尔值转换为字符串
+fn string_map(value: Option<&Value>) -> Option<HashMap<String, String>> {
+    let map = value?.as_object()?;
+    Some(
+        map.iter()
+            .filter_map(|(k, v)| {
+                let v = match v {
+                    Value::String(s) => s.clone(),
+                    Value::Number(n) => n.to_string(),
+                    Value::Bool(b) => b.to_string(),
+                    _ => return None,
+                };
+                Some((k.clone(), v))
+            })
+            .collect(),
+    )
+}
+
+fn get_str(obj: &Map<String, Value>, key: &str) -> Option<String> {
+    obj.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// 将单个客户端服务配置映射为 McpServerConfig，返回无法映射的提示
+///
+/// 沙箱配置无效或带有容器配置的服务拒绝导入；凭据助手会在连接时执行命令，不从其他客户端导入。
+pub fn parse_server(
+    name: &str,
+    obj: &Map<String, Value>,
+) -> Result<(McpServerConfig, Vec<String>)> {
+    let mut warnings = Vec::new();
+    let mut obj = obj.clone();
+
+    // Continue（旧格式）：连接参数在 transport 对象中
+    if let Some(Value::Object(transport)) = obj.remove("transport") {
+        for (key, value) in transport {
+            obj.entry(key).or_insert(value);
+        }
+    }
+    // Zed（旧格式）：command 为 { path, args, env }
+    if let Some(Value::Object(command)) = obj.get("command").cloned() {
+        obj.remove("command");
+        if let Some(path) = command.get("path") {
+            obj.insert("command".to_string(), path.clone());
+        }
+        for key in ["args", "env"] {
+            if let Some(value) = command.get(key) {
+                obj.entry(key.to_string()).or_insert(value.clone());
+            }
+        }
+    }
+
+    // Windsurf 使用 serverUrl，Gemini CLI 使用 httpUrl
+    let url = get_str(&obj, "url")
+        .or_else(|| get_str(&obj, "serverUrl"))
+        .or_else(|| get_str(&obj, "httpUrl"));
+    let command = get_str(&obj, "command");
+
+    let transport = match obj.get("type").and_then(Value::as_str) {
+        Some(kind) => match kind.to_lowercase().as_str() {
+            "stdio" => ServiceTransport::Stdio,
+            "http" | "streamable-http" | "streamablehttp" | "streamable_http" => {
+                ServiceTransport::Http
+            }
+            "sse" => {
+                warnings
+                    .push("SSE transport is deprecated, imported as Streamable HTTP".to_string());
+                ServiceTransport::Http
+            }
+            other => {
+                warnings.push(format!("Unknown transport type '{}'", other));
+                if url.is_some() && command.is_none() {
+                    ServiceTransport::Http
+                } else {
+                    ServiceTransport::Stdio
+                }
+            }
+        },
+        None if url.is_some() && command.is_none() => ServiceTransport::Http,
+        None => ServiceTransport::Stdio,
+    };
+    if transport == ServiceTransport::Stdio && command.is_none() {
+        warnings.push("STDIO server has no command".to_string());
+    }
+    if transport == ServiceTransport::Http && url.is_none() {
+        warnings.push("HTTP server has no URL".to_string());
+    }
+    if obj.contains_key("envFile") {
+        warnings.push("'envFile' is not supported, add the variables to env".to_string());
+    }
+    if obj.contains_key("container") {
+        return Err(McpError::InvalidConfiguration(
+            "Container settings are not imported, add this server manually".to_string(),
+        ));
+    }
+    if obj.contains_key("credential_helper") {
+        warnings.push(
+            "'credential_helper' runs a command and is not imported, configure it manually"
+                .to_string(),
+        );
+    }
+    let sandbox = obj
+        .get("sandbox")
+        .filter(|v| !v.is_null())
+        .map(|v| serde_json::from_value(v.clone()))
+        .transpose()
+        .map_err(|e| McpError::InvalidConfiguration(format!("Invalid sandbox: {}", e)))?;
+
+    let args = obj.get("args").and_then(Value::as_array).map(|arr| {
+        arr.iter()
+            .filter_map(|v| match v {
+                Value::String(s) => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+            .collect()
+    });
+    let enabled = !obj
+        .get("disabled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+        && obj.get("enabled").and_then(Value::as_bool).unwrap_or(true);
+
+    let config = McpServerConfig {
+        name: name.to_string(),
+        description: get_str(&obj, "description"),
+        command,
+        args,
+        env: string_map(obj.get("env")),
+        transport,
+        url,
+        headers: string_map(obj.get("headers")),
+        enabled,
+        // 以下为 mcprouter 自身导出的扩展字段
+        request_timeout: obj.get("request_timeout").and_then(Value::as_u64),
+        connect_timeout: obj.get("connect_timeout").and_then(Value::as_u64),
+        max_concurrent_calls: obj
+            .get("max_concurrent_calls")
+            .and_then(Value::as_u64)
+            .map(|v| v as u32),
+        lifecycle: obj
+            .get("lifecycle")
+            .and_then(Value::as_str)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default(),
+        idle_timeout: obj.get("idle_timeout").and_then(Value::as_u64),
+        restart_policy: obj
+            .get("restart_policy")
+            .and_then(Value::as_str)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default(),
+        max_restarts: obj
+            .get("max_restarts")
+            .and_then(Value::as_u64)
+            .map(|v| v as u32),
+        memory_limit_mb: obj.get("memory_limit_mb").and_then(Value::as_u64),
+        sandbox,
+        cwd: get_str(&obj, "cwd"),
+        env_inheritance: obj
+            .get("env_inheritance")
+            .and_then(Value::as_str)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default(),
+        env_allowlist: obj
+            .get("env_allowlist")
+            .and_then(|v| serde_json::from_value(v.clone()).ok()),
+        container: None,
+        runtime: get_str(&obj, "runtime").map(Into::into).unwrap_or_default(),
+        runtime_version: get_str(&obj, "runtime_version"),
+        credential_helper: None,
+    };
+
+    Ok((config, warnings))
+}
+
+/// 配置中可能引用输入项的字符串
+fn config_strings_mut(config: &mut McpServerConfig) -> Vec<&mut String> {
+    let mut strings: Vec<&mut String> = Vec::new();
+    strings.extend(config.command.as_mut());
+    strings.extend(config.url.as_mut());
+    strings.extend(config.cwd.as_mut());
+    strings.extend(config.args.iter_mut().flatten());
+    strings.extend(config.env.iter_mut().flat_map(|env| env.values_mut()));
+    strings.extend(config.headers.iter_mut().flat_map(|h| h.values_mut()));
+    strings
+}
+
+/// 配置中引用的 `${input:<id>}`
+pub fn referenced_inputs(config: &McpServerConfig) -> Vec<String> {
+    let mut config = config.clone();
+    let mut ids: Vec<String> = Vec::new();
+    for value in config_strings_mut(&mut config) {
+        let mut rest = value.as_str();
+        while let Some(start) = rest.find("${input:") {
+            let after = &rest[start + "${input:".len()..];
+            let Some(end) = after.find('}') else { break };
+            let id = after[..end].to_string();
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+            rest = &after[end + 1..];
+        }
+    }
+    ids
+}
+
+/// 用提供的取值替换 `${input:<id>}`
+pub fn apply_inputs(config: &mut McpServerConfig, values: &HashMap<String, String>) {
+    for value in config_strings_mut(config) {
+        for (id, input) in values {
+            let placeholder = format!("${{input:{}}}", id);
+            if value.contains(&placeholder) {
+                *value = value.replace(&placeholder, input);
+            }
+        }
+    }
+}
+
+/// 两个配置是否启动同一个服务（相同的命令和参数，或相同的 URL）
+fn same_definition(a: &McpServerConfig, b: &McpServerConfig) -> bool {
+    if a.transport != b.transport {
+        return false;
+    }
+    match a.transport {
+        ServiceTransport::Stdio => {
+            a.command.is_some()
+                && a.command == b.command
+                && a.args.clone().unwrap_or_default() == b.args.clone().unwrap_or_default()
+        }
+        ServiceTransport::Http => {
+            let normalize = |url: &Option<String>| {
+                url.as_deref()
+                    .map(|u| u.trim().trim_end_matches('/').to_string())
+            };
+            a.url.is_some() && normalize(&a.url) == normalize(&b.url)
+        }
+    }
+}
+
+/// 标记与已有服务重复的候选项（同名优先）
+pub fn mark_duplicates(candidates: &mut [ImportCandidate], existing: &[McpServerConfig]) {
+    for candidate in candidates {
+        candidate.duplicate = existing
+            .iter()
+            .find(|e| e.name == candidate.name)
+            .map(|e| Duplicate {
+                reason: DuplicateReason::Name,
+                existing: e.name.clone(),
+            })
+            .or_else(|| {
+                existing
+                    .iter()
+                    .find(|e| same_definition(&candidate.config, e))
+                    .map(|e| Duplicate {
+                        reason: DuplicateReason::Definition,
+                        existing: e.name.clone(),
+                    })
+            });
+    }
+}
+
+/// 去掉 JSONC 中的注释和尾随逗号（VS Code、Zed 的配置文件允许这些写法）
+pub fn strip_jsonc(content: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut out = String::with_capacity(content.len());
+    let mut i = 0;
+    let mut in_string = false;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            out.push(c);
+            if c == '\\' && i + 1 < chars.len() {
+                out.push(chars[i + 1]);
+                i += 1;
+            } else if c == '"' {
+                in_string = false;
+            }
+            i += 1;
+            continue;
+        }
+        match (c, chars.get(i + 1)) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+                i += 1;
+            }
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            (',', _) => {
+                // 下一个非空白字符是 } 或 ] 时丢弃逗号
+                let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+                if !matches!(next, Some('}') | Some(']')) {
+                    out.push(c);
+                }
+                i += 1;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vscode_config() {
+        let content = r#"{
+            // VS Code user mcp.json
+            "inputs": [
+                { "type": "promptString", "id": "github-token", "description": "Token", "password": true }
+            ],
+            "servers": {
+                "github": {
+                    "type": "http",
+                    "url": "https://api.githubcopilot.com/mcp/",
+                    "headers": { "Authorization": "Bearer ${input:github-token}" },
+                },
+                "fetch": { "type": "stdio", "command": "uvx", "args": ["mcp-server-fetch"], "disabled": true },
+            },
+        }"#;
+        let (servers, inputs) = parse_content(content).unwrap();
+        assert_eq!(inputs.len(), 1);
+        assert!(inputs[0].password);
+
+        let github = servers.iter().find(|s| s.name == "github").unwrap();
+        assert_eq!(github.config.transport, ServiceTransport::Http);
+        assert_eq!(github.required_inputs, vec!["github-token"]);
+        let fetch = servers.iter().find(|s| s.name == "fetch").unwrap();
+        assert_eq!(fetch.config.transport, ServiceTransport::Stdio);
+        assert!(!fetch.config.enabled);
+
+        let mut config = github.config.clone();
+        let values = HashMap::from([("github-token".to_string(), "abc".to_string())]);
+        apply_inputs(&mut config, &values);
+        assert_eq!(
+            config
+                .headers
+                .unwrap()
+                .get("Authorization")
+                .map(String::as_str),
+            Some("Bearer abc")
+        );
+    }
+
+    #[test]
+    fn test_parse_zed_continue_windsurf() {
+        let zed = serde_json::json!({
+            "context_servers": {
+                "sqlite": { "command": { "path": "uvx", "args": ["mcp-server-sqlite"], "env": {} } }
+            }
+        });
+        let (servers, _) = parse_config(&zed).unwrap();
+        assert_eq!(servers[0].config.command.as_deref(), Some("uvx"));
+        assert_eq!(
+            servers[0].config.args.as_ref().unwrap()[0],
+            "mcp-server-sqlite"
+        );
+
+        let continue_config = serde_json::json!({
+            "experimental": { "modelContextProtocolServers": [
+                { "transport": { "type": "stdio", "command": "npx", "args": ["-y", "x"] } }
+            ] }
+        });
+        let (servers, _) = parse_config(&continue_config).unwrap();
+        assert_eq!(servers[0].name, "server-1");
+        assert_eq!(servers[0].config.command.as_deref(), Some("npx"));
+
+        let windsurf = serde_json::json!({
+            "mcpServers": { "remote": { "serverUrl": "https://example.com/mcp" } }
+        });
+        let (servers, _) = parse_config(&windsurf).unwrap();
+        assert_eq!(servers[0].config.transport, ServiceTransport::Http);
+        assert_eq!(
+            servers[0].config.url.as_deref(),
+            Some("https://example.com/mcp")
+        );
+    }
+
+    #[test]
+    fn test_reject_unsafe_entries() {
+        let value = serde_json::json!({
+            "mcpServers": {
+                "bad-sandbox": { "command": "npx", "sandbox": { "network": "sometimes" } },
+                "container": { "container": { "image": "alpine", "mounts": ["/:/host"] } },
+                "helper": {
+                    "url": "https://example.com/mcp",
+                    "credential_helper": { "command": "sh", "args": ["-c", "id"] }
+                }
+            }
+        });
+        let (servers, _) = parse_config(&value).unwrap();
+        let find = |name: &str| servers.iter().find(|s| s.name == name).unwrap();
+
+        assert!(find("bad-sandbox").error.is_some());
+        assert!(find("container").error.is_some());
+        let helper = find("helper");
+        assert!(helper.error.is_none());
+        assert!(helper.config.credential_helper.is_none());
+        assert_eq!(helper.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_mark_duplicates() {
+        let value = serde_json::json!({
+            "mcpServers": {
+                "fs": { "command": "npx", "args": ["-y", "@modelcontextprotocol/server-filesystem"] },
+                "files": { "command": "npx", "args": ["-y", "@modelcontextprotocol/server-filesystem"] },
+                "new": { "url": "https://example.com/mcp" }
+            }
+        });
+        let (mut servers, _) = parse_config(&value).unwrap();
+        let mut existing = McpServerConfig::new(String::new(), "fs".to_string());
+        existing.command = Some("npx".to_string());
+        existing.args = Some(vec![
+            "-y".to_string(),
+            "@modelcontextprotocol/server-filesystem".to_string(),
+        ]);
+        mark_duplicates(&mut servers, &[existing]);
+
+        let find = |name: &str| servers.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(
+            find("fs").duplicate.as_ref().unwrap().reason,
+            DuplicateReason::Name
+        );
+        assert_eq!(
+            find("files").duplicate.as_ref().unwrap().reason,
+            DuplicateReason::Definition
+        );
+        assert!(find("new").duplicate.is_none());
+    }
+
+    #[test]
+    fn test_strip_jsonc_keeps_strings() {
+        let content = r#"{ "url": "http://a//b", /* c */ "x": "a,}", }"#;
+        let value: Value = serde_json::from_str(&strip_jsonc(content)).unwrap();
+        assert_eq!(value["url"], "http://a//b");
+        assert_eq!(value["x"], "a,}");
+    }
+}
@@ -1,8 +1,14 @@
 // Configuration Management Commands
 
+use crate::bundle::{self, BundleImportReport, BundleSummary, ConflictStrategy};
+use crate::client_export::{self, ClientConfigSnippet, ClientTarget, WriteClientConfigResult};
+use crate::client_import::{self, ClientConfigPreview, ImportResult, ImportStatus, McpClient};
 use crate::config as config_mod;
-use crate::error::Result;
+use crate::declarative::{self, ReconcileReport};
+use crate::error::{McpError, Result};
+use crate::types::McpServerConfig;
 use crate::SERVICE_MANAGER;
+use std::collections::{HashMap, HashSet};
 use tauri::Emitter;
 
 #[tauri::command]
@@ -38,179 +44,376 @@ pub async fn set_theme(app: tauri::AppHandle, theme: String) -> Result<()> {
 
 #[tauri::command]
 pub async fn update_config(config: config_mod::AppConfig) -> Result<String> {
-    config.save()?;
+    config_mod::CONFIG_SERVICE.update(config)?;
     Ok("Config updated".to_string())
 }
 
-#[tauri::command]
-pub async fn import_mcp_servers_config(
-    config_json: serde_json::Value,
-) -> Result<String> {
-    // Extract mcpServers object from config
-    if let Some(mcp_servers) = config_json.get("mcpServers").and_then(|v| v.as_object()) {
-        let mut added_servers = Vec::new();
-
-        for (service_name, service_config) in mcp_servers {
-            if let Some(service_obj) = service_config.as_object() {
-                tracing::info!("Processing service configuration for '{}'", service_name);
-                // Determine transport type based on available fields
-                let transport =
-                    // Priority 1: If url field exists, it's HTTP type
-                    if service_obj.contains_key("url") {
-                        crate::types::ServiceTransport::Http
-                    }
-                    // Priority 2: If command field exists, it's STDIO type
-                    else if service_obj.contains_key("command") {
-                        crate::types::ServiceTransport::Stdio
-                    }
-                    // Priority 3: Check explicit type field (for special cases)
-                    else if let Some(transport_str) = service_obj.get("type").and_then(|v| v.as_str()) {
-                        match transport_str.to_lowercase().as_str() {
-                            "http" => crate::types::ServiceTransport::Http,
-                            "stdio" => crate::types::ServiceTransport::Stdio,
-                            "sse" => {
-                                tracing::warn!("SSE transport is deprecated, falling back to HTTP");
-                                crate::types::ServiceTransport::Http
-                            },
-                            _ => {
-                                tracing::warn!("Unknown transport type '{}', falling back to STDIO", transport_str);
-                                crate::types::ServiceTransport::Stdio
-                            }
-                        }
-                    }
-                    // Priority 4: Default to STDIO
-                    else {
-                        tracing::warn!("No transport information found, defaulting to STDIO");
-                        crate::types::ServiceTransport::Stdio
-                    };
-
-                // Extract service configuration
-                let command = service_obj
-                    .get("command")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-                let args = service_obj
-                    .get("args")
-                    .and_then(|v| v.as_array())
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|v| v.as_str())
-                            .map(|s| s.to_string())
-                            .collect()
-                    });
-                let url = service_obj
-                    .get("url")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-                let description = service_obj
-                    .get("description")
-                    .and_then(|v| v.as_str())
-                    .map(|s| s.to_string());
-
-                // Extract environment variables
-                let env = service_obj
-                    .get("env")
-                    .and_then(|v| v.as_object())
-                    .map(|obj| {
-                        obj.iter()
-                            .filter_map(|(k, v)| v.as_str().map(|val| (k.clone(), val.to_string())))
-                            .collect::<std::collections::HashMap<String, String>>()
-                    });
-
-                // Extract headers
-                let headers = service_obj
-                    .get("headers")
-                    .and_then(|v| v.as_object())
-                    .map(|obj| {
-                        obj.iter()
-                            .filter_map(|(k, v)| v.as_str().map(|val| (k.clone(), val.to_string())))
-                            .collect::<std::collections::HashMap<String, String>>()
-                    });
-
-                // Create service configuration
-                let service_config = crate::types::McpServerConfig {
-                    name: service_name.clone(),
-                    description,
-                    command,
-                    args,
-                    transport,
-                    url,
-                    enabled: true,
-                    env,
-                    headers,
-                };
-
-                // Add service
-                {
-                    let service_manager = {
-                        let guard = SERVICE_MANAGER
-                            .lock()
-                            .expect("Failed to acquire SERVICE_MANAGER lock");
-                        guard.as_ref()
-                            .expect("SERVICE_MANAGER should be initialized")
-                            .clone()
-                    };
-                    tracing::info!("Importing service '{}': transport={:?}, command={:?}, url={:?}",
-                        service_name, service_config.transport, service_config.command, service_config.url);
-
-                    match service_manager.add_server(&service_config).await {
-                        Ok(()) => {
-                            tracing::info!("Successfully imported service '{}'", service_name);
-                            added_servers.push(service_name.clone());
-                        },
-                        Err(e) => {
-                            // 区分不同类型的错误
-                            let error_msg = e.to_string();
-                            if error_msg.contains("已存在")
-                                || error_msg.contains("already exists")
-                                || error_msg.contains("UNIQUE constraint") {
-                                // 服务已存在，视为成功导入，避免误报
-                                tracing::warn!("Service '{}' already exists in database, treating as imported", service_name);
-                                added_servers.push(service_name.clone());
-                            } else {
-                                tracing::error!("Failed to import service '{}': {}", service_name, e);
-                                // 继续处理其他服务
-                            }
-                        }
-                    }
-                }
-            }
+fn service_manager() -> std::sync::Arc<crate::mcp_manager::McpServerManager> {
+    let guard = SERVICE_MANAGER
+        .lock()
+        .expect("Failed to acquire SERVICE_MANAGER lock");
+    guard
+        .as_ref()
+        .expect("SERVICE_MANAGER should be initialized")
+        .clone()
+}
+
+/// 逐个导入服务，已存在的同名服务按冲突策略跳过、覆盖或改名导入
+async fn import_server_configs(
+    servers: Vec<McpServerConfig>,
+    strategy: ConflictStrategy,
+    input_values: &HashMap<String, String>,
+) -> Result<Vec<ImportResult>> {
+    let service_manager = service_manager();
+    let mut taken: HashSet<String> = service_manager
+        .list_server_configs()
+        .await?
+        .into_iter()
+        .map(|s| s.name)
+        .collect();
+    let mut results = Vec::new();
+
+    for mut config in servers {
+        let name = config.name.trim().to_string();
+        config.name = name.clone();
+        if name.is_empty() {
+            results.push(ImportResult {
+                name,
+                status: ImportStatus::Failed,
+                message: Some("Server name is required".to_string()),
+            });
+            continue;
         }
 
-        // Get service manager for auto-connect
-        let service_manager = {
-            let guard = SERVICE_MANAGER
-                .lock()
-                .expect("Failed to acquire SERVICE_MANAGER lock");
-            guard.as_ref()
-                .expect("SERVICE_MANAGER should be initialized")
-                .clone()
-        };
+        client_import::apply_inputs(&mut config, input_values);
+        let missing = client_import::referenced_inputs(&config);
+        if !missing.is_empty() {
+            results.push(ImportResult {
+                name,
+                status: ImportStatus::Failed,
+                message: Some(format!("Missing value for input: {}", missing.join(", "))),
+            });
+            continue;
+        }
 
-        if added_servers.is_empty() {
-            let error_msg = format!(
-                "No valid services found in configuration. Processed {} service(s). This may be because some services already exist. Check logs for detailed error information.",
-                mcp_servers.len()
-            );
-            tracing::error!("{}", error_msg);
-            return Err(crate::error::McpError::InvalidConfiguration(error_msg));
+        tracing::info!(
+            "Importing service '{}': transport={:?}, command={:?}, url={:?}",
+            name,
+            config.transport,
+            config.command,
+            config.url
+        );
+        let exists = taken.contains(&name);
+        let (status, outcome) = match (exists, strategy) {
+            (true, ConflictStrategy::Skip) => {
+                results.push(ImportResult {
+                    name,
+                    status: ImportStatus::Skipped,
+                    message: Some("Server already exists".to_string()),
+                });
+                continue;
+            }
+            (true, ConflictStrategy::Overwrite) => (
+                ImportStatus::Replaced,
+                service_manager.update_server(&name, &config).await,
+            ),
+            (true, ConflictStrategy::Rename) => {
+                config.name = bundle::rename_candidate(&name, &taken);
+                (
+                    ImportStatus::Renamed,
+                    service_manager.add_server(&config).await,
+                )
+            }
+            (false, _) => (
+                ImportStatus::Added,
+                service_manager.add_server(&config).await,
+            ),
+        };
+        match outcome {
+            Ok(()) => {
+                tracing::info!("✅ Imported service '{}' ({:?})", config.name, status);
+                taken.insert(config.name.clone());
+                let message =
+                    (config.name != name).then(|| format!("Imported as '{}'", config.name));
+                results.push(ImportResult {
+                    name,
+                    status,
+                    message,
+                });
+            }
+            Err(e) => {
+                tracing::error!("❌ Failed to import service '{}': {}", name, e);
+                results.push(ImportResult {
+                    name,
+                    status: ImportStatus::Failed,
+                    message: Some(e.to_string()),
+                });
+            }
         }
+    }
 
-        // Trigger auto-connect for newly added/updated servers
+    if results.iter().any(|r| {
+        matches!(
+            r.status,
+            ImportStatus::Added | ImportStatus::Replaced | ImportStatus::Renamed
+        )
+    }) {
         tracing::info!("Triggering auto-connect for imported servers");
         if let Err(e) = service_manager.auto_connect_enabled_services().await {
             tracing::error!("Failed to auto-connect services after import: {}", e);
         }
+    }
+    Ok(results)
+}
+
+#[tauri::command]
+pub async fn import_mcp_servers_config(config_json: serde_json::Value) -> Result<String> {
+    let (candidates, _) = client_import::parse_config(&config_json)?;
+    for candidate in &candidates {
+        for warning in &candidate.warnings {
+            tracing::warn!("Service '{}': {}", candidate.name, warning);
+        }
+        if let Some(error) = &candidate.error {
+            tracing::error!("Service '{}' rejected: {}", candidate.name, error);
+        }
+    }
+    let total = candidates.len();
+    let servers = candidates
+        .into_iter()
+        .filter(|c| c.error.is_none())
+        .map(|c| c.config)
+        .collect();
+    let results = import_server_configs(servers, ConflictStrategy::Skip, &HashMap::new()).await?;
 
-        Ok(format!(
-            "Successfully imported {} MCP server(s): {}",
-            added_servers.len(),
-            added_servers.join(", ")
-        ))
-    } else {
-        Err(crate::error::McpError::InvalidConfiguration(
-            "Invalid configuration format. Expected 'mcpServers' object.".to_string(),
-        ))
+    // 已存在的服务视为导入成功，避免误报
+    let imported: Vec<&str> = results
+        .iter()
+        .filter(|r| matches!(r.status, ImportStatus::Added | ImportStatus::Skipped))
+        .map(|r| r.name.as_str())
+        .collect();
+    if imported.is_empty() {
+        let error_msg = format!(
+            "No valid services found in configuration. Processed {} service(s). This may be because some services already exist. Check logs for detailed error information.",
+            total
+        );
+        tracing::error!("{}", error_msg);
+        return Err(McpError::InvalidConfiguration(error_msg));
     }
+
+    Ok(format!(
+        "Successfully imported {} MCP server(s): {}",
+        imported.len(),
+        imported.join(", ")
+    ))
+}
+
+/// 查找本机 MCP 客户端（Claude Desktop、Cursor、VS Code 等）的配置文件并预览可导入的服务
+#[tauri::command(rename_all = "snake_case")]
+pub async fn discover_client_configs() -> Result<Vec<ClientConfigPreview>> {
+    let existing = service_manager().list_server_configs().await?;
+    let mut previews = client_import::discover();
+    for preview in &mut previews {
+        client_import::mark_duplicates(&mut preview.servers, &existing);
+    }
+    Ok(previews)
+}
+
+/// 预览粘贴的配置（支持 mcpServers / servers / context_servers 格式和 JSONC）
+#[tauri::command(rename_all = "snake_case")]
+pub async fn preview_mcp_servers_import(config_json: String) -> Result<ClientConfigPreview> {
+    let (mut servers, inputs) = client_import::parse_content(&config_json)?;
+    let existing = service_manager().list_server_configs().await?;
+    client_import::mark_duplicates(&mut servers, &existing);
+    Ok(ClientConfigPreview {
+        client: McpClient::Other,
+        path: None,
+        servers,
+        inputs,
+        error: None,
+    })
 }
 
+/// 导入预览中选中的服务，返回每个服务的导入结果
+#[tauri::command(rename_all = "snake_case")]
+pub async fn import_mcp_servers(
+    servers: Vec<McpServerConfig>,
+    strategy: Option<ConflictStrategy>,
+    input_values: Option<HashMap<String, String>>,
+) -> Result<Vec<ImportResult>> {
+    import_server_configs(
+        servers,
+        strategy.unwrap_or_default(),
+        &input_values.unwrap_or_default(),
+    )
+    .await
+}
+
+/// 生成指向聚合接口的客户端配置，认证开启时带上 token 的 Authorization 头
+async fn build_client_config(
+    client: ClientTarget,
+    token_id: Option<String>,
+    server_name: Option<String>,
+) -> Result<ClientConfigSnippet> {
+    let server = config_mod::CONFIG_SERVICE.current().server.clone();
+    let token = match token_id.filter(|_| server.auth) {
+        Some(id) => {
+            let token_manager = crate::wait_for_token_manager().await?;
+            let token = token_manager
+                .orm_storage()
+                .get_token_by_id(&id)
+                .await?
+                .ok_or_else(|| McpError::NotFound(format!("Token '{}' not found", id)))?;
+            Some(token.value)
+        }
+        None => None,
+    };
+
+    let server_name = server_name
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| client_export::DEFAULT_SERVER_NAME.to_string());
+    let endpoint = client_export::router_endpoint(&server.host, server.port);
+    let snippet = client_export::build_snippet(client, &server_name, &endpoint, token.as_deref());
+    let text = serde_json::to_string_pretty(&snippet)
+        .map_err(|e| McpError::SerializationError(e.to_string()))?;
+    Ok(ClientConfigSnippet {
+        client,
+        endpoint,
+        snippet,
+        text,
+        path: client
+            .default_config_path(&server_name)
+            .map(|p| p.display().to_string()),
+    })
+}
+
+/// 生成目标客户端（Claude Desktop、Cursor、VS Code 等）连接聚合接口的配置片段
+#[tauri::command(rename_all = "snake_case")]
+pub async fn generate_client_config(
+    client: ClientTarget,
+    token_id: Option<String>,
+    server_name: Option<String>,
+) -> Result<ClientConfigSnippet> {
+    build_client_config(client, token_id, server_name).await
+}
+
+/// 将配置片段合并写入客户端配置文件，原文件会先备份
+#[tauri::command(rename_all = "snake_case")]
+pub async fn write_client_config(
+    client: ClientTarget,
+    token_id: Option<String>,
+    server_name: Option<String>,
+    path: Option<String>,
+) -> Result<WriteClientConfigResult> {
+    let config = build_client_config(client, token_id, server_name).await?;
+    let path = path
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| expand_path(&p))
+        .or_else(|| config.path.as_ref().map(std::path::PathBuf::from))
+        .ok_or_else(|| {
+            McpError::InvalidConfiguration(format!(
+                "No default config file for {:?}, please specify a path",
+                client
+            ))
+        })?;
+
+    let backup = client_export::write_snippet(&path, &config.snippet)?;
+    Ok(WriteClientConfigResult {
+        path: path.display().to_string(),
+        backup_path: backup.map(|p| p.display().to_string()),
+    })
+}
+
+/// 展开 `~/` 开头的路径
+pub(crate) fn expand_path(path: &str) -> std::path::PathBuf {
+    let path = path.trim();
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+        None => std::path::PathBuf::from(path),
+    }
+}
+
+/// 设置中配置的声明式配置文件路径
+pub(crate) fn configured_config_file() -> Option<std::path::PathBuf> {
+    config_mod::CONFIG_SERVICE
+        .current()
+        .settings
+        .as_ref()
+        .and_then(|s| s.config_file.clone())
+        .map(|path| expand_path(&path))
+}
+
+/// 导出服务、工具开关、Token 权限和应用设置，`.zip` 扩展名时写入 zip 包，否则写入 JSON
+#[tauri::command(rename_all = "snake_case")]
+pub async fn export_router_bundle(
+    path: String,
+    redact_secrets: Option<bool>,
+    include_settings: Option<bool>,
+) -> Result<BundleSummary> {
+    let path = expand_path(&path);
+    let token_manager = crate::wait_for_token_manager().await?;
+    let bundle = bundle::export(
+        &service_manager(),
+        &token_manager,
+        redact_secrets.unwrap_or(true),
+        include_settings.unwrap_or(true),
+    )
+    .await?;
+
+    let as_zip = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    let bytes = bundle::encode(&bundle, as_zip)?;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| McpError::ConfigError(format!("Failed to create directory: {}", e)))?;
+    }
+    std::fs::write(&path, bytes)
+        .map_err(|e| McpError::ConfigError(format!("Failed to write bundle: {}", e)))?;
+
+    tracing::info!("📦 Exported router bundle to {}", path.display());
+    Ok(bundle.summary(path.display().to_string()))
+}
+
+/// 导入配置包，strategy 决定同名服务和 Token 的处理方式（skip / overwrite / rename）
+#[tauri::command(rename_all = "snake_case")]
+pub async fn import_router_bundle(
+    path: String,
+    strategy: Option<ConflictStrategy>,
+    import_settings: Option<bool>,
+) -> Result<BundleImportReport> {
+    let path = expand_path(&path);
+    let bytes = std::fs::read(&path)
+        .map_err(|e| McpError::ConfigError(format!("Failed to read bundle: {}", e)))?;
+    let bundle = bundle::decode(&bytes)?;
+    let token_manager = crate::wait_for_token_manager().await?;
+    bundle::import(
+        bundle,
+        &service_manager(),
+        &token_manager,
+        strategy.unwrap_or_default(),
+        import_settings.unwrap_or(false),
+    )
+    .await
+}
+
+/// 将数据库同步到声明式配置文件，path 为空时使用设置中的 config_file，dry_run 时只报告漂移
+#[tauri::command(rename_all = "snake_case")]
+pub async fn reconcile_config_file(
+    path: Option<String>,
+    dry_run: Option<bool>,
+) -> Result<ReconcileReport> {
+    let path = match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => expand_path(&path),
+        None => configured_config_file()
+            .ok_or_else(|| McpError::ConfigError("No config file configured".to_string()))?,
+    };
+    let token_manager = crate::wait_for_token_manager().await?;
+    declarative::reconcile(
+        &path,
+        &service_manager(),
+        &token_manager,
+        dry_run.unwrap_or(false),
+    )
+    .await
+}
@@ -1,7 +1,7 @@
 // Dashboard Data Commands
 
 use crate::config;
-use crate::error::{McpError, Result};
+use crate::error::Result;
 use crate::{AGGREGATOR, MCP_CLIENT_MANAGER, STARTUP_TIME};
 use serde::{Deserialize, Serialize};
 use std::time::UNIX_EPOCH;
@@ -56,11 +56,7 @@ pub async fn get_dashboard_stats() -> Result<serde_json::Value> {
     let total_services = mcp_servers.len();
 
     // Get the current server configuration
-    let server_config = {
-        let config = config::AppConfig::load()
-            .map_err(|e| McpError::ConfigError(format!("Failed to load configuration: {}", e)))?;
-        config.server
-    };
+    let server_config = config::CONFIG_SERVICE.current().server.clone();
 
     // Get aggregator statistics
     // 获取聚合器的克隆，避免跨越 await 点持有锁
@@ -154,6 +150,42 @@ pub async fn get_dashboard_stats() -> Result<serde_json::Value> {
         })
         .sum::<u32>();
 
+    // Collect servers whose circuit breaker is not closed
+    let circuit_breakers: Vec<serde_json::Value> = services
+        .iter()
+        .filter(|s| s.enabled)
+        .filter_map(|s| {
+            s.circuit_breaker
+                .as_ref()
+                .filter(|b| b.state != crate::circuit_breaker::CircuitState::Closed)
+                .map(|b| {
+                    serde_json::json!({
+                        "name": s.name.as_ref(),
+                        "state": b.state,
+                        "failure_rate": b.failure_rate,
+                        "retry_after_seconds": b.retry_after_seconds,
+                    })
+                })
+        })
+        .collect();
+
+    // STDIO 服务当前进程实际使用的运行时
+    let runtimes: Vec<serde_json::Value> = services
+        .iter()
+        .filter_map(|s| {
+            s.active_runtime.as_ref().map(|runtime| {
+                serde_json::json!({
+                    "name": s.name.as_ref(),
+                    "kind": runtime.kind,
+                    "executable": runtime.executable,
+                })
+            })
+        })
+        .collect();
+
+    // STDIO 服务进程的 CPU / 内存占用（按内存降序）
+    let resource_usage = crate::MCP_CLIENT_MANAGER.resource_monitor().all_usage();
+
     Ok(serde_json::json!({
         "total_servers": total_services,
         "enabled_servers": enabled_services,
@@ -165,9 +197,14 @@ pub async fn get_dashboard_stats() -> Result<serde_json::Value> {
         "total_prompt_templates": total_prompt_templates,
         "active_clients": connections.len(),
         "startup_time": startup_time,
+        "circuit_breakers": circuit_breakers,
+        "resource_usage": resource_usage,
+        "runtimes": runtimes,
         "connections": {
             "active_clients": connections.len(),
             "active_services": aggregator_stats.get("active_connections").and_then(|v| v.as_u64()).unwrap_or(0),
+            "in_flight_requests": aggregator_stats.get("in_flight_requests").and_then(|v| v.as_u64()).unwrap_or(0),
+            "rejected_requests": aggregator_stats.get("rejected_requests").and_then(|v| v.as_u64()).unwrap_or(0),
         },
         "aggregator": {
             "endpoint": aggregator_endpoint,
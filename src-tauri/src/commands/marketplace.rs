@@ -1,7 +1,11 @@
 // Marketplace Service Commands
 
+use crate::container::ContainerConfig;
 use crate::error::{McpError, Result};
-use crate::types::{MarketplaceService, McpServerConfig, ServiceTransport};
+use crate::types::{
+    EnvInheritance, MarketplaceService, McpServerConfig, RestartPolicy, ServerLifecycle,
+    ServerRuntime, ServiceTransport,
+};
 use crate::{marketplace, MCP_CLIENT_MANAGER, SERVICE_MANAGER};
 use std::collections::HashMap;
 
@@ -43,24 +47,59 @@ pub async fn install_marketplace_service(
     };
 
     // Convert env vars into HashMap if provided
-    let env_map = env.map(|vars| vars.into_iter().collect::<HashMap<String, String>>());
+    let mut env_map = env.map(|vars| vars.into_iter().collect::<HashMap<String, String>>());
 
     // Check if install command is available
     let install_command = service.install_command.ok_or_else(|| {
         McpError::InvalidConfiguration("Cannot extract installation command, this service may not support one-click installation".to_string())
     })?;
 
+    // docker / podman run 命令转换为容器配置，内联的 -e KEY=VALUE 合并到 env（用户填写的值优先）
+    let (command, args, container) =
+        match ContainerConfig::from_run_command(&install_command.command, &install_command.args) {
+            Some((container, args, inline_env)) => {
+                if !inline_env.is_empty() {
+                    let env = env_map.get_or_insert_with(HashMap::new);
+                    for (key, value) in inline_env {
+                        env.entry(key).or_insert(value);
+                    }
+                }
+                (None, Some(args), Some(container))
+            }
+            None => (
+                Some(install_command.command.clone()),
+                Some(install_command.args.clone()),
+                None,
+            ),
+        };
+
     // Create service configuration and add to manager (one-click install)
     let config = McpServerConfig {
         name: service.name.clone(),
         description: Some(service.description.clone()),
-        command: Some(install_command.command.clone()),
-        args: Some(install_command.args.clone()),
+        command,
+        args,
         transport: service_transport,
         url: None,
         enabled: true,
         env: env_map,
         headers: None,
+        request_timeout: None,
+        connect_timeout: None,
+        max_concurrent_calls: None,
+        lifecycle: ServerLifecycle::default(),
+        idle_timeout: None,
+        restart_policy: RestartPolicy::default(),
+        max_restarts: None,
+        memory_limit_mb: None,
+        sandbox: None,
+        cwd: None,
+        env_inheritance: EnvInheritance::default(),
+        env_allowlist: None,
+        container,
+        runtime: ServerRuntime::default(),
+        runtime_version: None,
+        credential_helper: None,
     };
 
     // Persist into service manager
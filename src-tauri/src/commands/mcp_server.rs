@@ -1,9 +1,14 @@
 // MCP Server Management Commands - SQLite Version
 
+use crate::container::ContainerConfig;
+use crate::credential_helper::CredentialHelper;
 use crate::error::{McpError, Result};
 use crate::mcp_manager::McpServerManager;
+use crate::resource_monitor::ProcessUsage;
+use crate::sandbox::SandboxProfile;
 use crate::types::{
-    McpPromptInfo, McpResourceInfo, McpServerConfig, McpServerResult, McpToolInfo, ServiceTransport,
+    EnvInheritance, McpPromptInfo, McpResourceInfo, McpServerConfig, McpServerResult, McpToolInfo,
+    RestartPolicy, ServerLifecycle, ServerRuntime, ServiceStatus, ServiceTransport,
 };
 use serde::Deserialize;
 use std::collections::HashMap;
@@ -22,6 +27,22 @@ pub struct McpServerCreateRequest {
     pub description: Option<String>,
     pub env: Option<Vec<(String, String)>>,
     pub headers: Option<Vec<(String, String)>>,
+    pub request_timeout: Option<u64>,
+    pub connect_timeout: Option<u64>,
+    pub max_concurrent_calls: Option<u32>,
+    pub lifecycle: Option<ServerLifecycle>,
+    pub idle_timeout: Option<u64>,
+    pub restart_policy: Option<RestartPolicy>,
+    pub max_restarts: Option<u32>,
+    pub memory_limit_mb: Option<u64>,
+    pub sandbox: Option<SandboxProfile>,
+    pub cwd: Option<String>,
+    pub env_inheritance: Option<EnvInheritance>,
+    pub env_allowlist: Option<Vec<String>>,
+    pub container: Option<ContainerConfig>,
+    pub runtime: Option<ServerRuntime>,
+    pub runtime_version: Option<String>,
+    pub credential_helper: Option<CredentialHelper>,
 }
 
 /// MCP Server Update Request
@@ -38,6 +59,22 @@ pub struct McpServerUpdateRequest {
     pub env: Option<Vec<(String, String)>>,
     pub headers: Option<Vec<(String, String)>>,
     pub enabled: bool,
+    pub request_timeout: Option<u64>,
+    pub connect_timeout: Option<u64>,
+    pub max_concurrent_calls: Option<u32>,
+    pub lifecycle: Option<ServerLifecycle>,
+    pub idle_timeout: Option<u64>,
+    pub restart_policy: Option<RestartPolicy>,
+    pub max_restarts: Option<u32>,
+    pub memory_limit_mb: Option<u64>,
+    pub sandbox: Option<SandboxProfile>,
+    pub cwd: Option<String>,
+    pub env_inheritance: Option<EnvInheritance>,
+    pub env_allowlist: Option<Vec<String>>,
+    pub container: Option<ContainerConfig>,
+    pub runtime: Option<ServerRuntime>,
+    pub runtime_version: Option<String>,
+    pub credential_helper: Option<CredentialHelper>,
 }
 
 // Helper function to get MCP server manager from global state (with wait)
@@ -81,8 +118,33 @@ pub async fn add_mcp_server(
         headers: headers_map,
         env: env_map,
         enabled: true, // Default to enabled when adding
+        request_timeout: request.request_timeout,
+        connect_timeout: request.connect_timeout,
+        max_concurrent_calls: request.max_concurrent_calls,
+        lifecycle: request.lifecycle.unwrap_or_default(),
+        idle_timeout: request.idle_timeout,
+        restart_policy: request.restart_policy.unwrap_or_default(),
+        max_restarts: request.max_restarts,
+        memory_limit_mb: request.memory_limit_mb,
+        sandbox: request.sandbox,
+        cwd: request.cwd,
+        env_inheritance: request.env_inheritance.unwrap_or_default(),
+        env_allowlist: request.env_allowlist,
+        container: request.container,
+        runtime: request.runtime.unwrap_or_default(),
+        runtime_version: request
+            .runtime_version
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty()),
+        credential_helper: request
+            .credential_helper
+            .filter(|helper| !helper.command.trim().is_empty()),
     };
 
+    if config.transport == ServiceTransport::Stdio {
+        config.validated_cwd()?;
+    }
+
     let mcp_manager = get_mcp_manager().await?;
     mcp_manager.add_server(&config).await?;
 
@@ -146,14 +208,25 @@ pub async fn update_mcp_server(
     };
 
     // Convert env and headers from Vec<(String, String)> to HashMap<String, String>
-    let env_map = request
+    let mut env_map = request
         .env
         .map(|env_list| env_list.into_iter().collect::<HashMap<String, String>>());
 
-    let headers_map = request
+    let mut headers_map = request
         .headers
         .map(|header_list| header_list.into_iter().collect::<HashMap<String, String>>());
 
+    let mcp_manager = get_mcp_manager().await?;
+
+    // 列表接口返回的是脱敏值，未修改的字段沿用已保存的原值
+    let saved = mcp_manager.get_server_config(&request.name).await?;
+    if let Some(env) = env_map.as_mut() {
+        crate::secrets::restore_redacted(env, saved.as_ref().and_then(|s| s.env.as_ref()));
+    }
+    if let Some(headers) = headers_map.as_mut() {
+        crate::secrets::restore_redacted(headers, saved.as_ref().and_then(|s| s.headers.as_ref()));
+    }
+
     let config = McpServerConfig {
         name: request.name.clone(),
         description: request.description,
@@ -164,9 +237,32 @@ pub async fn update_mcp_server(
         headers: headers_map,
         env: env_map,
         enabled: request.enabled,
+        request_timeout: request.request_timeout,
+        connect_timeout: request.connect_timeout,
+        max_concurrent_calls: request.max_concurrent_calls,
+        lifecycle: request.lifecycle.unwrap_or_default(),
+        idle_timeout: request.idle_timeout,
+        restart_policy: request.restart_policy.unwrap_or_default(),
+        max_restarts: request.max_restarts,
+        memory_limit_mb: request.memory_limit_mb,
+        sandbox: request.sandbox,
+        cwd: request.cwd,
+        env_inheritance: request.env_inheritance.unwrap_or_default(),
+        env_allowlist: request.env_allowlist,
+        container: request.container,
+        runtime: request.runtime.unwrap_or_default(),
+        runtime_version: request
+            .runtime_version
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty()),
+        credential_helper: request
+            .credential_helper
+            .filter(|helper| !helper.command.trim().is_empty()),
     };
 
-    let mcp_manager = get_mcp_manager().await?;
+    if config.transport == ServiceTransport::Stdio {
+        config.validated_cwd()?;
+    }
     mcp_manager.update_server(&request.name, &config).await?;
 
     Ok(format!(
@@ -179,10 +275,49 @@ pub async fn update_mcp_server(
 pub async fn delete_mcp_server(name: String) -> Result<String> {
     let mcp_manager = get_mcp_manager().await?;
     mcp_manager.delete_server(&name).await?;
+    crate::MCP_CLIENT_MANAGER.disconnect_server(&name).await?;
+    crate::MCP_CLIENT_MANAGER.server_logs().clear(&name);
+    crate::MCP_CLIENT_MANAGER.process_monitor().remove(&name);
+    if let Err(e) = crate::MCP_CLIENT_MANAGER.clear_authorization(&name).await {
+        tracing::warn!("Failed to remove OAuth authorization of '{}': {}", name, e);
+    }
 
     Ok(format!("MCP server '{}' removed successfully", name))
 }
 
+/// 在浏览器中完成 HTTP 服务的 OAuth 授权并重新连接
+#[tauri::command(rename_all = "snake_case")]
+pub async fn authorize_mcp_server(name: String) -> Result<String> {
+    let mcp_manager = get_mcp_manager().await?;
+    let server_config = mcp_manager
+        .get_server_config(&name)
+        .await?
+        .ok_or_else(|| McpError::NotFound(format!("MCP server '{}' not found", name)))?;
+    if server_config.transport != ServiceTransport::Http {
+        return Err(McpError::ValidationError(
+            "OAuth authorization is only available for HTTP servers".to_string(),
+        ));
+    }
+
+    crate::MCP_CLIENT_MANAGER
+        .authorize_server(&server_config)
+        .await?;
+    if let Err(e) = mcp_manager.sync_server_manifests(&name).await {
+        tracing::error!("Failed to sync manifests for '{}': {}", name, e);
+    }
+
+    Ok(format!("MCP server '{}' authorized successfully", name))
+}
+
+/// 删除 HTTP 服务保存的 OAuth 授权并断开连接
+#[tauri::command(rename_all = "snake_case")]
+pub async fn clear_mcp_server_authorization(name: String) -> Result<String> {
+    crate::MCP_CLIENT_MANAGER.clear_authorization(&name).await?;
+    crate::MCP_CLIENT_MANAGER.disconnect_server(&name).await?;
+
+    Ok(format!("OAuth authorization of '{}' removed", name))
+}
+
 #[tauri::command(rename_all = "snake_case")]
 pub async fn toggle_mcp_server(name: String) -> Result<bool> {
     let mcp_manager = get_mcp_manager().await?;
@@ -191,19 +326,7 @@ pub async fn toggle_mcp_server(name: String) -> Result<bool> {
     // 根据新状态执行相应操作
     if new_state {
         // 启用：尝试连接并同步
-        if let Some(server) = mcp_manager.get_server_by_name(&name).await? {
-            let server_config = McpServerConfig {
-                name: server.name.to_string(),
-                description: server.description.as_ref().map(|d| d.as_ref().to_string()),
-                transport: server.transport.parse().unwrap_or(ServiceTransport::Stdio),
-                command: server.command,
-                args: server.args,
-                url: server.url,
-                headers: server.headers.as_ref().map(|h| (**h).clone()),
-                env: server.env.as_ref().map(|e| (**e).clone()),
-                enabled: true,
-            };
-
+        if let Some(server_config) = mcp_manager.get_server_config(&name).await? {
             // 异步连接和同步
             let server_name = name.clone();
             tokio::spawn(async move {
@@ -270,7 +393,32 @@ pub async fn toggle_mcp_server(name: String) -> Result<bool> {
 #[tauri::command(rename_all = "snake_case")]
 pub async fn list_mcp_servers() -> Result<McpServerResult> {
     let mcp_manager = get_mcp_manager().await?;
-    let (servers, total_count) = mcp_manager.list_servers(None, None).await?;
+    let (mut servers, total_count) = mcp_manager.list_servers(None, None).await?;
+
+    // 标记当前机器上无法解析的占位符
+    let configs = mcp_manager.list_server_configs().await?;
+    if configs.iter().any(crate::placeholders::contains_placeholders) {
+        let context = crate::placeholders::PlaceholderContext::load().await;
+        for server in &mut servers {
+            if let Some(config) = configs.iter().find(|c| c.name == *server.name) {
+                let unresolved = context.unresolved(config);
+                server.unresolved_placeholders = (!unresolved.is_empty()).then_some(unresolved);
+            }
+        }
+    }
+
+    // 标记已保存 OAuth 授权的 HTTP 服务
+    let http = ServiceTransport::Http.to_string();
+    for server in servers.iter_mut().filter(|s| s.transport == http) {
+        server.oauth_authorized = crate::MCP_CLIENT_MANAGER.is_authorized(&server.name).await;
+    }
+
+    // 明文的敏感 env / headers 不返回给前端，${secret:...} 引用保持原样
+    for server in &mut servers {
+        for map in [&mut server.env, &mut server.headers].into_iter().flatten() {
+            *map = Arc::new(crate::secrets::redact(map));
+        }
+    }
 
     Ok(McpServerResult {
         servers,
@@ -345,3 +493,38 @@ pub async fn list_mcp_server_prompts(server_name: String) -> Result<Vec<McpPromp
     let prompts = mcp_manager.list_mcp_server_prompts(&server_name).await?;
     Ok(prompts)
 }
+
+/// Get the latest stderr output of a STDIO MCP server
+///
+/// 默认从内存缓冲区读取；`from_file` 为 true 时读取 `~/.mcprouter/logs/servers/` 下的日志文件
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_mcp_server_logs(
+    server_name: String,
+    lines: Option<usize>,
+    from_file: Option<bool>,
+) -> Result<Vec<String>> {
+    let lines = lines.unwrap_or(200);
+    let server_logs = crate::MCP_CLIENT_MANAGER.server_logs();
+
+    if from_file.unwrap_or(false) {
+        server_logs.read_log_file(&server_name, lines).await
+    } else {
+        Ok(server_logs.tail(&server_name, lines))
+    }
+}
+
+/// 获取 MCP 服务器的运行状态（进程 PID、重启次数、最近一次退出原因等）
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_mcp_server_status(server_name: String) -> Result<ServiceStatus> {
+    let mcp_manager = get_mcp_manager().await?;
+    mcp_manager
+        .get_service_status(&server_name)
+        .await?
+        .ok_or_else(|| McpError::ServiceNotFound(server_name))
+}
+
+/// 获取所有 STDIO 服务进程的 CPU 与内存占用（按内存降序）
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_mcp_servers_resource_usage() -> Result<Vec<ProcessUsage>> {
+    Ok(crate::MCP_CLIENT_MANAGER.resource_monitor().all_usage())
+}
@@ -4,7 +4,9 @@ pub mod config;
 pub mod dashboard;
 pub mod marketplace;
 pub mod mcp_server;
+pub mod oauth;
 pub mod permission_management;
+pub mod secret;
 pub mod settings;
 pub mod token_management;
 pub mod tool;
@@ -17,7 +19,9 @@ pub use config::*;
 pub use dashboard::*;
 pub use marketplace::*;
 pub use mcp_server::*;
+pub use oauth::*;
 pub use permission_management::*;
+pub use secret::*;
 // Re-enable settings with renamed commands
 pub use settings::*;
 pub use token_management::*;
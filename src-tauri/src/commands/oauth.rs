@@ -0,0 +1,42 @@
+// OAuth Authorization Server Commands
+
+use crate::authorization_server::{AuthorizationServer, ConsentRequest, OAuthGrantInfo};
+use crate::error::{McpError, Result};
+use crate::AGGREGATOR;
+use std::sync::Arc;
+
+fn authorization_server() -> Result<Arc<AuthorizationServer>> {
+    AGGREGATOR
+        .lock()
+        .expect("Failed to acquire AGGREGATOR lock")
+        .as_ref()
+        .map(|aggregator| aggregator.authorization_server())
+        .ok_or_else(|| McpError::ServiceUnavailable("Aggregator is not running".to_string()))
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn list_oauth_consent_requests() -> Result<Vec<ConsentRequest>> {
+    Ok(authorization_server()?.pending_requests())
+}
+
+/// 批准时客户端获得 token_id 对应 Token 的权限
+#[tauri::command(rename_all = "snake_case")]
+pub async fn respond_oauth_consent(
+    request_id: String,
+    approve: bool,
+    token_id: Option<String>,
+) -> Result<()> {
+    authorization_server()?
+        .respond(&request_id, approve, token_id)
+        .await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn list_oauth_grants() -> Result<Vec<OAuthGrantInfo>> {
+    authorization_server()?.list_grants().await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn revoke_oauth_grant(id: String) -> Result<()> {
+    authorization_server()?.revoke_grant(&id).await
+}
@@ -0,0 +1,46 @@
+// Secret Store Commands
+
+use crate::declarative;
+use crate::error::Result;
+use crate::secrets::SecretInfo;
+use std::collections::HashSet;
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn list_secrets() -> Result<Vec<SecretInfo>> {
+    crate::wait_for_secret_store().await?.list().await
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn set_secret(name: String, value: String) -> Result<String> {
+    let name = name.trim();
+    crate::wait_for_secret_store()
+        .await?
+        .set(name, &value)
+        .await?;
+    Ok(crate::secrets::reference(name))
+}
+
+#[tauri::command(rename_all = "snake_case")]
+pub async fn delete_secret(name: String) -> Result<()> {
+    crate::wait_for_secret_store().await?.delete(&name).await
+}
+
+/// 把服务配置中明文的敏感 env / headers 移入密钥存储，返回迁移的数量
+///
+/// 声明式配置文件中的服务不迁移，否则下次同步时文件中的明文又会写回数据库
+#[tauri::command(rename_all = "snake_case")]
+pub async fn migrate_secrets() -> Result<usize> {
+    let secret_store = crate::wait_for_secret_store().await?;
+    let service_manager = crate::wait_for_service_manager().await?;
+    let managed: HashSet<String> = match crate::commands::config::configured_config_file() {
+        Some(path) => declarative::load(&path)?
+            .servers
+            .into_iter()
+            .map(|server| server.config.name)
+            .collect(),
+        None => HashSet::new(),
+    };
+    secret_store
+        .migrate_servers(&service_manager, &managed)
+        .await
+}
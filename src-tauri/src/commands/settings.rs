@@ -2,16 +2,14 @@
 
 use crate::config as config_mod;
 use crate::error::{McpError, Result};
-use crate::{build_main_tray, types, AGGREGATOR, MCP_CLIENT_MANAGER};
+use crate::{build_main_tray, types};
 use serde::Serialize;
-use std::sync::Arc;
 use tauri::Emitter;
 
 #[tauri::command(rename_all = "snake_case")]
 pub async fn get_settings(app: tauri::AppHandle) -> Result<serde_json::Value> {
     // Load configuration
-    let mut config = config_mod::AppConfig::load()
-        .map_err(|e| McpError::ConfigError(format!("Failed to load settings: {}", e)))?;
+    let mut config = (*config_mod::CONFIG_SERVICE.current()).clone();
 
     // Get actual autostart status from the system
     use tauri_plugin_autostart::ManagerExt;
@@ -43,6 +41,9 @@ pub async fn get_settings(app: tauri::AppHandle) -> Result<serde_json::Value> {
         timeout_seconds: u64,
         #[serde(default)]
         auth: bool,
+        #[serde(default)]
+        oauth: bool,
+        max_body_size: usize,
     }
 
     #[derive(Serialize)]
@@ -77,6 +78,18 @@ pub async fn get_settings(app: tauri::AppHandle) -> Result<serde_json::Value> {
         npm_registry: Option<String>,
         #[serde(default)]
         command_paths: std::collections::HashMap<String, String>,
+        #[serde(default)]
+        shell_path: Option<String>,
+        #[serde(default)]
+        shell_env_ttl: Option<u64>,
+        #[serde(default)]
+        tool_mirror_url: Option<String>,
+        #[serde(default)]
+        node_mirror_url: Option<String>,
+        #[serde(default)]
+        config_file: Option<String>,
+        #[serde(default)]
+        workspace: Option<String>,
     }
 
     #[derive(Serialize)]
@@ -93,6 +106,8 @@ pub async fn get_settings(app: tauri::AppHandle) -> Result<serde_json::Value> {
             max_connections: config.server.max_connections,
             timeout_seconds: config.server.timeout_seconds,
             auth: config.server.auth,
+            oauth: config.server.oauth,
+            max_body_size: config.server.max_body_size,
         },
         logging: config.logging.as_ref().map(|l| LoggingOut {
             level: l.level.clone(),
@@ -110,6 +125,12 @@ pub async fn get_settings(app: tauri::AppHandle) -> Result<serde_json::Value> {
             uv_index_url: s.uv_index_url.clone(),
             npm_registry: s.npm_registry.clone(),
             command_paths: s.command_paths.clone(),
+            shell_path: s.shell_path.clone(),
+            shell_env_ttl: s.shell_env_ttl,
+            tool_mirror_url: s.tool_mirror_url.clone(),
+            node_mirror_url: s.node_mirror_url.clone(),
+            config_file: s.config_file.clone(),
+            workspace: s.workspace.clone(),
         }),
     };
 
@@ -129,8 +150,7 @@ pub async fn save_settings(app: tauri::AppHandle, settings: serde_json::Value) -
     );
 
     // Load current config
-    let mut config = config_mod::AppConfig::load()
-        .map_err(|e| McpError::ConfigError(format!("Failed to load config: {}", e)))?;
+    let mut config = (*config_mod::CONFIG_SERVICE.current()).clone();
 
     // Snapshot old config before update
     let prev_config = config.clone();
@@ -167,6 +187,12 @@ pub async fn save_settings(app: tauri::AppHandle, settings: serde_json::Value) -
                 uv_index_url: None,
                 npm_registry: None,
                 command_paths: std::collections::HashMap::new(),
+                shell_path: None,
+                shell_env_ttl: None,
+                tool_mirror_url: None,
+                node_mirror_url: None,
+                config_file: None,
+                workspace: None,
             });
         }
         let settings_mut = config.settings
@@ -240,6 +266,51 @@ pub async fn save_settings(app: tauri::AppHandle, settings: serde_json::Value) -
             settings_mut.npm_registry = None;
         }
 
+        // Shell environment and tool download settings (settings page sends them nested under
+        // "settings")
+        let shell_obj = settings_obj
+            .get("settings")
+            .and_then(Value::as_object)
+            .unwrap_or(settings_obj);
+        if let Some(Value::String(shell_path)) = shell_obj.get("shell_path") {
+            settings_mut.shell_path = Some(shell_path.clone()).filter(|s| !s.trim().is_empty());
+        } else if let Some(Value::Null) = shell_obj.get("shell_path") {
+            settings_mut.shell_path = None;
+        }
+
+        if let Some(Value::Number(ttl)) = shell_obj.get("shell_env_ttl") {
+            settings_mut.shell_env_ttl = ttl.as_u64();
+        } else if let Some(Value::Null) = shell_obj.get("shell_env_ttl") {
+            settings_mut.shell_env_ttl = None;
+        }
+
+        if let Some(Value::String(mirror_url)) = shell_obj.get("tool_mirror_url") {
+            settings_mut.tool_mirror_url =
+                Some(mirror_url.trim().to_string()).filter(|s| !s.is_empty());
+        } else if let Some(Value::Null) = shell_obj.get("tool_mirror_url") {
+            settings_mut.tool_mirror_url = None;
+        }
+
+        if let Some(Value::String(mirror_url)) = shell_obj.get("node_mirror_url") {
+            settings_mut.node_mirror_url =
+                Some(mirror_url.trim().to_string()).filter(|s| !s.is_empty());
+        } else if let Some(Value::Null) = shell_obj.get("node_mirror_url") {
+            settings_mut.node_mirror_url = None;
+        }
+
+        if let Some(Value::String(config_file)) = shell_obj.get("config_file") {
+            settings_mut.config_file =
+                Some(config_file.trim().to_string()).filter(|s| !s.is_empty());
+        } else if let Some(Value::Null) = shell_obj.get("config_file") {
+            settings_mut.config_file = None;
+        }
+
+        if let Some(Value::String(workspace)) = shell_obj.get("workspace") {
+            settings_mut.workspace = Some(workspace.trim().to_string()).filter(|s| !s.is_empty());
+        } else if let Some(Value::Null) = shell_obj.get("workspace") {
+            settings_mut.workspace = None;
+        }
+
         // Command paths settings
         if let Some(Value::Object(cmd_paths)) = settings_obj.get("command_paths") {
             let mut new_command_paths = std::collections::HashMap::new();
@@ -301,18 +372,28 @@ pub async fn save_settings(app: tauri::AppHandle, settings: serde_json::Value) -
                     tracing::debug!("Updated timeout_seconds: {}", ts);
                 }
             }
+            if let Some(Value::Number(max_body)) = server_obj.get("max_body_size") {
+                if let Some(mb) = max_body.as_u64() {
+                    config.server.max_body_size = mb as usize;
+                    tracing::debug!("Updated max_body_size: {}", mb);
+                }
+            }
             if let Some(Value::Bool(auth)) = server_obj.get("auth") {
                 config.server.auth = *auth;
                 tracing::debug!("Updated auth: {}", auth);
             } else {
                 tracing::warn!("auth field not found in server config or not a boolean");
             }
+            if let Some(Value::Bool(oauth)) = server_obj.get("oauth") {
+                config.server.oauth = *oauth;
+                tracing::debug!("Updated oauth: {}", oauth);
+            }
         }
     }
 
-    // Save configuration
-    config
-        .save()
+    // Save configuration and notify subscribers (the aggregator applies server changes)
+    config_mod::CONFIG_SERVICE
+        .update(config.clone())
         .map_err(|e| McpError::ConfigError(format!("Failed to save config: {}", e)))?;
 
     // Post-save: detect tray visibility change and server restarts
@@ -324,73 +405,9 @@ pub async fn save_settings(app: tauri::AppHandle, settings: serde_json::Value) -
         .unwrap_or(true);
     let tray_changed = tray_old != tray_new;
 
-    let server_config_changed = prev_config.server.host != config.server.host
-        || prev_config.server.port != config.server.port
-        || prev_config.server.max_connections != config.server.max_connections
-        || prev_config.server.timeout_seconds != config.server.timeout_seconds
-        || prev_config.server.auth != config.server.auth;
-
-    if server_config_changed {
-        tracing::info!("Server configuration changed (restarting aggregator with new config)...");
-
-        // Stop existing aggregator
-        let aggregator_clone = {
-            let aggregator_guard = AGGREGATOR
-                .lock()
-                .expect("Failed to acquire AGGREGATOR lock");
-            (*aggregator_guard).clone()
-        };
-
-        if let Some(aggregator) = &aggregator_clone {
-            tracing::debug!("Shutting down existing aggregator...");
-            aggregator.trigger_shutdown().await;
-        }
-
-        // Wait for shutdown
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-        // Create new aggregator from latest config
-        let server_config = Arc::new(config.server.clone());
-
-        // Get TokenManager
-        let token_manager = crate::wait_for_token_manager().await?;
-
-        // Get service manager
-        let service_manager = crate::wait_for_service_manager().await?;
-
-        // Create new aggregator instance
-        tracing::debug!(
-            "Creating new aggregator with updated configuration (auth: {})",
-            server_config.auth
-        );
-        let new_aggregator = Arc::new(crate::aggregator::McpAggregator::new(
-            service_manager,
-            MCP_CLIENT_MANAGER.clone(),
-            server_config,
-            token_manager,
-            app.clone(),
-        ));
-
-        // Update global aggregator state
-        {
-            let mut aggregator_guard = AGGREGATOR
-                .lock()
-                .expect("Failed to acquire AGGREGATOR lock");
-            *aggregator_guard = Some(new_aggregator.clone());
-        }
-
-        // Restart aggregator
-        tracing::info!("Starting new aggregator with new configuration...");
-        if let Err(e) = new_aggregator.start().await {
-            tracing::error!("Failed to start new aggregator: {}", e);
-            return Err(McpError::InternalError(format!(
-                "Failed to start aggregator: {}",
-                e
-            )));
-        } else {
-            tracing::info!("Aggregator restarted successfully with new configuration");
-        }
-    }
+    let server_config_changed = config.server.requires_rebind(&prev_config.server)
+        || prev_config.server.auth != config.server.auth
+        || prev_config.server.oauth != config.server.oauth;
 
     // Handle tray changes
     if tray_changed {
@@ -418,7 +435,7 @@ pub async fn save_settings(app: tauri::AppHandle, settings: serde_json::Value) -
 
     if server_config_changed {
         Ok(format!(
-            "Settings saved successfully. Aggregator restarted on {}:{}",
+            "Settings saved successfully. Applying server settings on {}:{}",
             config.server.host, config.server.port
         ))
     } else {
@@ -469,10 +486,9 @@ pub async fn toggle_autostart(app: tauri::AppHandle) -> Result<String> {
 
 #[tauri::command(rename_all = "snake_case")]
 pub async fn get_language_preference() -> Result<Option<String>> {
-    let config = config_mod::AppConfig::load()
-        .map_err(|e| McpError::ConfigError(format!("Failed to load settings: {}", e)))?;
+    let config = config_mod::CONFIG_SERVICE.current();
 
-    Ok(config.settings.and_then(|s| s.language))
+    Ok(config.settings.as_ref().and_then(|s| s.language.clone()))
 }
 
 #[tauri::command(rename_all = "snake_case")]
@@ -486,8 +502,7 @@ pub async fn save_language_preference(app: tauri::AppHandle, language: String) -
     }
 
     // Load current config
-    let mut config = config_mod::AppConfig::load()
-        .map_err(|e| McpError::ConfigError(format!("Failed to load config: {}", e)))?;
+    let mut config = (*config_mod::CONFIG_SERVICE.current()).clone();
 
     // Update language preference
     {
@@ -505,6 +520,12 @@ pub async fn save_language_preference(app: tauri::AppHandle, language: String) -
                 uv_index_url: None,
                 npm_registry: None,
                 command_paths: Default::default(),
+                shell_path: None,
+                shell_env_ttl: None,
+                tool_mirror_url: None,
+                node_mirror_url: None,
+                config_file: None,
+                workspace: None,
             });
         } else {
             config.settings
@@ -515,8 +536,8 @@ pub async fn save_language_preference(app: tauri::AppHandle, language: String) -
     }
 
     // Save configuration
-    config
-        .save()
+    config_mod::CONFIG_SERVICE
+        .update(config)
         .map_err(|e| McpError::ConfigError(format!("Failed to save config: {}", e)))?;
 
     // Update tray menu to reflect new language (safe method)
@@ -585,3 +606,15 @@ pub async fn get_system_command_paths(command: String) -> Result<Vec<String>> {
 
     Ok(result)
 }
+
+/// 获取登录 Shell 环境变量，refresh 为 true 时忽略缓存重新加载
+///
+/// 除 PATH 等基础变量外只返回变量名，值被隐藏
+#[tauri::command(rename_all = "snake_case")]
+pub async fn get_shell_environment(
+    refresh: Option<bool>,
+) -> Result<crate::shell_environment::ShellEnvironmentSnapshot> {
+    let snapshot =
+        crate::shell_environment::ShellEnvironment::snapshot(refresh.unwrap_or(false)).await?;
+    Ok(snapshot.redacted())
+}
@@ -1,4 +1,5 @@
 use crate::error::Result;
+use crate::tool_manager::InstallOptions;
 use crate::types::ToolInfo;
 use std::sync::Arc;
 use tauri::State;
@@ -48,13 +49,17 @@ pub async fn install_all_tools(
     state.manager.install_all_tools().await
 }
 
-/// Install a specific tool
+/// Install a specific tool (optionally a given version, from a local archive, or roll back)
 #[tauri::command(rename_all = "snake_case")]
 pub async fn install_tool(
     state: State<'_, ToolManagerState>,
     tool_name: String,
+    options: Option<InstallOptions>,
 ) -> Result<()> {
-    state.manager.install_tool(&tool_name).await
+    state
+        .manager
+        .install_tool(&tool_name, &options.unwrap_or_default())
+        .await
 }
 
 /// Check Python runtime compatibility
@@ -6,8 +6,10 @@
 //! - Application global configuration
 
 pub mod file_manager;
+pub mod service;
 
 pub use file_manager::*;
+pub use service::*;
 
 // Re-export AppConfig for convenience
 pub use crate::AppConfig;
@@ -0,0 +1,163 @@
+// 进程内配置服务：保存当前配置，监听 config.json 的外部修改并通知订阅者
+
+use super::{ConfigError, Result};
+use crate::AppConfig;
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use std::path::Path;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::Duration;
+use tauri::Emitter;
+use tokio::sync::watch;
+
+/// 文件修改事件的合并时间
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 配置热加载失败时发送给前端的事件
+pub const CONFIG_ERROR_EVENT: &str = "config-error";
+/// 配置热加载成功时发送给前端的事件
+pub const CONFIG_RELOADED_EVENT: &str = "config-reloaded";
+
+pub static CONFIG_SERVICE: LazyLock<ConfigService> = LazyLock::new(ConfigService::new);
+
+pub struct ConfigService {
+    current: RwLock<Arc<AppConfig>>,
+    tx: watch::Sender<Arc<AppConfig>>,
+    watcher: Mutex<Option<Debouncer<RecommendedWatcher>>>,
+    /// config.json 无法读取时的错误，修复前拒绝保存，避免用默认配置覆盖原文件
+    load_error: RwLock<Option<String>>,
+}
+
+impl ConfigService {
+    fn new() -> Self {
+        let (config, load_error) = match AppConfig::load() {
+            Ok(config) => (config, None),
+            Err(e) => {
+                tracing::error!("Failed to load config, using defaults: {}", e);
+                (AppConfig::default(), Some(e.to_string()))
+            }
+        };
+        let config = Arc::new(config);
+        let (tx, _) = watch::channel(config.clone());
+        Self {
+            current: RwLock::new(config),
+            tx,
+            watcher: Mutex::new(None),
+            load_error: RwLock::new(load_error),
+        }
+    }
+
+    /// 当前生效的配置
+    pub fn current(&self) -> Arc<AppConfig> {
+        self.current
+            .read()
+            .expect("Failed to acquire config lock")
+            .clone()
+    }
+
+    /// 订阅配置变更
+    pub fn subscribe(&self) -> watch::Receiver<Arc<AppConfig>> {
+        self.tx.subscribe()
+    }
+
+    /// 校验并保存配置，然后通知订阅者；config.json 加载失败且尚未修复时拒绝保存
+    pub fn update(&self, config: AppConfig) -> Result<()> {
+        if let Some(e) = self
+            .load_error
+            .read()
+            .expect("Failed to acquire config lock")
+            .as_ref()
+        {
+            return Err(ConfigError::Invalid(format!(
+                "{} could not be loaded ({}), fix or remove it before saving settings",
+                AppConfig::config_path().display(),
+                e
+            )));
+        }
+        config.validate()?;
+        config.save()?;
+        self.publish(config);
+        Ok(())
+    }
+
+    /// 重新读取配置文件，内容无效时保留当前配置并返回错误；返回配置是否有变化
+    pub fn reload(&self) -> Result<bool> {
+        let path = AppConfig::config_path();
+        let content = std::fs::read_to_string(&path)?;
+        let config: AppConfig = serde_json::from_str(&content)
+            .map_err(|e| ConfigError::Invalid(format!("{} is not valid: {}", path.display(), e)))?;
+        config.validate()?;
+        // 文件已修复，允许再次保存
+        self.load_error
+            .write()
+            .expect("Failed to acquire config lock")
+            .take();
+
+        let changed = serde_json::to_value(&config).ok()
+            != serde_json::to_value(self.current().as_ref()).ok();
+        if changed {
+            tracing::info!("🔄 Reloaded configuration from {}", path.display());
+            self.publish(config);
+        }
+        Ok(changed)
+    }
+
+    fn publish(&self, config: AppConfig) {
+        let config = Arc::new(config);
+        *self.current.write().expect("Failed to acquire config lock") = config.clone();
+        self.tx.send_replace(config);
+    }
+
+    /// 监听 config.json，合法的修改会立即生效
+    pub fn watch(&self, app: tauri::AppHandle) -> Result<()> {
+        let path = AppConfig::config_path();
+        let debouncer = watch_file(&path, move || match CONFIG_SERVICE.reload() {
+            Ok(true) => {
+                let _ = app.emit(CONFIG_RELOADED_EVENT, ());
+            }
+            Ok(false) => {}
+            Err(e) => {
+                tracing::error!("❌ Rejected config change: {}", e);
+                let _ = app.emit(CONFIG_ERROR_EVENT, e.to_string());
+            }
+        })?;
+
+        *self.watcher.lock().expect("Failed to acquire watcher lock") = Some(debouncer);
+        tracing::info!("👀 Watching {} for changes", path.display());
+        Ok(())
+    }
+}
+
+/// 监听文件所在目录，文件被编辑器替换时也能收到事件；返回的 Debouncer 被丢弃时停止监听
+pub(crate) fn watch_file(
+    path: &Path,
+    on_change: impl Fn() + Send + 'static,
+) -> Result<Debouncer<RecommendedWatcher>> {
+    let dir = path
+        .parent()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| ConfigError::Invalid(format!("{} has no parent", path.display())))?;
+
+    let file_name = path.file_name().map(|name| name.to_os_string());
+    let mut debouncer = new_debouncer(WATCH_DEBOUNCE, move |res: DebounceEventResult| {
+        let events = match res {
+            Ok(events) => events,
+            Err(e) => {
+                tracing::warn!("File watcher error: {}", e);
+                return;
+            }
+        };
+        if events
+            .iter()
+            .any(|event| event.path.file_name() == file_name.as_deref())
+        {
+            on_change();
+        }
+    })
+    .map_err(|e| ConfigError::Invalid(format!("Failed to create file watcher: {}", e)))?;
+    debouncer
+        .watcher()
+        .watch(&dir, RecursiveMode::NonRecursive)
+        .map_err(|e| ConfigError::Invalid(format!("Failed to watch {}: {}", dir.display(), e)))?;
+    Ok(debouncer)
+}
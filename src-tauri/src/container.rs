@@ -0,0 +1,371 @@
+// 以容器方式运行 MCP 服务（docker / podman run -i）
+
+use crate::error::{McpError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+/// 容器标签，用于找到属于某个服务的容器
+const SERVER_LABEL: &str = "mcprouter.server";
+
+/// 容器运行时
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ContainerRuntime {
+    /// 优先 docker，其次 podman
+    #[default]
+    Auto,
+    Docker,
+    Podman,
+}
+
+/// 镜像拉取策略
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum PullPolicy {
+    Always,
+    /// 本地不存在时拉取
+    #[default]
+    Missing,
+    Never,
+}
+
+/// 容器启动配置；服务的 env 通过 `-e` 传入容器，args 作为容器命令参数
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContainerConfig {
+    #[serde(default)]
+    pub runtime: ContainerRuntime,
+    pub image: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    /// 挂载，格式为 `host:container[:ro]`
+    #[serde(default)]
+    pub mounts: Vec<String>,
+    /// 网络模式，例如 `none`、`bridge`、`host`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    #[serde(default)]
+    pub pull_policy: PullPolicy,
+    /// 其他 run 参数，原样传给运行时
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+/// `docker run` 中需要取值的选项
+const VALUE_FLAGS: &[&str] = &[
+    "--name",
+    "-w",
+    "--workdir",
+    "-u",
+    "--user",
+    "--mount",
+    "--entrypoint",
+    "-p",
+    "--publish",
+    "--platform",
+    "--memory",
+    "-m",
+    "--cpus",
+    "--add-host",
+    "--label",
+    "-l",
+];
+
+impl ContainerConfig {
+    /// 完整镜像引用
+    pub fn image_ref(&self) -> String {
+        match self.tag.as_deref().filter(|t| !t.is_empty()) {
+            Some(tag) => format!("{}:{}", self.image, tag),
+            None => self.image.clone(),
+        }
+    }
+
+    /// 从 `docker run ...` / `podman run ...` 命令解析容器配置
+    ///
+    /// 返回容器配置、容器命令参数以及命令中内联的环境变量（`-e KEY=VALUE`）。
+    /// 命令不是容器运行命令时返回 None。
+    pub fn from_run_command(
+        command: &str,
+        args: &[String],
+    ) -> Option<(ContainerConfig, Vec<String>, HashMap<String, String>)> {
+        let runtime = match Path::new(command).file_stem()?.to_str()? {
+            "docker" => ContainerRuntime::Docker,
+            "podman" => ContainerRuntime::Podman,
+            _ => return None,
+        };
+        let mut iter = args.iter();
+        if iter.next().map(String::as_str) != Some("run") {
+            return None;
+        }
+
+        let mut mounts = Vec::new();
+        let mut network = None;
+        let mut pull_policy = PullPolicy::default();
+        let mut extra_args = Vec::new();
+        let mut env = HashMap::new();
+        let mut image = None;
+
+        while let Some(arg) = iter.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with('-') => (flag, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            if !flag.starts_with('-') {
+                image = Some(arg.clone());
+                break;
+            }
+            let mut value = || inline_value.clone().or_else(|| iter.next().cloned());
+            match flag {
+                // 由路由器统一添加
+                "-i" | "--interactive" | "--rm" | "-t" | "--tty" | "-it" | "-ti" => {}
+                "-e" | "--env" => {
+                    if let Some(entry) = value() {
+                        match entry.split_once('=') {
+                            Some((key, val)) => env.insert(key.to_string(), val.to_string()),
+                            None => env.insert(entry, String::new()),
+                        };
+                    }
+                }
+                "-v" | "--volume" => mounts.extend(value()),
+                "--network" | "--net" => network = value(),
+                "--pull" => pull_policy = value().and_then(|v| v.parse().ok()).unwrap_or_default(),
+                _ if VALUE_FLAGS.contains(&flag) && inline_value.is_none() => {
+                    extra_args.push(arg.clone());
+                    extra_args.extend(iter.next().cloned());
+                }
+                _ => extra_args.push(arg.clone()),
+            }
+        }
+
+        let image = image?;
+        // 仅拆分最后一个 '/' 之后的标签，避免把 registry 端口当作标签
+        let (image, tag) = match image.rsplit_once(':') {
+            Some((name, tag)) if !tag.contains('/') && !name.ends_with('/') => {
+                (name.to_string(), Some(tag.to_string()))
+            }
+            _ => (image, None),
+        };
+
+        Some((
+            ContainerConfig {
+                runtime,
+                image,
+                tag,
+                mounts,
+                network,
+                pull_policy,
+                extra_args,
+            },
+            iter.cloned().collect(),
+            env,
+        ))
+    }
+}
+
+/// 查找容器运行时可执行文件
+pub fn resolve_runtime(runtime: ContainerRuntime) -> Result<PathBuf> {
+    let candidates: &[&str] = match runtime {
+        ContainerRuntime::Auto => &["docker", "podman"],
+        ContainerRuntime::Docker => &["docker"],
+        ContainerRuntime::Podman => &["podman"],
+    };
+    candidates
+        .iter()
+        .find_map(|name| which::which(name).ok())
+        .ok_or_else(|| {
+            McpError::InvalidConfiguration(format!(
+                "Container runtime not found in PATH: {}",
+                candidates.join(" / ")
+            ))
+        })
+}
+
+/// 本次启动使用的容器名（每次启动唯一，避免清理旧容器时误删新容器）
+pub fn container_name(server_name: &str) -> String {
+    let sanitized: String = server_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let suffix = uuid::Uuid::new_v4().simple().to_string();
+    format!("mcprouter-{}-{}", sanitized, &suffix[..8])
+}
+
+/// 构建 `run -i` 参数；`env_keys` 中的变量值由运行时 CLI 进程的环境传入，不出现在命令行中
+pub fn run_args<'a>(
+    container: &ContainerConfig,
+    server_name: &str,
+    name: &str,
+    args: &[String],
+    env_keys: impl Iterator<Item = &'a String>,
+) -> Vec<String> {
+    let mut run_args: Vec<String> = vec![
+        "run".into(),
+        "-i".into(),
+        "--rm".into(),
+        "--name".into(),
+        name.to_string(),
+        "--label".into(),
+        format!("{}={}", SERVER_LABEL, server_name),
+        "--pull".into(),
+        container.pull_policy.to_string(),
+    ];
+    if let Some(network) = container.network.as_deref().filter(|n| !n.is_empty()) {
+        run_args.extend(["--network".into(), network.to_string()]);
+    }
+    for mount in &container.mounts {
+        run_args.extend(["-v".into(), mount.clone()]);
+    }
+    for key in env_keys {
+        run_args.extend(["-e".into(), key.clone()]);
+    }
+    run_args.extend(container.extra_args.iter().cloned());
+    run_args.push(container.image_ref());
+    run_args.extend(args.iter().cloned());
+    run_args
+}
+
+/// 删除容器的命令（进程退出或服务停止后执行）
+pub fn remove_command(runtime: &Path, name: &str) -> Command {
+    let mut command = Command::new(runtime);
+    command.args(["rm", "-f", name]);
+    command
+}
+
+/// 删除属于该服务的残留容器（例如应用异常退出后遗留的容器）
+pub async fn remove_server_containers(runtime: &Path, server_name: &str) {
+    let output = Command::new(runtime)
+        .args([
+            "ps",
+            "-aq",
+            "--filter",
+            &format!("label={}={}", SERVER_LABEL, server_name),
+        ])
+        .output()
+        .await;
+    let ids: Vec<String> = match output {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .map(str::to_string)
+            .collect(),
+        _ => return,
+    };
+    if ids.is_empty() {
+        return;
+    }
+
+    tracing::info!(
+        "Removing {} stale container(s) of server '{}'",
+        ids.len(),
+        server_name
+    );
+    if let Err(e) = Command::new(runtime)
+        .arg("rm")
+        .arg("-f")
+        .args(&ids)
+        .output()
+        .await
+    {
+        tracing::warn!(
+            "Failed to remove stale containers of server '{}': {}",
+            server_name,
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_from_run_command() {
+        let args = strings(&[
+            "run",
+            "-i",
+            "--rm",
+            "-e",
+            "GITHUB_TOKEN",
+            "--env=LOG_LEVEL=debug",
+            "-v",
+            "/data:/data:ro",
+            "--network",
+            "none",
+            "-w",
+            "/app",
+            "registry.local:5000/mcp/github:1.2",
+            "--read-only",
+        ]);
+        let (container, args, env) = ContainerConfig::from_run_command("docker", &args).unwrap();
+        assert_eq!(container.runtime, ContainerRuntime::Docker);
+        assert_eq!(container.image, "registry.local:5000/mcp/github");
+        assert_eq!(container.tag.as_deref(), Some("1.2"));
+        assert_eq!(container.mounts, vec!["/data:/data:ro"]);
+        assert_eq!(container.network.as_deref(), Some("none"));
+        assert_eq!(container.extra_args, vec!["-w", "/app"]);
+        assert_eq!(args, vec!["--read-only"]);
+        assert_eq!(env.get("GITHUB_TOKEN").map(String::as_str), Some(""));
+        assert_eq!(env.get("LOG_LEVEL").map(String::as_str), Some("debug"));
+
+        let (container, _, _) = ContainerConfig::from_run_command(
+            "podman",
+            &strings(&["run", "registry.local:5000/img"]),
+        )
+        .unwrap();
+        assert_eq!(container.image_ref(), "registry.local:5000/img");
+        assert!(ContainerConfig::from_run_command("npx", &strings(&["run", "x"])).is_none());
+    }
+
+    #[test]
+    fn test_run_args_forward_env_keys_only() {
+        let container = ContainerConfig {
+            runtime: ContainerRuntime::Auto,
+            image: "mcp/fetch".to_string(),
+            tag: None,
+            mounts: vec![],
+            network: None,
+            pull_policy: PullPolicy::Never,
+            extra_args: vec![],
+        };
+        let env: HashMap<String, String> = [("API_KEY".to_string(), "secret".to_string())].into();
+        let args = run_args(&container, "fetch", "mcprouter-fetch-1", &[], env.keys());
+        assert!(args.windows(2).any(|w| w == ["-e", "API_KEY"]));
+        assert!(args.windows(2).any(|w| w == ["--pull", "never"]));
+        assert!(!args.iter().any(|a| a.contains("secret")));
+        assert_eq!(args.last().map(String::as_str), Some("mcp/fetch"));
+    }
+}
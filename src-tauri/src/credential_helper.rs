@@ -0,0 +1,404 @@
+// HTTP 服务的凭据助手：运行命令获取短期令牌，缓存后作为请求头注入
+
+use crate::error::{McpError, Result};
+use crate::types::{McpServerConfig, ServiceTransport};
+use rmcp::model::ClientJsonRpcMessage;
+use rmcp::transport::common::client_side_sse::BoxedSseResponse;
+use rmcp::transport::streamable_http_client::{
+    StreamableHttpClient, StreamableHttpError, StreamableHttpPostResponse,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+/// 未设置 ttl_seconds 且输出中没有过期时间时的缓存时间
+pub const DEFAULT_TTL_SECS: u64 = 300;
+
+/// 助手命令的最长运行时间
+const HELPER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 在过期前提前刷新，避免请求途中令牌失效
+const REFRESH_MARGIN: Duration = Duration::from_secs(30);
+
+/// 令牌在 format 中的占位符
+const TOKEN_PLACEHOLDER: &str = "{token}";
+
+fn default_header() -> String {
+    "Authorization".to_string()
+}
+
+fn default_format() -> String {
+    format!("Bearer {}", TOKEN_PLACEHOLDER)
+}
+
+/// 凭据助手配置
+///
+/// 命令的标准输出可以是令牌本身，也可以是包含 `access_token` / `accessToken` / `token`
+/// 和可选 `expires_in`（秒）的 JSON 对象
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CredentialHelper {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 注入的请求头名称
+    #[serde(default = "default_header")]
+    pub header: String,
+    /// 请求头取值模板，`{token}` 替换为命令输出的令牌
+    #[serde(default = "default_format")]
+    pub format: String,
+    /// 缓存秒数，优先于输出中的 expires_in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_seconds: Option<u64>,
+}
+
+struct CachedCredential {
+    value: String,
+    expires_at: Instant,
+}
+
+impl CachedCredential {
+    fn is_fresh(&self) -> bool {
+        Instant::now() + REFRESH_MARGIN < self.expires_at
+    }
+}
+
+type CredentialSlot = Arc<tokio::sync::Mutex<Option<CachedCredential>>>;
+
+/// HTTP 连接的鉴权状态
+struct ConnectionAuth {
+    /// 请求是否带有凭据助手或 OAuth 令牌
+    authenticated: bool,
+    /// 下游是否返回过 401
+    unauthorized: Arc<AtomicBool>,
+}
+
+/// 按服务缓存助手输出，并记录 HTTP 服务配置以便刷新凭据后重连
+#[derive(Default)]
+pub struct CredentialCache {
+    entries: std::sync::Mutex<HashMap<String, CredentialSlot>>,
+    servers: std::sync::Mutex<HashMap<String, McpServerConfig>>,
+    connections: std::sync::Mutex<HashMap<String, ConnectionAuth>>,
+}
+
+impl CredentialCache {
+    fn slot(&self, server_name: &str) -> CredentialSlot {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(entries.entry(server_name.to_string()).or_default())
+    }
+
+    /// 返回 (请求头名称, 取值)，缓存有效时不重新运行命令
+    pub async fn header(
+        &self,
+        server_name: &str,
+        helper: &CredentialHelper,
+    ) -> Result<(String, String)> {
+        // 持有该服务的锁运行助手，同一服务同一时间只运行一次，不阻塞其他服务
+        let slot = self.slot(server_name);
+        let mut entry = slot.lock().await;
+        if let Some(cached) = entry.as_ref().filter(|c| c.is_fresh()) {
+            return Ok((helper.header.clone(), cached.value.clone()));
+        }
+
+        let (token, expires_in) = run_helper(server_name, helper).await?;
+        let ttl = helper
+            .ttl_seconds
+            .map(Duration::from_secs)
+            .or(expires_in)
+            .unwrap_or(Duration::from_secs(DEFAULT_TTL_SECS));
+        let value = helper.format.replace(TOKEN_PLACEHOLDER, &token);
+        *entry = Some(CachedCredential {
+            value: value.clone(),
+            expires_at: Instant::now() + ttl,
+        });
+        tracing::debug!(
+            "Refreshed credential for server '{}', valid for {}s",
+            server_name,
+            ttl.as_secs()
+        );
+        Ok((helper.header.clone(), value))
+    }
+
+    /// 丢弃缓存的凭据，下次连接时重新运行助手
+    pub async fn invalidate(&self, server_name: &str) {
+        *self.slot(server_name).lock().await = None;
+    }
+
+    /// 缓存的凭据是否已过期（没有缓存时返回 false）
+    pub async fn is_expired(&self, server_name: &str) -> bool {
+        self.slot(server_name)
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|c| !c.is_fresh())
+    }
+
+    /// 包装新连接使用的客户端，记录下游返回的 401；authenticated 表示请求带有凭据
+    pub fn track(
+        &self,
+        server_name: &str,
+        client: reqwest::Client,
+        authenticated: bool,
+    ) -> TrackedHttpClient {
+        let unauthorized = Arc::new(AtomicBool::new(false));
+        self.connections
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(
+                server_name.to_string(),
+                ConnectionAuth {
+                    authenticated,
+                    unauthorized: unauthorized.clone(),
+                },
+            );
+        TrackedHttpClient {
+            inner: client,
+            unauthorized,
+        }
+    }
+
+    /// 最近一次连接是否收到过 HTTP 401
+    pub fn was_unauthorized(&self, server_name: &str) -> bool {
+        self.connections
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(server_name)
+            .is_some_and(|auth| auth.unauthorized.load(Ordering::Relaxed))
+    }
+
+    /// 带凭据的连接是否被下游以 HTTP 401 拒绝，此时请求未被执行，可以刷新凭据后重试
+    pub fn credential_rejected(&self, server_name: &str) -> bool {
+        self.connections
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(server_name)
+            .is_some_and(|auth| auth.authenticated && auth.unauthorized.load(Ordering::Relaxed))
+    }
+
+    /// 记录 HTTP 服务配置（凭据助手或 OAuth 令牌刷新后用它重连），其他服务移除；
+    /// 同时清除上次连接的鉴权状态
+    pub fn register(&self, config: &McpServerConfig) {
+        self.connections
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&config.name);
+        let mut servers = self.servers.lock().unwrap_or_else(|e| e.into_inner());
+        if config.transport == ServiceTransport::Http {
+            servers.insert(config.name.clone(), config.clone());
+        } else {
+            servers.remove(&config.name);
+        }
+    }
+
+    /// 已记录的 HTTP 服务配置
+    pub fn server_config(&self, server_name: &str) -> Option<McpServerConfig> {
+        self.servers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(server_name)
+            .cloned()
+    }
+}
+
+/// 包装 reqwest 客户端，根据真实的 HTTP 状态码记录 401
+#[derive(Clone)]
+pub struct TrackedHttpClient {
+    inner: reqwest::Client,
+    unauthorized: Arc<AtomicBool>,
+}
+
+impl TrackedHttpClient {
+    fn observe<T>(
+        &self,
+        result: std::result::Result<T, StreamableHttpError<reqwest::Error>>,
+    ) -> std::result::Result<T, StreamableHttpError<reqwest::Error>> {
+        let unauthorized = match &result {
+            Err(StreamableHttpError::AuthRequired(_)) => true,
+            Err(StreamableHttpError::Client(e)) => {
+                e.status() == Some(reqwest::StatusCode::UNAUTHORIZED)
+            }
+            _ => false,
+        };
+        if unauthorized {
+            self.unauthorized.store(true, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
+impl StreamableHttpClient for TrackedHttpClient {
+    type Error = reqwest::Error;
+
+    async fn post_message(
+        &self,
+        uri: Arc<str>,
+        message: ClientJsonRpcMessage,
+        session_id: Option<Arc<str>>,
+        auth_header: Option<String>,
+    ) -> std::result::Result<StreamableHttpPostResponse, StreamableHttpError<Self::Error>> {
+        let result = self
+            .inner
+            .post_message(uri, message, session_id, auth_header)
+            .await;
+        self.observe(result)
+    }
+
+    async fn delete_session(
+        &self,
+        uri: Arc<str>,
+        session_id: Arc<str>,
+        auth_header: Option<String>,
+    ) -> std::result::Result<(), StreamableHttpError<Self::Error>> {
+        let result = self
+            .inner
+            .delete_session(uri, session_id, auth_header)
+            .await;
+        self.observe(result)
+    }
+
+    async fn get_stream(
+        &self,
+        uri: Arc<str>,
+        session_id: Arc<str>,
+        last_event_id: Option<String>,
+        auth_header: Option<String>,
+    ) -> std::result::Result<BoxedSseResponse, StreamableHttpError<Self::Error>> {
+        let result = self
+            .inner
+            .get_stream(uri, session_id, last_event_id, auth_header)
+            .await;
+        self.observe(result)
+    }
+}
+
+/// 运行助手命令，返回令牌和输出中的有效期
+async fn run_helper(
+    server_name: &str,
+    helper: &CredentialHelper,
+) -> Result<(String, Option<Duration>)> {
+    let mut command = Command::new(&helper.command);
+    command
+        .args(&helper.args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // 助手通常是 gcloud / az 之类的 CLI，需要登录 Shell 的 PATH
+    if let Ok(shell_env) = crate::shell_environment::ShellEnvironment::load_environment().await {
+        command.envs(shell_env);
+    }
+
+    let output = tokio::time::timeout(HELPER_TIMEOUT, command.output())
+        .await
+        .map_err(|_| {
+            McpError::TimeoutError(format!(
+                "Credential helper for '{}' timed out after {}s",
+                server_name,
+                HELPER_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(|e| {
+            McpError::ProcessError(format!(
+                "Failed to run credential helper '{}' for '{}': {}",
+                helper.command, server_name, e
+            ))
+        })?;
+
+    if !output.status.success() {
+        return Err(McpError::AuthError(format!(
+            "Credential helper for '{}' exited with {}: {}",
+            server_name,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    parse_output(&String::from_utf8_lossy(&output.stdout)).ok_or_else(|| {
+        McpError::AuthError(format!(
+            "Credential helper for '{}' did not print a token",
+            server_name
+        ))
+    })
+}
+
+/// 解析助手输出：纯文本令牌或 JSON 对象
+fn parse_output(stdout: &str) -> Option<(String, Option<Duration>)> {
+    let stdout = stdout.trim();
+    if let Ok(serde_json::Value::Object(object)) = serde_json::from_str(stdout) {
+        let token = ["access_token", "accessToken", "token"]
+            .iter()
+            .find_map(|key| object.get(*key).and_then(|v| v.as_str()))
+            .filter(|token| !token.is_empty())?;
+        let expires_in = object
+            .get("expires_in")
+            .and_then(|v| v.as_u64())
+            .map(Duration::from_secs);
+        return Some((token.to_string(), expires_in));
+    }
+    (!stdout.is_empty()).then(|| (stdout.to_string(), None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_output() {
+        assert_eq!(
+            parse_output("ya29.token\n"),
+            Some(("ya29.token".to_string(), None))
+        );
+        assert_eq!(
+            parse_output(r#"{"access_token": "abc", "expires_in": 3599}"#),
+            Some(("abc".to_string(), Some(Duration::from_secs(3599))))
+        );
+        assert_eq!(
+            parse_output(r#"{"accessToken": "xyz"}"#),
+            Some(("xyz".to_string(), None))
+        );
+        assert_eq!(parse_output(r#"{"error": "not logged in"}"#), None);
+        assert_eq!(parse_output("  \n"), None);
+    }
+
+    #[test]
+    fn test_helper_defaults() {
+        let helper: CredentialHelper = serde_json::from_str(
+            r#"{"command": "gcloud", "args": ["auth", "print-access-token"]}"#,
+        )
+        .unwrap();
+        assert_eq!(helper.header, "Authorization");
+        assert_eq!(helper.format.replace(TOKEN_PLACEHOLDER, "t"), "Bearer t");
+        assert_eq!(helper.ttl_seconds, None);
+    }
+
+    #[test]
+    fn test_credential_rejected_only_on_authenticated_401() {
+        use rmcp::transport::streamable_http_client::AuthRequiredError;
+
+        let cache = CredentialCache::default();
+        let client = cache.track("a", reqwest::Client::new(), true);
+        let _ = client.observe::<()>(Err(StreamableHttpError::UnexpectedEndOfStream));
+        assert!(!cache.credential_rejected("a"));
+        let _ = client.observe::<()>(Err(StreamableHttpError::AuthRequired(AuthRequiredError {
+            www_authenticate_header: "Bearer".to_string(),
+        })));
+        assert!(cache.credential_rejected("a"));
+
+        // 重连后使用新的状态
+        cache.track("a", reqwest::Client::new(), true);
+        assert!(!cache.credential_rejected("a"));
+
+        let anonymous = cache.track("b", reqwest::Client::new(), false);
+        let _ =
+            anonymous.observe::<()>(Err(StreamableHttpError::AuthRequired(AuthRequiredError {
+                www_authenticate_header: "Bearer".to_string(),
+            })));
+        assert!(!cache.credential_rejected("b"));
+        assert!(cache.was_unauthorized("b"));
+        assert!(!cache.credential_rejected("unknown"));
+        assert!(!cache.was_unauthorized("unknown"));
+    }
+}
@@ -0,0 +1,755 @@
+// 声明式配置文件：用 JSON / TOML / YAML 描述服务、工具开关和 Token 授权，
+// 与数据库对比后增删改，未应用时作为漂移报告
+
+use crate::error::{McpError, Result};
+use crate::mcp_manager::McpServerManager;
+use crate::token_manager::TokenManager;
+use crate::types::{McpServerConfig, Token};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+/// 配置文件修改后自动同步成功时发送给前端的事件，附带同步报告
+pub const CONFIG_FILE_RECONCILED_EVENT: &str = "config-file-reconciled";
+/// 配置文件监听或自动同步失败时发送给前端的事件
+pub const CONFIG_FILE_ERROR_EVENT: &str = "config-file-error";
+
+/// 配置文件格式，按扩展名判断
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("toml") => Self::Toml,
+            Some("yaml") | Some("yml") => Self::Yaml,
+            _ => Self::Json,
+        }
+    }
+}
+
+/// 解析后的声明式配置
+#[derive(Debug, Clone, Default)]
+pub struct DeclarativeConfig {
+    /// 删除文件中未声明的服务和 Token
+    pub prune: bool,
+    pub servers: Vec<DeclaredServer>,
+    pub tokens: BTreeMap<String, DeclaredToken>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DeclaredServer {
+    pub config: McpServerConfig,
+    /// 工具启用状态，未列出的工具保持不变
+    pub tools: BTreeMap<String, bool>,
+}
+
+/// 声明的 Token，值不写入文件：新建时生成，已有 Token 保留原值
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DeclaredToken {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 过期时间（Unix 秒）
+    #[serde(default)]
+    pub expires_at: Option<u64>,
+    #[serde(default)]
+    pub tools: Vec<String>,
+    #[serde(default)]
+    pub resources: Vec<String>,
+    #[serde(default)]
+    pub prompts: Vec<String>,
+    #[serde(default)]
+    pub prompt_templates: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl DeclaredToken {
+    /// 授权列表，(resource_type, resource_path)
+    fn permissions(&self) -> BTreeSet<(String, String)> {
+        [
+            ("tool", &self.tools),
+            ("resource", &self.resources),
+            ("prompt", &self.prompts),
+            ("prompt_template", &self.prompt_templates),
+        ]
+        .into_iter()
+        .flat_map(|(kind, paths)| {
+            paths
+                .iter()
+                .map(move |path| (kind.to_string(), path.to_string()))
+        })
+        .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawConfig {
+    #[serde(default)]
+    prune: bool,
+    #[serde(default)]
+    servers: BTreeMap<String, Value>,
+    #[serde(default)]
+    tokens: BTreeMap<String, DeclaredToken>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Server,
+    Tool,
+    Token,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeAction {
+    Add,
+    Update,
+    Remove,
+}
+
+/// 配置文件与数据库之间的一处差异
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub kind: ChangeKind,
+    /// 服务名、Token 名，工具为 `server/tool`
+    pub name: String,
+    pub action: ChangeAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// 应用失败的原因
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 新建 Token 生成的值，只在应用后的报告中出现
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconcileReport {
+    pub path: String,
+    /// 只检查漂移，不修改数据库
+    pub dry_run: bool,
+    pub changes: Vec<ConfigChange>,
+}
+
+impl ReconcileReport {
+    /// 是否存在未同步的差异
+    pub fn has_drift(&self) -> bool {
+        !self.changes.is_empty()
+    }
+}
+
+/// 数据库中的当前状态
+#[derive(Debug, Clone, Default)]
+pub struct CurrentState {
+    pub servers: Vec<McpServerConfig>,
+    /// (server, tool, enabled)
+    pub tool_flags: Vec<(String, String, bool)>,
+    pub tokens: Vec<(Token, BTreeSet<(String, String)>)>,
+}
+
+/// 同步需要执行的操作
+#[derive(Debug, Clone)]
+enum Action {
+    AddServer(Box<McpServerConfig>),
+    UpdateServer(Box<McpServerConfig>),
+    RemoveServer(String),
+    SetTool {
+        server: String,
+        tool: String,
+        enabled: bool,
+    },
+    AddToken(String, DeclaredToken),
+    UpdateToken {
+        id: String,
+        name: String,
+        token: DeclaredToken,
+        permissions_changed: bool,
+    },
+    RemoveToken {
+        id: String,
+        name: String,
+    },
+}
+
+/// 解析配置文件内容
+pub fn parse(content: &str, format: ConfigFormat) -> Result<DeclarativeConfig> {
+    let invalid = |e: String| McpError::InvalidConfiguration(format!("Invalid config file: {}", e));
+    let value: Value = match format {
+        ConfigFormat::Json => serde_json::from_str(content).map_err(|e| invalid(e.to_string()))?,
+        ConfigFormat::Toml => toml::from_str(content).map_err(|e| invalid(e.to_string()))?,
+        ConfigFormat::Yaml => {
+            serde_yaml_ng::from_str(content).map_err(|e| invalid(e.to_string()))?
+        }
+    };
+    // 空文件
+    if value.is_null() {
+        return Ok(DeclarativeConfig::default());
+    }
+    let raw: RawConfig = serde_json::from_value(value).map_err(|e| invalid(e.to_string()))?;
+
+    let servers = raw
+        .servers
+        .into_iter()
+        .map(|(name, value)| parse_server(name, value))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(DeclarativeConfig {
+        prune: raw.prune,
+        servers,
+        tokens: raw.tokens,
+    })
+}
+
+/// 读取并解析配置文件
+pub fn load(path: &Path) -> Result<DeclarativeConfig> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| McpError::ConfigError(format!("Failed to read {}: {}", path.display(), e)))?;
+    parse(&content, ConfigFormat::from_path(path))
+}
+
+/// 服务以名称为键，`enabled` 默认为 true，未指定 `type` 时按是否有 `url` 推断
+fn parse_server(name: String, value: Value) -> Result<DeclaredServer> {
+    let Value::Object(mut obj) = value else {
+        return Err(McpError::InvalidConfiguration(format!(
+            "Server '{}' must be a table",
+            name
+        )));
+    };
+    let tools = match obj.remove("tools") {
+        Some(tools) => serde_json::from_value(tools).map_err(|e| {
+            McpError::InvalidConfiguration(format!("Invalid tools of server '{}': {}", name, e))
+        })?,
+        None => BTreeMap::new(),
+    };
+    obj.insert("name".to_string(), Value::String(name.clone()));
+    obj.entry("enabled").or_insert(Value::Bool(true));
+    if !obj.contains_key("type") {
+        let transport = if obj.contains_key("url") {
+            "http"
+        } else {
+            "stdio"
+        };
+        obj.insert("type".to_string(), Value::String(transport.to_string()));
+    }
+
+    let mut config: McpServerConfig = serde_json::from_value(Value::Object(obj))
+        .map_err(|e| McpError::InvalidConfiguration(format!("Invalid server '{}': {}", name, e)))?;
+    config.clean_fields();
+    Ok(DeclaredServer { config, tools })
+}
+
+/// 去掉空值、空字符串、空列表和空表，字符串去除首尾空白，使未填写与填写空值等价
+fn normalise(value: Value) -> Value {
+    match value {
+        Value::String(s) => Value::String(s.trim().to_string()),
+        Value::Array(items) => Value::Array(items.into_iter().map(normalise).collect()),
+        Value::Object(obj) => Value::Object(
+            obj.into_iter()
+                .map(|(key, value)| (key, normalise(value)))
+                .filter(|(_, value)| match value {
+                    Value::Null => false,
+                    Value::String(s) => !s.is_empty(),
+                    Value::Array(items) => !items.is_empty(),
+                    Value::Object(obj) => !obj.is_empty(),
+                    _ => true,
+                })
+                .collect(),
+        ),
+        value => value,
+    }
+}
+
+/// 有差异的顶层字段，两边都按传输类型清理并规范化后再比较
+fn changed_fields(desired: &McpServerConfig, current: &McpServerConfig) -> Vec<String> {
+    let mut current = current.clone();
+    current.clean_fields();
+    let (Ok(desired), Ok(current)) = (serde_json::to_value(desired), serde_json::to_value(current))
+    else {
+        return Vec::new();
+    };
+    let (Value::Object(desired), Value::Object(current)) = (normalise(desired), normalise(current))
+    else {
+        return Vec::new();
+    };
+    let keys: BTreeSet<&String> = desired.keys().chain(current.keys()).collect();
+    keys.into_iter()
+        .filter(|key| desired.get(*key) != current.get(*key))
+        .cloned()
+        .collect()
+}
+
+/// 计算将数据库同步到声明式配置所需的操作
+fn plan(desired: &DeclarativeConfig, current: &CurrentState) -> Vec<(ConfigChange, Action)> {
+    let change = |kind, name: String, action, detail: Option<String>| ConfigChange {
+        kind,
+        name,
+        action,
+        detail,
+        error: None,
+        token_value: None,
+    };
+    let mut actions = Vec::new();
+
+    let servers: HashMap<&str, &McpServerConfig> = current
+        .servers
+        .iter()
+        .map(|s| (s.name.as_str(), s))
+        .collect();
+    let flags: HashMap<(&str, &str), bool> = current
+        .tool_flags
+        .iter()
+        .map(|(server, tool, enabled)| ((server.as_str(), tool.as_str()), *enabled))
+        .collect();
+
+    for declared in &desired.servers {
+        let name = declared.config.name.clone();
+        match servers.get(name.as_str()) {
+            None => actions.push((
+                change(ChangeKind::Server, name.clone(), ChangeAction::Add, None),
+                Action::AddServer(Box::new(declared.config.clone())),
+            )),
+            Some(existing) => {
+                let fields = changed_fields(&declared.config, existing);
+                if !fields.is_empty() {
+                    actions.push((
+                        change(
+                            ChangeKind::Server,
+                            name.clone(),
+                            ChangeAction::Update,
+                            Some(format!("changed: {}", fields.join(", "))),
+                        ),
+                        Action::UpdateServer(Box::new(declared.config.clone())),
+                    ));
+                }
+            }
+        }
+
+        for (tool, enabled) in &declared.tools {
+            let current = flags.get(&(name.as_str(), tool.as_str())).copied();
+            if current == Some(*enabled) {
+                continue;
+            }
+            let action = if current.is_some() {
+                ChangeAction::Update
+            } else {
+                ChangeAction::Add
+            };
+            actions.push((
+                change(
+                    ChangeKind::Tool,
+                    format!("{}/{}", name, tool),
+                    action,
+                    Some(if *enabled { "enabled" } else { "disabled" }.to_string()),
+                ),
+                Action::SetTool {
+                    server: name.clone(),
+                    tool: tool.clone(),
+                    enabled: *enabled,
+                },
+            ));
+        }
+    }
+
+    if desired.prune {
+        let declared: BTreeSet<&str> = desired
+            .servers
+            .iter()
+            .map(|s| s.config.name.as_str())
+            .collect();
+        for server in &current.servers {
+            if !declared.contains(server.name.as_str()) {
+                actions.push((
+                    change(
+                        ChangeKind::Server,
+                        server.name.clone(),
+                        ChangeAction::Remove,
+                        None,
+                    ),
+                    Action::RemoveServer(server.name.clone()),
+                ));
+            }
+        }
+    }
+
+    for (name, token) in &desired.tokens {
+        // 同名 Token 只同步第一个
+        match current.tokens.iter().find(|(t, _)| &t.name == name) {
+            None => actions.push((
+                change(ChangeKind::Token, name.clone(), ChangeAction::Add, None),
+                Action::AddToken(name.clone(), token.clone()),
+            )),
+            Some((existing, permissions)) => {
+                let mut fields = Vec::new();
+                if existing.description != token.description {
+                    fields.push("description");
+                }
+                if existing.enabled != token.enabled {
+                    fields.push("enabled");
+                }
+                if existing.expires_at != token.expires_at {
+                    fields.push("expires_at");
+                }
+                let permissions_changed = *permissions != token.permissions();
+                if permissions_changed {
+                    fields.push("permissions");
+                }
+                if !fields.is_empty() {
+                    actions.push((
+                        change(
+                            ChangeKind::Token,
+                            name.clone(),
+                            ChangeAction::Update,
+                            Some(format!("changed: {}", fields.join(", "))),
+                        ),
+                        Action::UpdateToken {
+                            id: existing.id.clone(),
+                            name: name.clone(),
+                            token: token.clone(),
+                            permissions_changed,
+                        },
+                    ));
+                }
+            }
+        }
+    }
+
+    if desired.prune {
+        for (token, _) in &current.tokens {
+            if !desired.tokens.contains_key(&token.name) {
+                actions.push((
+                    change(
+                        ChangeKind::Token,
+                        token.name.clone(),
+                        ChangeAction::Remove,
+                        None,
+                    ),
+                    Action::RemoveToken {
+                        id: token.id.clone(),
+                        name: token.name.clone(),
+                    },
+                ));
+            }
+        }
+    }
+
+    actions
+}
+
+/// 读取数据库中的当前状态
+async fn current_state(
+    service_manager: &McpServerManager,
+    token_manager: &TokenManager,
+) -> Result<CurrentState> {
+    let storage = token_manager.orm_storage();
+    let mut tokens = Vec::new();
+    for token in storage.get_all_tokens().await? {
+        let permissions = storage
+            .get_token_permissions(&token.id)
+            .await?
+            .into_iter()
+            .filter(|p| p.allowed)
+            .map(|p| (p.resource_type, p.resource_path))
+            .collect();
+        tokens.push((token, permissions));
+    }
+    Ok(CurrentState {
+        servers: service_manager.list_server_configs().await?,
+        tool_flags: service_manager.list_tool_flags().await?,
+        tokens,
+    })
+}
+
+/// 将数据库同步到配置文件，dry_run 时只返回差异
+pub async fn reconcile(
+    path: &Path,
+    service_manager: &McpServerManager,
+    token_manager: &TokenManager,
+    dry_run: bool,
+) -> Result<ReconcileReport> {
+    let desired = load(path)?;
+    let current = current_state(service_manager, token_manager).await?;
+    let actions = plan(&desired, &current);
+
+    let mut changes = Vec::with_capacity(actions.len());
+    let mut servers_changed = false;
+    for (mut change, action) in actions {
+        if !dry_run {
+            servers_changed |= change.kind == ChangeKind::Server;
+            match apply(action, service_manager, token_manager).await {
+                Ok(token_value) => change.token_value = token_value,
+                Err(e) => {
+                    tracing::error!(
+                        "❌ Failed to apply {:?} {:?} '{}': {}",
+                        change.action,
+                        change.kind,
+                        change.name,
+                        e
+                    );
+                    change.error = Some(e.to_string());
+                }
+            }
+        }
+        changes.push(change);
+    }
+
+    if servers_changed {
+        if let Err(e) = service_manager.auto_connect_enabled_services().await {
+            tracing::error!("Failed to auto-connect services after reconcile: {}", e);
+        }
+    }
+
+    if dry_run {
+        tracing::info!(
+            "🔍 {} drift(s) between {} and the database",
+            changes.len(),
+            path.display()
+        );
+    } else {
+        tracing::info!(
+            "🔄 Reconciled {}: {} change(s) applied",
+            path.display(),
+            changes.iter().filter(|c| c.error.is_none()).count()
+        );
+    }
+    Ok(ReconcileReport {
+        path: path.display().to_string(),
+        dry_run,
+        changes,
+    })
+}
+
+/// 应用一项操作，新建 Token 时返回生成的值
+async fn apply(
+    action: Action,
+    service_manager: &McpServerManager,
+    token_manager: &TokenManager,
+) -> Result<Option<String>> {
+    let storage = token_manager.orm_storage();
+    match action {
+        Action::AddServer(config) => service_manager.add_server(&config).await?,
+        Action::UpdateServer(config) => {
+            service_manager.update_server(&config.name, &config).await?
+        }
+        Action::RemoveServer(name) => {
+            service_manager.delete_server(&name).await?;
+            crate::MCP_CLIENT_MANAGER.disconnect_server(&name).await?;
+            crate::MCP_CLIENT_MANAGER.server_logs().clear(&name);
+            crate::MCP_CLIENT_MANAGER.process_monitor().remove(&name);
+        }
+        Action::SetTool {
+            server,
+            tool,
+            enabled,
+        } => {
+            service_manager
+                .set_tool_enabled(&server, &tool, enabled)
+                .await?
+        }
+        Action::AddToken(name, declared) => {
+            let token = Token {
+                id: uuid::Uuid::now_v7().to_string(),
+                value: token_manager.generate_token(),
+                name,
+                description: declared.description.clone(),
+                created_at: chrono::Utc::now().timestamp() as u64,
+                expires_at: declared.expires_at,
+                last_used_at: None,
+                usage_count: 0,
+                enabled: declared.enabled,
+                allowed_tools: None,
+                allowed_resources: None,
+                allowed_prompts: None,
+                allowed_prompt_templates: None,
+            };
+            let permissions: Vec<_> = declared.permissions().into_iter().collect();
+            // Token 和权限在同一个事务中写入
+            storage.replace_tokens(&[], &token, &permissions).await?;
+            tracing::info!("Created token '{}' from config file", token.name);
+            return Ok(Some(token.value));
+        }
+        Action::UpdateToken {
+            id,
+            name,
+            token,
+            permissions_changed,
+        } => {
+            storage
+                .update_token_metadata(
+                    &id,
+                    token.description.clone(),
+                    token.enabled,
+                    token.expires_at,
+                )
+                .await?;
+            if permissions_changed {
+                let permissions: Vec<_> = token.permissions().into_iter().collect();
+                storage.replace_token_permissions(&id, &permissions).await?;
+            }
+            tracing::info!("Updated token '{}' from config file", name);
+        }
+        Action::RemoveToken { id, name } => {
+            storage.delete_tokens(&[id]).await?;
+            tracing::info!("Removed token '{}' not declared in config file", name);
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const YAML: &str = r#"
+prune: true
+servers:
+  github:
+    command: npx
+    args: ["-y", "@modelcontextprotocol/server-github"]
+    tools:
+      delete_repository: false
+  docs:
+    url: https://example.com/mcp
+tokens:
+  ci:
+    description: CI pipeline
+    tools: ["github__*"]
+"#;
+
+    #[test]
+    fn test_parse_formats() {
+        let yaml = parse(YAML, ConfigFormat::Yaml).unwrap();
+        assert!(yaml.prune);
+        assert_eq!(yaml.servers.len(), 2);
+        let docs = yaml
+            .servers
+            .iter()
+            .find(|s| s.config.name == "docs")
+            .unwrap();
+        assert_eq!(docs.config.url.as_deref(), Some("https://example.com/mcp"));
+        assert!(docs.config.enabled);
+        let github = yaml
+            .servers
+            .iter()
+            .find(|s| s.config.name == "github")
+            .unwrap();
+        assert_eq!(github.tools.get("delete_repository"), Some(&false));
+        assert_eq!(yaml.tokens["ci"].tools, vec!["github__*"]);
+
+        let toml = parse(
+            "[servers.github]\ncommand = \"npx\"\nenabled = false\n",
+            ConfigFormat::Toml,
+        )
+        .unwrap();
+        assert!(!toml.prune);
+        assert!(!toml.servers[0].config.enabled);
+
+        assert!(parse("", ConfigFormat::Yaml).unwrap().servers.is_empty());
+        assert!(parse("{\"servers\": {\"x\": 1}}", ConfigFormat::Json).is_err());
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("mcprouter.yml")),
+            ConfigFormat::Yaml
+        );
+    }
+
+    #[test]
+    fn test_changed_fields_normalises_empty_values() {
+        let desired = parse(YAML, ConfigFormat::Yaml).unwrap();
+        let docs = desired
+            .servers
+            .iter()
+            .find(|s| s.config.name == "docs")
+            .unwrap();
+
+        let mut current = docs.config.clone();
+        current.description = Some(String::new());
+        current.headers = Some(HashMap::new());
+        current.args = Some(vec!["stale".to_string()]);
+        current.url = Some(" https://example.com/mcp ".to_string());
+        assert!(changed_fields(&docs.config, &current).is_empty());
+
+        current.url = Some("https://example.com/other".to_string());
+        assert_eq!(changed_fields(&docs.config, &current), vec!["url"]);
+    }
+
+    #[test]
+    fn test_plan_reports_drift() {
+        let desired = parse(YAML, ConfigFormat::Yaml).unwrap();
+        let mut github = desired.servers[1].config.clone();
+        assert_eq!(github.name, "github");
+        github.args = Some(vec!["old".to_string()]);
+        let mut stale = github.clone();
+        stale.name = "stale".to_string();
+        let token = Token {
+            id: "t1".to_string(),
+            name: "ci".to_string(),
+            value: "v".to_string(),
+            description: Some("CI pipeline".to_string()),
+            created_at: 0,
+            expires_at: None,
+            last_used_at: None,
+            usage_count: 0,
+            enabled: true,
+            allowed_tools: None,
+            allowed_resources: None,
+            allowed_prompts: None,
+            allowed_prompt_templates: None,
+        };
+        let current = CurrentState {
+            servers: vec![github, stale],
+            tool_flags: vec![("github".to_string(), "delete_repository".to_string(), true)],
+            tokens: vec![(token, BTreeSet::new())],
+        };
+
+        let changes: Vec<(ChangeKind, String, ChangeAction, Option<String>)> =
+            plan(&desired, &current)
+                .into_iter()
+                .map(|(c, _)| (c.kind, c.name, c.action, c.detail))
+                .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (ChangeKind::Server, "docs".into(), ChangeAction::Add, None),
+                (
+                    ChangeKind::Server,
+                    "github".into(),
+                    ChangeAction::Update,
+                    Some("changed: args".into())
+                ),
+                (
+                    ChangeKind::Tool,
+                    "github/delete_repository".into(),
+                    ChangeAction::Update,
+                    Some("disabled".into())
+                ),
+                (
+                    ChangeKind::Server,
+                    "stale".into(),
+                    ChangeAction::Remove,
+                    None
+                ),
+                (
+                    ChangeKind::Token,
+                    "ci".into(),
+                    ChangeAction::Update,
+                    Some("changed: permissions".into())
+                ),
+            ]
+        );
+    }
+}
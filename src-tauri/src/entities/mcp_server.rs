@@ -1,4 +1,11 @@
-use crate::types::ServiceTransport;
+use crate::container::ContainerConfig;
+use crate::credential_helper::CredentialHelper;
+use crate::sandbox::SandboxProfile;
+use crate::storage::StorageError;
+use crate::types::{
+    EnvInheritance, McpServerConfig, RestartPolicy, ServerLifecycle, ServerRuntime,
+    ServiceTransport,
+};
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -41,6 +48,54 @@ pub struct Model {
     /// 是否启用
     pub enabled: bool,
 
+    /// 单次请求超时（秒）
+    pub request_timeout: Option<i64>,
+
+    /// 连接超时（秒）
+    pub connect_timeout: Option<i64>,
+
+    /// 最大并发调用数
+    pub max_concurrent_calls: Option<i32>,
+
+    /// 生命周期：'eager' | 'lazy'
+    pub lifecycle: Option<String>,
+
+    /// 空闲超时（秒，仅 lazy 服务）
+    pub idle_timeout: Option<i64>,
+
+    /// 重启策略：'never' | 'on-failure' | 'always'
+    pub restart_policy: Option<String>,
+
+    /// on-failure 策略的最大重启次数
+    pub max_restarts: Option<i32>,
+
+    /// 进程内存上限（MB）
+    pub memory_limit_mb: Option<i64>,
+
+    /// 沙箱配置 (JSON)
+    pub sandbox: Option<String>,
+
+    /// 工作目录
+    pub cwd: Option<String>,
+
+    /// 环境变量继承策略：'inherit-all' | 'allowlist' | 'none'
+    pub env_inheritance: Option<String>,
+
+    /// allowlist 策略下继承的变量名 (JSON 数组)
+    pub env_allowlist: Option<String>,
+
+    /// 容器配置 (JSON)
+    pub container: Option<String>,
+
+    /// 运行时：'auto' | 'bun' | 'system-node' | 可执行文件路径
+    pub runtime: Option<String>,
+
+    /// 固定的运行时版本（Node.js / Python）
+    pub runtime_version: Option<String>,
+
+    /// 凭据助手配置 (JSON)
+    pub credential_helper: Option<String>,
+
     /// 创建时间
     #[sea_orm(column_type = "TimestampWithTimeZone")]
     pub created_at: ChronoDateTimeWithTimeZone,
@@ -146,6 +201,121 @@ impl Model {
             .unwrap_or(false)
     }
 
+    /// 获取生命周期，未设置或无法解析时为 eager
+    pub fn get_lifecycle(&self) -> ServerLifecycle {
+        self.lifecycle
+            .as_deref()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// 获取运行时，未设置时为 auto
+    pub fn get_runtime(&self) -> ServerRuntime {
+        self.runtime
+            .clone()
+            .map(ServerRuntime::from)
+            .unwrap_or_default()
+    }
+
+    /// 获取重启策略，未设置或无法解析时为 on-failure
+    pub fn get_restart_policy(&self) -> RestartPolicy {
+        self.restart_policy
+            .as_deref()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// 获取环境变量继承策略，未设置或无法解析时为 inherit-all
+    pub fn get_env_inheritance(&self) -> EnvInheritance {
+        self.env_inheritance
+            .as_deref()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// 解析 allowlist 策略下继承的变量名
+    pub fn parse_env_allowlist(&self) -> Option<Vec<String>> {
+        self.env_allowlist
+            .as_deref()
+            .and_then(|v| serde_json::from_str(v).ok())
+    }
+
+    /// 解析沙箱配置
+    ///
+    /// 无法解析时返回错误而不是视为未配置，避免服务在没有沙箱的情况下启动
+    pub fn parse_sandbox(&self) -> Result<Option<SandboxProfile>, StorageError> {
+        let Some(sandbox) = self.sandbox.as_deref() else {
+            return Ok(None);
+        };
+        serde_json::from_str(sandbox).map(Some).map_err(|e| {
+            StorageError::InvalidData(format!(
+                "Invalid sandbox profile for server '{}': {}",
+                self.name, e
+            ))
+        })
+    }
+
+    /// 解析容器配置，无法解析时视为未配置
+    pub fn parse_container(&self) -> Option<ContainerConfig> {
+        let container = self.container.as_deref()?;
+        serde_json::from_str(container)
+            .map_err(|e| {
+                tracing::warn!("Invalid container config for server '{}': {}", self.name, e);
+            })
+            .ok()
+    }
+
+    /// 解析凭据助手配置
+    ///
+    /// 无法解析时返回错误而不是视为未配置，避免服务在没有凭据的情况下连接
+    pub fn parse_credential_helper(&self) -> Result<Option<CredentialHelper>, StorageError> {
+        let Some(helper) = self.credential_helper.as_deref() else {
+            return Ok(None);
+        };
+        serde_json::from_str(helper).map(Some).map_err(|e| {
+            StorageError::InvalidData(format!(
+                "Invalid credential helper for server '{}': {}",
+                self.name, e
+            ))
+        })
+    }
+
+    /// 转换为连接配置（transport 由调用方解析），沙箱或凭据助手配置无效时返回错误
+    pub fn to_server_config(
+        &self,
+        transport: ServiceTransport,
+    ) -> Result<McpServerConfig, StorageError> {
+        Ok(McpServerConfig {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            command: self.command.clone(),
+            args: self.parse_args().ok(),
+            env: self.parse_env().ok(),
+            transport,
+            url: self.url.clone(),
+            headers: self.parse_headers().ok(),
+            enabled: self.enabled,
+            request_timeout: self.request_timeout.and_then(|v| u64::try_from(v).ok()),
+            connect_timeout: self.connect_timeout.and_then(|v| u64::try_from(v).ok()),
+            max_concurrent_calls: self
+                .max_concurrent_calls
+                .and_then(|v| u32::try_from(v).ok()),
+            lifecycle: self.get_lifecycle(),
+            idle_timeout: self.idle_timeout.and_then(|v| u64::try_from(v).ok()),
+            restart_policy: self.get_restart_policy(),
+            max_restarts: self.max_restarts.and_then(|v| u32::try_from(v).ok()),
+            memory_limit_mb: self.memory_limit_mb.and_then(|v| u64::try_from(v).ok()),
+            sandbox: self.parse_sandbox()?,
+            cwd: self.cwd.clone(),
+            env_inheritance: self.get_env_inheritance(),
+            env_allowlist: self.parse_env_allowlist(),
+            container: self.parse_container(),
+            runtime: self.get_runtime(),
+            runtime_version: self.runtime_version.clone(),
+            credential_helper: self.parse_credential_helper()?,
+        })
+    }
+
     /// 检查是否为 http 类型
     pub fn is_http_type(&self) -> bool {
         self.get_server_type()
@@ -154,7 +324,6 @@ impl Model {
     }
 }
 
-
 impl Default for Model {
     fn default() -> Self {
         Self {
@@ -168,9 +337,24 @@ impl Default for Model {
             headers: None,
             env: None,
             enabled: true,
+            request_timeout: None,
+            connect_timeout: None,
+            max_concurrent_calls: None,
+            lifecycle: None,
+            idle_timeout: None,
+            restart_policy: None,
+            max_restarts: None,
+            memory_limit_mb: None,
+            sandbox: None,
+            cwd: None,
+            env_inheritance: None,
+            env_allowlist: None,
+            container: None,
+            runtime: None,
+            runtime_version: None,
+            credential_helper: None,
             created_at: chrono::Utc::now().into(),
             updated_at: chrono::Utc::now().into(),
         }
     }
 }
-
@@ -8,6 +8,11 @@ pub mod mcp_server;
 pub mod mcp_tool;
 pub mod mcp_resource;
 pub mod mcp_prompt;
+pub mod secret;
+pub mod oauth_credential;
+pub mod oauth_client;
+pub mod oauth_grant;
+pub mod pending_tool_flag;
 
 /// Prelude 模块，重新导出常用的 SeaORM 实体和类型
 pub mod prelude {
@@ -18,6 +23,11 @@ pub mod prelude {
         mcp_tool::Entity as McpTool,
         mcp_resource::Entity as McpResource,
         mcp_prompt::Entity as McpPrompt,
+        secret::Entity as Secret,
+        oauth_credential::Entity as OauthCredential,
+        oauth_client::Entity as OauthClient,
+        oauth_grant::Entity as OauthGrant,
+        pending_tool_flag::Entity as PendingToolFlag,
 
         token::Column as TokenColumn,
         permission::Column as PermissionColumn,
@@ -25,6 +35,11 @@ pub mod prelude {
         mcp_tool::Column as McpToolColumn,
         mcp_resource::Column as McpResourceColumn,
         mcp_prompt::Column as McpPromptColumn,
+        secret::Column as SecretColumn,
+        oauth_credential::Column as OauthCredentialColumn,
+        oauth_client::Column as OauthClientColumn,
+        oauth_grant::Column as OauthGrantColumn,
+        pending_tool_flag::Column as PendingToolFlagColumn,
     };
 }
 
@@ -35,6 +50,11 @@ pub use mcp_server::Entity as McpServer;
 pub use mcp_tool::Entity as McpTool;
 pub use mcp_resource::Entity as McpResource;
 pub use mcp_prompt::Entity as McpPrompt;
+pub use secret::Entity as Secret;
+pub use oauth_credential::Entity as OauthCredential;
+pub use oauth_client::Entity as OauthClient;
+pub use oauth_grant::Entity as OauthGrant;
+pub use pending_tool_flag::Entity as PendingToolFlag;
 
 pub use token::Model as TokenModel;
 pub use permission::Model as PermissionModel;
@@ -42,6 +62,11 @@ pub use mcp_server::Model as McpServerModel;
 pub use mcp_tool::Model as McpToolModel;
 pub use mcp_resource::Model as McpResourceModel;
 pub use mcp_prompt::Model as McpPromptModel;
+pub use secret::Model as SecretModel;
+pub use oauth_credential::Model as OauthCredentialModel;
+pub use oauth_client::Model as OauthClientModel;
+pub use oauth_grant::Model as OauthGrantModel;
+pub use pending_tool_flag::Model as PendingToolFlagModel;
 
 pub use token::ActiveModel as TokenActiveModel;
 pub use permission::ActiveModel as PermissionActiveModel;
@@ -49,10 +74,20 @@ pub use mcp_server::ActiveModel as McpServerActiveModel;
 pub use mcp_tool::ActiveModel as McpToolActiveModel;
 pub use mcp_resource::ActiveModel as McpResourceActiveModel;
 pub use mcp_prompt::ActiveModel as McpPromptActiveModel;
+pub use secret::ActiveModel as SecretActiveModel;
+pub use oauth_credential::ActiveModel as OauthCredentialActiveModel;
+pub use oauth_client::ActiveModel as OauthClientActiveModel;
+pub use oauth_grant::ActiveModel as OauthGrantActiveModel;
+pub use pending_tool_flag::ActiveModel as PendingToolFlagActiveModel;
 
 pub use token::Column as TokenColumn;
 pub use permission::Column as PermissionColumn;
 pub use mcp_server::Column as McpServerColumn;
 pub use mcp_tool::Column as McpToolColumn;
 pub use mcp_resource::Column as McpResourceColumn;
-pub use mcp_prompt::Column as McpPromptColumn;
\ No newline at end of file
+pub use mcp_prompt::Column as McpPromptColumn;
+pub use secret::Column as SecretColumn;
+pub use oauth_credential::Column as OauthCredentialColumn;
+pub use oauth_client::Column as OauthClientColumn;
+pub use oauth_grant::Column as OauthGrantColumn;
+pub use pending_tool_flag::Column as PendingToolFlagColumn;
\ No newline at end of file
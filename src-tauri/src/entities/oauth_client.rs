@@ -0,0 +1,29 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// OAuth 客户端实体
+///
+/// 对应数据库表 oauth_clients，记录通过动态客户端注册接入聚合器的应用
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "oauth_clients")]
+pub struct Model {
+    /// 主键，客户端 ID
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub client_id: String,
+
+    /// 客户端名称
+    pub client_name: String,
+
+    /// 重定向地址列表（JSON 数组）
+    #[sea_orm(column_type = "Text")]
+    pub redirect_uris: String,
+
+    /// 创建时间
+    #[sea_orm(column_type = "TimestampWithTimeZone")]
+    pub created_at: ChronoDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,30 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 下游服务 OAuth 凭据实体
+///
+/// 对应数据库表 oauth_credentials，客户端注册信息和令牌序列化为 JSON 后使用主密钥加密存储
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "oauth_credentials")]
+pub struct Model {
+    /// 主键，服务名称
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub server_name: String,
+
+    /// Base64 编码的 nonce + 密文
+    #[sea_orm(column_type = "Text")]
+    pub ciphertext: String,
+
+    /// 创建时间
+    #[sea_orm(column_type = "TimestampWithTimeZone")]
+    pub created_at: ChronoDateTimeWithTimeZone,
+
+    /// 更新时间
+    #[sea_orm(column_type = "TimestampWithTimeZone")]
+    pub updated_at: ChronoDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,47 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// OAuth 授权实体
+///
+/// 对应数据库表 oauth_grants，每条记录把一个客户端绑定到一个已有 Token 的权限上
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "oauth_grants")]
+pub struct Model {
+    /// 主键，UUID v7 格式
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+
+    /// 客户端 ID
+    pub client_id: String,
+
+    /// 授权所使用的 Token ID
+    pub token_id: String,
+
+    /// 访问令牌的 SHA-256 摘要
+    #[sea_orm(unique)]
+    pub access_token_hash: String,
+
+    /// 访问令牌过期时间
+    #[sea_orm(column_type = "TimestampWithTimeZone")]
+    pub access_expires_at: ChronoDateTimeWithTimeZone,
+
+    /// 刷新令牌的 SHA-256 摘要
+    #[sea_orm(unique)]
+    pub refresh_token_hash: String,
+
+    /// 上一个刷新令牌的摘要，用于发现已轮换的刷新令牌被重复使用
+    pub previous_refresh_token_hash: Option<String>,
+
+    /// 创建时间
+    #[sea_orm(column_type = "TimestampWithTimeZone")]
+    pub created_at: ChronoDateTimeWithTimeZone,
+
+    /// 更新时间（最近一次签发令牌）
+    #[sea_orm(column_type = "TimestampWithTimeZone")]
+    pub updated_at: ChronoDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,30 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 待应用的工具启用状态实体
+///
+/// 对应数据库表 pending_tool_flags。导入配置时服务的工具可能尚未同步，
+/// 启用状态先保存在这里，同步到对应工具时再写入 mcp_server_tools
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "pending_tool_flags")]
+pub struct Model {
+    /// 关联的服务器ID
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub server_id: String,
+
+    /// 工具名称
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub tool_name: String,
+
+    /// 是否启用
+    pub enabled: bool,
+
+    /// 更新时间
+    #[sea_orm(column_type = "TimestampWithTimeZone")]
+    pub updated_at: ChronoDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,30 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 密钥实体
+///
+/// 对应数据库表 secrets，值使用主密钥加密后存储
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, Serialize, Deserialize)]
+#[sea_orm(table_name = "secrets")]
+pub struct Model {
+    /// 主键，密钥名称
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub name: String,
+
+    /// Base64 编码的 nonce + 密文
+    #[sea_orm(column_type = "Text")]
+    pub ciphertext: String,
+
+    /// 创建时间
+    #[sea_orm(column_type = "TimestampWithTimeZone")]
+    pub created_at: ChronoDateTimeWithTimeZone,
+
+    /// 更新时间
+    #[sea_orm(column_type = "TimestampWithTimeZone")]
+    pub updated_at: ChronoDateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
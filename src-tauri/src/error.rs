@@ -19,6 +19,9 @@ pub enum McpError {
     #[error("Service not found: {0}")]
     ServiceNotFound(String),
 
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
     #[error("Service already exists: {0}")]
     ServiceAlreadyExists(String),
 
@@ -31,6 +34,9 @@ pub enum McpError {
     #[error("HTTP error: {0}")]
     HttpError(#[from] reqwest::Error),
 
+    #[error("HTTP {0} error: {1}")]
+    HttpStatusError(u16, String),
+
     #[error("MCP protocol error: {0}")]
     ProtocolError(String),
 
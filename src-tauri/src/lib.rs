@@ -1,16 +1,34 @@
 pub mod aggregator;
 pub mod auth_context;
+pub mod authorization_server;
+pub mod bundle;
+pub mod circuit_breaker;
+pub mod client_export;
+pub mod client_import;
 pub mod commands;
 pub mod config;
+pub mod container;
+pub mod credential_helper;
+pub mod declarative;
 pub mod error;
 pub mod marketplace;
 pub mod mcp_client;
 pub mod mcp_manager;
+pub mod oauth_client;
+pub mod placeholders;
+pub mod process_monitor;
+pub mod resource_monitor;
+pub mod sandbox;
+pub mod secrets;
+pub mod server_logs;
 pub mod shell_environment;
 pub mod storage;
+pub mod supervisor;
 pub mod token_manager;
 pub mod tool_manager;
+pub mod traffic;
 pub mod types;
+pub mod utils;
 
 // SeaORM 实体模块
 pub mod entities;
@@ -202,6 +220,26 @@ pub async fn wait_for_token_manager(
     ))
 }
 
+/// Wait for the secret store to be initialized (with timeout)
+pub async fn wait_for_secret_store(
+) -> Result<Arc<crate::secrets::SecretStore>, crate::error::McpError> {
+    let mut attempts = 0;
+    let max_attempts = 50; // 5 seconds max
+
+    while attempts < max_attempts {
+        if let Some(ref store) = *SECRET_STORE.read().await {
+            return Ok(store.clone());
+        }
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        attempts += 1;
+    }
+
+    Err(crate::error::McpError::InternalError(
+        "Secret store initialization timeout".to_string(),
+    ))
+}
+
 // Global state - use MCP Server Manager
 static SERVICE_MANAGER: std::sync::Mutex<Option<Arc<McpServerManager>>> =
     std::sync::Mutex::new(None);
@@ -244,6 +282,10 @@ pub static AGGREGATOR: std::sync::LazyLock<
 static TOKEN_MANAGER: std::sync::LazyLock<TokenManagerState> =
     std::sync::LazyLock::new(|| Arc::new(tokio::sync::RwLock::new(None)));
 
+static SECRET_STORE: std::sync::LazyLock<
+    tokio::sync::RwLock<Option<Arc<crate::secrets::SecretStore>>>,
+> = std::sync::LazyLock::new(|| tokio::sync::RwLock::new(None));
+
 #[allow(dead_code)]
 static STORAGE_MANAGER: std::sync::LazyLock<std::sync::Mutex<Option<Arc<StorageManager>>>> =
     std::sync::LazyLock::new(|| std::sync::Mutex::new(None));
@@ -295,7 +337,7 @@ fn get_tray_text(key: &str, language: &str) -> &'static str {
 /// Returns the menu and cloned menu items for event handling
 fn build_tray_menu(app: &tauri::AppHandle) -> tauri::Result<tauri::menu::Menu<tauri::Wry>> {
     // Load configuration to get current language and theme
-    let config = AppConfig::load().unwrap_or_default();
+    let config = crate::config::CONFIG_SERVICE.current();
     let language = config
         .settings
         .as_ref()
@@ -642,6 +684,11 @@ pub async fn run() {
             let mcp_client_manager = MCP_CLIENT_MANAGER.clone();
             let server_config = Arc::new(config.server.clone());
 
+            // Reload config.json when it is edited outside the app
+            if let Err(e) = crate::config::CONFIG_SERVICE.watch(app.handle().clone()) {
+                tracing::warn!("Failed to watch config file: {}", e);
+            }
+
             // 2.6) Initialize SeaORM database and managers in background
             let config_dir_for_init = config_dir.clone();
             let mcp_client_manager_for_init = mcp_client_manager.clone();
@@ -721,16 +768,16 @@ Initialization process may be partially completed, set managers are still usable
                 main_window.on_window_event(move |event| {
                     if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                         // Load latest config synchronously
-                        let cfg = AppConfig::load().ok();
+                        let cfg = crate::config::CONFIG_SERVICE.current();
                         let tray_enabled = cfg
+                            .settings
                             .as_ref()
-                            .and_then(|c| c.settings.as_ref())
                             .and_then(|s| s.system_tray.as_ref())
                             .and_then(|t| t.enabled)
                             .unwrap_or(true);
                         let minimize_on_close = cfg
+                            .settings
                             .as_ref()
-                            .and_then(|c| c.settings.as_ref())
                             .and_then(|s| s.system_tray.as_ref())
                             .and_then(|t| t.close_to_tray)
                             .unwrap_or(false);
@@ -772,6 +819,18 @@ Initialization process may be partially completed, set managers are still usable
             set_theme,
             update_config,
             import_mcp_servers_config,
+            discover_client_configs,
+            preview_mcp_servers_import,
+            import_mcp_servers,
+            generate_client_config,
+            write_client_config,
+            export_router_bundle,
+            import_router_bundle,
+            reconcile_config_file,
+            list_secrets,
+            set_secret,
+            delete_secret,
+            migrate_secrets,
             add_mcp_server,
             update_mcp_server,
             toggle_mcp_server,
@@ -781,10 +840,15 @@ Initialization process may be partially completed, set managers are still usable
             install_marketplace_service,
             // Enhanced Service Management
             delete_mcp_server,
+            authorize_mcp_server,
+            clear_mcp_server_authorization,
             // Tool DB Commands
             list_mcp_server_tools,
             list_mcp_server_resources,
             list_mcp_server_prompts,
+            get_mcp_server_logs,
+            get_mcp_server_status,
+            get_mcp_servers_resource_usage,
             // Legacy Commands
             toggle_mcp_server_tool,
             enable_all_mcp_server_tools,
@@ -792,6 +856,7 @@ Initialization process may be partially completed, set managers are still usable
             // Settings commands
             get_settings,
             save_settings,
+            get_shell_environment,
             check_path_validity,
             get_system_command_paths,
             get_dashboard_stats,
@@ -809,6 +874,11 @@ Initialization process may be partially completed, set managers are still usable
             cleanup_expired_tokens,
             validate_token,
             get_tokens_for_dashboard,
+            // OAuth Authorization Server Commands
+            list_oauth_consent_requests,
+            respond_oauth_consent,
+            list_oauth_grants,
+            revoke_oauth_grant,
             // Real-time Token Management Commands (已统一到 update_token_permission)
             // 统一的权限更新命令
             update_token_permission,
@@ -884,6 +954,19 @@ async fn initialize_managers(
         *token_manager_guard = Some(token_manager.clone());
     }
 
+    // Stage 2c: Initialize secret store
+    match crate::secrets::SecretStore::new(storage_manager.orm_storage()) {
+        Ok(secret_store) => {
+            *SECRET_STORE.write().await = Some(Arc::new(secret_store));
+        }
+        Err(e) => {
+            tracing::error!(
+                "Failed to initialize secret store, ${{secret:...}} references will not resolve: {}",
+                e
+            );
+        }
+    }
+
     // Stage 3: Create and start aggregator immediately (no delay)
     let mcp_server_manager_for_agg = mcp_server_manager.clone();
     let token_manager_for_agg = token_manager.clone();
@@ -901,11 +984,17 @@ async fn initialize_managers(
             app_for_agg.clone(),
         )
         .await;
+
+        // 聚合接口启动后再订阅配置变更，避免与初始化重复创建
+        watch_server_config((*server_config_for_agg).clone(), app_for_agg).await;
     });
 
     // Stage 4: Load and connect services in background (fully non-blocking)
     tokio::spawn(async move {
-        load_and_connect_services(mcp_server_manager).await;
+        load_and_connect_services(mcp_server_manager.clone()).await;
+
+        // 服务连接后再同步声明式配置文件，避免与自动连接并发
+        watch_config_file(mcp_server_manager, token_manager, app).await;
     });
 
     // Mark initialization as completed at the manager level
@@ -978,6 +1067,175 @@ async fn create_and_start_aggregator(
     }
 }
 
+/// Apply server config changes published by the config service to the running aggregator
+async fn watch_server_config(mut running: ServerConfig, app: tauri::AppHandle) {
+    let mut rx = crate::config::CONFIG_SERVICE.subscribe();
+    loop {
+        let next = rx.borrow_and_update().server.clone();
+        running = apply_server_config(running, next, &app).await;
+        if rx.changed().await.is_err() {
+            break;
+        }
+    }
+}
+
+/// 监听设置中的声明式配置文件，启动和文件修改时同步到数据库，路径变化时切换监听目标
+async fn watch_config_file(
+    service_manager: Arc<McpServerManager>,
+    token_manager: Arc<crate::token_manager::TokenManager>,
+    app: tauri::AppHandle,
+) {
+    let mut rx = crate::config::CONFIG_SERVICE.subscribe();
+    let (changed_tx, mut changed_rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut watching: Option<(std::path::PathBuf, _)> = None;
+    loop {
+        let path = rx
+            .borrow_and_update()
+            .settings
+            .as_ref()
+            .and_then(|s| s.config_file.clone())
+            .map(|path| commands::config::expand_path(&path));
+        if watching.as_ref().map(|(current, _)| current) != path.as_ref() {
+            // 丢弃旧的 Debouncer 即停止监听
+            watching = None;
+            if let Some(path) = path {
+                let tx = changed_tx.clone();
+                match crate::config::watch_file(&path, move || {
+                    let _ = tx.send(());
+                }) {
+                    Ok(debouncer) => {
+                        tracing::info!("👀 Watching {} for changes", path.display());
+                        watching = Some((path, debouncer));
+                        let _ = changed_tx.send(());
+                    }
+                    Err(e) => {
+                        tracing::error!("❌ Failed to watch config file: {}", e);
+                        let _ = app.emit(declarative::CONFIG_FILE_ERROR_EVENT, e.to_string());
+                    }
+                }
+            }
+        }
+
+        tokio::select! {
+            changed = rx.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+            Some(()) = changed_rx.recv() => {
+                let Some((path, _)) = watching.as_ref() else {
+                    continue;
+                };
+                // 合并等待期间排队的事件
+                while changed_rx.try_recv().is_ok() {}
+                sync_config_file(path, &service_manager, &token_manager, &app).await;
+            }
+        }
+    }
+}
+
+/// 将声明式配置文件同步到数据库，有变更或失败时通知前端
+async fn sync_config_file(
+    path: &std::path::Path,
+    service_manager: &McpServerManager,
+    token_manager: &crate::token_manager::TokenManager,
+    app: &tauri::AppHandle,
+) {
+    match declarative::reconcile(path, service_manager, token_manager, false).await {
+        Ok(report) if report.has_drift() => {
+            let _ = app.emit(declarative::CONFIG_FILE_RECONCILED_EVENT, report);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::error!("❌ Failed to reconcile {}: {}", path.display(), e);
+            let _ = app.emit(declarative::CONFIG_FILE_ERROR_EVENT, e.to_string());
+        }
+    }
+}
+
+/// Rebind or toggle auth / OAuth as needed; returns the server config that is actually running
+async fn apply_server_config(
+    running: ServerConfig,
+    next: ServerConfig,
+    app: &tauri::AppHandle,
+) -> ServerConfig {
+    if next.requires_rebind(&running) {
+        tracing::info!(
+            "Server configuration changed, rebinding aggregator on {}:{}",
+            next.host,
+            next.port
+        );
+        match restart_aggregator(next.clone(), app).await {
+            Ok(()) => return next,
+            Err(e) => {
+                tracing::error!("❌ Failed to apply server configuration: {}", e);
+                let _ = app.emit(
+                    crate::config::CONFIG_ERROR_EVENT,
+                    format!(
+                        "Failed to listen on {}:{} ({}), keeping {}:{}",
+                        next.host, next.port, e, running.host, running.port
+                    ),
+                );
+                if let Err(e) = restart_aggregator(running.clone(), app).await {
+                    tracing::error!("❌ Failed to restore previous aggregator: {}", e);
+                }
+                return running;
+            }
+        }
+    }
+
+    let auth_changed = next.auth != running.auth;
+    let oauth_changed = next.is_oauth_enabled() != running.is_oauth_enabled();
+    if auth_changed || oauth_changed {
+        let aggregator = AGGREGATOR
+            .lock()
+            .expect("Failed to acquire AGGREGATOR lock")
+            .clone();
+        if let Some(aggregator) = aggregator {
+            if auth_changed {
+                aggregator.set_auth_enabled(next.auth);
+            }
+            if oauth_changed {
+                aggregator.set_oauth_enabled(next.is_oauth_enabled());
+            }
+        }
+    }
+    next
+}
+
+/// Replace the running aggregator with one listening on the given server config
+async fn restart_aggregator(
+    server_config: ServerConfig,
+    app: &tauri::AppHandle,
+) -> Result<(), crate::error::McpError> {
+    let previous = AGGREGATOR
+        .lock()
+        .expect("Failed to acquire AGGREGATOR lock")
+        .clone();
+    if let Some(aggregator) = &previous {
+        tracing::debug!("Shutting down existing aggregator...");
+        // 等待监听任务退出，确保端口已释放
+        aggregator.shutdown().await;
+    }
+
+    let aggregator = Arc::new(aggregator::McpAggregator::new(
+        wait_for_service_manager().await?,
+        MCP_CLIENT_MANAGER.clone(),
+        Arc::new(server_config),
+        wait_for_token_manager().await?,
+        app.clone(),
+    ));
+    *AGGREGATOR
+        .lock()
+        .expect("Failed to acquire AGGREGATOR lock") = Some(aggregator.clone());
+
+    aggregator.start().await.map_err(|e| {
+        crate::error::McpError::InternalError(format!("Failed to start aggregator: {}", e))
+    })?;
+    tracing::info!("Aggregator restarted successfully with new configuration");
+    Ok(())
+}
+
 /// Load and connect MCP services
 async fn load_and_connect_services(mcp_server_manager: Arc<crate::mcp_manager::McpServerManager>) {
     tracing::info!("🚀 Starting services initialization");
@@ -1100,6 +1358,16 @@ async fn load_and_connect_services(mcp_server_manager: Arc<crate::mcp_manager::M
         });
     });
 
+    // Phase 4: Start health supervisor (ping, reconnect with backoff)
+    // and process resource monitor (CPU/memory, memory limits)
+    crate::MCP_CLIENT_MANAGER
+        .resource_monitor()
+        .start(Arc::clone(&mcp_server_manager));
+    let supervisor = Arc::new(crate::supervisor::ServiceSupervisor::new(
+        mcp_server_manager,
+    ));
+    supervisor.start();
+
     // Mark initialization as managers created (aggregator can start independently)
     update_initialization_state(crate::types::InitializationState::ManagersCreated).await;
     tracing::info!(
@@ -1,25 +1,39 @@
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerSnapshot, CircuitState};
 use crate::config::AppConfig;
+use crate::container::ContainerConfig;
+use crate::credential_helper::CredentialCache;
 use crate::error::{McpError, Result};
-use crate::types::{ConnectionStatus, McpConnection, McpServerConfig, McpService};
+use crate::oauth_client::OAuthClient;
+use crate::types::{
+    ConnectionStatus, EnvInheritance, LaunchedRuntime, McpConnection, McpServerConfig, McpService,
+    ServerRuntime,
+};
 use reqwest::header;
 use rmcp::model::Tool;
 use rmcp::service::ServiceExt;
-use rmcp::transport::child_process::TokioChildProcess;
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::process::Command;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock, Semaphore};
 
 /// Helper function to create a reqwest client with logging for HTTP transport
+///
+/// 单个 HTTP 请求的整体超时与服务的 `request_timeout` 一致，避免下游挂起时连接一直占用
 fn create_http_reqwest_client(
     custom_headers: Option<&HashMap<String, String>>,
+    credential_header: Option<&(String, String)>,
+    connect_timeout: Duration,
+    request_timeout: Duration,
 ) -> Result<reqwest::Client> {
     let user_agent = crate::commands::app_info::get_user_agent_static();
     let mut client_builder = reqwest::Client::builder()
         .user_agent(user_agent)
-        .timeout(Duration::from_secs(30));
+        .connect_timeout(connect_timeout)
+        .timeout(request_timeout);
 
     // Add default headers for HTTP transport
     let mut headers = header::HeaderMap::new();
@@ -54,16 +68,129 @@ fn create_http_reqwest_client(
         }
     }
 
+    // 凭据助手生成的请求头覆盖同名的自定义请求头
+    if let Some((key, value)) = credential_header {
+        let header_name = header::HeaderName::from_bytes(key.as_bytes()).map_err(|e| {
+            McpError::InvalidConfiguration(format!("Invalid credential header '{}': {}", key, e))
+        })?;
+        let mut header_value = header::HeaderValue::from_str(value).map_err(|e| {
+            McpError::AuthError(format!(
+                "Credential helper produced an invalid header value: {}",
+                e
+            ))
+        })?;
+        header_value.set_sensitive(true);
+        headers.insert(header_name, header_value);
+        tracing::debug!("Added credential helper header: {}", key);
+    }
+
     client_builder = client_builder.default_headers(headers);
     client_builder
         .build()
         .map_err(|e| McpError::ConnectionError(e.to_string()))
 }
 
+/// Map a downstream request error to `McpError` by its origin
+///
+/// JSON-RPC errors other than internal errors (invalid params, unknown tool, ...) are caused by
+/// the caller and become `ProtocolError`; HTTP errors keep their status code
+fn request_error(error: rmcp::ServiceError) -> McpError {
+    match &error {
+        rmcp::ServiceError::McpError(data)
+            if data.code != rmcp::model::ErrorCode::INTERNAL_ERROR =>
+        {
+            McpError::ProtocolError(error.to_string())
+        }
+        rmcp::ServiceError::Timeout { .. } => McpError::TimeoutError(error.to_string()),
+        rmcp::ServiceError::TransportSend(_) | rmcp::ServiceError::TransportClosed => {
+            match http_status(&error) {
+                Some(status) => McpError::HttpStatusError(status, error.to_string()),
+                None => McpError::ConnectionError(error.to_string()),
+            }
+        }
+        _ => McpError::ServiceError(error.to_string()),
+    }
+}
+
+/// HTTP status code returned by a streamable HTTP server, if the error carries one
+fn http_status(error: &rmcp::ServiceError) -> Option<u16> {
+    use rmcp::transport::streamable_http_client::StreamableHttpError;
+
+    let rmcp::ServiceError::TransportSend(error) = error else {
+        return None;
+    };
+    match error
+        .error
+        .downcast_ref::<StreamableHttpError<reqwest::Error>>()?
+    {
+        StreamableHttpError::Client(e) => e.status().map(|status| status.as_u16()),
+        StreamableHttpError::AuthRequired(_) => Some(401),
+        _ => None,
+    }
+}
+
+/// Number of stderr lines attached to connection failure messages
+const STDERR_TAIL_LINES: usize = 20;
+/// Maximum time to wait for the stderr reader after a failed handshake
+const STDERR_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Per-service request limits applied to call_tool / get_prompt / read_resource
+#[derive(Clone)]
+struct CallLimits {
+    request_timeout: Duration,
+    max_concurrent_calls: Option<u32>,
+    semaphore: Option<Arc<Semaphore>>,
+}
+
+/// Last use and number of in-flight calls of a service
+struct ServiceActivity {
+    last_used: Instant,
+    in_flight: usize,
+}
+
+impl ServiceActivity {
+    fn new() -> Self {
+        Self {
+            last_used: Instant::now(),
+            in_flight: 0,
+        }
+    }
+}
+
+/// Marks a call as in flight until dropped
+struct InFlightCall {
+    activity: Arc<std::sync::Mutex<HashMap<String, ServiceActivity>>>,
+    service_id: String,
+}
+
+impl Drop for InFlightCall {
+    fn drop(&mut self) {
+        let mut activity = self.activity.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = activity
+            .entry(self.service_id.clone())
+            .or_insert_with(ServiceActivity::new);
+        entry.in_flight = entry.in_flight.saturating_sub(1);
+        entry.last_used = Instant::now();
+    }
+}
+
 pub struct McpClientManager {
     connections: Arc<RwLock<HashMap<String, McpConnection>>>,
     connection_cache_ttl: std::time::Duration,
     tool_manager: Arc<crate::tool_manager::ToolManager>,
+    server_logs: Arc<crate::server_logs::ServerLogManager>,
+    process_monitor: Arc<crate::process_monitor::ProcessMonitor>,
+    resource_monitor: Arc<crate::resource_monitor::ResourceMonitor>,
+    circuit_breakers: Arc<std::sync::Mutex<HashMap<String, CircuitBreaker>>>,
+    call_limits: Arc<std::sync::Mutex<HashMap<String, CallLimits>>>,
+    /// 每个服务最近一次调用的时间和进行中的调用数（用于 lazy 服务的空闲停止）
+    activity: Arc<std::sync::Mutex<HashMap<String, ServiceActivity>>>,
+    /// 按需启动时的每服务锁，避免并发首次调用启动多个进程
+    start_locks: Arc<std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>>,
+    /// HTTP 服务凭据助手输出的缓存
+    credentials: Arc<CredentialCache>,
+    /// 下游 HTTP 服务的 OAuth 授权
+    oauth: Arc<OAuthClient>,
 }
 
 impl Clone for McpClientManager {
@@ -72,6 +199,15 @@ impl Clone for McpClientManager {
             connections: Arc::clone(&self.connections),
             connection_cache_ttl: self.connection_cache_ttl,
             tool_manager: Arc::clone(&self.tool_manager),
+            server_logs: Arc::clone(&self.server_logs),
+            process_monitor: Arc::clone(&self.process_monitor),
+            resource_monitor: Arc::clone(&self.resource_monitor),
+            circuit_breakers: Arc::clone(&self.circuit_breakers),
+            call_limits: Arc::clone(&self.call_limits),
+            activity: Arc::clone(&self.activity),
+            start_locks: Arc::clone(&self.start_locks),
+            credentials: Arc::clone(&self.credentials),
+            oauth: Arc::clone(&self.oauth),
         }
     }
 }
@@ -79,13 +215,41 @@ impl Clone for McpClientManager {
 impl McpClientManager {
     pub fn new(_config: AppConfig) -> Self {
         let tool_manager = Arc::new(crate::tool_manager::ToolManager::new());
+        let process_monitor = Arc::new(crate::process_monitor::ProcessMonitor::new());
+        let resource_monitor = Arc::new(crate::resource_monitor::ResourceMonitor::new(Arc::clone(
+            &process_monitor,
+        )));
         Self {
             connections: Arc::new(RwLock::new(HashMap::new())),
             connection_cache_ttl: std::time::Duration::from_secs(300),
             tool_manager,
+            server_logs: Arc::new(crate::server_logs::ServerLogManager::new()),
+            process_monitor,
+            resource_monitor,
+            circuit_breakers: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            call_limits: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            activity: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            start_locks: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            credentials: Arc::new(CredentialCache::default()),
+            oauth: Arc::new(OAuthClient::new()),
         }
     }
 
+    /// Access the stderr log collector of STDIO services
+    pub fn server_logs(&self) -> Arc<crate::server_logs::ServerLogManager> {
+        Arc::clone(&self.server_logs)
+    }
+
+    /// Access the child process monitor of STDIO services
+    pub fn process_monitor(&self) -> Arc<crate::process_monitor::ProcessMonitor> {
+        Arc::clone(&self.process_monitor)
+    }
+
+    /// Access the CPU/memory monitor of STDIO service processes
+    pub fn resource_monitor(&self) -> Arc<crate::resource_monitor::ResourceMonitor> {
+        Arc::clone(&self.resource_monitor)
+    }
+
     /// Check if cached connection is still valid
     async fn is_connection_cache_valid(&self, service_name: &str) -> bool {
         if let Some(connection) = self.connections.read().await.get(service_name) {
@@ -99,6 +263,18 @@ impl McpClientManager {
         false
     }
 
+    /// Append the last stderr lines of a STDIO service to an error message
+    fn with_stderr_tail(&self, service_name: &str, message: String) -> String {
+        let tail = self
+            .server_logs
+            .session_tail(service_name, STDERR_TAIL_LINES);
+        if tail.is_empty() {
+            message
+        } else {
+            format!("{}\nstderr:\n{}", message, tail.join("\n"))
+        }
+    }
+
     pub async fn ensure_connection(
         &self,
         service_config: &McpServerConfig,
@@ -112,14 +288,22 @@ impl McpClientManager {
             }
         }
 
-        let connection_result = match service_config.transport {
-            crate::types::ServiceTransport::Stdio => {
-                self.create_stdio_connection(service_config).await
-            }
-            crate::types::ServiceTransport::Http => {
-                self.create_http_connection(service_config).await
+        self.apply_call_limits(service_config);
+        // 记录未展开的配置，刷新凭据后用它重连
+        self.credentials.register(service_config);
+
+        let mut connection_result = self.connect_with_timeout(service_config).await;
+        // 需要 OAuth 授权的 HTTP 服务：打开浏览器授权后重连一次（授权不计入连接超时）
+        if connection_result.is_err() {
+            let unauthorized = self.credentials.was_unauthorized(service_name);
+            if self.oauth.should_prompt(service_config, unauthorized) {
+                tracing::info!("Server '{}' requires OAuth authorization", service_name);
+                connection_result = match self.authorize(service_config).await {
+                    Ok(()) => self.connect_with_timeout(service_config).await,
+                    Err(auth_error) => Err(auth_error),
+                };
             }
-        };
+        }
 
         // Store the connection result (success or failure) in cache
         let connection = match connection_result {
@@ -128,11 +312,13 @@ impl McpClientManager {
                     .write()
                     .await
                     .insert(service_name.clone(), conn.clone());
+                self.reset_circuit_breaker(service_name);
+                self.touch(service_name);
                 Ok(conn)
             }
             Err(e) => {
-                // Store failed connection status with error message
-                let error_message = e.to_string();
+                // Store failed connection status with error message, keep the original error
+                // so timeouts and auth failures are classified correctly by callers
                 let failed_connection = McpConnection {
                     service_id: service_name.clone(),
                     server_info: None,
@@ -141,88 +327,132 @@ impl McpClientManager {
                         is_connected: false,
                         is_connecting: false,
                         last_connected: Some(chrono::Utc::now()),
-                        error_message: Some(error_message.clone()),
+                        error_message: Some(e.to_string()),
                     },
                 };
                 self.connections
                     .write()
                     .await
                     .insert(service_name.clone(), failed_connection);
-                Err(McpError::ConnectionError(error_message))
+                Err(e)
             }
         };
 
         connection
     }
 
+    /// 在浏览器中完成服务的 OAuth 授权
+    async fn authorize(&self, service_config: &McpServerConfig) -> Result<()> {
+        let resolved = Self::resolve_config(service_config).await?;
+        self.oauth.authorize(&resolved).await
+    }
+
+    /// Run the OAuth flow of an HTTP service and reconnect with the new token
+    pub async fn authorize_server(&self, service_config: &McpServerConfig) -> Result<()> {
+        self.authorize(service_config).await?;
+        self.ensure_connection(service_config, true).await?;
+        Ok(())
+    }
+
+    /// Remove the stored OAuth authorization of a service
+    pub async fn clear_authorization(&self, service_id: &str) -> Result<()> {
+        self.oauth.sign_out(service_id).await
+    }
+
+    /// Whether a service has a stored OAuth authorization
+    pub async fn is_authorized(&self, service_id: &str) -> bool {
+        self.oauth.is_authorized(service_id).await
+    }
+
+    /// 在连接超时内创建连接，超时后终止未完成握手的进程
+    async fn connect_with_timeout(
+        &self,
+        service_config: &McpServerConfig,
+    ) -> Result<McpConnection> {
+        let service_name = &service_config.name;
+        let connect_timeout = service_config.connect_timeout();
+        let connect = async {
+            let service_config = Self::resolve_config(service_config).await?;
+            match service_config.transport {
+                crate::types::ServiceTransport::Stdio => {
+                    self.create_stdio_connection(&service_config).await
+                }
+                crate::types::ServiceTransport::Http => {
+                    self.create_http_connection(&service_config).await
+                }
+            }
+        };
+        match tokio::time::timeout(connect_timeout, connect).await {
+            Ok(result) => result,
+            Err(_) => {
+                // 超时后终止已启动但未完成握手的进程
+                self.process_monitor.stop(service_name);
+                Err(McpError::TimeoutError(self.with_stderr_tail(
+                    service_name,
+                    format!(
+                        "Connection to '{}' timed out after {}s",
+                        service_name,
+                        connect_timeout.as_secs()
+                    ),
+                )))
+            }
+        }
+    }
+
+    /// 展开占位符并解析 `${secret:name}` 引用，没有占位符时直接借用原配置
+    async fn resolve_config(service_config: &McpServerConfig) -> Result<Cow<'_, McpServerConfig>> {
+        if !crate::placeholders::contains_placeholders(service_config) {
+            return Ok(Cow::Borrowed(service_config));
+        }
+
+        let context = crate::placeholders::PlaceholderContext::load().await;
+        let (expanded, unresolved) = context.expand_server_config(service_config);
+        if !unresolved.is_empty() {
+            return Err(McpError::ConfigError(format!(
+                "Unresolved placeholders in server '{}': {}",
+                service_config.name,
+                unresolved.join(", ")
+            )));
+        }
+
+        if !crate::secrets::has_references(&expanded) {
+            return Ok(Cow::Owned(expanded));
+        }
+        let secret_store = crate::wait_for_secret_store().await?;
+        Ok(Cow::Owned(
+            secret_store.resolve_server_config(&expanded).await?,
+        ))
+    }
+
     /// Create STDIO connection using managed tools
     async fn create_stdio_connection(
         &self,
         service_config: &McpServerConfig,
     ) -> Result<McpConnection> {
+        if let Some(ref container) = service_config.container {
+            return self
+                .create_container_connection(service_config, container)
+                .await;
+        }
+
         let command = service_config.command.as_ref().ok_or_else(|| {
             McpError::InvalidConfiguration("STDIO service requires command".to_string())
         })?;
 
-        // Initialize tool manager and ensure tools are available
-        self.tool_manager.initialize().await?;
-        self.tool_manager.ensure_tools_for_command(command).await?;
-
-        // Get original args from config
-        let original_args = service_config.args.as_ref().cloned().unwrap_or_default();
-
-        // For npx commands, we need to combine command and args before conversion
-        let (converted_command, final_args) = {
-            let first_word = command.split_whitespace().next().unwrap_or("");
-            if first_word == "npx" {
-                // Combine command and args into a single command string
-                let full_command = if original_args.is_empty() {
-                    command.to_string()
-                } else {
-                    format!("{} {}", command, original_args.join(" "))
-                };
-
-                // Convert the full command
-                let converted = self.tool_manager.convert_command(&full_command);
-
-                // Parse converted command and filter arguments
-                let converted_parts: Vec<&str> = converted.split_whitespace().collect();
-                if converted_parts.len() >= 3
-                    && converted_parts[0] == "bun"
-                    && converted_parts[1] == "x"
-                {
-                    let mut filtered_args = Vec::new();
-                    for arg in converted_parts.iter() {
-                        let arg_lower = arg.to_lowercase();
-                        // Skip "bun" and -y/--yes, keep x and everything else
-                        if *arg != "bun" && arg_lower != "-y" && arg_lower != "--yes" {
-                            filtered_args.push(arg.to_string());
-                        } else if *arg != "bun" {
-                            tracing::debug!(
-                                "Removed npx-specific argument '{}' when converting to bun x",
-                                arg
-                            );
-                        }
-                    }
-                    (converted, filtered_args)
-                } else {
-                    // If converted command doesn't start with "bun x", use converted as command and empty args
-                    (converted, vec![])
-                }
-            } else {
-                // Non-npx commands: use regular conversion and keep original args
-                (self.tool_manager.convert_command(command), original_args)
-            }
+        let mut env_vars = HashMap::new();
+        let sandbox = service_config.sandbox.as_ref();
+        // 按服务的继承策略（以及沙箱白名单）过滤继承的环境变量
+        let inherits_env = |key: &str| {
+            service_config.inherits_env(key) && sandbox.is_none_or(|s| s.allows_inherited_env(key))
         };
 
-        let mut env_vars = service_config.env.clone().unwrap_or_default();
-
         // *** 新增：加载 Shell 环境变量 ***
         match crate::shell_environment::ShellEnvironment::load_environment().await {
             Ok(shell_env) => {
-                // 合并环境变量（shell 环境优先）
                 for (key, value) in shell_env {
-                    env_vars.insert(key, value);
+                    if inherits_env(&key) {
+                        env_vars.insert(key, value);
+                    }
                 }
                 tracing::debug!("Loaded shell environment variables");
             }
@@ -231,82 +461,404 @@ impl McpClientManager {
                     "Failed to load shell environment, using current process env: {}",
                     e
                 );
-                // 继续使用当前进程环境变量
+                // 继续使用当前进程环境变量（受继承策略限制时命令会清空环境，需显式传入）
+                for (key, value) in std::env::vars() {
+                    if inherits_env(&key) {
+                        env_vars.insert(key, value);
+                    }
+                }
             }
         }
 
-        // Get the executable path
-        let executable_path = {
+        // 按服务的运行时设置解析可执行文件（使用 Shell 环境的 PATH 查找系统命令）
+        let (executable_path, final_args, runtime) = self
+            .resolve_command(service_config, command, &mut env_vars)
+            .await?;
+
+        // Load settings and apply environment configuration
+        if let Some(settings) = crate::config::CONFIG_SERVICE.current().settings.clone() {
+            // Apply environment variables based on command type
             let first_word = command.split_whitespace().next().unwrap_or("");
-            if first_word == "npx" && converted_command.starts_with("bun x") {
-                // For converted npx commands, use bun executable
-                self.tool_manager
-                    .get_executable_path("bun")
-                    .await
-                    .unwrap_or_else(|_| {
-                        tracing::warn!(
-                            "Tool not found in managed directory, falling back to system PATH"
-                        );
-                        PathBuf::from("bun")
-                    })
-            } else {
-                // For other commands, use regular path resolution
-                self.tool_manager
-                    .get_executable_path(&converted_command)
-                    .await
-                    .unwrap_or_else(|_| {
-                        tracing::warn!(
-                            "Tool not found in managed directory, falling back to system PATH"
-                        );
-                        PathBuf::from(
-                            converted_command
-                                .split_whitespace()
-                                .next()
-                                .unwrap_or(&converted_command),
-                        )
-                    })
+            if first_word == "uvx" || first_word == "uv" {
+                if let Some(uv_index_url) = settings.uv_index_url {
+                    env_vars.insert("UV_INDEX_URL".to_string(), uv_index_url);
+                }
+            } else if first_word == "npx" || first_word == "npm" {
+                if let Some(npm_registry) = settings.npm_registry {
+                    env_vars.insert("NPM_CONFIG_REGISTRY".to_string(), npm_registry);
+                }
+            }
+        }
+
+        // 服务自身配置的 env 优先于 Shell 环境和全局设置
+        env_vars.extend(service_config.env.clone().unwrap_or_default());
+
+        // 沙箱中的工作目录同样需要在宿主机上存在，随后挂载到沙箱内
+        let cwd = service_config.validated_cwd()?;
+
+        tracing::info!(
+            "Creating STDIO MCP service: {} ({} runtime: {}), {} args",
+            service_config.name,
+            runtime.kind,
+            runtime.executable,
+            final_args.len()
+        );
+
+        // 沙箱中的 HOME 是空的临时目录，内置运行时所在目录需只读挂载
+        let sandbox = sandbox.map(|profile| {
+            let mut profile = profile.clone();
+            if self.tool_manager.is_managed(&executable_path) {
+                let bin_dir = self.tool_manager.bin_dir().display().to_string();
+                profile.read_only_paths.push(bin_dir);
+            }
+            profile
+        });
+
+        // Create transport
+        let mut command_builder = match sandbox.as_ref() {
+            Some(profile) => {
+                tracing::info!(
+                    "🔒 Starting service '{}' in sandbox (network: {}, allowed paths: {:?})",
+                    service_config.name,
+                    profile.network,
+                    profile.allowed_paths
+                );
+                let home = env_vars
+                    .get("HOME")
+                    .cloned()
+                    .or_else(|| dirs::home_dir().map(|h| h.to_string_lossy().to_string()));
+                crate::sandbox::build_command(
+                    profile,
+                    &executable_path,
+                    &final_args,
+                    home.as_deref(),
+                    cwd.as_deref(),
+                )?
+            }
+            None => {
+                let mut command_builder = Command::new(&executable_path);
+                command_builder.args(&final_args);
+                if service_config.env_inheritance != EnvInheritance::InheritAll {
+                    // 不继承当前进程的环境变量，只使用上面过滤后的变量
+                    command_builder.env_clear();
+                }
+                if let Some(ref dir) = cwd {
+                    command_builder.current_dir(dir);
+                }
+                command_builder
             }
         };
+        for (key, value) in env_vars {
+            command_builder.env(key, value);
+        }
 
-        // Load settings and apply environment configuration
-        if let Ok(config) = crate::config::AppConfig::load() {
-            if let Some(settings) = config.settings {
-                // Apply environment variables based on command type
-                let first_word = command.split_whitespace().next().unwrap_or("");
-                if first_word == "uvx" || first_word == "uv" {
-                    if let Some(uv_index_url) = settings.uv_index_url {
-                        env_vars.insert("UV_INDEX_URL".to_string(), uv_index_url);
+        let program = executable_path.display().to_string();
+        self.spawn_stdio_service(service_config, command_builder, &program, runtime, None)
+            .await
+    }
+
+    /// 根据服务的运行时设置解析可执行文件、参数以及实际使用的运行时
+    async fn resolve_command(
+        &self,
+        service_config: &McpServerConfig,
+        command: &str,
+        env_vars: &mut HashMap<String, String>,
+    ) -> Result<(PathBuf, Vec<String>, LaunchedRuntime)> {
+        let mut words = command.split_whitespace();
+        let first_word = words.next().unwrap_or("");
+        // 命令中附带的参数放在配置的 args 之前
+        let args: Vec<String> = words
+            .map(str::to_string)
+            .chain(service_config.args.iter().flatten().cloned())
+            .collect();
+        let launched = |kind: &str, path: &Path| LaunchedRuntime {
+            kind: kind.to_string(),
+            executable: path.display().to_string(),
+        };
+
+        if let ServerRuntime::Path(path) = &service_config.runtime {
+            let path = match (path.strip_prefix("~/"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => PathBuf::from(path),
+            };
+            if !path.is_file() {
+                return Err(McpError::InvalidConfiguration(format!(
+                    "Runtime executable '{}' does not exist",
+                    path.display()
+                )));
+            }
+            return Ok((path.clone(), args, launched("path", &path)));
+        }
+
+        // 设置中为该命令指定的可执行文件，只读取一次当前配置
+        let configured = crate::config::CONFIG_SERVICE
+            .current()
+            .settings
+            .as_ref()
+            .and_then(|settings| {
+                crate::tool_manager::ToolManager::configured_command_path(
+                    &settings.command_paths,
+                    first_word,
+                )
+            });
+
+        let runtime_version = service_config.runtime_version.as_deref();
+        if matches!(first_word, "npx" | "npm" | "node") {
+            // 内置 Node.js：显式选择，或在 auto 下固定了版本
+            let managed_node = service_config.runtime == ServerRuntime::Node
+                || (service_config.runtime == ServerRuntime::Auto && runtime_version.is_some());
+            if managed_node {
+                let version = self.tool_manager.ensure_node(runtime_version).await?;
+                let path = self.tool_manager.node_command_path(&version, first_word);
+                // npx / npm 脚本通过 `env node` 启动，需要把内置 Node.js 放在 PATH 最前面
+                let bin_dir = self.tool_manager.node_bin_dir(&version);
+                let separator = if cfg!(windows) { ";" } else { ":" };
+                let search_path = match env_vars.get("PATH") {
+                    Some(path) => format!("{}{}{}", bin_dir.display(), separator, path),
+                    None => bin_dir.display().to_string(),
+                };
+                env_vars.insert("PATH".to_string(), search_path);
+                return Ok((path.clone(), args, launched("node", &path)));
+            }
+            if let Some(version) = runtime_version {
+                tracing::warn!(
+                    "Runtime version {} of service '{}' is ignored with the {} runtime",
+                    version,
+                    service_config.name,
+                    service_config.runtime
+                );
+            }
+
+            let search_path = env_vars.get("PATH").map(String::as_str);
+            match service_config.runtime {
+                ServerRuntime::SystemNode => {
+                    let path = configured
+                        .or_else(|| {
+                            self.tool_manager
+                                .find_system_command(first_word, search_path)
+                        })
+                        .ok_or_else(|| {
+                            McpError::ToolNotFound(format!(
+                                "{} (system Node.js runtime)",
+                                first_word
+                            ))
+                        })?;
+                    return Ok((path.clone(), args, launched("system-node", &path)));
+                }
+                // auto：设置中指定的路径优先；node 没有 Bun 的等价转换，优先使用系统 Node.js
+                ServerRuntime::Auto => {
+                    let system = configured.or_else(|| {
+                        (first_word == "node")
+                            .then(|| {
+                                self.tool_manager
+                                    .find_system_command(first_word, search_path)
+                            })
+                            .flatten()
+                    });
+                    if let Some(path) = system {
+                        return Ok((path.clone(), args, launched("system-node", &path)));
                     }
-                } else if first_word == "npx" || first_word == "npm" {
-                    if let Some(npm_registry) = settings.npm_registry {
-                        env_vars.insert("NPM_CONFIG_REGISTRY".to_string(), npm_registry);
+                }
+                _ => {}
+            }
+
+            // npx / npm 默认使用内置 Bun，auto 下 Bun 不可用时退回系统命令
+            let bun = match self.managed_bun().await {
+                Ok(bun) => bun,
+                Err(e) if service_config.runtime == ServerRuntime::Auto => {
+                    let path = self
+                        .tool_manager
+                        .find_system_command(first_word, search_path)
+                        .ok_or(e)?;
+                    tracing::info!(
+                        "Managed Bun is unavailable, using system '{}' for service '{}'",
+                        first_word,
+                        service_config.name
+                    );
+                    return Ok((path.clone(), args, launched("system-node", &path)));
+                }
+                Err(e) => return Err(e),
+            };
+            let args = if first_word == "npx" {
+                // npx 转换为 bun x，并去掉 bun 不支持的 -y / --yes
+                std::iter::once("x".to_string())
+                    .chain(args.into_iter().filter(|arg| {
+                        let arg = arg.to_lowercase();
+                        arg != "-y" && arg != "--yes"
+                    }))
+                    .collect()
+            } else {
+                args
+            };
+            return Ok((bun.clone(), args, launched("bun", &bun)));
+        }
+
+        // 设置中指定的路径优先于内置工具
+        let (path, kind) = match configured {
+            Some(path) => (path, "system"),
+            None => {
+                self.tool_manager.initialize().await?;
+                self.tool_manager
+                    .ensure_tools_for_command(first_word)
+                    .await?;
+                match self.tool_manager.get_executable_path(first_word).await {
+                    Ok(path) if self.tool_manager.is_managed(&path) => (path, "managed"),
+                    Ok(path) => (path, "system"),
+                    Err(_) => {
+                        tracing::debug!(
+                            "'{}' not found in managed directory, falling back to system PATH",
+                            first_word
+                        );
+                        (PathBuf::from(first_word), "system")
                     }
                 }
             }
+        };
+
+        if matches!(first_word, "uv" | "uvx") {
+            // uv 管理的 Python 安装在内置目录；固定版本时只使用该版本的内置 Python
+            env_vars.extend(self.tool_manager.uv_python_env());
+            if runtime_version.is_some() {
+                let version = self.tool_manager.ensure_python(runtime_version).await?;
+                env_vars.insert("UV_PYTHON".to_string(), version);
+                env_vars.insert(
+                    "UV_PYTHON_PREFERENCE".to_string(),
+                    "only-managed".to_string(),
+                );
+            }
         }
+        Ok((path.clone(), args, launched(kind, &path)))
+    }
 
-        tracing::debug!(
-            "Creating STDIO MCP service: {} (converted to: {}), args: {:?}",
+    /// 内置 Bun 的路径，未安装时先安装
+    async fn managed_bun(&self) -> Result<PathBuf> {
+        self.tool_manager.initialize().await?;
+        self.tool_manager.ensure_tools_for_command("bun").await?;
+        self.tool_manager.get_executable_path("bun").await
+    }
+
+    /// 在容器中运行服务：`docker/podman run -i --rm`，通过容器 CLI 的 stdio 通信
+    async fn create_container_connection(
+        &self,
+        service_config: &McpServerConfig,
+        container: &ContainerConfig,
+    ) -> Result<McpConnection> {
+        let runtime = crate::container::resolve_runtime(container.runtime)?;
+        if service_config.sandbox.is_some() {
+            tracing::warn!(
+                "Service '{}' runs in a container, sandbox profile is ignored",
+                service_config.name
+            );
+        }
+
+        // 清理上次异常退出遗留的容器
+        crate::container::remove_server_containers(&runtime, &service_config.name).await;
+
+        // 容器不继承宿主环境，只有服务配置的 env 通过 `-e KEY` 传入；
+        // 变量值放在容器 CLI 的环境中，避免出现在进程命令行里
+        let service_env = service_config.env.clone().unwrap_or_default();
+        let name = crate::container::container_name(&service_config.name);
+        let args = service_config.args.clone().unwrap_or_default();
+        let run_args = crate::container::run_args(
+            container,
+            &service_config.name,
+            &name,
+            &args,
+            service_env.keys(),
+        );
+
+        tracing::info!(
+            "🐳 Starting service '{}' in container {} ({})",
             service_config.name,
-            converted_command,
-            final_args
+            name,
+            container.image_ref()
         );
 
-        // Create transport
-        let mut command_builder = Command::new(executable_path);
-        command_builder.args(&final_args);
-        for (key, value) in env_vars {
-            command_builder.env(key, value);
+        let mut command_builder = Command::new(&runtime);
+        command_builder.args(&run_args);
+        // 容器 CLI 自身需要 Shell 环境（DOCKER_HOST、代理等）
+        match crate::shell_environment::ShellEnvironment::load_environment().await {
+            Ok(shell_env) => {
+                command_builder.envs(shell_env);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load shell environment, using current process env: {}",
+                    e
+                );
+            }
         }
+        command_builder.envs(service_env);
+
+        let program = format!("{} ({})", runtime.display(), container.image_ref());
+        let cleanup = crate::container::remove_command(&runtime, &name);
+        let runtime = LaunchedRuntime {
+            kind: "container".to_string(),
+            executable: runtime.display().to_string(),
+        };
+        self.spawn_stdio_service(
+            service_config,
+            command_builder,
+            &program,
+            runtime,
+            Some(cleanup),
+        )
+        .await
+    }
 
-        let transport = TokioChildProcess::new(command_builder)
+    /// 启动子进程并建立 STDIO MCP 会话
+    async fn spawn_stdio_service(
+        &self,
+        service_config: &McpServerConfig,
+        mut command_builder: Command,
+        program: &str,
+        runtime: LaunchedRuntime,
+        cleanup: Option<Command>,
+    ) -> Result<McpConnection> {
+        // 自行启动子进程，以便监视退出码并获取 PID；stderr 写入服务日志
+        command_builder
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        let mut child = command_builder
+            .spawn()
             .map_err(|e| McpError::ConnectionError(e.to_string()))?;
+        let (Some(stdout), Some(stdin)) = (child.stdout.take(), child.stdin.take()) else {
+            return Err(McpError::ConnectionError(
+                "Failed to capture stdio of child process".to_string(),
+            ));
+        };
+        let stderr_reader = child.stderr.take().map(|stderr| {
+            self.server_logs
+                .spawn_reader(&service_config.name, program, stderr)
+        });
+        tracing::debug!(
+            "Started process for service '{}' (pid: {:?})",
+            service_config.name,
+            child.id()
+        );
+        self.process_monitor
+            .watch(&service_config.name, child, runtime, cleanup);
 
         // Create service
-        let service =
-            ().serve(transport)
-                .await
-                .map_err(|e| McpError::ConnectionError(e.to_string()))?;
+        let service = match ().serve((stdout, stdin)).await {
+            Ok(service) => service,
+            Err(e) => {
+                // 等待 stderr 读取任务读完进程退出前的输出（子进程可能把管道留给后代进程，需限时）
+                if let Some(reader) = stderr_reader {
+                    let _ = tokio::time::timeout(STDERR_DRAIN_TIMEOUT, reader).await;
+                }
+                let message = match self.process_monitor.exited(&service_config.name) {
+                    Some(exit) => format!("{} (process {})", e, exit),
+                    None => e.to_string(),
+                };
+                self.process_monitor.stop(&service_config.name);
+                return Err(McpError::ConnectionError(
+                    self.with_stderr_tail(&service_config.name, message),
+                ));
+            }
+        };
 
         let server_info = service.peer_info();
 
@@ -340,48 +892,86 @@ impl McpClientManager {
         tracing::debug!("Creating HTTP MCP service: {}", url);
         tracing::debug!("Service '{}' URL: {}", service_config.name, url);
 
-        // Log custom headers if present
+        // Log custom header names if present (values may contain resolved secrets)
         if let Some(headers) = &service_config.headers {
             tracing::debug!(
                 "Service '{}' custom headers: {:?}",
                 service_config.name,
-                headers
+                headers.keys().collect::<Vec<_>>()
             );
         }
 
-        // Create HTTP client with reqwest including custom headers
-        tracing::debug!("Creating HTTP client for service '{}'", service_config.name);
-        let client = create_http_reqwest_client(service_config.headers.as_ref())?;
-
-        // Create HTTP transport configuration with stateless mode enabled
-        let mut config =
-            rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig::with_uri(
-                url.as_ref(),
-            );
-        // Enable stateless mode to support servers that don't support SSE streaming
-        config.allow_stateless = true;
-
-        // Use the public type alias - reqwest::Client implements StreamableHttpClient
-        let transport = rmcp::transport::StreamableHttpClientTransport::with_client(client, config);
+        let helper = service_config.credential_helper.as_ref();
+        let mut refreshed = false;
+        let service = loop {
+            // 凭据助手优先，否则使用保存的 OAuth 令牌
+            let credential = match helper {
+                Some(helper) => Some(
+                    self.credentials
+                        .header(&service_config.name, helper)
+                        .await?,
+                ),
+                None => self
+                    .oauth
+                    .access_token(service_config)
+                    .await?
+                    .map(|token| ("Authorization".to_string(), format!("Bearer {}", token))),
+            };
 
-        // Add detailed error logging for connection failures
-        let service = match ().serve(transport).await {
-            Ok(service) => {
-                tracing::info!(
-                    "Successfully connected to HTTP MCP service: {}",
-                    service_config.name
-                );
-                service
-            }
-            Err(e) => {
-                let error_msg = e.to_string();
-                tracing::error!(
-                    "Failed to connect to HTTP MCP service '{}': {}",
-                    service_config.name,
-                    error_msg
+            // Create HTTP client with reqwest including custom headers
+            tracing::debug!("Creating HTTP client for service '{}'", service_config.name);
+            let client = create_http_reqwest_client(
+                service_config.headers.as_ref(),
+                credential.as_ref(),
+                service_config.connect_timeout(),
+                service_config.request_timeout(),
+            )?;
+
+            // Create HTTP transport configuration with stateless mode enabled
+            let mut config =
+                rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig::with_uri(
+                    url.as_ref(),
                 );
+            // Enable stateless mode to support servers that don't support SSE streaming
+            config.allow_stateless = true;
+
+            // 记录下游返回的 HTTP 401，用于判断是否需要刷新凭据
+            let client = self
+                .credentials
+                .track(&service_config.name, client, credential.is_some());
+            let transport =
+                rmcp::transport::StreamableHttpClientTransport::with_client(client, config);
+
+            // Add detailed error logging for connection failures
+            match ().serve(transport).await {
+                Ok(service) => {
+                    tracing::info!(
+                        "Successfully connected to HTTP MCP service: {}",
+                        service_config.name
+                    );
+                    break service;
+                }
+                Err(e) => {
+                    let error = McpError::ConnectionError(e.to_string());
+                    // 缓存的令牌可能已被服务端吊销，刷新后重试一次
+                    if !refreshed && self.credentials.credential_rejected(&service_config.name) {
+                        tracing::warn!(
+                            "HTTP MCP service '{}' rejected the cached credential, refreshing",
+                            service_config.name
+                        );
+                        self.credentials.invalidate(&service_config.name).await;
+                        self.oauth.reject(&service_config.name);
+                        refreshed = true;
+                        continue;
+                    }
 
-                return Err(McpError::ConnectionError(error_msg));
+                    tracing::error!(
+                        "Failed to connect to HTTP MCP service '{}': {}",
+                        service_config.name,
+                        error
+                    );
+                    return Err(error);
+                }
             }
         };
 
@@ -412,29 +1002,18 @@ impl McpClientManager {
                 tracing::debug!("Disconnected from service: {}", service_id);
             }
         }
+        self.process_monitor.stop(service_id);
         Ok(())
     }
 
     pub async fn list_tools(&self, connection_id: &str) -> Result<Vec<Tool>> {
-        let connections = self.connections.read().await;
-        let connection = connections
-            .get(connection_id)
-            .ok_or_else(|| McpError::ServiceNotFound(connection_id.to_string()))?;
-
-        if !connection.status.is_connected {
-            return Err(McpError::ConnectionError(
-                "Service not connected".to_string(),
-            ));
-        }
-
-        if let Some(ref client_arc) = connection.client {
+        if let Some(client) = self.connected_client(connection_id).await? {
             tracing::debug!(
                 "Attempting to fetch tools from rmcp client {}",
                 connection_id
             );
 
-            // Extract the peer from the service
-            let peer = client_arc.peer();
+            let peer = client.peer();
 
             // Create list tools request with pagination support
             let request =
@@ -446,10 +1025,9 @@ impl McpClientManager {
             // Send request via peer and get response
             // Note: We need to convert to ClientRequest
             let client_request: rmcp::model::ClientRequest = request.into();
-            let server_result = peer
-                .send_request(client_request)
-                .await
-                .map_err(|e| McpError::ServiceError(e.to_string()))?;
+            let server_result = self
+                .send_list_request(connection_id, peer, client_request)
+                .await?;
             let duration = start_time.elapsed();
 
             // Convert ServerResult to ListToolsResult
@@ -492,24 +1070,13 @@ impl McpClientManager {
     }
 
     pub async fn list_resources(&self, connection_id: &str) -> Result<Vec<rmcp::model::Resource>> {
-        let connections = self.connections.read().await;
-        let connection = connections
-            .get(connection_id)
-            .ok_or_else(|| McpError::ServiceNotFound(connection_id.to_string()))?;
-
-        if !connection.status.is_connected {
-            return Err(McpError::ConnectionError(
-                "Service not connected".to_string(),
-            ));
-        }
-
-        if let Some(ref client_arc) = connection.client {
+        if let Some(client) = self.connected_client(connection_id).await? {
             tracing::debug!(
                 "Attempting to fetch resources from rmcp client {}",
                 connection_id
             );
 
-            let peer = client_arc.peer();
+            let peer = client.peer();
 
             let request =
                 rmcp::model::ListResourcesRequest::with_param(rmcp::model::PaginatedRequestParam {
@@ -518,10 +1085,9 @@ impl McpClientManager {
 
             let start_time = std::time::Instant::now();
             let client_request: rmcp::model::ClientRequest = request.into();
-            let server_result = peer
-                .send_request(client_request)
-                .await
-                .map_err(|e| McpError::ServiceError(e.to_string()))?;
+            let server_result = self
+                .send_list_request(connection_id, peer, client_request)
+                .await?;
             let duration = start_time.elapsed();
 
             let result = match server_result {
@@ -557,24 +1123,13 @@ impl McpClientManager {
     }
 
     pub async fn list_prompts(&self, connection_id: &str) -> Result<Vec<rmcp::model::Prompt>> {
-        let connections = self.connections.read().await;
-        let connection = connections
-            .get(connection_id)
-            .ok_or_else(|| McpError::ServiceNotFound(connection_id.to_string()))?;
-
-        if !connection.status.is_connected {
-            return Err(McpError::ConnectionError(
-                "Service not connected".to_string(),
-            ));
-        }
-
-        if let Some(ref client_arc) = connection.client {
+        if let Some(client) = self.connected_client(connection_id).await? {
             tracing::debug!(
                 "Attempting to fetch prompts from rmcp client {}",
                 connection_id
             );
 
-            let peer = client_arc.peer();
+            let peer = client.peer();
 
             let request =
                 rmcp::model::ListPromptsRequest::with_param(rmcp::model::PaginatedRequestParam {
@@ -583,10 +1138,9 @@ impl McpClientManager {
 
             let start_time = std::time::Instant::now();
             let client_request: rmcp::model::ClientRequest = request.into();
-            let server_result = peer
-                .send_request(client_request)
-                .await
-                .map_err(|e| McpError::ServiceError(e.to_string()))?;
+            let server_result = self
+                .send_list_request(connection_id, peer, client_request)
+                .await?;
             let duration = start_time.elapsed();
 
             let result = match server_result {
@@ -626,11 +1180,10 @@ impl McpClientManager {
         }
     }
 
-    pub async fn read_resource(
-        &self,
-        connection_id: &str,
-        uri: &str,
-    ) -> Result<rmcp::model::ReadResourceResult> {
+    /// Clone the client of a connected service so requests don't hold the connections lock
+    ///
+    /// 返回 None 表示连接存在但没有可用的客户端
+    async fn connected_client(&self, connection_id: &str) -> Result<Option<Arc<McpService>>> {
         let connections = self.connections.read().await;
         let connection = connections
             .get(connection_id)
@@ -641,15 +1194,365 @@ impl McpClientManager {
                 "Service not connected".to_string(),
             ));
         }
+        Ok(connection.client.clone())
+    }
+
+    /// Send a list request with the service's request timeout
+    ///
+    /// list 请求不经过 guarded_call，下游挂起时也不能无限等待
+    async fn send_list_request(
+        &self,
+        connection_id: &str,
+        peer: &rmcp::service::Peer<rmcp::service::RoleClient>,
+        request: rmcp::model::ClientRequest,
+    ) -> Result<rmcp::model::ServerResult> {
+        let request_timeout = self.call_limits(connection_id).request_timeout;
+        match tokio::time::timeout(request_timeout, peer.send_request(request)).await {
+            Ok(result) => result.map_err(request_error),
+            Err(_) => Err(McpError::TimeoutError(format!(
+                "Request to '{}' timed out after {}s",
+                connection_id,
+                request_timeout.as_secs()
+            ))),
+        }
+    }
+
+    /// Apply per-service request limits from config
+    ///
+    /// 并发上限未变化时保留原信号量，避免影响正在进行的调用
+    pub fn apply_call_limits(&self, service_config: &McpServerConfig) {
+        let mut limits = self.call_limits.lock().unwrap_or_else(|e| e.into_inner());
+        let max_concurrent_calls = service_config.max_concurrent_calls.filter(|v| *v > 0);
+
+        let semaphore = match limits.get(&service_config.name) {
+            Some(existing) if existing.max_concurrent_calls == max_concurrent_calls => {
+                existing.semaphore.clone()
+            }
+            _ => max_concurrent_calls.map(|max| Arc::new(Semaphore::new(max as usize))),
+        };
+
+        limits.insert(
+            service_config.name.clone(),
+            CallLimits {
+                request_timeout: service_config.request_timeout(),
+                max_concurrent_calls,
+                semaphore,
+            },
+        );
+        drop(limits);
+
+        if let Some(breaker) = self
+            .circuit_breakers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(&service_config.name)
+        {
+            breaker.set_request_timeout(service_config.request_timeout());
+        }
+    }
+
+    fn call_limits(&self, service_id: &str) -> CallLimits {
+        let limits = self.call_limits.lock().unwrap_or_else(|e| e.into_inner());
+        limits
+            .get(service_id)
+            .cloned()
+            .unwrap_or_else(|| CallLimits {
+                request_timeout: Duration::from_secs(crate::types::DEFAULT_REQUEST_TIMEOUT_SECS),
+                max_concurrent_calls: None,
+                semaphore: None,
+            })
+    }
+
+    /// Run a request through `guarded_call`, refreshing the credential helper token if needed
+    ///
+    /// HTTP 服务在凭据助手或 OAuth 令牌过期时先重连；带凭据的请求被下游以 HTTP 401
+    /// 拒绝（请求未执行）时刷新凭据、重连并重试一次
+    async fn with_credential_refresh<T, F, Fut>(&self, service_id: &str, request: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let Some(service_config) = self.credentials.server_config(service_id) else {
+            return self.guarded_call(service_id, request()).await;
+        };
+
+        if self.is_connected(service_id).await && self.credential_expired(service_id).await {
+            self.refresh_credential(&service_config, false).await?;
+        }
+
+        match self.guarded_call(service_id, request()).await {
+            Err(_) if self.credentials.credential_rejected(service_id) => {
+                tracing::warn!(
+                    "Service '{}' returned 401, refreshing credential and retrying",
+                    service_id
+                );
+                self.refresh_credential(&service_config, true).await?;
+                self.guarded_call(service_id, request()).await
+            }
+            result => result,
+        }
+    }
+
+    async fn credential_expired(&self, service_id: &str) -> bool {
+        self.credentials.is_expired(service_id).await || self.oauth.is_expired(service_id)
+    }
+
+    /// 刷新凭据助手或 OAuth 令牌并重连，并发调用只会重连一次
+    async fn refresh_credential(
+        &self,
+        service_config: &McpServerConfig,
+        rejected: bool,
+    ) -> Result<()> {
+        let service_name = &service_config.name;
+        let lock = {
+            let mut locks = self.start_locks.lock().unwrap_or_else(|e| e.into_inner());
+            Arc::clone(locks.entry(service_name.clone()).or_default())
+        };
+        let _guard = lock.lock().await;
+
+        // 等待锁期间其他调用可能已经完成刷新
+        if !rejected && !self.credential_expired(service_name).await {
+            return Ok(());
+        }
+        if rejected {
+            self.credentials.invalidate(service_name).await;
+            self.oauth.reject(service_name);
+        }
+        self.ensure_connection(service_config, true).await?;
+        Ok(())
+    }
+
+    /// Run a request with the service's concurrency limit, timeout and circuit breaker
+    ///
+    /// 并发已满且在超时时间内无法获取许可、或熔断打开时直接返回 ServiceUnavailable，
+    /// 否则记录调用结果和耗时
+    async fn guarded_call<T>(
+        &self,
+        service_id: &str,
+        request: impl std::future::Future<Output = Result<T>>,
+    ) -> Result<T> {
+        let limits = self.call_limits(service_id);
+        let start_time = std::time::Instant::now();
+        // 调用结束（包括取消）前不会被当作空闲服务停止
+        let _in_flight = self.begin_call(service_id);
+
+        let _permit = match &limits.semaphore {
+            Some(semaphore) => {
+                match tokio::time::timeout(
+                    limits.request_timeout,
+                    Arc::clone(semaphore).acquire_owned(),
+                )
+                .await
+                {
+                    Ok(Ok(permit)) => Some(permit),
+                    Ok(Err(e)) => return Err(McpError::ServiceError(e.to_string())),
+                    Err(_) => {
+                        return Err(McpError::ServiceUnavailable(format!(
+                            "Too many concurrent calls to '{}' (limit {})",
+                            service_id,
+                            limits.max_concurrent_calls.unwrap_or_default()
+                        )))
+                    }
+                }
+            }
+            None => None,
+        };
+
+        let permit = {
+            let mut breakers = self
+                .circuit_breakers
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            let breaker = breakers
+                .entry(service_id.to_string())
+                .or_insert_with(|| CircuitBreaker::new(limits.request_timeout));
+            match breaker.try_acquire() {
+                Ok(permit) => permit,
+                Err(retry_after) => {
+                    return Err(McpError::ServiceUnavailable(format!(
+                        "Circuit breaker for '{}' is open, retry in {}s",
+                        service_id,
+                        retry_after.as_secs().max(1)
+                    )));
+                }
+            }
+        };
+
+        // 排队等待的时间也计入请求超时
+        let remaining = limits.request_timeout.saturating_sub(start_time.elapsed());
+        let call_start = std::time::Instant::now();
+        let result = match tokio::time::timeout(remaining, request).await {
+            Ok(result) => result,
+            Err(_) => Err(McpError::TimeoutError(format!(
+                "Request to '{}' timed out after {}s",
+                service_id,
+                limits.request_timeout.as_secs()
+            ))),
+        };
+        let latency = call_start.elapsed();
+
+        // 只有下游故障计入失败率，调用方的参数错误等不影响熔断
+        let failed = result
+            .as_ref()
+            .err()
+            .is_some_and(crate::circuit_breaker::is_failure);
+        let mut breakers = self
+            .circuit_breakers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(breaker) = breakers.get_mut(service_id) {
+            breaker.record(permit, failed, latency);
+        }
+        drop(breakers);
+
+        result
+    }
+
+    fn begin_call(&self, service_id: &str) -> InFlightCall {
+        let mut activity = self.activity.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = activity
+            .entry(service_id.to_string())
+            .or_insert_with(ServiceActivity::new);
+        entry.in_flight += 1;
+        entry.last_used = Instant::now();
+        InFlightCall {
+            activity: Arc::clone(&self.activity),
+            service_id: service_id.to_string(),
+        }
+    }
+
+    /// Record activity on a service
+    pub fn touch(&self, service_id: &str) {
+        let mut activity = self.activity.lock().unwrap_or_else(|e| e.into_inner());
+        activity
+            .entry(service_id.to_string())
+            .or_insert_with(ServiceActivity::new)
+            .last_used = Instant::now();
+    }
+
+    /// Time since the last call to a service, None if it was never used
+    ///
+    /// 有调用进行中时返回零
+    pub fn idle_duration(&self, service_id: &str) -> Option<Duration> {
+        let activity = self.activity.lock().unwrap_or_else(|e| e.into_inner());
+        activity.get(service_id).map(|a| {
+            if a.in_flight > 0 {
+                Duration::ZERO
+            } else {
+                a.last_used.elapsed()
+            }
+        })
+    }
+
+    /// Whether a service currently has a live connection
+    pub async fn is_connected(&self, service_id: &str) -> bool {
+        self.connections
+            .read()
+            .await
+            .get(service_id)
+            .map(|c| c.status.is_connected)
+            .unwrap_or(false)
+    }
+
+    /// Start a lazy service on first use
+    ///
+    /// 同一服务的并发首次调用只会启动一个进程
+    pub async fn start_on_demand(&self, service_config: &McpServerConfig) -> Result<()> {
+        let service_name = &service_config.name;
+        let lock = {
+            let mut locks = self.start_locks.lock().unwrap_or_else(|e| e.into_inner());
+            Arc::clone(locks.entry(service_name.clone()).or_default())
+        };
+        let _guard = lock.lock().await;
+
+        if self.is_connected(service_name).await {
+            self.touch(service_name);
+            return Ok(());
+        }
+
+        tracing::info!("🚀 Starting lazy server '{}' on demand", service_name);
+        self.ensure_connection(service_config, true).await?;
+        self.touch(service_name);
+        Ok(())
+    }
+
+    /// Stop a lazy service that has been idle for `idle_timeout`, it will be started again on next use
+    ///
+    /// 持有启动锁后重新检查空闲时间，有调用进行中或期间被使用过时不停止，返回是否已停止
+    pub async fn stop_idle(&self, service_id: &str, idle_timeout: Duration) -> Result<bool> {
+        let lock = {
+            let mut locks = self.start_locks.lock().unwrap_or_else(|e| e.into_inner());
+            Arc::clone(locks.entry(service_id.to_string()).or_default())
+        };
+        let _guard = lock.lock().await;
+
+        {
+            let mut activity = self.activity.lock().unwrap_or_else(|e| e.into_inner());
+            match activity.get(service_id) {
+                Some(a) if a.in_flight > 0 || a.last_used.elapsed() < idle_timeout => {
+                    return Ok(false)
+                }
+                _ => {}
+            }
+            activity.remove(service_id);
+        }
+
+        self.disconnect_server(service_id).await?;
+        Ok(true)
+    }
+
+    /// Get the circuit breaker state of a service
+    pub fn circuit_breaker_snapshot(&self, service_id: &str) -> CircuitBreakerSnapshot {
+        let breakers = self
+            .circuit_breakers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        breakers
+            .get(service_id)
+            .map(|breaker| breaker.snapshot())
+            .unwrap_or_else(|| {
+                CircuitBreaker::new(Duration::from_secs(
+                    crate::types::DEFAULT_REQUEST_TIMEOUT_SECS,
+                ))
+                .snapshot()
+            })
+    }
+
+    /// Reset the circuit breaker of a service (e.g. after reconnecting)
+    pub fn reset_circuit_breaker(&self, service_id: &str) {
+        let mut breakers = self
+            .circuit_breakers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(breaker) = breakers.get_mut(service_id) {
+            breaker.reset();
+        }
+    }
 
-        if let Some(ref client_arc) = connection.client {
+    pub async fn read_resource(
+        &self,
+        connection_id: &str,
+        uri: &str,
+    ) -> Result<rmcp::model::ReadResourceResult> {
+        self.with_credential_refresh(connection_id, || {
+            self.send_read_resource(connection_id, uri)
+        })
+        .await
+    }
+
+    async fn send_read_resource(
+        &self,
+        connection_id: &str,
+        uri: &str,
+    ) -> Result<rmcp::model::ReadResourceResult> {
+        if let Some(client) = self.connected_client(connection_id).await? {
             tracing::debug!(
                 "Attempting to read resource '{}' from rmcp client {}",
                 uri,
                 connection_id
             );
 
-            let peer = client_arc.peer();
+            let peer = client.peer();
 
             let request =
                 rmcp::model::Request::<_, _>::new(rmcp::model::ReadResourceRequestParam {
@@ -662,7 +1565,7 @@ impl McpClientManager {
             let server_result = peer
                 .send_request(client_request)
                 .await
-                .map_err(|e| McpError::ServiceError(e.to_string()))?;
+                .map_err(request_error)?;
             let duration = start_time.elapsed();
 
             let result = match server_result {
@@ -696,25 +1599,26 @@ impl McpClientManager {
         name: &str,
         arguments: Option<HashMap<String, rmcp::model::PromptArgument>>,
     ) -> Result<rmcp::model::GetPromptResult> {
-        let connections = self.connections.read().await;
-        let connection = connections
-            .get(connection_id)
-            .ok_or_else(|| McpError::ServiceNotFound(connection_id.to_string()))?;
-
-        if !connection.status.is_connected {
-            return Err(McpError::ConnectionError(
-                "Service not connected".to_string(),
-            ));
-        }
+        self.with_credential_refresh(connection_id, || {
+            self.send_get_prompt(connection_id, name, arguments.clone())
+        })
+        .await
+    }
 
-        if let Some(ref client_arc) = connection.client {
+    async fn send_get_prompt(
+        &self,
+        connection_id: &str,
+        name: &str,
+        arguments: Option<HashMap<String, rmcp::model::PromptArgument>>,
+    ) -> Result<rmcp::model::GetPromptResult> {
+        if let Some(client) = self.connected_client(connection_id).await? {
             tracing::debug!(
                 "Attempting to get prompt '{}' from rmcp client {}",
                 name,
                 connection_id
             );
 
-            let peer = client_arc.peer();
+            let peer = client.peer();
 
             let arguments_map = arguments
                 .map(|args| {
@@ -734,7 +1638,7 @@ impl McpClientManager {
             let server_result = peer
                 .send_request(client_request)
                 .await
-                .map_err(|e| McpError::ServiceError(e.to_string()))?;
+                .map_err(request_error)?;
             let duration = start_time.elapsed();
 
             let result = match server_result {
@@ -768,25 +1672,26 @@ impl McpClientManager {
         name: &str,
         arguments: Option<HashMap<String, serde_json::Value>>,
     ) -> Result<rmcp::model::CallToolResult> {
-        let connections = self.connections.read().await;
-        let connection = connections
-            .get(connection_id)
-            .ok_or_else(|| McpError::ServiceNotFound(connection_id.to_string()))?;
-
-        if !connection.status.is_connected {
-            return Err(McpError::ConnectionError(
-                "Service not connected".to_string(),
-            ));
-        }
+        self.with_credential_refresh(connection_id, || {
+            self.send_call_tool(connection_id, name, arguments.clone())
+        })
+        .await
+    }
 
-        if let Some(ref client_arc) = connection.client {
+    async fn send_call_tool(
+        &self,
+        connection_id: &str,
+        name: &str,
+        arguments: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<rmcp::model::CallToolResult> {
+        if let Some(client) = self.connected_client(connection_id).await? {
             tracing::debug!(
                 "Attempting to call tool '{}' from rmcp client {}",
                 name,
                 connection_id
             );
 
-            let peer = client_arc.peer();
+            let peer = client.peer();
 
             let arguments_map = arguments
                 .map(|args| args.into_iter().collect::<serde_json::Map<_, _>>())
@@ -802,7 +1707,7 @@ impl McpClientManager {
             let server_result = peer
                 .send_request(client_request)
                 .await
-                .map_err(|e| McpError::ServiceError(e.to_string()))?;
+                .map_err(request_error)?;
             let duration = start_time.elapsed();
 
             let result = match server_result {
@@ -837,15 +1742,29 @@ impl McpClientManager {
     pub async fn get_connection_status(&self, service_id: &str) -> (String, Option<String>) {
         if let Some(connection) = self.connections.read().await.get(service_id) {
             if connection.status.is_connected {
-                ("connected".to_string(), None)
+                // 连接正常但熔断器打开时，附带熔断信息
+                let breaker = self.circuit_breaker_snapshot(service_id);
+                let message = match breaker.state {
+                    CircuitState::Closed => None,
+                    state => Some(format!(
+                        "Circuit breaker {} (failure rate {:.0}% over last {} calls)",
+                        state,
+                        breaker.failure_rate * 100.0,
+                        breaker.recent_calls
+                    )),
+                };
+                ("connected".to_string(), message)
             } else {
                 // 如果有错误消息，说明连接失败，返回 failed 状态
                 // 否则返回 disconnected 状态
-                if connection.status.error_message.is_some() {
-                    (
-                        "failed".to_string(),
-                        connection.status.error_message.clone(),
-                    )
+                if let Some(error_message) = &connection.status.error_message {
+                    // 失败信息中附带最近的 stderr 输出，便于定位 STDIO 服务启动失败原因
+                    let message = if error_message.contains("\nstderr:\n") {
+                        error_message.clone()
+                    } else {
+                        self.with_stderr_tail(service_id, error_message.clone())
+                    };
+                    ("failed".to_string(), Some(message))
                 } else {
                     ("disconnected".to_string(), None)
                 }
@@ -855,6 +1774,64 @@ impl McpClientManager {
         }
     }
 
+    /// Check whether a connected service is still alive
+    ///
+    /// 先检查传输层是否已关闭（子进程退出或 HTTP 连接失效），再发送 MCP ping 请求
+    pub async fn check_health(&self, service_id: &str, ping_timeout: Duration) -> Result<()> {
+        // 只在读锁内克隆客户端，避免 ping 期间阻塞其他写操作
+        let client = {
+            let connections = self.connections.read().await;
+            let connection = connections
+                .get(service_id)
+                .ok_or_else(|| McpError::ServiceNotFound(service_id.to_string()))?;
+            if !connection.status.is_connected {
+                return Err(McpError::ConnectionError(
+                    "Service not connected".to_string(),
+                ));
+            }
+            connection.client.clone().ok_or_else(|| {
+                McpError::ServiceError("No client available for connection".to_string())
+            })?
+        };
+
+        if let Some(exit) = self.process_monitor.exited(service_id) {
+            return Err(McpError::ConnectionError(format!("Process {}", exit)));
+        }
+
+        let peer = client.peer();
+        if peer.is_transport_closed() {
+            return Err(McpError::ConnectionError(
+                "Transport closed (process exited or connection lost)".to_string(),
+            ));
+        }
+
+        let request = rmcp::model::ClientRequest::PingRequest(Default::default());
+        match tokio::time::timeout(ping_timeout, peer.send_request(request)).await {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => Err(McpError::ConnectionError(format!("Ping failed: {}", e))),
+            Err(_) => Err(McpError::TimeoutError(format!(
+                "Ping timed out after {}s",
+                ping_timeout.as_secs()
+            ))),
+        }
+    }
+
+    /// Mark a connection as failed, release its client and stop its process
+    pub async fn mark_failed(&self, service_id: &str, error_message: String) {
+        let mut connections = self.connections.write().await;
+        if let Some(connection) = connections.get_mut(service_id) {
+            connection.client = None;
+            connection.server_info = None;
+            connection.status.is_connected = false;
+            connection.status.is_connecting = false;
+            connection.status.error_message =
+                Some(self.with_stderr_tail(service_id, error_message));
+        }
+        drop(connections);
+        // 终止可能仍在运行但已无响应的进程
+        self.process_monitor.stop(service_id);
+    }
+
     /// Disconnect a specific server, handling both STDIO and HTTP types properly
     pub async fn disconnect_server(&self, server_name: &str) -> Result<()> {
         let mut connections = self.connections.write().await;
@@ -869,6 +1846,9 @@ impl McpClientManager {
                 tracing::info!("Closed MCP client for server '{}'", server_name);
             }
 
+            // Stop the child process of STDIO servers
+            self.process_monitor.stop(server_name);
+
             // Clear server info
             connection.server_info = None;
 
@@ -930,3 +1910,27 @@ impl McpClientManager {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stop_idle_waits_for_in_flight_calls() {
+        let manager = McpClientManager::new(AppConfig::default());
+        assert_eq!(manager.idle_duration("demo"), None);
+
+        let call = manager.begin_call("demo");
+        assert_eq!(manager.idle_duration("demo"), Some(Duration::ZERO));
+        assert!(!manager.stop_idle("demo", Duration::ZERO).await.unwrap());
+
+        drop(call);
+        assert!(manager.idle_duration("demo").is_some());
+        assert!(!manager
+            .stop_idle("demo", Duration::from_secs(60))
+            .await
+            .unwrap());
+        assert!(manager.stop_idle("demo", Duration::ZERO).await.unwrap());
+        assert_eq!(manager.idle_duration("demo"), None);
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::error::Result;
 use crate::storage::orm_storage::Storage;
-use crate::types::{McpServerConfig, McpServerInfo};
+use crate::types::{McpServerConfig, McpServerInfo, ServiceStatus};
 use sea_orm::Set;
 use std::sync::Arc;
 use std::time::Duration;
@@ -57,6 +57,13 @@ impl McpServerManager {
 
         let mut server_infos = Vec::new();
         for s in servers {
+            let lifecycle = s.get_lifecycle();
+            let sandbox = s.parse_sandbox().unwrap_or_default();
+            let env_inheritance = s.get_env_inheritance();
+            let env_allowlist = s.parse_env_allowlist();
+            let container = s.parse_container();
+            let runtime = s.get_runtime();
+            let restart_policy = s.get_restart_policy();
             let args = s
                 .args
                 .and_then(|a| serde_json::from_str::<Vec<String>>(&a).ok())
@@ -86,6 +93,12 @@ impl McpServerManager {
                 "connected".to_string()
             } else if s.enabled && connection_status == "connecting" {
                 "connecting".to_string()
+            } else if s.enabled
+                && connection_status == "disconnected"
+                && lifecycle == crate::types::ServerLifecycle::Lazy
+            {
+                // lazy 服务未运行时处于空闲状态，首次调用时启动
+                "idle".to_string()
             } else if s.enabled && connection_status == "disconnected" {
                 "disconnected".to_string()
             } else if s.enabled {
@@ -126,6 +139,27 @@ impl McpServerManager {
                 resource_count: Some(resource_count),
                 prompt_count: Some(prompt_count),
                 prompt_template_count: Some(prompt_template_count),
+                circuit_breaker: Some(crate::MCP_CLIENT_MANAGER.circuit_breaker_snapshot(&s.name)),
+                request_timeout: s.request_timeout.and_then(|v| u64::try_from(v).ok()),
+                connect_timeout: s.connect_timeout.and_then(|v| u64::try_from(v).ok()),
+                max_concurrent_calls: s.max_concurrent_calls.and_then(|v| u32::try_from(v).ok()),
+                lifecycle,
+                idle_timeout: s.idle_timeout.and_then(|v| u64::try_from(v).ok()),
+                restart_policy,
+                max_restarts: s.max_restarts.and_then(|v| u32::try_from(v).ok()),
+                memory_limit_mb: s.memory_limit_mb.and_then(|v| u64::try_from(v).ok()),
+                sandbox,
+                cwd: s.cwd,
+                env_inheritance,
+                env_allowlist,
+                container,
+                runtime,
+                runtime_version: s.runtime_version.clone(),
+                credential_helper: s.parse_credential_helper().unwrap_or_default(),
+                active_runtime: crate::MCP_CLIENT_MANAGER.process_monitor().runtime(&s.name),
+                resource_usage: crate::MCP_CLIENT_MANAGER.resource_monitor().usage(&s.name),
+                unresolved_placeholders: None,
+                oauth_authorized: false,
             });
         }
 
@@ -143,6 +177,13 @@ impl McpServerManager {
             })?;
 
         if let Some(s) = server {
+            let lifecycle = s.get_lifecycle();
+            let sandbox = s.parse_sandbox().unwrap_or_default();
+            let env_inheritance = s.get_env_inheritance();
+            let env_allowlist = s.parse_env_allowlist();
+            let container = s.parse_container();
+            let runtime = s.get_runtime();
+            let restart_policy = s.get_restart_policy();
             let args = s
                 .args
                 .and_then(|a| serde_json::from_str::<Vec<String>>(&a).ok())
@@ -172,6 +213,12 @@ impl McpServerManager {
                 "connected".to_string()
             } else if s.enabled && connection_status == "connecting" {
                 "connecting".to_string()
+            } else if s.enabled
+                && connection_status == "disconnected"
+                && lifecycle == crate::types::ServerLifecycle::Lazy
+            {
+                // lazy 服务未运行时处于空闲状态，首次调用时启动
+                "idle".to_string()
             } else if s.enabled && connection_status == "disconnected" {
                 "disconnected".to_string()
             } else if s.enabled {
@@ -212,12 +259,84 @@ impl McpServerManager {
                 resource_count: Some(resource_count),
                 prompt_count: Some(prompt_count),
                 prompt_template_count: Some(prompt_template_count),
+                circuit_breaker: Some(crate::MCP_CLIENT_MANAGER.circuit_breaker_snapshot(&s.name)),
+                request_timeout: s.request_timeout.and_then(|v| u64::try_from(v).ok()),
+                connect_timeout: s.connect_timeout.and_then(|v| u64::try_from(v).ok()),
+                max_concurrent_calls: s.max_concurrent_calls.and_then(|v| u32::try_from(v).ok()),
+                lifecycle,
+                idle_timeout: s.idle_timeout.and_then(|v| u64::try_from(v).ok()),
+                restart_policy,
+                max_restarts: s.max_restarts.and_then(|v| u32::try_from(v).ok()),
+                memory_limit_mb: s.memory_limit_mb.and_then(|v| u64::try_from(v).ok()),
+                sandbox,
+                cwd: s.cwd,
+                env_inheritance,
+                env_allowlist,
+                container,
+                runtime,
+                runtime_version: s.runtime_version.clone(),
+                credential_helper: s.parse_credential_helper().unwrap_or_default(),
+                active_runtime: crate::MCP_CLIENT_MANAGER.process_monitor().runtime(&s.name),
+                resource_usage: crate::MCP_CLIENT_MANAGER.resource_monitor().usage(&s.name),
+                unresolved_placeholders: None,
+                oauth_authorized: false,
             }))
         } else {
             Ok(None)
         }
     }
 
+    /// Get the runtime status of a server, including its child process
+    pub async fn get_service_status(&self, name: &str) -> Result<Option<ServiceStatus>> {
+        let Some(server) = self.get_server_by_name(name).await? else {
+            return Ok(None);
+        };
+
+        let process_monitor = crate::MCP_CLIENT_MANAGER.process_monitor();
+        Ok(Some(ServiceStatus {
+            name: server.name.to_string(),
+            enabled: server.enabled,
+            status: server.status,
+            pid: process_monitor.pid(name),
+            port: None,
+            version: server.version,
+            start_time: process_monitor.started_at(name),
+            error_message: server.error_message,
+            restart_count: process_monitor.restart_count(name),
+            last_exit_reason: process_monitor.last_exit(name).map(|exit| exit.to_string()),
+            resource_usage: server.resource_usage,
+            runtime: server.active_runtime,
+        }))
+    }
+
+    /// Get the connection config of a server
+    pub async fn get_server_config(&self, name: &str) -> Result<Option<McpServerConfig>> {
+        Ok(self.orm_storage.get_server_config(name).await?)
+    }
+
+    /// Get the connection configs of all servers
+    pub async fn list_server_configs(&self) -> Result<Vec<McpServerConfig>> {
+        let (servers, _) = self.orm_storage.list_mcp_servers(None, None).await?;
+        Ok(servers
+            .into_iter()
+            .filter_map(|s| {
+                let transport = s
+                    .server_type
+                    .parse::<crate::types::ServiceTransport>()
+                    .ok()?;
+                s.to_server_config(transport)
+                    .inspect_err(|e| tracing::warn!("Skipping server '{}': {}", s.name, e))
+                    .ok()
+            })
+            .collect())
+    }
+
+    /// List names of all enabled servers
+    pub async fn list_enabled_server_names(&self) -> Result<Vec<String>> {
+        let servers = self.orm_storage.get_enabled_servers().await?;
+        Ok(servers.into_iter().map(|s| s.name).collect())
+    }
+
     /// Delete an MCP server
     pub async fn delete_server(&self, name: &str) -> Result<()> {
         self.orm_storage
@@ -889,6 +1008,9 @@ impl McpServerManager {
         self.delete_server(name).await?;
         self.add_server(config).await?;
 
+        // 超时与并发限制无需重连即可生效
+        crate::MCP_CLIENT_MANAGER.apply_call_limits(config);
+
         // 如果服务器启用且需要重连，则重新连接并同步
         if config.enabled && should_reconnect {
             let server_name = name.to_string();
@@ -968,7 +1090,15 @@ impl McpServerManager {
             old_args != new_config.args.as_ref().cloned().unwrap_or_default()
         };
 
-        transport_changed || command_changed || args_changed || url_changed || enabled_changed
+        let credential_helper_changed = old_server.parse_credential_helper().unwrap_or_default()
+            != new_config.credential_helper;
+
+        transport_changed
+            || command_changed
+            || args_changed
+            || url_changed
+            || enabled_changed
+            || credential_helper_changed
     }
 
     /// Toggle tool enabled status (real implementation)
@@ -977,18 +1107,18 @@ impl McpServerManager {
             // Find the tool by name
             let tools = self
                 .orm_storage
-                .list_server_tools(&raw_server.id)
+                .list_server_tool_flags(&raw_server.id)
                 .await
                 .map_err(|e| {
                     crate::error::McpError::DatabaseError(format!("Failed to get tools: {}", e))
                 })?;
 
-            if let Some(tool) = tools.iter().find(|t| t.name == tool_name) {
+            if let Some((_, enabled)) = tools.iter().find(|(name, _)| name == tool_name) {
                 // Toggle the tool's enabled status
-                let new_enabled = !tool.enabled;
-
-                // Update tool in database (would need to implement this method in OrmStorage)
-                // For now, just return the new status
+                let new_enabled = !enabled;
+                self.orm_storage
+                    .set_tool_enabled(&raw_server.id, tool_name, new_enabled)
+                    .await?;
                 tracing::info!(
                     "Toggled tool '{}' on server '{}' to enabled: {}",
                     tool_name,
@@ -1010,6 +1140,45 @@ impl McpServerManager {
         }
     }
 
+    /// List enabled flags of all cached tools as (server, tool, enabled),
+    /// including flags waiting for their tools to be synced
+    pub async fn list_tool_flags(&self) -> Result<Vec<(String, String, bool)>> {
+        let (servers, _) = self.orm_storage.list_mcp_servers(None, None).await?;
+        let mut flags = Vec::new();
+        for server in servers {
+            let synced = self.orm_storage.list_server_tool_flags(&server.id).await?;
+            let pending = self.orm_storage.list_pending_tool_flags(&server.id).await?;
+            for (tool, enabled) in pending {
+                if !synced.iter().any(|(name, _)| *name == tool) {
+                    flags.push((server.name.clone(), tool, enabled));
+                }
+            }
+            for (tool, enabled) in synced {
+                flags.push((server.name.clone(), tool, enabled));
+            }
+        }
+        Ok(flags)
+    }
+
+    /// Set the enabled flag of a tool, kept when the server's tools are synced later
+    pub async fn set_tool_enabled(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        enabled: bool,
+    ) -> Result<()> {
+        let raw_server = self
+            .get_raw_server_by_name(server_name)
+            .await?
+            .ok_or_else(|| {
+                crate::error::McpError::NotFound(format!("Server '{}' not found", server_name))
+            })?;
+        self.orm_storage
+            .set_tool_enabled(&raw_server.id, tool_name, enabled)
+            .await?;
+        Ok(())
+    }
+
     /// Enable all tools (real implementation)
     pub async fn enable_all_tools(&self, server_name: &str) -> Result<()> {
         if let Some(raw_server) = self.get_raw_server_by_name(server_name).await? {
@@ -1101,34 +1270,13 @@ impl McpServerManager {
                             crate::types::ServiceTransport::Stdio
                         });
 
-                    let server_config = crate::types::McpServerConfig {
-                        name: raw_server.name,
-                        description: raw_server.description,
-                        transport,
-                        command: raw_server.command,
-                        args: raw_server
-                            .args
-                            .and_then(|a| serde_json::from_str::<Vec<String>>(&a).ok()),
-                        url: raw_server.url,
-                        headers: raw_server
-                            .headers
-                            .and_then(|h| serde_json::from_str::<serde_json::Value>(&h).ok())
-                            .and_then(|v| {
-                                serde_json::from_value::<std::collections::HashMap<String, String>>(
-                                    v,
-                                )
-                                .ok()
-                            }),
-                        env: raw_server
-                            .env
-                            .and_then(|e| serde_json::from_str::<serde_json::Value>(&e).ok())
-                            .and_then(|v| {
-                                serde_json::from_value::<std::collections::HashMap<String, String>>(
-                                    v,
-                                )
-                                .ok()
-                            }),
-                        enabled: raw_server.enabled,
+                    let server_config = match raw_server.to_server_config(transport) {
+                        Ok(config) => config,
+                        Err(e) => {
+                            failed_count += 1;
+                            tracing::error!("Not connecting to server '{}': {}", server.name, e);
+                            continue;
+                        }
                     };
 
                     tracing::debug!(
@@ -1221,6 +1369,30 @@ impl McpServerManager {
                     server_name
                 );
 
+                // 保留已有工具的启用状态，并应用导入时保存的待应用状态
+                let mut tool_flags: std::collections::HashMap<String, bool> = self
+                    .orm_storage
+                    .list_server_tool_flags(&raw_server.id)
+                    .await
+                    .map(|flags| flags.into_iter().collect())
+                    .unwrap_or_default();
+                match self
+                    .orm_storage
+                    .list_pending_tool_flags(&raw_server.id)
+                    .await
+                {
+                    Ok(pending) => {
+                        for (tool, enabled) in pending {
+                            tool_flags.entry(tool).or_insert(enabled);
+                        }
+                    }
+                    Err(e) => tracing::warn!(
+                        "Failed to load pending tool flags of server '{}': {}",
+                        server_name,
+                        e
+                    ),
+                }
+
                 // Convert tools to database models
                 let tool_models: Vec<crate::entities::mcp_tool::ActiveModel> = tools
                     .into_iter()
@@ -1251,7 +1423,7 @@ impl McpServerManager {
                                 }
                             })
                             .flatten()),
-                        enabled: Set(true),
+                        enabled: Set(tool_flags.get(&*tool.name).copied().unwrap_or(true)),
                         created_at: Set(chrono::Utc::now().into()),
                         updated_at: Set(chrono::Utc::now().into()),
                         ..Default::default()
@@ -1438,10 +1610,24 @@ impl McpServerManager {
     /// 此方法使用信号量限制同时连接的服务器数量，避免资源竞争
     pub async fn auto_connect_enabled_services_batched(&self) -> Result<()> {
         const BATCH_SIZE: usize = 3; // 同时最多连接3个服务器
-        const CONNECTION_TIMEOUT: Duration = Duration::from_secs(15);
 
         let (servers, _) = self.orm_storage.list_mcp_servers(None, None).await?;
-        let enabled_servers: Vec<_> = servers.iter().filter(|s| s.enabled).collect();
+        // lazy 服务在首次调用时启动，这里跳过
+        let (lazy_servers, enabled_servers): (Vec<_>, Vec<_>) = servers
+            .iter()
+            .filter(|s| s.enabled)
+            .partition(|s| s.get_lifecycle() == crate::types::ServerLifecycle::Lazy);
+        if !lazy_servers.is_empty() {
+            tracing::info!(
+                "Skipping {} lazy server(s) at startup, they will start on first use",
+                lazy_servers.len()
+            );
+            let lazy_servers: Vec<_> = lazy_servers.into_iter().cloned().collect();
+            let manager = self.clone();
+            tokio::spawn(async move {
+                manager.discover_lazy_manifests(lazy_servers).await;
+            });
+        }
 
         if enabled_servers.is_empty() {
             tracing::info!("No enabled servers to connect");
@@ -1478,31 +1664,12 @@ impl McpServerManager {
                     crate::types::ServiceTransport::Stdio
                 });
 
-            let server_config = crate::types::McpServerConfig {
-                name: server.name.clone(),
-                description: server.description.clone(),
-                transport,
-                command: server.command.clone(),
-                args: server
-                    .args
-                    .as_ref()
-                    .and_then(|a| serde_json::from_str::<Vec<String>>(a).ok()),
-                url: server.url.clone(),
-                headers: server
-                    .headers
-                    .as_ref()
-                    .and_then(|h| serde_json::from_str::<serde_json::Value>(h).ok())
-                    .and_then(|v| {
-                        serde_json::from_value::<std::collections::HashMap<String, String>>(v).ok()
-                    }),
-                env: server
-                    .env
-                    .as_ref()
-                    .and_then(|e| serde_json::from_str::<serde_json::Value>(e).ok())
-                    .and_then(|v| {
-                        serde_json::from_value::<std::collections::HashMap<String, String>>(v).ok()
-                    }),
-                enabled: server.enabled,
+            let server_config = match server.to_server_config(transport) {
+                Ok(config) => config,
+                Err(e) => {
+                    tracing::error!("[Batch] Not starting server '{}': {}", server.name, e);
+                    continue;
+                }
             };
 
             let server_name = server.name.clone();
@@ -1515,8 +1682,9 @@ impl McpServerManager {
 
                 tracing::info!("[Batch] Connecting to server: {}", server_name);
 
+                // 使用服务自身配置的连接超时
                 match tokio::time::timeout(
-                    CONNECTION_TIMEOUT,
+                    server_config.connect_timeout(),
                     crate::MCP_CLIENT_MANAGER.ensure_connection(&server_config, false),
                 )
                 .await
@@ -1548,6 +1716,68 @@ impl McpServerManager {
         Ok(())
     }
 
+    /// 为从未同步过清单的 lazy 服务启动一次以获取工具、资源和提示词，随后停止
+    ///
+    /// 否则这些服务的工具在首次调用前对客户端不可见，也就不会被调用
+    async fn discover_lazy_manifests(&self, servers: Vec<crate::entities::mcp_server::Model>) {
+        for server in servers {
+            match self.orm_storage.has_server_manifests(&server.id).await {
+                Ok(false) => {}
+                Ok(true) => continue,
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to check cached manifests of lazy server '{}': {}",
+                        server.name,
+                        e
+                    );
+                    continue;
+                }
+            }
+
+            let transport = server
+                .server_type
+                .parse()
+                .unwrap_or(crate::types::ServiceTransport::Stdio);
+            let server_config = match server.to_server_config(transport) {
+                Ok(config) => config,
+                Err(e) => {
+                    tracing::error!("Not starting lazy server '{}': {}", server.name, e);
+                    continue;
+                }
+            };
+
+            tracing::info!(
+                "Starting lazy server '{}' once to discover its manifests",
+                server.name
+            );
+            if let Err(e) = crate::MCP_CLIENT_MANAGER
+                .start_on_demand(&server_config)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to start lazy server '{}' for discovery: {}",
+                    server.name,
+                    e
+                );
+                continue;
+            }
+            if let Err(e) = self.sync_server_manifests(&server.name).await {
+                tracing::warn!(
+                    "Failed to sync manifests for lazy server '{}': {}",
+                    server.name,
+                    e
+                );
+            }
+            // 没有进行中的调用时立即停止，之后按需启动
+            if let Err(e) = crate::MCP_CLIENT_MANAGER
+                .stop_idle(&server.name, Duration::ZERO)
+                .await
+            {
+                tracing::warn!("Failed to stop lazy server '{}': {}", server.name, e);
+            }
+        }
+    }
+
     /// 后台同步所有服务器的清单
     ///
     /// 此方法在后台异步同步所有已连接服务器的工具、资源和提示词
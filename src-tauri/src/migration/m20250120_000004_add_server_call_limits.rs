@@ -0,0 +1,93 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add request_timeout column to mcp_servers table
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .add_column(
+                        ColumnDef::new(McpServers::RequestTimeout)
+                            .big_integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Add connect_timeout column to mcp_servers table
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .add_column(
+                        ColumnDef::new(McpServers::ConnectTimeout)
+                            .big_integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Add max_concurrent_calls column to mcp_servers table
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .add_column(
+                        ColumnDef::new(McpServers::MaxConcurrentCalls)
+                            .integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Remove columns from mcp_servers table (one by one)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .drop_column(McpServers::RequestTimeout)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .drop_column(McpServers::ConnectTimeout)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .drop_column(McpServers::MaxConcurrentCalls)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum McpServers {
+    Table,
+    RequestTimeout,
+    ConnectTimeout,
+    MaxConcurrentCalls,
+}
@@ -0,0 +1,61 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add lifecycle column to mcp_servers table
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .add_column(ColumnDef::new(McpServers::Lifecycle).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Add idle_timeout column to mcp_servers table
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .add_column(ColumnDef::new(McpServers::IdleTimeout).big_integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Remove columns from mcp_servers table (one by one)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .drop_column(McpServers::Lifecycle)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .drop_column(McpServers::IdleTimeout)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum McpServers {
+    Table,
+    Lifecycle,
+    IdleTimeout,
+}
@@ -0,0 +1,61 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add restart_policy column to mcp_servers table
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .add_column(ColumnDef::new(McpServers::RestartPolicy).string().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Add max_restarts column to mcp_servers table
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .add_column(ColumnDef::new(McpServers::MaxRestarts).integer().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Remove columns from mcp_servers table (one by one)
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .drop_column(McpServers::RestartPolicy)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .drop_column(McpServers::MaxRestarts)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum McpServers {
+    Table,
+    RestartPolicy,
+    MaxRestarts,
+}
@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Add memory_limit_mb column to mcp_servers table
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .add_column(
+                        ColumnDef::new(McpServers::MemoryLimitMb)
+                            .big_integer()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(McpServers::Table)
+                    .drop_column(McpServers::MemoryLimitMb)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum McpServers {
+    Table,
+    MemoryLimitMb,
+}
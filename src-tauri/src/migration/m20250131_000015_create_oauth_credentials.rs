@@ -0,0 +1,64 @@
+//! 创建 oauth_credentials 表
+//!
+//! 存储下游 HTTP 服务的 OAuth 客户端注册信息和令牌，使用密钥存储的主密钥加密
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OauthCredentials::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OauthCredentials::ServerName)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthCredentials::Ciphertext)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthCredentials::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(OauthCredentials::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OauthCredentials::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum OauthCredentials {
+    Table,
+    ServerName,
+    Ciphertext,
+    CreatedAt,
+    UpdatedAt,
+}
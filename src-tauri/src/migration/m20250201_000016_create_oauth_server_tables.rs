@@ -0,0 +1,117 @@
+//! 创建 oauth_clients 和 oauth_grants 表
+//!
+//! 存储聚合器内置授权服务器动态注册的客户端以及授予它们的访问令牌，令牌仅保存 SHA-256 摘要
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OauthClients::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OauthClients::ClientId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OauthClients::ClientName).string().not_null())
+                    .col(ColumnDef::new(OauthClients::RedirectUris).text().not_null())
+                    .col(
+                        ColumnDef::new(OauthClients::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OauthGrants::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OauthGrants::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(OauthGrants::ClientId).string().not_null())
+                    .col(ColumnDef::new(OauthGrants::TokenId).string().not_null())
+                    .col(
+                        ColumnDef::new(OauthGrants::AccessTokenHash)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthGrants::AccessExpiresAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthGrants::RefreshTokenHash)
+                            .string()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(
+                        ColumnDef::new(OauthGrants::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        ColumnDef::new(OauthGrants::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OauthGrants::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(OauthClients::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum OauthClients {
+    Table,
+    ClientId,
+    ClientName,
+    RedirectUris,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum OauthGrants {
+    Table,
+    Id,
+    ClientId,
+    TokenId,
+    AccessTokenHash,
+    AccessExpiresAt,
+    RefreshTokenHash,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,78 @@
+//! 创建 pending_tool_flags 表
+//!
+//! 保存工具尚未同步时设置的启用状态，并将之前写入 mcp_server_tools 的占位记录迁移过来
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PendingToolFlags::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PendingToolFlags::ServerId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PendingToolFlags::ToolName)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PendingToolFlags::Enabled)
+                            .boolean()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PendingToolFlags::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(PendingToolFlags::ServerId)
+                            .col(PendingToolFlags::ToolName),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // 占位记录没有输入架构，不能作为真实工具提供
+        let db = manager.get_connection();
+        db.execute_unprepared(
+            "INSERT OR REPLACE INTO pending_tool_flags (server_id, tool_name, enabled, updated_at) \
+             SELECT server_id, name, enabled, updated_at FROM mcp_server_tools \
+             WHERE input_schema IS NULL",
+        )
+        .await?;
+        db.execute_unprepared("DELETE FROM mcp_server_tools WHERE input_schema IS NULL")
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PendingToolFlags::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum PendingToolFlags {
+    Table,
+    ServerId,
+    ToolName,
+    Enabled,
+    UpdatedAt,
+}
@@ -0,0 +1,47 @@
+//! 为 oauth_grants 表添加 previous_refresh_token_hash 列
+//!
+//! 记录上一个刷新令牌的摘要，已轮换的刷新令牌再次出现时撤销整个授权
+
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OauthGrants::Table)
+                    .add_column(
+                        ColumnDef::new(OauthGrants::PreviousRefreshTokenHash)
+                            .string()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OauthGrants::Table)
+                    .drop_column(OauthGrants::PreviousRefreshTokenHash)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum OauthGrants {
+    Table,
+    PreviousRefreshTokenHash,
+}
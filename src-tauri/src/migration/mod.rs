@@ -7,6 +7,21 @@ use sea_orm_migration::prelude::*;
 mod m20240101_000001_create_initial_tables;
 mod m20241212_000002_add_mcp_metadata_fields;
 mod m20250113_remove_version_fields;
+mod m20250120_000004_add_server_call_limits;
+mod m20250121_000005_add_server_lifecycle;
+mod m20250122_000006_add_server_restart_policy;
+mod m20250123_000007_add_server_memory_limit;
+mod m20250124_000008_add_server_sandbox;
+mod m20250125_000009_add_server_cwd_env_policy;
+mod m20250126_000010_add_server_container;
+mod m20250127_000011_add_server_runtime;
+mod m20250128_000012_add_server_runtime_version;
+mod m20250129_000013_create_secrets;
+mod m20250130_000014_add_server_credential_helper;
+mod m20250131_000015_create_oauth_credentials;
+mod m20250201_000016_create_oauth_server_tables;
+mod m20250202_000017_create_pending_tool_flags;
+mod m20250203_000018_add_oauth_grant_previous_refresh_hash;
 
 pub struct Migrator;
 
@@ -17,6 +32,21 @@ impl MigratorTrait for Migrator {
             Box::new(m20240101_000001_create_initial_tables::Migration),
             Box::new(m20241212_000002_add_mcp_metadata_fields::Migration),
             Box::new(m20250113_remove_version_fields::Migration),
+            Box::new(m20250120_000004_add_server_call_limits::Migration),
+            Box::new(m20250121_000005_add_server_lifecycle::Migration),
+            Box::new(m20250122_000006_add_server_restart_policy::Migration),
+            Box::new(m20250123_000007_add_server_memory_limit::Migration),
+            Box::new(m20250124_000008_add_server_sandbox::Migration),
+            Box::new(m20250125_000009_add_server_cwd_env_policy::Migration),
+            Box::new(m20250126_000010_add_server_container::Migration),
+            Box::new(m20250127_000011_add_server_runtime::Migration),
+            Box::new(m20250128_000012_add_server_runtime_version::Migration),
+            Box::new(m20250129_000013_create_secrets::Migration),
+            Box::new(m20250130_000014_add_server_credential_helper::Migration),
+            Box::new(m20250131_000015_create_oauth_credentials::Migration),
+            Box::new(m20250201_000016_create_oauth_server_tables::Migration),
+            Box::new(m20250202_000017_create_pending_tool_flags::Migration),
+            Box::new(m20250203_000018_add_oauth_grant_previous_refresh_hash::Migration),
         ]
     }
 }
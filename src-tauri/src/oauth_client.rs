@@ -0,0 +1,948 @@
+// 下游 HTTP 服务的 OAuth 2.1 授权：元数据发现、动态注册、PKCE 授权码和令牌刷新
+
+use crate::error::{McpError, Result};
+use crate::types::{McpServerConfig, ServiceTransport};
+use base64::Engine;
+use reqwest::{header, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+
+/// 动态注册时使用的客户端名称
+const CLIENT_NAME: &str = "MCP Router";
+
+/// 回环重定向地址的路径
+const CALLBACK_PATH: &str = "/callback";
+
+/// 等待用户在浏览器中完成授权的最长时间
+const AUTHORIZE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 发现、注册和令牌请求的超时
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 在过期前提前刷新，避免请求途中令牌失效
+const REFRESH_MARGIN_SECS: u64 = 60;
+
+const PROTECTED_RESOURCE_WELL_KNOWN: &str = "/.well-known/oauth-protected-resource";
+const AUTHORIZATION_SERVER_WELL_KNOWN: &str = "/.well-known/oauth-authorization-server";
+const OPENID_WELL_KNOWN: &str = "/.well-known/openid-configuration";
+
+const CALLBACK_SUCCESS_PAGE: &str = "<!doctype html><html><body style=\"font-family: sans-serif\">\
+<h3>Authorization complete</h3><p>You can close this window and return to MCP Router.</p>\
+</body></html>";
+const CALLBACK_FAILURE_PAGE: &str = "<!doctype html><html><body style=\"font-family: sans-serif\">\
+<h3>Authorization failed</h3><p>Return to MCP Router for details.</p></body></html>";
+
+/// 持久化的客户端注册信息和令牌
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredCredential {
+    /// 授权时的服务 URL，URL 变化后需要重新授权
+    server_url: String,
+    /// RFC 8707 resource 参数
+    resource: String,
+    issuer: String,
+    token_endpoint: String,
+    client_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    client_secret: Option<String>,
+    redirect_uri: String,
+    access_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<String>,
+    /// 过期时间（Unix 秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+}
+
+impl StoredCredential {
+    fn needs_refresh(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| now_secs() + REFRESH_MARGIN_SECS >= expires_at)
+    }
+
+    fn apply(&mut self, tokens: TokenResponse) {
+        self.access_token = tokens.access_token;
+        // 授权服务器可能不轮换刷新令牌
+        if tokens.refresh_token.is_some() {
+            self.refresh_token = tokens.refresh_token;
+        }
+        self.expires_at = tokens.expires_in.map(|secs| now_secs() + secs);
+        if tokens.scope.is_some() {
+            self.scope = tokens.scope;
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ProtectedResourceMetadata {
+    #[serde(default)]
+    resource: Option<String>,
+    #[serde(default)]
+    authorization_servers: Vec<String>,
+    #[serde(default)]
+    scopes_supported: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorizationServerMetadata {
+    #[serde(default)]
+    issuer: Option<String>,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    #[serde(default)]
+    registration_endpoint: Option<String>,
+    #[serde(default)]
+    code_challenge_methods_supported: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientRegistration {
+    client_id: String,
+    #[serde(default)]
+    client_secret: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    refresh_token: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    scope: Option<String>,
+}
+
+/// 下游 HTTP 服务的 OAuth 授权和令牌管理
+pub struct OAuthClient {
+    http: reqwest::Client,
+    /// 每个服务的授权锁，避免并发打开多个授权页面
+    flows: std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>,
+    /// 内存中的令牌过期时间（Unix 秒），用于调用前主动刷新
+    expiries: std::sync::Mutex<HashMap<String, u64>>,
+    /// 被服务端拒绝、下次连接时需要刷新的令牌
+    rejected: std::sync::Mutex<HashSet<String>>,
+    /// 本次运行中已自动打开过授权页面的服务
+    prompted: std::sync::Mutex<HashSet<String>>,
+}
+
+impl Default for OAuthClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OAuthClient {
+    pub fn new() -> Self {
+        let http = reqwest::Client::builder()
+            .user_agent(crate::commands::app_info::get_user_agent_static())
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self {
+            http,
+            flows: std::sync::Mutex::new(HashMap::new()),
+            expiries: std::sync::Mutex::new(HashMap::new()),
+            rejected: std::sync::Mutex::new(HashSet::new()),
+            prompted: std::sync::Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// 返回服务可用的访问令牌，临近过期或被拒绝时使用刷新令牌换取新令牌
+    ///
+    /// 没有授权、URL 已变化或刷新令牌失效时返回 None，由服务端的 401 触发重新授权
+    pub async fn access_token(&self, config: &McpServerConfig) -> Result<Option<String>> {
+        let Some(url) = config.url.as_deref() else {
+            return Ok(None);
+        };
+        let store = crate::wait_for_secret_store().await.map_err(|e| {
+            McpError::AuthError(format!(
+                "Cannot load OAuth credential of server '{}': {}",
+                config.name, e
+            ))
+        })?;
+        let Some(mut credential) = load_credential(&store, &config.name).await? else {
+            return Ok(None);
+        };
+
+        if credential.server_url != url {
+            tracing::info!(
+                "URL of server '{}' changed, discarding its OAuth authorization",
+                config.name
+            );
+            self.discard(&store, &config.name).await?;
+            return Ok(None);
+        }
+
+        let rejected = lock(&self.rejected).remove(&config.name);
+        if rejected || credential.needs_refresh() {
+            let Some(refresh_token) = credential.refresh_token.clone() else {
+                self.discard(&store, &config.name).await?;
+                return Ok(None);
+            };
+            let mut form = vec![
+                ("grant_type", "refresh_token".to_string()),
+                ("refresh_token", refresh_token),
+                ("client_id", credential.client_id.clone()),
+                ("resource", credential.resource.clone()),
+            ];
+            if let Some(secret) = &credential.client_secret {
+                form.push(("client_secret", secret.clone()));
+            }
+            match self.request_token(&credential.token_endpoint, &form).await {
+                Ok(tokens) => {
+                    credential.apply(tokens);
+                    save_credential(&store, &config.name, &credential).await?;
+                    tracing::debug!("Refreshed OAuth token of server '{}'", config.name);
+                }
+                // 授权服务器拒绝刷新令牌，需要重新授权
+                Err(McpError::AuthError(e)) => {
+                    tracing::warn!(
+                        "Failed to refresh OAuth token of server '{}': {}",
+                        config.name,
+                        e
+                    );
+                    self.discard(&store, &config.name).await?;
+                    return Ok(None);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.remember_expiry(&config.name, credential.expires_at);
+        Ok(Some(credential.access_token))
+    }
+
+    /// 运行完整的授权流程：发现、动态注册、在浏览器中授权并换取令牌
+    ///
+    /// `config` 必须是已展开占位符和密钥引用的配置
+    pub async fn authorize(&self, config: &McpServerConfig) -> Result<()> {
+        let url = config.url.as_deref().ok_or_else(|| {
+            McpError::InvalidConfiguration("OAuth authorization requires a server URL".to_string())
+        })?;
+        let server_url = Url::parse(url)
+            .map_err(|e| McpError::InvalidConfiguration(format!("Invalid server URL: {}", e)))?;
+
+        let flow = {
+            let mut flows = lock(&self.flows);
+            Arc::clone(flows.entry(config.name.clone()).or_default())
+        };
+        let _guard = flow.lock().await;
+        lock(&self.prompted).insert(config.name.clone());
+
+        let store = crate::wait_for_secret_store().await?;
+        let previous = load_credential(&store, &config.name).await.ok().flatten();
+
+        // 1. 受保护资源元数据
+        let challenge = self.probe(&server_url, config.headers.as_ref()).await;
+        let resource_metadata = self
+            .fetch_resource_metadata(&server_url, challenge.get("resource_metadata"))
+            .await?
+            .unwrap_or_default();
+        let resource = resource_metadata
+            .resource
+            .clone()
+            .unwrap_or_else(|| url.to_string());
+
+        // 2. 授权服务器元数据；没有资源元数据时按旧版规范使用服务的源站
+        let issuer = match resource_metadata.authorization_servers.first() {
+            Some(issuer) => issuer.clone(),
+            None => origin(&server_url).to_string(),
+        };
+        let issuer_url = Url::parse(&issuer).map_err(|e| {
+            McpError::AuthError(format!("Invalid authorization server '{}': {}", issuer, e))
+        })?;
+        let metadata = self
+            .fetch_authorization_server_metadata(&issuer_url)
+            .await?;
+        // RFC 8414 §3.3：元数据中的 issuer 必须与请求的授权服务器一致
+        if !same_issuer(metadata.issuer.as_deref(), &issuer) {
+            return Err(McpError::AuthError(format!(
+                "Authorization server metadata of '{}' has issuer '{}'",
+                issuer,
+                metadata.issuer.as_deref().unwrap_or_default()
+            )));
+        }
+        if !metadata.code_challenge_methods_supported.is_empty()
+            && !metadata
+                .code_challenge_methods_supported
+                .iter()
+                .any(|method| method == "S256")
+        {
+            return Err(McpError::AuthError(format!(
+                "Authorization server '{}' does not support PKCE with S256",
+                issuer
+            )));
+        }
+
+        // 3. 回环监听和客户端注册；上次注册的端口仍可用时复用注册信息
+        let reusable = previous.filter(|c| c.issuer == issuer);
+        let (listener, registration, redirect_uri) =
+            match bind_previous_redirect(reusable.as_ref()).await {
+                Some((listener, previous)) => (
+                    listener,
+                    ClientRegistration {
+                        client_id: previous.client_id.clone(),
+                        client_secret: previous.client_secret.clone(),
+                    },
+                    previous.redirect_uri.clone(),
+                ),
+                None => {
+                    let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+                    let redirect_uri = format!(
+                        "http://127.0.0.1:{}{}",
+                        listener.local_addr()?.port(),
+                        CALLBACK_PATH
+                    );
+                    let registration = self.register_client(&metadata, &redirect_uri).await?;
+                    (listener, registration, redirect_uri)
+                }
+            };
+
+        // 4. 浏览器授权（PKCE）
+        let verifier = random_token::<32>();
+        let state = random_token::<16>();
+        let scope = challenge.get("scope").cloned().or_else(|| {
+            (!resource_metadata.scopes_supported.is_empty())
+                .then(|| resource_metadata.scopes_supported.join(" "))
+        });
+        let mut authorize_url = Url::parse(&metadata.authorization_endpoint)
+            .map_err(|e| McpError::AuthError(format!("Invalid authorization endpoint: {}", e)))?;
+        {
+            let mut query = authorize_url.query_pairs_mut();
+            query
+                .append_pair("response_type", "code")
+                .append_pair("client_id", &registration.client_id)
+                .append_pair("redirect_uri", &redirect_uri)
+                .append_pair("code_challenge", &pkce_challenge(&verifier))
+                .append_pair("code_challenge_method", "S256")
+                .append_pair("state", &state)
+                .append_pair("resource", &resource);
+            if let Some(scope) = &scope {
+                query.append_pair("scope", scope);
+            }
+        }
+
+        tracing::info!(
+            "🔑 Opening browser to authorize server '{}' with {}",
+            config.name,
+            issuer
+        );
+        tauri_plugin_opener::open_url(authorize_url.as_str(), None::<&str>).map_err(|e| {
+            McpError::AuthError(format!("Failed to open browser for authorization: {}", e))
+        })?;
+
+        let code = tokio::time::timeout(AUTHORIZE_TIMEOUT, wait_for_callback(&listener, &state))
+            .await
+            .map_err(|_| {
+                McpError::TimeoutError(format!(
+                    "Authorization of server '{}' was not completed within {}s",
+                    config.name,
+                    AUTHORIZE_TIMEOUT.as_secs()
+                ))
+            })??;
+
+        // 5. 换取令牌并保存
+        let mut form = vec![
+            ("grant_type", "authorization_code".to_string()),
+            ("code", code),
+            ("redirect_uri", redirect_uri.clone()),
+            ("client_id", registration.client_id.clone()),
+            ("code_verifier", verifier),
+            ("resource", resource.clone()),
+        ];
+        if let Some(secret) = &registration.client_secret {
+            form.push(("client_secret", secret.clone()));
+        }
+        let tokens = self.request_token(&metadata.token_endpoint, &form).await?;
+
+        let mut credential = StoredCredential {
+            server_url: url.to_string(),
+            resource,
+            issuer,
+            token_endpoint: metadata.token_endpoint,
+            client_id: registration.client_id,
+            client_secret: registration.client_secret,
+            redirect_uri,
+            access_token: String::new(),
+            refresh_token: None,
+            expires_at: None,
+            scope,
+        };
+        credential.apply(tokens);
+        save_credential(&store, &config.name, &credential).await?;
+        self.remember_expiry(&config.name, credential.expires_at);
+        lock(&self.rejected).remove(&config.name);
+
+        tracing::info!("✅ Authorized server '{}'", config.name);
+        Ok(())
+    }
+
+    /// 连接失败后是否自动打开授权页面
+    ///
+    /// 仅对未配置凭据助手、下游返回 HTTP 401 的服务生效，且每个服务在本次运行中
+    /// 只自动打开一次，之后需要在界面上手动授权
+    pub fn should_prompt(&self, config: &McpServerConfig, unauthorized: bool) -> bool {
+        config.transport == ServiceTransport::Http
+            && config.credential_helper.is_none()
+            && unauthorized
+            && lock(&self.prompted).insert(config.name.clone())
+    }
+
+    /// 内存中的令牌是否已临近过期（没有令牌时返回 false）
+    pub fn is_expired(&self, server_name: &str) -> bool {
+        lock(&self.expiries)
+            .get(server_name)
+            .is_some_and(|expires_at| now_secs() + REFRESH_MARGIN_SECS >= *expires_at)
+    }
+
+    /// 服务端拒绝了令牌，下次连接时先刷新
+    pub fn reject(&self, server_name: &str) {
+        if lock(&self.expiries).contains_key(server_name) {
+            lock(&self.rejected).insert(server_name.to_string());
+        }
+    }
+
+    /// 服务是否已保存授权
+    pub async fn is_authorized(&self, server_name: &str) -> bool {
+        match crate::wait_for_secret_store().await {
+            Ok(store) => matches!(load_credential(&store, server_name).await, Ok(Some(_))),
+            Err(_) => false,
+        }
+    }
+
+    /// 删除服务保存的授权
+    pub async fn sign_out(&self, server_name: &str) -> Result<()> {
+        let store = crate::wait_for_secret_store().await?;
+        self.discard(&store, server_name).await?;
+        lock(&self.prompted).remove(server_name);
+        Ok(())
+    }
+
+    async fn discard(&self, store: &crate::secrets::SecretStore, server_name: &str) -> Result<()> {
+        store.delete_oauth_credential(server_name).await?;
+        lock(&self.expiries).remove(server_name);
+        lock(&self.rejected).remove(server_name);
+        Ok(())
+    }
+
+    fn remember_expiry(&self, server_name: &str, expires_at: Option<u64>) {
+        let mut expiries = lock(&self.expiries);
+        match expires_at {
+            Some(expires_at) => expiries.insert(server_name.to_string(), expires_at),
+            None => expiries.remove(server_name),
+        };
+    }
+
+    /// 不带令牌请求服务，返回 401 响应中 `WWW-Authenticate` 的参数
+    async fn probe(
+        &self,
+        server_url: &Url,
+        headers: Option<&HashMap<String, String>>,
+    ) -> HashMap<String, String> {
+        let mut request = self
+            .http
+            .post(server_url.clone())
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .json(&serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {},
+                    "clientInfo": { "name": CLIENT_NAME, "version": env!("CARGO_PKG_VERSION") }
+                }
+            }));
+        for (key, value) in headers.into_iter().flatten() {
+            if !key.eq_ignore_ascii_case("authorization") {
+                request = request.header(key, value);
+            }
+        }
+
+        match request.send().await {
+            Ok(response) if response.status() == StatusCode::UNAUTHORIZED => response
+                .headers()
+                .get(header::WWW_AUTHENTICATE)
+                .and_then(|value| value.to_str().ok())
+                .map(parse_auth_params)
+                .unwrap_or_default(),
+            _ => HashMap::new(),
+        }
+    }
+
+    /// 获取受保护资源元数据，`resource` 与服务 URL 不符时返回错误（RFC 9728 §3.3）
+    async fn fetch_resource_metadata(
+        &self,
+        server_url: &Url,
+        advertised: Option<&String>,
+    ) -> Result<Option<ProtectedResourceMetadata>> {
+        let advertised = advertised.and_then(|url| server_url.join(url).ok());
+        for url in advertised
+            .into_iter()
+            .chain(well_known_urls(server_url, PROTECTED_RESOURCE_WELL_KNOWN))
+        {
+            let metadata: ProtectedResourceMetadata = match self.get_json(url.clone()).await {
+                Ok(metadata) => metadata,
+                Err(e) => {
+                    tracing::debug!("No protected resource metadata at {}: {}", url, e);
+                    continue;
+                }
+            };
+            if let Some(resource) = &metadata.resource {
+                if !resource_matches(resource, server_url) {
+                    return Err(McpError::AuthError(format!(
+                        "Protected resource metadata at {} is for '{}', not {}",
+                        url, resource, server_url
+                    )));
+                }
+            }
+            return Ok(Some(metadata));
+        }
+        Ok(None)
+    }
+
+    async fn fetch_authorization_server_metadata(
+        &self,
+        issuer: &Url,
+    ) -> Result<AuthorizationServerMetadata> {
+        let mut urls = well_known_urls(issuer, AUTHORIZATION_SERVER_WELL_KNOWN);
+        urls.extend(well_known_urls(issuer, OPENID_WELL_KNOWN));
+        // OpenID Connect Discovery 1.0 的路径追加形式
+        if let Ok(url) = Url::parse(&format!(
+            "{}{}",
+            issuer.as_str().trim_end_matches('/'),
+            OPENID_WELL_KNOWN
+        )) {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+
+        for url in urls {
+            match self.get_json(url.clone()).await {
+                Ok(metadata) => return Ok(metadata),
+                Err(e) => tracing::debug!("No authorization server metadata at {}: {}", url, e),
+            }
+        }
+        Err(McpError::AuthError(format!(
+            "Authorization server metadata of '{}' not found",
+            issuer
+        )))
+    }
+
+    async fn register_client(
+        &self,
+        metadata: &AuthorizationServerMetadata,
+        redirect_uri: &str,
+    ) -> Result<ClientRegistration> {
+        let endpoint = metadata.registration_endpoint.as_deref().ok_or_else(|| {
+            McpError::AuthError(
+                "Authorization server does not support dynamic client registration".to_string(),
+            )
+        })?;
+        let response = self
+            .http
+            .post(endpoint)
+            .json(&serde_json::json!({
+                "client_name": CLIENT_NAME,
+                "redirect_uris": [redirect_uri],
+                "grant_types": ["authorization_code", "refresh_token"],
+                "response_types": ["code"],
+                "token_endpoint_auth_method": "none",
+            }))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(McpError::AuthError(format!(
+                "Client registration failed with {}: {}",
+                status,
+                body.trim()
+            )));
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn request_token(
+        &self,
+        token_endpoint: &str,
+        form: &[(&str, String)],
+    ) -> Result<TokenResponse> {
+        let response = self
+            .http
+            .post(token_endpoint)
+            .header(header::ACCEPT, "application/json")
+            .form(form)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(McpError::AuthError(format!(
+                "Token request failed with status {}: {}",
+                status.as_u16(),
+                body.trim()
+            )));
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: Url) -> Result<T> {
+        Ok(self
+            .http
+            .get(url)
+            .header(header::ACCEPT, "application/json")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?)
+    }
+}
+
+async fn load_credential(
+    store: &crate::secrets::SecretStore,
+    server_name: &str,
+) -> Result<Option<StoredCredential>> {
+    match store.get_oauth_credential(server_name).await? {
+        Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+        None => Ok(None),
+    }
+}
+
+async fn save_credential(
+    store: &crate::secrets::SecretStore,
+    server_name: &str,
+    credential: &StoredCredential,
+) -> Result<()> {
+    store
+        .set_oauth_credential(server_name, &serde_json::to_string(credential)?)
+        .await
+}
+
+/// 重新绑定上次注册的回环端口，成功时可以复用已注册的客户端
+async fn bind_previous_redirect(
+    previous: Option<&StoredCredential>,
+) -> Option<(TcpListener, &StoredCredential)> {
+    let previous = previous?;
+    let port = Url::parse(&previous.redirect_uri).ok()?.port()?;
+    let listener = TcpListener::bind(("127.0.0.1", port)).await.ok()?;
+    Some((listener, previous))
+}
+
+/// 接受回环地址上的浏览器重定向，校验 state 后返回授权码
+async fn wait_for_callback(listener: &TcpListener, state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+
+        let mut buffer = vec![0u8; 8192];
+        let mut len = 0;
+        while len < buffer.len() {
+            let read = stream.read(&mut buffer[len..]).await?;
+            if read == 0 {
+                break;
+            }
+            len += read;
+            if buffer[..len].windows(4).any(|w| w == b"\r\n\r\n") {
+                break;
+            }
+        }
+
+        let request = String::from_utf8_lossy(&buffer[..len]);
+        let target = request
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|target| Url::parse(&format!("http://127.0.0.1{}", target)).ok());
+        let Some(target) = target.filter(|url| url.path() == CALLBACK_PATH) else {
+            // 浏览器的 favicon 等其他请求
+            let _ = stream
+                .write_all(
+                    b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                )
+                .await;
+            continue;
+        };
+
+        let params: HashMap<String, String> = target.query_pairs().into_owned().collect();
+        let result = parse_callback(&params, state);
+        let (status, page) = match &result {
+            Ok(_) => ("200 OK", CALLBACK_SUCCESS_PAGE),
+            Err(_) => ("400 Bad Request", CALLBACK_FAILURE_PAGE),
+        };
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            page.len(),
+            page
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+
+        // state 不符的请求不是本次授权的重定向，继续等待直到超时
+        if params.get("state").map(String::as_str) != Some(state) {
+            tracing::warn!("Ignoring OAuth callback with a mismatched state");
+            continue;
+        }
+        return result;
+    }
+}
+
+/// 从重定向参数中取出授权码
+fn parse_callback(params: &HashMap<String, String>, state: &str) -> Result<String> {
+    if params.get("state").map(String::as_str) != Some(state) {
+        return Err(McpError::AuthError(
+            "Authorization response has a mismatched state".to_string(),
+        ));
+    }
+    if let Some(error) = params.get("error") {
+        let description = params
+            .get("error_description")
+            .map(|d| format!(": {}", d))
+            .unwrap_or_default();
+        return Err(McpError::AuthError(format!(
+            "Authorization was denied ({}){}",
+            error, description
+        )));
+    }
+    params
+        .get("code")
+        .filter(|code| !code.is_empty())
+        .cloned()
+        .ok_or_else(|| McpError::AuthError("Authorization response has no code".to_string()))
+}
+
+/// 解析 `WWW-Authenticate: Bearer key="value", key=value` 中的参数
+fn parse_auth_params(value: &str) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    let mut rest = value.trim();
+    // 去掉认证方案
+    if let Some((scheme, tail)) = rest.split_once(char::is_whitespace) {
+        if !scheme.contains('=') {
+            rest = tail;
+        }
+    }
+
+    while let Some((key, tail)) = rest.split_once('=') {
+        let key = key
+            .trim()
+            .trim_start_matches(',')
+            .trim()
+            .to_ascii_lowercase();
+        let tail = tail.trim_start();
+        let (value, tail) = match tail.strip_prefix('"') {
+            Some(quoted) => match quoted.find('"') {
+                Some(end) => (&quoted[..end], &quoted[end + 1..]),
+                None => (quoted, ""),
+            },
+            None => match tail.find(',') {
+                Some(end) => (tail[..end].trim(), &tail[end..]),
+                None => (tail.trim(), ""),
+            },
+        };
+        params.insert(key, value.to_string());
+        rest = tail.trim_start().trim_start_matches(',').trim_start();
+    }
+    params
+}
+
+/// RFC 8414 / RFC 9728 的 well-known 地址：先插入路径，再使用根路径
+fn well_known_urls(base: &Url, well_known: &str) -> Vec<Url> {
+    let path = base.path().trim_end_matches('/');
+    let mut paths = Vec::new();
+    if !path.is_empty() {
+        paths.push(format!("{}{}", well_known, path));
+    }
+    paths.push(well_known.to_string());
+
+    paths
+        .into_iter()
+        .map(|path| {
+            let mut url = origin(base);
+            url.set_path(&path);
+            url
+        })
+        .collect()
+}
+
+/// 资源元数据的 `resource` 是否标识该服务：同源，且路径为服务路径本身或其上级
+fn resource_matches(resource: &str, server_url: &Url) -> bool {
+    let Ok(resource) = Url::parse(resource) else {
+        return false;
+    };
+    if resource.origin() != server_url.origin() {
+        return false;
+    }
+    let resource_path = resource.path().trim_end_matches('/');
+    let server_path = server_url.path().trim_end_matches('/');
+    server_path == resource_path
+        || server_path
+            .strip_prefix(resource_path)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// 授权服务器元数据的 issuer 是否与请求的授权服务器一致，仅忽略末尾的 `/`
+fn same_issuer(metadata_issuer: Option<&str>, issuer: &str) -> bool {
+    metadata_issuer.is_some_and(|value| value.trim_end_matches('/') == issuer.trim_end_matches('/'))
+}
+
+fn origin(url: &Url) -> Url {
+    let mut origin = url.clone();
+    origin.set_path("/");
+    origin.set_query(None);
+    origin.set_fragment(None);
+    origin
+}
+
+pub(crate) fn random_token<const N: usize>() -> String {
+    let bytes: [u8; N] = rand::random();
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// PKCE S256：BASE64URL(SHA256(code_verifier))
+pub(crate) fn pkce_challenge(verifier: &str) -> String {
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+}
+
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+pub(crate) fn lock<T>(mutex: &std::sync::Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_www_authenticate() {
+        let params = parse_auth_params(
+            r#"Bearer error="invalid_token", resource_metadata="https://mcp.example.com/.well-known/oauth-protected-resource", scope=files:read"#,
+        );
+        assert_eq!(
+            params.get("resource_metadata").map(String::as_str),
+            Some("https://mcp.example.com/.well-known/oauth-protected-resource")
+        );
+        assert_eq!(
+            params.get("error").map(String::as_str),
+            Some("invalid_token")
+        );
+        assert_eq!(params.get("scope").map(String::as_str), Some("files:read"));
+        assert!(parse_auth_params("Bearer").is_empty());
+    }
+
+    #[test]
+    fn test_builds_well_known_urls() {
+        let server = Url::parse("https://mcp.example.com/v1/mcp?x=1").unwrap();
+        let urls: Vec<String> = well_known_urls(&server, PROTECTED_RESOURCE_WELL_KNOWN)
+            .iter()
+            .map(Url::to_string)
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://mcp.example.com/.well-known/oauth-protected-resource/v1/mcp",
+                "https://mcp.example.com/.well-known/oauth-protected-resource",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validates_callback_and_pkce() {
+        let params = HashMap::from([
+            ("code".to_string(), "abc".to_string()),
+            ("state".to_string(), "s1".to_string()),
+        ]);
+        assert_eq!(parse_callback(&params, "s1").unwrap(), "abc");
+        assert!(parse_callback(&params, "s2").is_err());
+        let denied = HashMap::from([("error".to_string(), "access_denied".to_string())]);
+        assert!(parse_callback(&denied, "s1")
+            .unwrap_err()
+            .to_string()
+            .contains("mismatched state"));
+
+        assert_eq!(
+            pkce_challenge("dBjftJeZ4CVP-mJ0kIIgsPOLqh6Is4iQhHW5NaAVMfk"),
+            "BJRRuK3nDJ8Bi0w9WbkhIippayT_gDq3dButn0dnPws"
+        );
+    }
+
+    #[test]
+    fn test_checks_resource_and_issuer() {
+        let server = Url::parse("https://mcp.example.com/v1/mcp").unwrap();
+        assert!(resource_matches("https://mcp.example.com/v1/mcp", &server));
+        assert!(resource_matches("https://mcp.example.com/v1/mcp/", &server));
+        assert!(resource_matches("https://mcp.example.com/v1", &server));
+        assert!(resource_matches("https://mcp.example.com", &server));
+        assert!(!resource_matches("https://mcp.example.com/v1/mc", &server));
+        assert!(!resource_matches("https://mcp.example.com/other", &server));
+        assert!(!resource_matches(
+            "https://evil.example.com/v1/mcp",
+            &server
+        ));
+        assert!(!resource_matches("http://mcp.example.com/v1/mcp", &server));
+        assert!(!resource_matches("not a url", &server));
+
+        assert!(same_issuer(
+            Some("https://auth.example.com/"),
+            "https://auth.example.com"
+        ));
+        assert!(same_issuer(
+            Some("https://auth.example.com/tenant"),
+            "https://auth.example.com/tenant"
+        ));
+        assert!(!same_issuer(
+            Some("https://evil.example.com"),
+            "https://auth.example.com"
+        ));
+        assert!(!same_issuer(None, "https://auth.example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_callback_ignores_mismatched_state() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let waiter = tokio::spawn(async move { wait_for_callback(&listener, "s1").await });
+
+        let get = |target: &'static str| async move {
+            let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", port))
+                .await
+                .unwrap();
+            stream
+                .write_all(format!("GET {} HTTP/1.1\r\nHost: x\r\n\r\n", target).as_bytes())
+                .await
+                .unwrap();
+            let mut response = String::new();
+            let _ = stream.read_to_string(&mut response).await;
+            response
+        };
+
+        assert!(get("/callback?error=access_denied&state=forged")
+            .await
+            .starts_with("HTTP/1.1 400"));
+        assert!(get("/callback?code=evil&state=s2")
+            .await
+            .starts_with("HTTP/1.1 400"));
+        assert!(!waiter.is_finished());
+        assert!(get("/callback?code=abc&state=s1")
+            .await
+            .starts_with("HTTP/1.1 200"));
+        assert_eq!(waiter.await.unwrap().unwrap(), "abc");
+    }
+}
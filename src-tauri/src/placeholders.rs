@@ -0,0 +1,285 @@
+// 连接时展开服务配置中的 ${env:NAME}、${home}、${workspace} 等占位符
+
+use crate::types::McpServerConfig;
+use std::collections::HashMap;
+
+const ENV_PREFIX: &str = "env:";
+
+/// 展开占位符时可用的取值
+#[derive(Debug, Clone)]
+pub struct PlaceholderContext {
+    env: HashMap<String, String>,
+    home: Option<String>,
+    workspace: Option<String>,
+    config_dir: Option<String>,
+}
+
+enum Lookup {
+    Value(String),
+    Unresolved,
+    /// 变量不在允许发往远端的列表中
+    Denied,
+    Verbatim,
+}
+
+impl PlaceholderContext {
+    /// 使用登录 Shell 环境（加载失败时使用当前进程环境）和当前设置创建
+    pub async fn load() -> Self {
+        let env = match crate::shell_environment::ShellEnvironment::load_environment().await {
+            Ok(env) => env,
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to load shell environment for placeholders, using current process env: {}",
+                    e
+                );
+                std::env::vars().collect()
+            }
+        };
+        let home = dirs::home_dir().map(|p| p.to_string_lossy().into_owned());
+        let workspace = crate::config::CONFIG_SERVICE
+            .current()
+            .settings
+            .as_ref()
+            .and_then(|s| s.workspace.clone())
+            .map(|path| match (path.strip_prefix("~/"), home.as_deref()) {
+                (Some(rest), Some(home)) => format!("{}/{}", home, rest),
+                _ => path,
+            });
+        let config_dir = crate::config::AppConfig::config_path()
+            .parent()
+            .map(|p| p.to_string_lossy().into_owned());
+
+        Self {
+            env,
+            home,
+            workspace,
+            config_dir,
+        }
+    }
+
+    fn lookup(&self, expr: &str, remote: Option<&McpServerConfig>) -> Lookup {
+        if let Some(rest) = expr.strip_prefix(ENV_PREFIX) {
+            let (name, default) = match rest.split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (rest, None),
+            };
+            if remote.is_some_and(|config| !config.env_allowlisted(name)) {
+                return Lookup::Denied;
+            }
+            return match (self.env.get(name).filter(|v| !v.is_empty()), default) {
+                (Some(value), _) => Lookup::Value(value.clone()),
+                (None, Some(default)) => Lookup::Value(default.to_string()),
+                (None, None) => Lookup::Unresolved,
+            };
+        }
+
+        let value = match expr {
+            "home" | "userHome" => &self.home,
+            "workspace" | "workspaceFolder" => &self.workspace,
+            "config_dir" => &self.config_dir,
+            _ => return Lookup::Verbatim,
+        };
+        match value {
+            Some(value) => Lookup::Value(value.clone()),
+            None => Lookup::Unresolved,
+        }
+    }
+
+    /// 展开字符串中的占位符，无法解析的占位符保持原样并记录到 `unresolved`
+    pub fn expand(&self, value: &str, unresolved: &mut Vec<String>) -> String {
+        self.expand_for(value, None, unresolved)
+    }
+
+    /// 展开发往远端的 url / headers，`${env:...}` 只允许 env_allowlist 中的变量
+    fn expand_remote(
+        &self,
+        value: &str,
+        config: &McpServerConfig,
+        unresolved: &mut Vec<String>,
+    ) -> String {
+        self.expand_for(value, Some(config), unresolved)
+    }
+
+    fn expand_for(
+        &self,
+        value: &str,
+        remote: Option<&McpServerConfig>,
+        unresolved: &mut Vec<String>,
+    ) -> String {
+        let mut expanded = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            let Some(len) = rest[start + 2..].find('}') else {
+                break;
+            };
+            let end = start + 2 + len + 1;
+            let placeholder = &rest[start..end];
+            let expr = &rest[start + 2..end - 1];
+
+            expanded.push_str(&rest[..start]);
+            let missing = match self.lookup(expr, remote) {
+                Lookup::Value(value) => {
+                    expanded.push_str(&value);
+                    None
+                }
+                Lookup::Verbatim => {
+                    expanded.push_str(placeholder);
+                    None
+                }
+                Lookup::Unresolved => Some(placeholder.to_string()),
+                Lookup::Denied => Some(format!("{} (not in env_allowlist)", placeholder)),
+            };
+            if let Some(missing) = missing {
+                if !unresolved.contains(&missing) {
+                    unresolved.push(missing);
+                }
+                expanded.push_str(placeholder);
+            }
+            rest = &rest[end..];
+        }
+        expanded.push_str(rest);
+        expanded
+    }
+
+    /// 返回展开后的配置副本和无法解析的占位符
+    pub fn expand_server_config(&self, config: &McpServerConfig) -> (McpServerConfig, Vec<String>) {
+        let mut unresolved = Vec::new();
+        let mut expanded = config.clone();
+
+        for value in [&mut expanded.command, &mut expanded.cwd]
+            .into_iter()
+            .flatten()
+        {
+            *value = self.expand(value, &mut unresolved);
+        }
+        if let Some(url) = expanded.url.as_mut() {
+            *url = self.expand_remote(url, config, &mut unresolved);
+        }
+        for arg in expanded.args.iter_mut().flatten() {
+            *arg = self.expand(arg, &mut unresolved);
+        }
+        if let Some(helper) = expanded.credential_helper.as_mut() {
+            helper.command = self.expand(&helper.command, &mut unresolved);
+            for arg in helper.args.iter_mut() {
+                *arg = self.expand(arg, &mut unresolved);
+            }
+        }
+        for value in expanded.env.iter_mut().flatten().map(|(_, v)| v) {
+            *value = self.expand(value, &mut unresolved);
+        }
+        for value in expanded.headers.iter_mut().flatten().map(|(_, v)| v) {
+            *value = self.expand_remote(value, config, &mut unresolved);
+        }
+
+        (expanded, unresolved)
+    }
+
+    /// 配置中无法解析的占位符，用于校验展示
+    pub fn unresolved(&self, config: &McpServerConfig) -> Vec<String> {
+        self.expand_server_config(config).1
+    }
+}
+
+/// 配置的可展开字段中是否包含 `${...}`
+pub fn contains_placeholders(config: &McpServerConfig) -> bool {
+    let strings = [&config.command, &config.url, &config.cwd]
+        .into_iter()
+        .flatten()
+        .chain(config.args.iter().flatten())
+        .chain(
+            config
+                .credential_helper
+                .iter()
+                .flat_map(|helper| std::iter::once(&helper.command).chain(&helper.args)),
+        );
+    let values = [&config.env, &config.headers]
+        .into_iter()
+        .flatten()
+        .flat_map(|map| map.values());
+    strings.chain(values).any(|value| value.contains("${"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> PlaceholderContext {
+        PlaceholderContext {
+            env: HashMap::from([
+                ("USER".to_string(), "alice".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]),
+            home: Some("/home/alice".to_string()),
+            workspace: None,
+            config_dir: Some("/home/alice/.mcprouter".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_expands_known_placeholders() {
+        let context = context();
+        let mut unresolved = Vec::new();
+        assert_eq!(
+            context.expand("${home}/src/${env:USER}", &mut unresolved),
+            "/home/alice/src/alice"
+        );
+        assert_eq!(
+            context.expand("${env:EMPTY:-fallback} ${env:MISSING:-}", &mut unresolved),
+            "fallback "
+        );
+        assert_eq!(
+            context.expand("Bearer ${secret:token} ${PATH}", &mut unresolved),
+            "Bearer ${secret:token} ${PATH}"
+        );
+        assert!(unresolved.is_empty());
+    }
+
+    #[test]
+    fn test_reports_unresolved_placeholders() {
+        let context = context();
+        let mut config = McpServerConfig::new(String::new(), "fs".to_string());
+        config.command = Some("npx".to_string());
+        config.args = Some(vec![
+            "${workspace}".to_string(),
+            "${env:TOKEN}".to_string(),
+            "${workspace}".to_string(),
+        ]);
+
+        assert!(contains_placeholders(&config));
+        assert_eq!(
+            context.unresolved(&config),
+            vec!["${workspace}".to_string(), "${env:TOKEN}".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_remote_fields_only_expand_allowlisted_env() {
+        let context = context();
+        let mut config = McpServerConfig::new(String::new(), "api".to_string());
+        config.transport = crate::types::ServiceTransport::Http;
+        config.url = Some("https://example.com/${env:USER}".to_string());
+        config.headers = Some(HashMap::from([(
+            "X-User".to_string(),
+            "${env:USER:-nobody}".to_string(),
+        )]));
+
+        let (expanded, unresolved) = context.expand_server_config(&config);
+        assert_eq!(
+            expanded.url.as_deref(),
+            Some("https://example.com/${env:USER}")
+        );
+        assert_eq!(
+            unresolved,
+            vec![
+                "${env:USER} (not in env_allowlist)".to_string(),
+                "${env:USER:-nobody} (not in env_allowlist)".to_string(),
+            ]
+        );
+
+        config.env_allowlist = Some(vec!["US*".to_string()]);
+        let (expanded, unresolved) = context.expand_server_config(&config);
+        assert_eq!(expanded.url.as_deref(), Some("https://example.com/alice"));
+        assert_eq!(expanded.headers.unwrap()["X-User"], "alice");
+        assert!(unresolved.is_empty());
+    }
+}
@@ -0,0 +1,313 @@
+// STDIO 服务子进程监视：记录 PID、退出码/信号，并在进程退出时通知监控任务
+
+use crate::types::LaunchedRuntime;
+use std::collections::HashMap;
+use std::process::ExitStatus;
+use std::sync::{Arc, Mutex};
+use tokio::process::{Child, Command};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// 进程连续运行超过该时间后，之前的重启不再计入重启次数
+const STABLE_UPTIME: chrono::TimeDelta = chrono::TimeDelta::minutes(10);
+
+/// 子进程退出信息
+#[derive(Debug, Clone)]
+pub struct ProcessExit {
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+    pub exited_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl ProcessExit {
+    fn from_status(status: &ExitStatus) -> Self {
+        #[cfg(unix)]
+        let signal = {
+            use std::os::unix::process::ExitStatusExt;
+            status.signal()
+        };
+        #[cfg(not(unix))]
+        let signal = None;
+
+        Self {
+            code: status.code(),
+            signal,
+            exited_at: chrono::Utc::now(),
+        }
+    }
+
+    /// 是否正常退出（退出码为 0）
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+impl std::fmt::Display for ProcessExit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.code, self.signal) {
+            (_, Some(signal)) => write!(f, "killed by signal {}", signal),
+            (Some(code), None) => write!(f, "exited with code {}", code),
+            (None, None) => write!(f, "exited"),
+        }
+    }
+}
+
+#[derive(Default)]
+struct ProcessState {
+    pid: Option<u32>,
+    runtime: Option<LaunchedRuntime>,
+    started_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// 每次启动新进程时递增，用于忽略旧进程的退出事件
+    generation: u64,
+    stop: Option<CancellationToken>,
+    last_exit: Option<ProcessExit>,
+    /// last_exit 所属的进程代数
+    last_exit_generation: u64,
+    restart_count: u32,
+}
+
+impl ProcessState {
+    /// 当前进程的运行时长，已退出时计算到退出时间
+    fn uptime(&self) -> Option<chrono::TimeDelta> {
+        let started_at = self.started_at?;
+        let ended_at = match &self.last_exit {
+            Some(exit) if self.last_exit_generation == self.generation => exit.exited_at,
+            _ => chrono::Utc::now(),
+        };
+        Some(ended_at - started_at)
+    }
+}
+
+/// STDIO 子进程监视器
+#[derive(Default)]
+pub struct ProcessMonitor {
+    states: Mutex<HashMap<String, ProcessState>>,
+    exit_notify: Mutex<HashMap<String, Arc<Notify>>>,
+}
+
+impl ProcessMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 接管子进程并等待其退出；同名服务的旧进程会被终止
+    ///
+    /// `cleanup` 在进程退出或被停止后执行（例如删除对应的容器）
+    pub fn watch(
+        self: &Arc<Self>,
+        server_name: &str,
+        mut child: Child,
+        runtime: LaunchedRuntime,
+        cleanup: Option<Command>,
+    ) {
+        let token = CancellationToken::new();
+        let generation = {
+            let mut states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+            let state = states.entry(server_name.to_string()).or_default();
+            if let Some(previous) = state.stop.replace(token.clone()) {
+                previous.cancel();
+            }
+            state.generation += 1;
+            state.pid = child.id();
+            state.runtime = Some(runtime);
+            state.started_at = Some(chrono::Utc::now());
+            state.generation
+        };
+
+        let monitor = Arc::clone(self);
+        let server_name = server_name.to_string();
+        tokio::spawn(async move {
+            tokio::select! {
+                status = child.wait() => match status {
+                    Ok(status) => monitor.on_exit(&server_name, generation, ProcessExit::from_status(&status)),
+                    Err(e) => tracing::warn!("Failed to wait for process of server '{}': {}", server_name, e),
+                },
+                _ = token.cancelled() => {
+                    // 主动停止，不记录为异常退出
+                    if let Err(e) = child.kill().await {
+                        tracing::debug!("Failed to kill process of server '{}': {}", server_name, e);
+                    }
+                }
+            }
+            if let Some(mut cleanup) = cleanup {
+                if let Err(e) = cleanup.output().await {
+                    tracing::debug!("Cleanup of server '{}' failed: {}", server_name, e);
+                }
+            }
+        });
+    }
+
+    fn on_exit(&self, server_name: &str, generation: u64, exit: ProcessExit) {
+        {
+            let mut states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(state) = states.get_mut(server_name) else {
+                return;
+            };
+            if state.generation != generation {
+                return;
+            }
+            state.pid = None;
+            state.stop = None;
+            state.last_exit = Some(exit.clone());
+            state.last_exit_generation = generation;
+        }
+
+        if exit.success() {
+            tracing::info!("Process of server '{}' {}", server_name, exit);
+        } else {
+            tracing::warn!("💥 Process of server '{}' {}", server_name, exit);
+        }
+        self.exit_notify(server_name).notify_one();
+    }
+
+    /// 终止服务的当前进程
+    pub fn stop(&self, server_name: &str) {
+        let mut states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(state) = states.get_mut(server_name) {
+            if let Some(token) = state.stop.take() {
+                token.cancel();
+            }
+            state.pid = None;
+        }
+    }
+
+    /// 服务删除时清除所有记录
+    pub fn remove(&self, server_name: &str) {
+        self.stop(server_name);
+        self.states
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(server_name);
+        self.exit_notify
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(server_name);
+    }
+
+    /// 进程退出时触发的通知
+    pub fn exit_notify(&self, server_name: &str) -> Arc<Notify> {
+        let mut notifies = self.exit_notify.lock().unwrap_or_else(|e| e.into_inner());
+        Arc::clone(notifies.entry(server_name.to_string()).or_default())
+    }
+
+    pub fn pid(&self, server_name: &str) -> Option<u32> {
+        let states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        states.get(server_name).and_then(|s| s.pid)
+    }
+
+    /// 当前进程使用的运行时
+    pub fn runtime(&self, server_name: &str) -> Option<LaunchedRuntime> {
+        let states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        states
+            .get(server_name)
+            .filter(|s| s.pid.is_some())
+            .and_then(|s| s.runtime.clone())
+    }
+
+    pub fn started_at(&self, server_name: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+        let states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        states.get(server_name).and_then(|s| s.started_at)
+    }
+
+    /// 最近一次进程退出信息（可能属于之前的进程）
+    pub fn last_exit(&self, server_name: &str) -> Option<ProcessExit> {
+        let states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        states.get(server_name).and_then(|s| s.last_exit.clone())
+    }
+
+    /// 当前进程已自行退出时返回退出信息
+    pub fn exited(&self, server_name: &str) -> Option<ProcessExit> {
+        let states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        states
+            .get(server_name)
+            .filter(|s| s.last_exit_generation == s.generation)
+            .and_then(|s| s.last_exit.clone())
+    }
+
+    pub fn restart_count(&self, server_name: &str) -> u32 {
+        let states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        states
+            .get(server_name)
+            .map(|s| s.restart_count)
+            .unwrap_or(0)
+    }
+
+    /// 记录一次重启，返回当前的重启次数
+    ///
+    /// 失败的进程稳定运行超过 `STABLE_UPTIME` 时先清零，
+    /// 使 max_restarts 限制的是短时间内的连续崩溃而不是累计次数
+    pub fn record_restart(&self, server_name: &str) -> u32 {
+        let mut states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        let state = states.entry(server_name.to_string()).or_default();
+        if state.uptime().is_some_and(|uptime| uptime >= STABLE_UPTIME) {
+            state.restart_count = 0;
+        }
+        state.restart_count += 1;
+        state.restart_count
+    }
+
+    pub fn reset_restart_count(&self, server_name: &str) {
+        let mut states = self.states.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(state) = states.get_mut(server_name) {
+            state.restart_count = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_started_at(monitor: &ProcessMonitor, server_name: &str, ago: chrono::TimeDelta) {
+        let mut states = monitor.states.lock().unwrap();
+        let state = states.entry(server_name.to_string()).or_default();
+        state.generation += 1;
+        state.started_at = Some(chrono::Utc::now() - ago);
+    }
+
+    #[test]
+    fn test_record_restart_counts_crash_loop() {
+        let monitor = ProcessMonitor::new();
+        set_started_at(&monitor, "demo", chrono::TimeDelta::seconds(5));
+        assert_eq!(monitor.record_restart("demo"), 1);
+        set_started_at(&monitor, "demo", chrono::TimeDelta::seconds(5));
+        assert_eq!(monitor.record_restart("demo"), 2);
+        assert_eq!(monitor.restart_count("demo"), 2);
+
+        monitor.reset_restart_count("demo");
+        assert_eq!(monitor.restart_count("demo"), 0);
+    }
+
+    #[test]
+    fn test_record_restart_resets_after_stable_uptime() {
+        let monitor = ProcessMonitor::new();
+        for _ in 0..3 {
+            set_started_at(&monitor, "demo", chrono::TimeDelta::seconds(5));
+            monitor.record_restart("demo");
+        }
+        assert_eq!(monitor.restart_count("demo"), 3);
+
+        set_started_at(
+            &monitor,
+            "demo",
+            STABLE_UPTIME + chrono::TimeDelta::seconds(1),
+        );
+        assert_eq!(monitor.record_restart("demo"), 1);
+    }
+
+    #[test]
+    fn test_uptime_ends_at_exit() {
+        let monitor = ProcessMonitor::new();
+        set_started_at(&monitor, "demo", chrono::TimeDelta::hours(1));
+        let generation = monitor.states.lock().unwrap()["demo"].generation;
+        // 启动后很快退出，之后等待了很久才重启，仍算作崩溃
+        let exit = ProcessExit {
+            code: Some(1),
+            signal: None,
+            exited_at: chrono::Utc::now() - chrono::TimeDelta::minutes(59),
+        };
+        monitor.on_exit("demo", generation, exit);
+        assert_eq!(monitor.record_restart("demo"), 1);
+        assert_eq!(monitor.record_restart("demo"), 2);
+    }
+}
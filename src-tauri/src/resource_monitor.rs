@@ -0,0 +1,349 @@
+// STDIO 服务进程资源占用监控（Linux 下读取 /proc）
+
+use crate::mcp_manager::McpServerManager;
+use crate::process_monitor::ProcessMonitor;
+use crate::types::McpServerConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 采样间隔
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// 服务进程树的资源占用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessUsage {
+    pub server_name: String,
+    /// 服务主进程 PID
+    pub pid: u32,
+    /// 进程树中的进程数（含子进程，例如 npx 启动的 node）
+    pub process_count: usize,
+    /// 进程树 CPU 占用（100 表示占满一个核心）
+    pub cpu_percent: f64,
+    /// 进程树常驻内存（RSS）字节数
+    pub memory_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_limit_bytes: Option<u64>,
+    pub sampled_at: chrono::DateTime<chrono::Utc>,
+}
+
+struct Sample {
+    cpu_ticks: u64,
+    at: Instant,
+    usage: ProcessUsage,
+}
+
+/// 定期采样每个 STDIO 服务进程树的 CPU 与内存，超出内存限制时终止进程，
+/// 由监控任务按服务的重启策略决定是否重启
+pub struct ResourceMonitor {
+    process_monitor: Arc<ProcessMonitor>,
+    samples: Mutex<HashMap<String, Sample>>,
+}
+
+impl ResourceMonitor {
+    pub fn new(process_monitor: Arc<ProcessMonitor>) -> Self {
+        Self {
+            process_monitor,
+            samples: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 启动后台采样任务
+    pub fn start(self: &Arc<Self>, mcp_server_manager: Arc<McpServerManager>) {
+        if !cfg!(target_os = "linux") {
+            tracing::info!("Process resource monitoring is only supported on Linux");
+            return;
+        }
+
+        let monitor = Arc::clone(self);
+        tokio::spawn(async move {
+            tracing::info!("📈 Process resource monitor started");
+            loop {
+                tokio::time::sleep(SAMPLE_INTERVAL).await;
+                monitor.sample_all(&mcp_server_manager).await;
+            }
+        });
+    }
+
+    async fn sample_all(&self, mcp_server_manager: &McpServerManager) {
+        let names = match mcp_server_manager.list_enabled_server_names().await {
+            Ok(names) => names,
+            Err(e) => {
+                tracing::debug!("Resource monitor failed to list servers: {}", e);
+                return;
+            }
+        };
+
+        let mut targets = Vec::new();
+        for name in names {
+            let Some(pid) = self.process_monitor.pid(&name) else {
+                continue;
+            };
+            let Ok(Some(config)) = mcp_server_manager.get_server_config(&name).await else {
+                continue;
+            };
+            // 容器服务的 PID 是容器运行时 CLI，不是服务进程本身
+            if config.container.is_some() {
+                continue;
+            }
+            targets.push((config, pid));
+        }
+
+        // 清理已停止、已禁用或容器服务的采样
+        {
+            let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+            samples.retain(|name, _| targets.iter().any(|(config, _)| &config.name == name));
+        }
+        if targets.is_empty() {
+            return;
+        }
+
+        // 每次采样只扫描一次 /proc，文件读取放到阻塞线程中
+        let roots: Vec<u32> = targets.iter().map(|(_, pid)| *pid).collect();
+        let trees = match tokio::task::spawn_blocking(move || {
+            let table = ProcessTable::read();
+            roots
+                .into_iter()
+                .map(|pid| table.as_ref().and_then(|table| table.tree_usage(pid)))
+                .collect::<Vec<_>>()
+        })
+        .await
+        {
+            Ok(trees) => trees,
+            Err(e) => {
+                tracing::debug!("Resource sampling task failed: {}", e);
+                return;
+            }
+        };
+
+        for ((config, pid), tree) in targets.into_iter().zip(trees) {
+            let Some(tree) = tree else {
+                continue;
+            };
+            let memory_limit = config.memory_limit_bytes();
+            let usage = self.record_sample(&config.name, pid, tree, memory_limit);
+
+            if let Some(limit) = memory_limit {
+                if usage.memory_bytes > limit {
+                    self.stop_over_limit(&config, &usage, limit).await;
+                }
+            }
+        }
+    }
+
+    fn record_sample(
+        &self,
+        server_name: &str,
+        pid: u32,
+        tree: ProcessTreeUsage,
+        memory_limit: Option<u64>,
+    ) -> ProcessUsage {
+        let now = Instant::now();
+
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let cpu_percent = match samples.get(server_name) {
+            Some(previous) if previous.usage.pid == pid => {
+                let elapsed = now.duration_since(previous.at).as_secs_f64();
+                if elapsed > 0.0 {
+                    let ticks = tree.cpu_ticks.saturating_sub(previous.cpu_ticks) as f64;
+                    ticks / CLOCK_TICKS_PER_SECOND / elapsed * 100.0
+                } else {
+                    0.0
+                }
+            }
+            _ => 0.0,
+        };
+
+        let usage = ProcessUsage {
+            server_name: server_name.to_string(),
+            pid,
+            process_count: tree.process_count,
+            cpu_percent,
+            memory_bytes: tree.memory_bytes,
+            memory_limit_bytes: memory_limit,
+            sampled_at: chrono::Utc::now(),
+        };
+        samples.insert(
+            server_name.to_string(),
+            Sample {
+                cpu_ticks: tree.cpu_ticks,
+                at: now,
+                usage: usage.clone(),
+            },
+        );
+        usage
+    }
+
+    /// 终止超出内存限制的进程，是否重启由监控任务按服务的重启策略决定
+    async fn stop_over_limit(&self, config: &McpServerConfig, usage: &ProcessUsage, limit: u64) {
+        let message = format!(
+            "Memory usage of {} MB exceeded the limit of {} MB",
+            usage.memory_bytes / 1024 / 1024,
+            limit / 1024 / 1024
+        );
+        tracing::warn!("🧠 Server '{}': {}, stopping", config.name, message);
+
+        self.samples
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&config.name);
+        crate::MCP_CLIENT_MANAGER
+            .mark_failed(&config.name, message)
+            .await;
+        self.process_monitor.exit_notify(&config.name).notify_one();
+    }
+
+    /// 获取服务最近一次采样结果
+    pub fn usage(&self, server_name: &str) -> Option<ProcessUsage> {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        samples.get(server_name).map(|s| s.usage.clone())
+    }
+
+    /// 获取所有服务最近一次采样结果（按内存占用降序）
+    pub fn all_usage(&self) -> Vec<ProcessUsage> {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let mut usage: Vec<ProcessUsage> = samples.values().map(|s| s.usage.clone()).collect();
+        usage.sort_by(|a, b| b.memory_bytes.cmp(&a.memory_bytes));
+        usage
+    }
+}
+
+/// 进程树汇总
+struct ProcessTreeUsage {
+    process_count: usize,
+    cpu_ticks: u64,
+    memory_bytes: u64,
+}
+
+/// Linux 的 USER_HZ，/proc/<pid>/stat 中 CPU 时间的单位
+const CLOCK_TICKS_PER_SECOND: f64 = 100.0;
+
+/// 一次 /proc 扫描得到的所有进程的父进程与 CPU 时间
+struct ProcessTable {
+    stats: HashMap<u32, (u32, u64)>,
+    children: HashMap<u32, Vec<u32>>,
+}
+
+impl ProcessTable {
+    /// 扫描 /proc，非 Linux 系统上返回 None
+    fn read() -> Option<Self> {
+        let mut stats: HashMap<u32, (u32, u64)> = HashMap::new();
+        for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+            let Some(pid) = entry
+                .file_name()
+                .to_str()
+                .and_then(|s| s.parse::<u32>().ok())
+            else {
+                continue;
+            };
+            if let Some(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid))
+                .ok()
+                .and_then(|content| parse_stat(&content))
+            {
+                stats.insert(pid, stat);
+            }
+        }
+
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for (pid, (ppid, _)) in &stats {
+            children.entry(*ppid).or_default().push(*pid);
+        }
+        Some(Self { stats, children })
+    }
+
+    /// 以 `root_pid` 为根的进程树的 CPU 时间和内存
+    fn tree_usage(&self, root_pid: u32) -> Option<ProcessTreeUsage> {
+        if !self.stats.contains_key(&root_pid) {
+            return None;
+        }
+
+        let mut usage = ProcessTreeUsage {
+            process_count: 0,
+            cpu_ticks: 0,
+            memory_bytes: 0,
+        };
+        let mut stack = vec![root_pid];
+        while let Some(pid) = stack.pop() {
+            if let Some((_, ticks)) = self.stats.get(&pid) {
+                usage.process_count += 1;
+                usage.cpu_ticks += ticks;
+                usage.memory_bytes += read_rss_bytes(pid).unwrap_or(0);
+            }
+            if let Some(pids) = self.children.get(&pid) {
+                stack.extend(pids);
+            }
+        }
+        Some(usage)
+    }
+}
+
+/// 解析 /proc/<pid>/stat 的内容，返回 (ppid, utime + stime)
+fn parse_stat(content: &str) -> Option<(u32, u64)> {
+    // 进程名可能包含空格和括号，从最后一个 ')' 之后开始解析
+    let rest = &content[content.rfind(')')? + 1..];
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    // fields[0] 为 state（第 3 个字段），ppid 为第 4 个，utime/stime 为第 14/15 个
+    let ppid = fields.get(1)?.parse().ok()?;
+    let utime: u64 = fields.get(11)?.parse().ok()?;
+    let stime: u64 = fields.get(12)?.parse().ok()?;
+    Some((ppid, utime + stime))
+}
+
+/// 读取 /proc/<pid>/status 中的 VmRSS
+fn read_rss_bytes(pid: u32) -> Option<u64> {
+    let content = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    parse_rss_bytes(&content)
+}
+
+fn parse_rss_bytes(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = "1234 (node) S 1000 1234 1234 0 -1 4194560 5000 0 0 0 150 25 0 0 20 0 11 0 \
+                    100 1000000 5000 18446744073709551615";
+        assert_eq!(parse_stat(stat), Some((1000, 175)));
+    }
+
+    #[test]
+    fn test_parse_stat_with_parentheses_in_name() {
+        let stat = "42 (my (odd) proc) R 7 42 42 0 -1 0 0 0 0 0 3 4 0 0 20 0 1 0 1 1 1";
+        assert_eq!(parse_stat(stat), Some((7, 7)));
+    }
+
+    #[test]
+    fn test_parse_stat_rejects_truncated_content() {
+        assert_eq!(parse_stat("42 (node) S 7 42"), None);
+        assert_eq!(parse_stat("42 node S"), None);
+    }
+
+    #[test]
+    fn test_parse_rss_bytes() {
+        let status = "Name:\tnode\nVmPeak:\t  200 kB\nVmRSS:\t  1024 kB\nThreads:\t11\n";
+        assert_eq!(parse_rss_bytes(status), Some(1024 * 1024));
+        assert_eq!(parse_rss_bytes("Name:\tnode\n"), None);
+    }
+
+    #[test]
+    fn test_tree_usage_sums_descendants() {
+        let stats = HashMap::from([(10, (1, 5)), (11, (10, 3)), (12, (11, 2)), (20, (1, 100))]);
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+        for (pid, (ppid, _)) in &stats {
+            children.entry(*ppid).or_default().push(*pid);
+        }
+        let table = ProcessTable { stats, children };
+
+        let usage = table.tree_usage(10).unwrap();
+        assert_eq!(usage.process_count, 3);
+        assert_eq!(usage.cpu_ticks, 10);
+        assert!(table.tree_usage(99).is_none());
+    }
+}
@@ -0,0 +1,395 @@
+// STDIO 服务沙箱：限制文件系统访问、网络、资源上限与继承的环境变量
+//
+// Linux 下使用 bubblewrap (bwrap) 隔离文件系统与网络，资源上限通过 prlimit 设置。
+// 启用沙箱但缺少所需工具时拒绝启动服务，而不是退化为不受限运行。
+
+use crate::error::{McpError, Result};
+use crate::types::BASE_ENV_VARS;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::process::Command;
+
+/// 沙箱内只读挂载的系统目录（不存在时跳过）
+#[cfg(target_os = "linux")]
+const SYSTEM_READ_ONLY_PATHS: &[&str] = &[
+    "/usr",
+    "/bin",
+    "/sbin",
+    "/lib",
+    "/lib32",
+    "/lib64",
+    "/etc",
+    "/opt",
+    "/nix/store",
+];
+
+/// 单个 STDIO 服务的沙箱配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SandboxProfile {
+    /// 可读写的路径（其余用户目录不可见，HOME 为空的临时目录）
+    #[serde(default)]
+    pub allowed_paths: Vec<String>,
+    /// 只读路径
+    #[serde(default)]
+    pub read_only_paths: Vec<String>,
+    /// 是否允许访问网络（bunx / uvx 首次运行需要下载依赖）
+    #[serde(default = "default_true")]
+    pub network: bool,
+    /// 虚拟地址空间上限（MB，RLIMIT_AS）
+    ///
+    /// 限制的是地址空间而不是实际占用的内存。node / bun / deno 启动时会预留大量地址空间，
+    /// 设置后无法启动，因此对这些运行时拒绝该选项；限制实际内存请使用服务的 memory_limit_mb。
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_mb: Option<u64>,
+    /// 最大打开文件数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_open_files: Option<u64>,
+    /// 最大进程数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_processes: Option<u64>,
+    /// CPU 时间上限（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cpu_seconds: Option<u64>,
+    /// 丢弃继承的 Shell 环境变量，仅保留基础变量、env_allowlist 和服务自身配置的 env
+    #[serde(default = "default_true")]
+    pub drop_env: bool,
+    /// drop_env 时额外保留的环境变量
+    #[serde(default)]
+    pub env_allowlist: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for SandboxProfile {
+    fn default() -> Self {
+        Self {
+            allowed_paths: Vec::new(),
+            read_only_paths: Vec::new(),
+            network: true,
+            max_memory_mb: None,
+            max_open_files: None,
+            max_processes: None,
+            max_cpu_seconds: None,
+            drop_env: true,
+            env_allowlist: Vec::new(),
+        }
+    }
+}
+
+impl SandboxProfile {
+    /// 是否保留继承的环境变量
+    pub fn allows_inherited_env(&self, key: &str) -> bool {
+        !self.drop_env
+            || BASE_ENV_VARS.contains(&key)
+            || self.env_allowlist.iter().any(|k| k == key)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn has_rlimits(&self) -> bool {
+        self.max_memory_mb.is_some()
+            || self.max_open_files.is_some()
+            || self.max_processes.is_some()
+            || self.max_cpu_seconds.is_some()
+    }
+
+    /// prlimit 参数
+    #[cfg(target_os = "linux")]
+    fn rlimit_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if let Some(mb) = self.max_memory_mb {
+            args.push(format!("--as={}", mb * 1024 * 1024));
+        }
+        if let Some(n) = self.max_open_files {
+            args.push(format!("--nofile={}", n));
+        }
+        if let Some(n) = self.max_processes {
+            args.push(format!("--nproc={}", n));
+        }
+        if let Some(secs) = self.max_cpu_seconds {
+            args.push(format!("--cpu={}", secs));
+        }
+        args
+    }
+}
+
+/// 预留大量虚拟地址空间、无法在 RLIMIT_AS 下运行的 JavaScript 运行时
+#[cfg(target_os = "linux")]
+const ADDRESS_SPACE_HUNGRY_RUNTIMES: &[&str] = &["node", "npx", "npm", "bun", "bunx", "deno"];
+
+/// 构建在沙箱中运行 `program` 的命令
+///
+/// 返回的命令不继承当前进程的环境变量，调用方需显式设置。
+/// `cwd` 不在 allowed_paths 或 read_only_paths 中时只读挂载，未指定时使用第一个 allowed_paths。
+#[cfg(target_os = "linux")]
+pub fn build_command(
+    profile: &SandboxProfile,
+    program: &Path,
+    args: &[String],
+    home: Option<&str>,
+    cwd: Option<&Path>,
+) -> Result<Command> {
+    if profile.max_memory_mb.is_some() {
+        let runtime = program
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if ADDRESS_SPACE_HUNGRY_RUNTIMES.contains(&runtime.as_str()) {
+            return Err(McpError::InvalidConfiguration(format!(
+                "Sandbox max_memory_mb limits virtual address space and prevents {} from starting, \
+                 use the server's memory_limit_mb instead",
+                runtime
+            )));
+        }
+    }
+
+    let bwrap = which::which("bwrap").map_err(|_| {
+        McpError::InvalidConfiguration(
+            "Sandbox requires bubblewrap (bwrap), but it was not found in PATH".to_string(),
+        )
+    })?;
+    let sandbox_args = bwrap_args(profile, program, args, home, cwd);
+
+    // 资源上限由外层 prlimit 设置，bwrap 及其子进程继承
+    let mut command = if profile.has_rlimits() {
+        let prlimit = which::which("prlimit").map_err(|_| {
+            McpError::InvalidConfiguration(
+                "Sandbox resource limits require prlimit (util-linux), but it was not found in PATH"
+                    .to_string(),
+            )
+        })?;
+        let mut command = Command::new(prlimit);
+        command
+            .args(profile.rlimit_args())
+            .arg("--")
+            .arg(bwrap)
+            .args(&sandbox_args);
+        command
+    } else {
+        let mut command = Command::new(bwrap);
+        command.args(&sandbox_args);
+        command
+    };
+    command.env_clear();
+    Ok(command)
+}
+
+/// bwrap 的参数（不含 bwrap 本身）
+#[cfg(target_os = "linux")]
+fn bwrap_args(
+    profile: &SandboxProfile,
+    program: &Path,
+    args: &[String],
+    home: Option<&str>,
+    cwd: Option<&Path>,
+) -> Vec<String> {
+    let mut sandbox_args: Vec<String> = vec![
+        "--die-with-parent".into(),
+        "--new-session".into(),
+        "--unshare-pid".into(),
+        "--unshare-ipc".into(),
+        "--unshare-uts".into(),
+    ];
+    if !profile.network {
+        sandbox_args.push("--unshare-net".into());
+    }
+    for path in SYSTEM_READ_ONLY_PATHS {
+        sandbox_args.extend(["--ro-bind-try".into(), path.to_string(), path.to_string()]);
+    }
+    sandbox_args.extend([
+        "--proc".into(),
+        "/proc".into(),
+        "--dev".into(),
+        "/dev".into(),
+        "--tmpfs".into(),
+        "/tmp".into(),
+    ]);
+    // HOME 替换为空的临时目录，包管理器缓存写入其中
+    if let Some(home) = home {
+        sandbox_args.extend(["--tmpfs".into(), home.to_string()]);
+    }
+    // 可执行文件所在目录（例如托管的 bun / uv）
+    if let Some(dir) = program.parent().filter(|d| !d.as_os_str().is_empty()) {
+        let dir = dir.to_string_lossy().to_string();
+        sandbox_args.extend(["--ro-bind-try".into(), dir.clone(), dir]);
+    }
+    for path in &profile.read_only_paths {
+        sandbox_args.extend(["--ro-bind".into(), path.clone(), path.clone()]);
+    }
+    for path in &profile.allowed_paths {
+        sandbox_args.extend(["--bind".into(), path.clone(), path.clone()]);
+    }
+    if let Some(dir) = cwd {
+        let mounted = profile
+            .allowed_paths
+            .iter()
+            .chain(&profile.read_only_paths)
+            .any(|path| dir.starts_with(path));
+        if !mounted {
+            let dir = dir.to_string_lossy().to_string();
+            sandbox_args.extend(["--ro-bind".into(), dir.clone(), dir]);
+        }
+    }
+    let workdir = cwd
+        .map(|dir| dir.to_string_lossy().to_string())
+        .or_else(|| profile.allowed_paths.first().cloned())
+        .unwrap_or_else(|| "/tmp".to_string());
+    sandbox_args.extend(["--chdir".into(), workdir, "--".into()]);
+    sandbox_args.push(program.to_string_lossy().to_string());
+    sandbox_args.extend(args.iter().cloned());
+    sandbox_args
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn build_command(
+    _profile: &SandboxProfile,
+    _program: &Path,
+    _args: &[String],
+    _home: Option<&str>,
+    _cwd: Option<&Path>,
+) -> Result<Command> {
+    Err(McpError::InvalidConfiguration(
+        "Sandboxed execution is only supported on Linux".to_string(),
+    ))
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    /// 参数中 `flag` 之后紧跟的两个值
+    fn has_pair(args: &[String], flag: &str, from: &str, to: &str) -> bool {
+        args.windows(3)
+            .any(|w| w[0] == flag && w[1] == from && w[2] == to)
+    }
+
+    #[test]
+    fn test_bwrap_args_isolate_by_default() {
+        let profile = SandboxProfile {
+            network: false,
+            ..SandboxProfile::default()
+        };
+        let args = bwrap_args(
+            &profile,
+            Path::new("/opt/runtimes/bun"),
+            &["x".to_string(), "server".to_string()],
+            Some("/home/user"),
+            None,
+        );
+
+        for flag in ["--die-with-parent", "--unshare-pid", "--unshare-net"] {
+            assert!(args.iter().any(|a| a == flag), "missing {}", flag);
+        }
+        assert!(has_pair(&args, "--ro-bind-try", "/usr", "/usr"));
+        assert!(has_pair(
+            &args,
+            "--ro-bind-try",
+            "/opt/runtimes",
+            "/opt/runtimes"
+        ));
+        assert!(args
+            .windows(2)
+            .any(|w| w[0] == "--tmpfs" && w[1] == "/home/user"));
+        assert!(args.windows(2).any(|w| w[0] == "--chdir" && w[1] == "/tmp"));
+
+        // `--` 之后是要运行的程序及其参数
+        let separator = args.iter().position(|a| a == "--").unwrap();
+        assert_eq!(&args[separator + 1..], ["/opt/runtimes/bun", "x", "server"]);
+    }
+
+    #[test]
+    fn test_bwrap_args_bind_configured_paths() {
+        let profile = SandboxProfile {
+            allowed_paths: vec!["/work".to_string()],
+            read_only_paths: vec!["/data".to_string()],
+            ..SandboxProfile::default()
+        };
+        let args = bwrap_args(&profile, Path::new("/usr/bin/python3"), &[], None, None);
+
+        assert!(!args.iter().any(|a| a == "--unshare-net"));
+        assert!(has_pair(&args, "--bind", "/work", "/work"));
+        assert!(has_pair(&args, "--ro-bind", "/data", "/data"));
+        // 未指定 cwd 时使用第一个可写路径
+        assert!(args
+            .windows(2)
+            .any(|w| w[0] == "--chdir" && w[1] == "/work"));
+        // 绑定挂载必须出现在 chdir 之前
+        let bind = args.iter().position(|a| a == "--bind").unwrap();
+        let chdir = args.iter().position(|a| a == "--chdir").unwrap();
+        assert!(bind < chdir);
+    }
+
+    #[test]
+    fn test_bwrap_args_mount_cwd() {
+        let profile = SandboxProfile {
+            allowed_paths: vec!["/work".to_string()],
+            ..SandboxProfile::default()
+        };
+        let program = Path::new("/usr/bin/python3");
+
+        let args = bwrap_args(&profile, program, &[], None, Some(Path::new("/srv/app")));
+        assert!(has_pair(&args, "--ro-bind", "/srv/app", "/srv/app"));
+        assert!(args
+            .windows(2)
+            .any(|w| w[0] == "--chdir" && w[1] == "/srv/app"));
+
+        // 已在可写路径中的目录不再重复挂载
+        let args = bwrap_args(&profile, program, &[], None, Some(Path::new("/work/sub")));
+        assert!(!args.iter().any(|a| a == "--ro-bind"));
+        assert!(args
+            .windows(2)
+            .any(|w| w[0] == "--chdir" && w[1] == "/work/sub"));
+    }
+
+    #[test]
+    fn test_rlimit_args() {
+        let profile = SandboxProfile {
+            max_memory_mb: Some(512),
+            max_open_files: Some(256),
+            max_cpu_seconds: Some(60),
+            ..SandboxProfile::default()
+        };
+        assert!(profile.has_rlimits());
+        assert_eq!(
+            profile.rlimit_args(),
+            [
+                format!("--as={}", 512 * 1024 * 1024),
+                "--nofile=256".to_string(),
+                "--cpu=60".to_string(),
+            ]
+        );
+        assert!(!SandboxProfile::default().has_rlimits());
+    }
+
+    #[test]
+    fn test_address_space_limit_rejected_for_js_runtimes() {
+        let profile = SandboxProfile {
+            max_memory_mb: Some(512),
+            ..SandboxProfile::default()
+        };
+        for program in ["/usr/bin/node", "/opt/bun/bun", "/usr/local/bin/npx"] {
+            let error = build_command(&profile, Path::new(program), &[], None, None)
+                .err()
+                .unwrap();
+            assert!(error.to_string().contains("memory_limit_mb"));
+        }
+    }
+
+    #[test]
+    fn test_allows_inherited_env() {
+        let profile = SandboxProfile {
+            env_allowlist: vec!["GITHUB_TOKEN".to_string()],
+            ..SandboxProfile::default()
+        };
+        assert!(profile.allows_inherited_env("PATH"));
+        assert!(profile.allows_inherited_env("GITHUB_TOKEN"));
+        assert!(!profile.allows_inherited_env("AWS_SECRET_ACCESS_KEY"));
+
+        let inherit = SandboxProfile {
+            drop_env: false,
+            ..SandboxProfile::default()
+        };
+        assert!(inherit.allows_inherited_env("AWS_SECRET_ACCESS_KEY"));
+    }
+}
@@ -0,0 +1,605 @@
+// 加密的密钥存储，服务的 env / headers 通过 ${secret:name} 引用密钥
+
+use crate::error::{McpError, Result};
+use crate::mcp_manager::McpServerManager;
+use crate::storage::orm_storage::Storage;
+use crate::types::McpServerConfig;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 列表接口中替代敏感值的占位符
+pub const REDACTED: &str = "<redacted>";
+
+/// 使用口令派生主密钥时读取的环境变量
+pub const PASSPHRASE_ENV: &str = "MCPROUTER_SECRET_PASSPHRASE";
+
+const KEY_FILE: &str = "secret.key";
+const SALT_FILE: &str = "secret.salt";
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const SALT_LEN: usize = 16;
+
+const REFERENCE_PREFIX: &str = "${secret:";
+
+/// 绑定名称（AAD）加密的密文前缀
+const BOUND_PREFIX: &str = "v2:";
+
+/// 名称中包含这些片段的 env / header 被视为敏感值
+const SENSITIVE_MARKERS: &[&str] = &[
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "API_KEY",
+    "APIKEY",
+    "ACCESS_KEY",
+    "PRIVATE_KEY",
+    "CREDENTIAL",
+    "AUTHORIZATION",
+    "COOKIE",
+    "PAT",
+];
+
+/// 密钥元信息，不包含值
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretInfo {
+    pub name: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+pub struct SecretStore {
+    storage: Arc<Storage>,
+    cipher: ChaCha20Poly1305,
+}
+
+impl SecretStore {
+    /// 加载主密钥并创建密钥存储
+    pub fn new(storage: Arc<Storage>) -> Result<Self> {
+        let passphrase = std::env::var(PASSPHRASE_ENV).ok().filter(|p| !p.is_empty());
+        let key = load_master_key(&key_dir(), passphrase.as_deref())?;
+        Ok(Self {
+            storage,
+            cipher: ChaCha20Poly1305::new(Key::from_slice(&key)),
+        })
+    }
+
+    pub async fn list(&self) -> Result<Vec<SecretInfo>> {
+        Ok(self
+            .storage
+            .list_secrets()
+            .await?
+            .into_iter()
+            .map(|secret| SecretInfo {
+                name: secret.name,
+                created_at: secret.created_at.to_rfc3339(),
+                updated_at: secret.updated_at.to_rfc3339(),
+            })
+            .collect())
+    }
+
+    pub async fn set(&self, name: &str, value: &str) -> Result<()> {
+        validate_name(name)?;
+        let ciphertext = encrypt(&self.cipher, &secret_aad(name), value)?;
+        self.storage.upsert_secret(name, &ciphertext).await?;
+        Ok(())
+    }
+
+    pub async fn get(&self, name: &str) -> Result<String> {
+        let secret = self
+            .storage
+            .get_secret(name)
+            .await?
+            .ok_or_else(|| McpError::NotFound(format!("Secret '{}' not found", name)))?;
+        decrypt(&self.cipher, &secret_aad(name), &secret.ciphertext).map_err(|e| {
+            McpError::ConfigError(format!("Failed to decrypt secret '{}': {}", name, e))
+        })
+    }
+
+    pub async fn delete(&self, name: &str) -> Result<()> {
+        self.storage.delete_secret(name).await?;
+        Ok(())
+    }
+
+    /// 读取下游服务的 OAuth 凭据（JSON），与密钥共用主密钥加密
+    pub async fn get_oauth_credential(&self, server_name: &str) -> Result<Option<String>> {
+        let Some(credential) = self.storage.get_oauth_credential(server_name).await? else {
+            return Ok(None);
+        };
+        let aad = oauth_credential_aad(server_name);
+        let json = decrypt(&self.cipher, &aad, &credential.ciphertext).map_err(|e| {
+            McpError::ConfigError(format!(
+                "Failed to decrypt OAuth credential of '{}': {}",
+                server_name, e
+            ))
+        })?;
+        Ok(Some(json))
+    }
+
+    pub async fn set_oauth_credential(&self, server_name: &str, json: &str) -> Result<()> {
+        let ciphertext = encrypt(&self.cipher, &oauth_credential_aad(server_name), json)?;
+        self.storage
+            .upsert_oauth_credential(server_name, &ciphertext)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_oauth_credential(&self, server_name: &str) -> Result<()> {
+        self.storage.delete_oauth_credential(server_name).await?;
+        Ok(())
+    }
+
+    /// 替换字符串中的所有 `${secret:name}` 引用
+    pub async fn resolve(&self, value: &str) -> Result<String> {
+        let mut resolved = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some((start, end, name)) = next_reference(rest) {
+            resolved.push_str(&rest[..start]);
+            resolved.push_str(&self.get(name).await?);
+            rest = &rest[end..];
+        }
+        resolved.push_str(rest);
+        Ok(resolved)
+    }
+
+    /// 返回 env 和 headers 中的密钥引用均已解析的配置副本
+    pub async fn resolve_server_config(&self, config: &McpServerConfig) -> Result<McpServerConfig> {
+        let mut resolved = config.clone();
+        for map in [&mut resolved.env, &mut resolved.headers]
+            .into_iter()
+            .flatten()
+        {
+            for value in map.values_mut() {
+                if value.contains(REFERENCE_PREFIX) {
+                    *value = self.resolve(value).await.map_err(|e| {
+                        McpError::ConfigError(format!("Server '{}': {}", config.name, e))
+                    })?;
+                }
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// 把配置中明文的敏感值移入密钥存储并替换为引用，返回迁移的数量；
+    /// 同名密钥已存在且值不同时不覆盖，保留明文
+    pub async fn migrate_server_config(&self, config: &mut McpServerConfig) -> Result<usize> {
+        let server_name = config.name.clone();
+        let mut migrated = 0;
+        for map in [&mut config.env, &mut config.headers].into_iter().flatten() {
+            for (key, value) in map.iter_mut() {
+                if !is_sensitive_key(key) || value.is_empty() || value.contains("${") {
+                    continue;
+                }
+                let name = secret_name_for(&server_name, key);
+                if self.storage.get_secret(&name).await?.is_some() {
+                    match self.get(&name).await {
+                        Ok(existing) if existing == *value => {}
+                        _ => {
+                            tracing::warn!(
+                                "Secret '{}' already exists, leaving '{}' of server '{}' in plaintext",
+                                name,
+                                key,
+                                server_name
+                            );
+                            continue;
+                        }
+                    }
+                } else {
+                    self.set(&name, value).await?;
+                }
+                *value = reference(&name);
+                migrated += 1;
+            }
+        }
+        Ok(migrated)
+    }
+
+    /// 迁移所有服务中的明文敏感值，返回迁移的数量；跳过 `skipped` 中的服务
+    pub async fn migrate_servers(
+        &self,
+        manager: &McpServerManager,
+        skipped: &HashSet<String>,
+    ) -> Result<usize> {
+        let mut total = 0;
+        for mut config in manager.list_server_configs().await? {
+            if skipped.contains(&config.name) {
+                tracing::debug!(
+                    "Skipping secret migration of file-managed server '{}'",
+                    config.name
+                );
+                continue;
+            }
+            let migrated = self.migrate_server_config(&mut config).await?;
+            if migrated > 0 {
+                self.storage
+                    .update_mcp_server(&config.name, &config)
+                    .await?;
+                tracing::info!(
+                    "🔐 Moved {} plaintext value(s) of server '{}' into the secret store",
+                    migrated,
+                    config.name
+                );
+                total += migrated;
+            }
+        }
+        Ok(total)
+    }
+}
+
+/// 密钥密文绑定的附加数据，防止密文在不同名称之间互换
+fn secret_aad(name: &str) -> String {
+    format!("secret:{}", name)
+}
+
+fn oauth_credential_aad(server_name: &str) -> String {
+    format!("oauth:{}", server_name)
+}
+
+/// 加密并绑定 aad，结果为 `v2:` + base64(nonce + 密文)
+fn encrypt(cipher: &ChaCha20Poly1305, aad: &str, plaintext: &str) -> Result<String> {
+    let nonce: [u8; NONCE_LEN] = rand::random();
+    let payload = Payload {
+        msg: plaintext.as_bytes(),
+        aad: aad.as_bytes(),
+    };
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), payload)
+        .map_err(|_| McpError::InternalError("Failed to encrypt secret".to_string()))?;
+
+    let mut payload = nonce.to_vec();
+    payload.extend_from_slice(&ciphertext);
+    Ok(format!(
+        "{}{}",
+        BOUND_PREFIX,
+        base64::engine::general_purpose::STANDARD.encode(payload)
+    ))
+}
+
+/// 解密 `encrypt` 的结果并校验 aad
+fn decrypt(
+    cipher: &ChaCha20Poly1305,
+    aad: &str,
+    encoded: &str,
+) -> std::result::Result<String, String> {
+    let encoded = encoded
+        .strip_prefix(BOUND_PREFIX)
+        .ok_or_else(|| "unsupported ciphertext format".to_string())?;
+    let payload = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("invalid encoding: {}", e))?;
+    if payload.len() < NONCE_LEN {
+        return Err("ciphertext is truncated".to_string());
+    }
+    let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: aad.as_bytes(),
+            },
+        )
+        .map_err(|_| "wrong master key or passphrase, or the value was moved".to_string())?;
+    String::from_utf8(plaintext).map_err(|e| format!("invalid UTF-8: {}", e))
+}
+
+/// 生成 `${secret:name}` 引用
+pub fn reference(name: &str) -> String {
+    format!("{}{}}}", REFERENCE_PREFIX, name)
+}
+
+/// 配置的 env 或 headers 中是否包含密钥引用
+pub fn has_references(config: &McpServerConfig) -> bool {
+    [&config.env, &config.headers]
+        .into_iter()
+        .flatten()
+        .any(|map| map.values().any(|value| value.contains(REFERENCE_PREFIX)))
+}
+
+/// 密钥名称只允许字母、数字、`_`、`-` 和 `.`
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+    {
+        return Err(McpError::ValidationError(format!(
+            "Invalid secret name '{}': use letters, digits, '_', '-' or '.'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// 名称看起来像凭据的 env / header
+pub fn is_sensitive_key(key: &str) -> bool {
+    let upper = key.to_ascii_uppercase().replace('-', "_");
+    SENSITIVE_MARKERS.iter().any(|marker| {
+        if *marker == "PAT" {
+            // 避免误伤 PATH 之类的名称
+            upper.split('_').any(|part| part == "PAT")
+        } else {
+            upper.contains(marker)
+        }
+    })
+}
+
+/// 隐藏敏感值，只有恰好是一个密钥引用的值保持原样
+pub fn redact(map: &HashMap<String, String>) -> HashMap<String, String> {
+    map.iter()
+        .map(|(key, value)| {
+            let value = if is_sensitive_key(key) && !value.is_empty() && !is_reference(value) {
+                REDACTED.to_string()
+            } else {
+                value.clone()
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+/// 编辑时回传的占位符恢复为已保存的原值
+pub fn restore_redacted(
+    map: &mut HashMap<String, String>,
+    saved: Option<&HashMap<String, String>>,
+) {
+    for (key, value) in map.iter_mut() {
+        if value == REDACTED {
+            if let Some(original) = saved.and_then(|saved| saved.get(key)) {
+                *value = original.clone();
+            }
+        }
+    }
+}
+
+/// 迁移时为服务的某个 env / header 生成密钥名称：两部分分别编码后以 `.` 连接，
+/// 不同的 (服务, 键) 不会得到相同名称
+fn secret_name_for(server: &str, key: &str) -> String {
+    format!(
+        "{}.{}",
+        crate::utils::encode_name(server),
+        crate::utils::encode_name(key)
+    )
+}
+
+/// 值是否恰好是一个 `${secret:name}` 引用，前后没有其他内容
+fn is_reference(value: &str) -> bool {
+    matches!(
+        next_reference(value),
+        Some((0, end, name)) if end == value.len() && validate_name(name).is_ok()
+    )
+}
+
+/// 查找下一个引用，返回 (起始位置, 结束位置, 名称)
+fn next_reference(value: &str) -> Option<(usize, usize, &str)> {
+    let start = value.find(REFERENCE_PREFIX)?;
+    let name_start = start + REFERENCE_PREFIX.len();
+    let len = value[name_start..].find('}')?;
+    Some((
+        start,
+        name_start + len + 1,
+        &value[name_start..name_start + len],
+    ))
+}
+
+fn key_dir() -> PathBuf {
+    crate::config::AppConfig::config_path()
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// 有口令时用 Argon2 派生主密钥，否则读取或创建密钥文件
+fn load_master_key(dir: &Path, passphrase: Option<&str>) -> Result<[u8; KEY_LEN]> {
+    if let Some(passphrase) = passphrase {
+        let salt_path = dir.join(SALT_FILE);
+        let salt = match std::fs::read(&salt_path) {
+            Ok(salt) => salt,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let salt: [u8; SALT_LEN] = rand::random();
+                write_private_file(&salt_path, &salt)?;
+                salt.to_vec()
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut key = [0u8; KEY_LEN];
+        argon2::Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| McpError::ConfigError(format!("Failed to derive master key: {}", e)))?;
+        return Ok(key);
+    }
+
+    let key_path = dir.join(KEY_FILE);
+    match std::fs::read_to_string(&key_path) {
+        Ok(content) => {
+            ensure_private(&key_path)?;
+            base64::engine::general_purpose::STANDARD
+                .decode(content.trim())
+                .ok()
+                .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+                .ok_or_else(|| {
+                    McpError::ConfigError(format!("Invalid master key in {}", key_path.display()))
+                })
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let key: [u8; KEY_LEN] = rand::random();
+            write_private_file(
+                &key_path,
+                base64::engine::general_purpose::STANDARD
+                    .encode(key)
+                    .as_bytes(),
+            )?;
+            tracing::info!(
+                "🔑 Created secret store master key at {}",
+                key_path.display()
+            );
+            Ok(key)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_private_file(path: &Path, contents: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)?;
+    Ok(())
+}
+
+/// 主密钥文件对其他用户可读时收紧为 0600
+fn ensure_private(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = std::fs::metadata(path)?.permissions().mode();
+        if mode & 0o077 != 0 {
+            tracing::warn!(
+                "Master key {} had permissions {:o}, restricting to 600",
+                path.display(),
+                mode & 0o777
+            );
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_sensitive_keys() {
+        assert!(is_sensitive_key("GITHUB_TOKEN"));
+        assert!(is_sensitive_key("Authorization"));
+        assert!(is_sensitive_key("x-api-key"));
+        assert!(is_sensitive_key("GITHUB_PAT"));
+        assert!(!is_sensitive_key("PATH"));
+        assert!(!is_sensitive_key("NODE_ENV"));
+    }
+
+    #[test]
+    fn test_redacts_plaintext_but_keeps_references() {
+        let map = HashMap::from([
+            ("GITHUB_TOKEN".to_string(), "ghp_123".to_string()),
+            ("API_KEY".to_string(), reference("api_key")),
+            ("LOG_LEVEL".to_string(), "debug".to_string()),
+            (
+                "AUTHORIZATION".to_string(),
+                "Bearer ${secret:gh}".to_string(),
+            ),
+            ("DB_PASSWORD".to_string(), "hunter2${HOME}".to_string()),
+            (
+                "ACCESS_KEY".to_string(),
+                "${secret:a}${secret:b}".to_string(),
+            ),
+            ("PRIVATE_KEY".to_string(), "${secret:bad name}".to_string()),
+        ]);
+        let mut redacted = redact(&map);
+        assert_eq!(redacted["GITHUB_TOKEN"], REDACTED);
+        assert_eq!(redacted["API_KEY"], "${secret:api_key}");
+        assert_eq!(redacted["LOG_LEVEL"], "debug");
+        assert_eq!(redacted["AUTHORIZATION"], REDACTED);
+        assert_eq!(redacted["DB_PASSWORD"], REDACTED);
+        assert_eq!(redacted["ACCESS_KEY"], REDACTED);
+        assert_eq!(redacted["PRIVATE_KEY"], REDACTED);
+
+        restore_redacted(&mut redacted, Some(&map));
+        assert_eq!(redacted, map);
+    }
+
+    #[test]
+    fn test_finds_references() {
+        assert_eq!(
+            next_reference("Bearer ${secret:gh.pat}!"),
+            Some((7, 23, "gh.pat"))
+        );
+        assert_eq!(next_reference("${secret:unterminated"), None);
+    }
+
+    #[test]
+    fn test_secret_names_do_not_collide() {
+        let name = secret_name_for("GitHub Server", "GITHUB_TOKEN");
+        assert_eq!(name, "GitHub_20Server.GITHUB_5fTOKEN");
+        assert!(validate_name(&name).is_ok());
+        assert_ne!(secret_name_for("a_b", "c"), secret_name_for("a", "b_c"));
+        assert_ne!(secret_name_for("a.b", "c"), secret_name_for("a", "b.c"));
+        assert_ne!(secret_name_for("a b", "c"), secret_name_for("a_b", "c"));
+    }
+
+    #[test]
+    fn test_encrypt_binds_name() {
+        let key: [u8; KEY_LEN] = rand::random();
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&key));
+
+        let sealed = encrypt(&cipher, &secret_aad("a"), "value").unwrap();
+        assert!(sealed.starts_with(BOUND_PREFIX));
+        assert_eq!(
+            decrypt(&cipher, &secret_aad("a"), &sealed).unwrap(),
+            "value"
+        );
+        assert!(decrypt(&cipher, &secret_aad("b"), &sealed).is_err());
+        assert!(decrypt(&cipher, &oauth_credential_aad("a"), &sealed).is_err());
+
+        let other = ChaCha20Poly1305::new(Key::from_slice(&[0u8; KEY_LEN]));
+        assert!(decrypt(&other, &secret_aad("a"), &sealed).is_err());
+
+        // 没有绑定名称的旧格式密文不再解密
+        let nonce = [7u8; NONCE_LEN];
+        let mut legacy = nonce.to_vec();
+        legacy.extend(
+            cipher
+                .encrypt(Nonce::from_slice(&nonce), b"old".as_ref())
+                .unwrap(),
+        );
+        let legacy = base64::engine::general_purpose::STANDARD.encode(legacy);
+        assert!(decrypt(&cipher, &secret_aad("x"), &legacy).is_err());
+        assert!(decrypt(&cipher, "", "v2:dG9vc2hvcnQ=").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_load_master_key_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("mcprouter-secrets-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+
+        let key = load_master_key(&dir, None).unwrap();
+        let key_path = dir.join(KEY_FILE);
+        assert_eq!(mode(&key_path), 0o600);
+        assert_eq!(load_master_key(&dir, None).unwrap(), key);
+
+        std::fs::set_permissions(&key_path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert_eq!(load_master_key(&dir, None).unwrap(), key);
+        assert_eq!(mode(&key_path), 0o600);
+
+        let derived = load_master_key(&dir, Some("passphrase")).unwrap();
+        assert_eq!(mode(&dir.join(SALT_FILE)), 0o600);
+        assert_ne!(derived, key);
+        assert_eq!(load_master_key(&dir, Some("passphrase")).unwrap(), derived);
+        assert_ne!(load_master_key(&dir, Some("other")).unwrap(), derived);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
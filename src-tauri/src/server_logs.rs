@@ -0,0 +1,337 @@
+use crate::error::{McpError, Result};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::task::JoinHandle;
+
+/// 每个服务在内存中保留的 stderr 行数
+const MAX_BUFFER_LINES: usize = 1000;
+/// 单个日志文件的最大大小（超过后轮转）
+const MAX_LOG_FILE_SIZE: u64 = 5 * 1024 * 1024;
+/// 保留的历史日志文件数量（name.log.1 ... name.log.N）
+const MAX_ROTATED_FILES: usize = 3;
+/// 每次启动时写入的会话分隔标记前缀
+const SESSION_MARKER: &str = "----- mcprouter: starting ";
+
+/// STDIO 服务 stderr 输出的收集器
+///
+/// 每个服务一个环形缓冲区，同时写入 `~/.mcprouter/logs/servers/<name>.log`，
+/// 文件按大小轮转。
+pub struct ServerLogManager {
+    log_dir: PathBuf,
+    buffers: RwLock<HashMap<String, VecDeque<String>>>,
+}
+
+impl Default for ServerLogManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ServerLogManager {
+    pub fn new() -> Self {
+        let home_dir = std::env::var("HOME")
+            .or_else(|_| std::env::var("USERPROFILE"))
+            .unwrap_or_else(|_| ".".to_string());
+        Self::with_log_dir(
+            PathBuf::from(home_dir)
+                .join(".mcprouter")
+                .join("logs")
+                .join("servers"),
+        )
+    }
+
+    /// 使用指定的日志目录
+    pub fn with_log_dir(log_dir: PathBuf) -> Self {
+        Self {
+            log_dir,
+            buffers: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 获取服务对应的日志文件路径，服务名经过编码，不同的服务名不会映射到同一个文件
+    pub fn log_file_path(&self, server_name: &str) -> PathBuf {
+        self.log_dir
+            .join(format!("{}.log", crate::utils::encode_name(server_name)))
+    }
+
+    /// 标记一次新的进程启动并持续读取子进程 stderr，直到管道关闭
+    ///
+    /// 后续 `session_tail` 只返回本次启动之后的输出。`program` 只用于会话标记，
+    /// 不应包含参数（参数中可能带有展开后的密钥）。返回的任务在 stderr
+    /// 关闭且所有内容写入日志文件后结束。
+    pub fn spawn_reader<R>(
+        self: &Arc<Self>,
+        server_name: &str,
+        program: &str,
+        stderr: R,
+    ) -> JoinHandle<()>
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let marker = format!(
+            "{}{} at {} -----",
+            SESSION_MARKER,
+            program,
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+        );
+        self.push_line(server_name, marker.clone());
+
+        let manager = Arc::clone(self);
+        let server_name = server_name.to_string();
+        tokio::spawn(async move {
+            let mut log_file = LogFile::new(manager.log_file_path(&server_name));
+            log_file.write_line(&marker).await;
+
+            let mut lines = BufReader::new(stderr).lines();
+            loop {
+                match lines.next_line().await {
+                    Ok(Some(line)) => {
+                        tracing::debug!("[{}] stderr: {}", server_name, line);
+                        log_file.write_line(&line).await;
+                        manager.push_line(&server_name, line);
+                    }
+                    Ok(None) => break,
+                    Err(e) => {
+                        tracing::debug!("Stopped reading stderr of '{}': {}", server_name, e);
+                        break;
+                    }
+                }
+            }
+            log_file.flush().await;
+            tracing::debug!("stderr stream of '{}' closed", server_name);
+        })
+    }
+
+    /// 获取最近的 `lines` 行输出
+    pub fn tail(&self, server_name: &str, lines: usize) -> Vec<String> {
+        let buffers = self.buffers.read().unwrap_or_else(|e| e.into_inner());
+        match buffers.get(server_name) {
+            Some(buffer) => {
+                let skip = buffer.len().saturating_sub(lines);
+                buffer.iter().skip(skip).cloned().collect()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// 获取最近一次启动以来的最后 `lines` 行输出（不含分隔标记）
+    pub fn session_tail(&self, server_name: &str, lines: usize) -> Vec<String> {
+        let buffers = self.buffers.read().unwrap_or_else(|e| e.into_inner());
+        let Some(buffer) = buffers.get(server_name) else {
+            return Vec::new();
+        };
+
+        let session: Vec<&String> = buffer
+            .iter()
+            .rev()
+            .take_while(|line| !line.starts_with(SESSION_MARKER))
+            .collect();
+
+        session.into_iter().take(lines).rev().cloned().collect()
+    }
+
+    /// 从日志文件中读取最后 `lines` 行（包含已轮转的内容之外的当前文件）
+    pub async fn read_log_file(&self, server_name: &str, lines: usize) -> Result<Vec<String>> {
+        let path = self.log_file_path(server_name);
+        let content = match tokio::fs::read(&path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(McpError::IoError(e)),
+        };
+
+        let content = String::from_utf8_lossy(&content);
+        let all_lines: Vec<&str> = content.lines().collect();
+        let skip = all_lines.len().saturating_sub(lines);
+        Ok(all_lines
+            .into_iter()
+            .skip(skip)
+            .map(|line| line.to_string())
+            .collect())
+    }
+
+    /// 清除服务的日志缓冲区（服务被删除时调用）
+    pub fn clear(&self, server_name: &str) {
+        let mut buffers = self.buffers.write().unwrap_or_else(|e| e.into_inner());
+        buffers.remove(server_name);
+    }
+
+    fn push_line(&self, server_name: &str, line: String) {
+        let mut buffers = self.buffers.write().unwrap_or_else(|e| e.into_inner());
+        let buffer = buffers
+            .entry(server_name.to_string())
+            .or_insert_with(|| VecDeque::with_capacity(MAX_BUFFER_LINES));
+        if buffer.len() >= MAX_BUFFER_LINES {
+            buffer.pop_front();
+        }
+        buffer.push_back(line);
+    }
+}
+
+/// 单个服务的日志文件，由 stderr 读取任务独占，整个进程生命周期内只打开一次
+struct LogFile {
+    path: PathBuf,
+    file: Option<tokio::fs::File>,
+    size: u64,
+}
+
+impl LogFile {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            file: None,
+            size: 0,
+        }
+    }
+
+    async fn write_line(&mut self, line: &str) {
+        if self.file.is_none() {
+            if let Err(e) = self.open().await {
+                tracing::warn!("Failed to open log file {}: {}", self.path.display(), e);
+                return;
+            }
+        }
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+
+        let data = format!("{}\n", line);
+        if let Err(e) = file.write_all(data.as_bytes()).await {
+            tracing::warn!("Failed to write log file {}: {}", self.path.display(), e);
+            // 下次写入时重新打开
+            self.file = None;
+            return;
+        }
+        self.size += data.len() as u64;
+        if self.size >= MAX_LOG_FILE_SIZE {
+            self.flush().await;
+            self.file = None;
+        }
+    }
+
+    async fn flush(&mut self) {
+        if let Some(file) = self.file.as_mut() {
+            if let Err(e) = file.flush().await {
+                tracing::warn!("Failed to flush log file {}: {}", self.path.display(), e);
+            }
+        }
+    }
+
+    /// 打开日志文件（追加写入），文件已超过大小限制时先轮转
+    async fn open(&mut self) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+
+        let size = match tokio::fs::metadata(&self.path).await {
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        };
+        self.size = if size >= MAX_LOG_FILE_SIZE {
+            rotate(&self.path).await?;
+            0
+        } else {
+            size
+        };
+
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        self.file = Some(file);
+        Ok(())
+    }
+}
+
+/// 将 name.log 轮转为 name.log.1，已有的历史文件依次后移，超出数量的删除
+async fn rotate(path: &Path) -> std::io::Result<()> {
+    let rotated = |index: usize| PathBuf::from(format!("{}.{}", path.display(), index));
+    match tokio::fs::remove_file(rotated(MAX_ROTATED_FILES)).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    for index in (1..MAX_ROTATED_FILES).rev() {
+        match tokio::fs::rename(rotated(index), rotated(index + 1)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    tokio::fs::rename(path, rotated(1)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_manager(name: &str) -> Arc<ServerLogManager> {
+        let dir = std::env::temp_dir().join(format!(
+            "mcprouter-server-logs-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        Arc::new(ServerLogManager::with_log_dir(dir))
+    }
+
+    #[test]
+    fn test_log_file_path_is_unambiguous() {
+        let manager = test_manager("path");
+        let slash = manager.log_file_path("a/b");
+        let underscore = manager.log_file_path("a_b");
+        assert_ne!(slash, underscore);
+        assert!(slash.ends_with("a_2fb.log"));
+        assert!(underscore.ends_with("a_5fb.log"));
+        assert_ne!(manager.log_file_path("a_2fb"), slash);
+        assert!(manager
+            .log_file_path("../x")
+            .starts_with(manager.log_file_path("").parent().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_reader_writes_session_to_file() {
+        let manager = test_manager("reader");
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        let handle = manager.spawn_reader("demo", "/usr/bin/demo", reader);
+        writer.write_all(b"first\nsecond\n").await.unwrap();
+        drop(writer);
+        handle.await.unwrap();
+
+        assert_eq!(manager.session_tail("demo", 10), vec!["first", "second"]);
+        let lines = manager.read_log_file("demo", 10).await.unwrap();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("----- mcprouter: starting /usr/bin/demo at "));
+        assert_eq!(&lines[1..], ["first", "second"]);
+
+        // 新的会话只返回本次启动之后的输出
+        let (writer, reader) = tokio::io::duplex(1024);
+        let handle = manager.spawn_reader("demo", "/usr/bin/demo", reader);
+        drop(writer);
+        handle.await.unwrap();
+        assert!(manager.session_tail("demo", 10).is_empty());
+        assert_eq!(manager.tail("demo", 10).len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_rotate_keeps_limited_history() {
+        let manager = test_manager("rotate");
+        let path = manager.log_file_path("demo");
+        tokio::fs::create_dir_all(path.parent().unwrap())
+            .await
+            .unwrap();
+        for index in 0..=MAX_ROTATED_FILES + 1 {
+            tokio::fs::write(&path, index.to_string()).await.unwrap();
+            rotate(&path).await.unwrap();
+        }
+
+        assert!(!path.exists());
+        let newest = format!("{}.1", path.display());
+        assert_eq!(
+            tokio::fs::read_to_string(&newest).await.unwrap(),
+            (MAX_ROTATED_FILES + 1).to_string()
+        );
+        let overflow = format!("{}.{}", path.display(), MAX_ROTATED_FILES + 1);
+        assert!(!Path::new(&overflow).exists());
+    }
+}
@@ -1,33 +1,164 @@
 use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
 use crate::error::Result;
+use serde::Serialize;
+use tokio::sync::Mutex;
 
-/// 简化的 Shell 环境加载器，自动加载登录环境变量
+/// 默认缓存时间（秒）
+pub const DEFAULT_SHELL_ENV_TTL_SECS: u64 = 300;
+
+/// 登录 Shell 启动超时，防止 rc 文件中的交互命令卡住连接
+const SHELL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// env 输出前的标记，用于忽略 rc 文件打印到 stdout 的内容
+const OUTPUT_MARKER: &str = "__MCPROUTER_ENV_START__";
+
+/// 已加载的 Shell 环境
+#[derive(Debug, Clone, Serialize)]
+pub struct ShellEnvironmentSnapshot {
+    /// 使用的 Shell 路径
+    pub shell: String,
+    pub variables: HashMap<String, String>,
+    pub loaded_at: chrono::DateTime<chrono::Utc>,
+    /// 加载耗时（毫秒）
+    pub load_duration_ms: u64,
+    pub ttl_seconds: u64,
+}
+
+impl ShellEnvironmentSnapshot {
+    /// 返回给前端展示的副本：只保留基础变量的值，其余变量只显示名称
+    pub fn redacted(mut self) -> Self {
+        for (key, value) in self.variables.iter_mut() {
+            if !DISPLAYED_VARS.contains(&key.as_str()) {
+                *value = crate::secrets::REDACTED.to_string();
+            }
+        }
+        self
+    }
+}
+
+/// 展示时保留值的变量，其余变量的值可能包含凭据
+const DISPLAYED_VARS: &[&str] = &[
+    "PATH", "HOME", "USER", "LOGNAME", "SHELL", "LANG", "LC_ALL", "LC_CTYPE", "TZ", "TMPDIR",
+    "TERM",
+];
+
+struct CachedEnvironment {
+    snapshot: ShellEnvironmentSnapshot,
+    loaded: Instant,
+}
+
+static CACHE: LazyLock<Mutex<Option<CachedEnvironment>>> = LazyLock::new(|| Mutex::new(None));
+
+/// 登录 Shell 环境加载器，结果按 TTL 缓存
 pub struct ShellEnvironment;
 
 impl ShellEnvironment {
-    /// 加载系统环境变量（自动检测平台）
+    /// 加载系统环境变量（自动检测平台），缓存未过期时直接返回
     pub async fn load_environment() -> Result<HashMap<String, String>> {
+        Ok(Self::snapshot(false).await?.variables)
+    }
+
+    /// 获取 Shell 环境快照，`refresh` 为 true 时忽略缓存重新加载
+    pub async fn snapshot(refresh: bool) -> Result<ShellEnvironmentSnapshot> {
+        let settings = crate::config::CONFIG_SERVICE.current().settings.clone();
+        let ttl = settings
+            .as_ref()
+            .and_then(|s| s.shell_env_ttl)
+            .unwrap_or(DEFAULT_SHELL_ENV_TTL_SECS);
+        let shell = Self::detect_shell(settings.as_ref().and_then(|s| s.shell_path.as_deref()));
+
+        // 持有锁加载，并发的首次连接只会启动一次 Shell
+        let mut cache = CACHE.lock().await;
+        if !refresh {
+            if let Some(cached) = cache.as_ref() {
+                if cached.snapshot.shell == shell
+                    && cached.loaded.elapsed() < Duration::from_secs(ttl)
+                {
+                    return Ok(cached.snapshot.clone());
+                }
+            }
+        }
+
+        let started = Instant::now();
+        let variables = Self::load_from_shell(&shell).await?;
+        let snapshot = ShellEnvironmentSnapshot {
+            shell,
+            variables,
+            loaded_at: chrono::Utc::now(),
+            load_duration_ms: started.elapsed().as_millis() as u64,
+            ttl_seconds: ttl,
+        };
+        tracing::info!(
+            "🐚 Loaded {} environment variables from {} in {} ms",
+            snapshot.variables.len(),
+            snapshot.shell,
+            snapshot.load_duration_ms
+        );
+
+        *cache = Some(CachedEnvironment {
+            snapshot: snapshot.clone(),
+            loaded: Instant::now(),
+        });
+        Ok(snapshot)
+    }
+
+    /// 选择 Shell：设置中的路径 > $SHELL > zsh > bash
+    fn detect_shell(configured: Option<&str>) -> String {
+        if cfg!(windows) {
+            return "cmd.exe".to_string();
+        }
+        if let Some(shell) = configured.map(str::trim).filter(|s| !s.is_empty()) {
+            return shell.to_string();
+        }
+        if let Ok(shell) = std::env::var("SHELL") {
+            if !shell.is_empty() {
+                return shell;
+            }
+        }
+        if std::path::Path::new("/bin/zsh").exists() {
+            "zsh".to_string()
+        } else {
+            "bash".to_string()
+        }
+    }
+
+    async fn load_from_shell(shell: &str) -> Result<HashMap<String, String>> {
+        use std::process::Stdio;
         use tokio::process::Command;
 
-        let output = if cfg!(windows) {
+        let mut command = if cfg!(windows) {
             // Windows: 使用 cmd.exe /c set
-            Command::new("cmd.exe")
-                .args(["/c", "set"])
-                .output()
-                .await?
+            let mut command = Command::new(shell);
+            command.args(["/c", "set"]);
+            command
         } else {
-            // macOS/Linux: 优先使用 zsh，回退到 bash
-            let shell = if std::path::Path::new("/bin/zsh").exists() {
-                "zsh"
-            } else {
-                "bash"
-            };
-
-            Command::new(shell)
-                .args(["-ilc", "env"])
-                .output()
-                .await?
+            // 交互式登录 Shell 才会加载完整的 rc 配置；fish 与 POSIX Shell 都支持 -l -i -c
+            let mut command = Command::new(shell);
+            command.args([
+                "-l",
+                "-i",
+                "-c",
+                &format!("printf '%s' {}; env -0", OUTPUT_MARKER),
+            ]);
+            command
         };
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+
+        let output = tokio::time::timeout(SHELL_TIMEOUT, command.output())
+            .await
+            .map_err(|_| {
+                crate::error::McpError::ShellError(format!(
+                    "Timed out after {}s loading environment from {}",
+                    SHELL_TIMEOUT.as_secs(),
+                    shell
+                ))
+            })??;
 
         if !output.status.success() {
             return Err(crate::error::McpError::ShellError(
@@ -37,13 +168,11 @@ impl ShellEnvironment {
         }
 
         let env_output = String::from_utf8_lossy(&output.stdout);
-        let mut env_vars = HashMap::new();
-
-        for line in env_output.lines() {
-            if let Some((key, value)) = line.split_once('=') {
-                env_vars.insert(key.to_string(), value.to_string());
-            }
-        }
+        let mut env_vars = if cfg!(windows) {
+            parse_lines(&env_output)
+        } else {
+            parse_nul_separated(&env_output)
+        };
 
         // 确保管理工具目录在 PATH 中
         if let Ok(home_dir) = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")) {
@@ -62,10 +191,51 @@ impl ShellEnvironment {
     }
 }
 
+/// 解析 `env -0` 输出，值中可以包含换行
+fn parse_nul_separated(output: &str) -> HashMap<String, String> {
+    let output = match output.find(OUTPUT_MARKER) {
+        Some(index) => &output[index + OUTPUT_MARKER.len()..],
+        None => output,
+    };
+    output
+        .split('\0')
+        .filter_map(|entry| entry.split_once('='))
+        .filter(|(key, _)| !key.is_empty())
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// 解析 `set` 输出（每行一个变量）
+fn parse_lines(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_redacted_keeps_only_displayed_values() {
+        let snapshot = ShellEnvironmentSnapshot {
+            shell: "/bin/zsh".to_string(),
+            variables: HashMap::from([
+                ("PATH".to_string(), "/usr/bin".to_string()),
+                ("DATABASE_URL".to_string(), "postgres://u:p@db".to_string()),
+            ]),
+            loaded_at: chrono::Utc::now(),
+            load_duration_ms: 0,
+            ttl_seconds: 0,
+        }
+        .redacted();
+
+        assert_eq!(snapshot.variables["PATH"], "/usr/bin");
+        assert_eq!(snapshot.variables["DATABASE_URL"], crate::secrets::REDACTED);
+    }
+
     #[tokio::test]
     async fn test_load_environment() {
         // Test that we can load environment without panicking
@@ -108,4 +278,18 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_nul_separated_multiline_values() {
+        let output = format!(
+            "Welcome!\n{}FOO=bar\0MULTI=line1\nline2\0EMPTY=\0EQ=a=b\0",
+            OUTPUT_MARKER
+        );
+        let env_vars = parse_nul_separated(&output);
+        assert_eq!(env_vars.get("FOO").map(String::as_str), Some("bar"));
+        assert_eq!(env_vars.get("MULTI").map(String::as_str), Some("line1\nline2"));
+        assert_eq!(env_vars.get("EMPTY").map(String::as_str), Some(""));
+        assert_eq!(env_vars.get("EQ").map(String::as_str), Some("a=b"));
+        assert!(!env_vars.contains_key("Welcome!\nFOO"));
+    }
+}
@@ -159,6 +159,46 @@ impl Storage {
                 .as_ref()
                 .map(|env| serde_json::to_string(env).unwrap_or_default())),
             enabled: Set(config.enabled),
+            request_timeout: Set(config
+                .request_timeout
+                .map(|v| i64::try_from(v).unwrap_or(i64::MAX))),
+            connect_timeout: Set(config
+                .connect_timeout
+                .map(|v| i64::try_from(v).unwrap_or(i64::MAX))),
+            max_concurrent_calls: Set(config
+                .max_concurrent_calls
+                .map(|v| i32::try_from(v).unwrap_or(i32::MAX))),
+            lifecycle: Set(Some(config.lifecycle.to_string())),
+            idle_timeout: Set(config
+                .idle_timeout
+                .map(|v| i64::try_from(v).unwrap_or(i64::MAX))),
+            restart_policy: Set(Some(config.restart_policy.to_string())),
+            max_restarts: Set(config
+                .max_restarts
+                .map(|v| i32::try_from(v).unwrap_or(i32::MAX))),
+            memory_limit_mb: Set(config
+                .memory_limit_mb
+                .map(|v| i64::try_from(v).unwrap_or(i64::MAX))),
+            sandbox: Set(config
+                .sandbox
+                .as_ref()
+                .map(|sandbox| serde_json::to_string(sandbox).unwrap_or_default())),
+            cwd: Set(config.cwd.clone()),
+            env_inheritance: Set(Some(config.env_inheritance.to_string())),
+            env_allowlist: Set(config
+                .env_allowlist
+                .as_ref()
+                .map(|allowlist| serde_json::to_string(allowlist).unwrap_or_default())),
+            container: Set(config
+                .container
+                .as_ref()
+                .map(|container| serde_json::to_string(container).unwrap_or_default())),
+            runtime: Set(Some(config.runtime.to_string())),
+            runtime_version: Set(config.runtime_version.clone()),
+            credential_helper: Set(config
+                .credential_helper
+                .as_ref()
+                .map(|helper| serde_json::to_string(helper).unwrap_or_default())),
             created_at: Set(now.into()),
             updated_at: Set(now.into()),
         };
@@ -221,6 +261,46 @@ impl Storage {
             .as_ref()
             .map(|env| serde_json::to_string(env).unwrap_or_default()));
         active_server.enabled = Set(config.enabled);
+        active_server.request_timeout = Set(config
+            .request_timeout
+            .map(|v| i64::try_from(v).unwrap_or(i64::MAX)));
+        active_server.connect_timeout = Set(config
+            .connect_timeout
+            .map(|v| i64::try_from(v).unwrap_or(i64::MAX)));
+        active_server.max_concurrent_calls = Set(config
+            .max_concurrent_calls
+            .map(|v| i32::try_from(v).unwrap_or(i32::MAX)));
+        active_server.lifecycle = Set(Some(config.lifecycle.to_string()));
+        active_server.idle_timeout = Set(config
+            .idle_timeout
+            .map(|v| i64::try_from(v).unwrap_or(i64::MAX)));
+        active_server.restart_policy = Set(Some(config.restart_policy.to_string()));
+        active_server.max_restarts = Set(config
+            .max_restarts
+            .map(|v| i32::try_from(v).unwrap_or(i32::MAX)));
+        active_server.memory_limit_mb = Set(config
+            .memory_limit_mb
+            .map(|v| i64::try_from(v).unwrap_or(i64::MAX)));
+        active_server.sandbox = Set(config
+            .sandbox
+            .as_ref()
+            .map(|sandbox| serde_json::to_string(sandbox).unwrap_or_default()));
+        active_server.cwd = Set(config.cwd.clone());
+        active_server.env_inheritance = Set(Some(config.env_inheritance.to_string()));
+        active_server.env_allowlist = Set(config
+            .env_allowlist
+            .as_ref()
+            .map(|allowlist| serde_json::to_string(allowlist).unwrap_or_default()));
+        active_server.container = Set(config
+            .container
+            .as_ref()
+            .map(|container| serde_json::to_string(container).unwrap_or_default()));
+        active_server.runtime = Set(Some(config.runtime.to_string()));
+        active_server.runtime_version = Set(config.runtime_version.clone());
+        active_server.credential_helper = Set(config
+            .credential_helper
+            .as_ref()
+            .map(|helper| serde_json::to_string(helper).unwrap_or_default()));
         active_server.updated_at = Set(now.into());
 
         active_server
@@ -257,6 +337,12 @@ impl Storage {
             .await
             .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
 
+        PendingToolFlag::delete_many()
+            .filter(PendingToolFlagColumn::ServerId.eq(&server_id))
+            .exec(&txn)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
+
         McpResource::delete_many()
             .filter(McpResourceColumn::ServerId.eq(&server_id))
             .exec(&txn)
@@ -301,6 +387,121 @@ impl Storage {
         Ok(tools)
     }
 
+    /// 服务器是否已有同步过的工具、资源或提示词
+    pub async fn has_server_manifests(&self, server_id: &str) -> Result<bool, StorageError> {
+        let map_err = |e: sea_orm::DbErr| StorageError::Database(format!("Failed to count: {}", e));
+        let tools = McpTool::find()
+            .filter(McpToolColumn::ServerId.eq(server_id))
+            .count(&self.db)
+            .await
+            .map_err(map_err)?;
+        if tools > 0 {
+            return Ok(true);
+        }
+        let resources = McpResource::find()
+            .filter(McpResourceColumn::ServerId.eq(server_id))
+            .count(&self.db)
+            .await
+            .map_err(map_err)?;
+        if resources > 0 {
+            return Ok(true);
+        }
+        let prompts = McpPrompt::find()
+            .filter(McpPromptColumn::ServerId.eq(server_id))
+            .count(&self.db)
+            .await
+            .map_err(map_err)?;
+        Ok(prompts > 0)
+    }
+
+    /// 获取服务器所有工具的启用状态（包括已禁用的工具）
+    pub async fn list_server_tool_flags(
+        &self,
+        server_id: &str,
+    ) -> Result<Vec<(String, bool)>, StorageError> {
+        let tools = McpTool::find()
+            .filter(McpToolColumn::ServerId.eq(server_id))
+            .order_by_asc(McpToolColumn::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))?;
+
+        Ok(tools.into_iter().map(|t| (t.name, t.enabled)).collect())
+    }
+
+    /// 设置工具启用状态，工具尚未同步时保存为待应用状态，同步到该工具时再应用
+    pub async fn set_tool_enabled(
+        &self,
+        server_id: &str,
+        tool_name: &str,
+        enabled: bool,
+    ) -> Result<(), StorageError> {
+        let now = chrono::Utc::now();
+        let existing = McpTool::find()
+            .filter(McpToolColumn::ServerId.eq(server_id))
+            .filter(McpToolColumn::Name.eq(tool_name))
+            .one(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))?;
+
+        if let Some(tool) = existing {
+            let mut active: mcp_tool::ActiveModel = tool.into();
+            active.enabled = Set(enabled);
+            active.updated_at = Set(now.into());
+            active
+                .update(&self.db)
+                .await
+                .map_err(|e| StorageError::Database(format!("Failed to update: {}", e)))?;
+            return Ok(());
+        }
+
+        let pending = PendingToolFlag::find_by_id((server_id.to_string(), tool_name.to_string()))
+            .one(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))?;
+        match pending {
+            Some(flag) => {
+                let mut active: pending_tool_flag::ActiveModel = flag.into();
+                active.enabled = Set(enabled);
+                active.updated_at = Set(now.into());
+                active
+                    .update(&self.db)
+                    .await
+                    .map_err(|e| StorageError::Database(format!("Failed to update: {}", e)))?;
+            }
+            None => {
+                let flag = pending_tool_flag::ActiveModel {
+                    server_id: Set(server_id.to_string()),
+                    tool_name: Set(tool_name.to_string()),
+                    enabled: Set(enabled),
+                    updated_at: Set(now.into()),
+                };
+                flag.insert(&self.db)
+                    .await
+                    .map_err(|e| StorageError::Database(format!("Failed to insert: {}", e)))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 获取服务器尚未同步的工具的启用状态
+    pub async fn list_pending_tool_flags(
+        &self,
+        server_id: &str,
+    ) -> Result<Vec<(String, bool)>, StorageError> {
+        let flags = PendingToolFlag::find()
+            .filter(PendingToolFlagColumn::ServerId.eq(server_id))
+            .order_by_asc(PendingToolFlagColumn::ToolName)
+            .all(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))?;
+
+        Ok(flags
+            .into_iter()
+            .map(|f| (f.tool_name, f.enabled))
+            .collect())
+    }
+
     /// 批量获取多个服务器的工具
     pub async fn list_tools_by_server_ids(
         &self,
@@ -345,7 +546,9 @@ impl Storage {
             .filter(McpResourceColumn::Enabled.eq(true))
             .all(&self.db)
             .await
-            .map_err(|e| StorageError::Database(format!("Failed to batch query resources: {}", e)))?;
+            .map_err(|e| {
+                StorageError::Database(format!("Failed to batch query resources: {}", e))
+            })?;
 
         // 返回 (server_id, resource) 元组，方便后续处理
         Ok(resources
@@ -388,17 +591,35 @@ impl Storage {
             .collect())
     }
 
-    /// 批量更新服务器工具
+    /// 批量更新服务器工具，已同步工具的待应用启用状态随之删除
     pub async fn upsert_server_tools(
         &self,
         server_id: &str,
         tools: Vec<mcp_tool::ActiveModel>,
     ) -> Result<(), StorageError> {
+        let tool_names: Vec<String> = tools
+            .iter()
+            .filter_map(|tool| match &tool.name {
+                sea_orm::ActiveValue::Set(name) | sea_orm::ActiveValue::Unchanged(name) => {
+                    Some(name.clone())
+                }
+                sea_orm::ActiveValue::NotSet => None,
+            })
+            .collect();
         let txn =
             self.db.begin().await.map_err(|e| {
                 StorageError::Database(format!("Failed to begin transaction: {}", e))
             })?;
 
+        PendingToolFlag::delete_many()
+            .filter(PendingToolFlagColumn::ServerId.eq(server_id))
+            .filter(PendingToolFlagColumn::ToolName.is_in(tool_names))
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                StorageError::Database(format!("Failed to delete pending tool flags: {}", e))
+            })?;
+
         // First, delete existing tools for this server
         crate::entities::mcp_tool::Entity::delete_many()
             .filter(crate::entities::mcp_tool::Column::ServerId.eq(server_id))
@@ -607,34 +828,12 @@ impl Storage {
     ) -> Result<Option<McpServerConfig>, StorageError> {
         if let Some(server) = self.get_mcp_server(server_name).await? {
             // 转换为配置
-            let args = server
-                .args
-                .and_then(|args| serde_json::from_str(&args).ok())
-                .unwrap_or_default();
-            let headers = server
-                .headers
-                .and_then(|headers| serde_json::from_str(&headers).ok())
-                .unwrap_or_default();
-            let env = server
-                .env
-                .and_then(|env| serde_json::from_str(&env).ok())
-                .unwrap_or_default();
             let transport = server
                 .server_type
                 .parse::<ServiceTransport>()
                 .map_err(|e| StorageError::InvalidData(format!("Invalid transport type: {}", e)))?;
 
-            let config = McpServerConfig {
-                name: server.name,
-                description: server.description,
-                command: server.command,
-                args: Some(args),
-                url: server.url,
-                headers: Some(headers),
-                env: Some(env),
-                transport,
-                enabled: server.enabled,
-            };
+            let config = server.to_server_config(transport)?;
             Ok(Some(config))
         } else {
             Ok(None)
@@ -674,10 +873,164 @@ impl Storage {
 
     /// 创建新的 Token
     pub async fn create_token(&self, token: &Token) -> Result<String, StorageError> {
-        use sea_orm::Set;
+        let token_id = token.id.clone();
+        let models = vec![Self::token_active_model(token)];
+
+        token::Entity::insert_many(models)
+            .exec(&self.db)
+            .await
+            .map_err(|e| {
+                if e.to_string().contains("UNIQUE constraint failed") {
+                    StorageError::AlreadyExists(format!("Token '{}' already exists", token.name))
+                } else {
+                    StorageError::Database(format!("Failed to create: {}", e))
+                }
+            })?;
+
+        Ok(token_id)
+    }
+
+    /// 在一个事务中创建 Token 及其权限，并删除被它替换的 Token（连同权限和 OAuth 授权）
+    ///
+    /// 任何一步失败时不做任何修改，被替换的 Token 保持不变。
+    pub async fn replace_tokens(
+        &self,
+        replaced_ids: &[String],
+        token: &Token,
+        permissions: &[(String, String)],
+    ) -> Result<(), StorageError> {
+        let txn =
+            self.db.begin().await.map_err(|e| {
+                StorageError::Database(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        Self::delete_tokens_in(&txn, replaced_ids).await?;
+
+        token::Entity::insert_many(vec![Self::token_active_model(token)])
+            .exec(&txn)
+            .await
+            .map_err(|e| {
+                if e.to_string().contains("UNIQUE constraint failed") {
+                    StorageError::AlreadyExists(format!("Token '{}' already exists", token.name))
+                } else {
+                    StorageError::Database(format!("Failed to create: {}", e))
+                }
+            })?;
+
+        Self::insert_permissions_in(&txn, &token.id, permissions).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// 在一个事务中删除 Token 及其权限和 OAuth 授权
+    pub async fn delete_tokens(&self, ids: &[String]) -> Result<(), StorageError> {
+        let txn =
+            self.db.begin().await.map_err(|e| {
+                StorageError::Database(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        Self::delete_tokens_in(&txn, ids).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// 在一个事务中用给定的权限替换 Token 的全部权限
+    pub async fn replace_token_permissions(
+        &self,
+        token_id: &str,
+        permissions: &[(String, String)],
+    ) -> Result<(), StorageError> {
+        let txn =
+            self.db.begin().await.map_err(|e| {
+                StorageError::Database(format!("Failed to begin transaction: {}", e))
+            })?;
+
+        permission::Entity::delete_many()
+            .filter(permission::Column::TokenId.eq(token_id))
+            .exec(&txn)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
+        Self::insert_permissions_in(&txn, token_id, permissions).await?;
+
+        txn.commit()
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to commit transaction: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn delete_tokens_in<C: ConnectionTrait>(
+        db: &C,
+        ids: &[String],
+    ) -> Result<(), StorageError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        token::Entity::delete_many()
+            .filter(token::Column::Id.is_in(ids.iter().cloned()))
+            .exec(db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
+        permission::Entity::delete_many()
+            .filter(permission::Column::TokenId.is_in(ids.iter().cloned()))
+            .exec(db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
+        oauth_grant::Entity::delete_many()
+            .filter(oauth_grant::Column::TokenId.is_in(ids.iter().cloned()))
+            .exec(db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
+        Ok(())
+    }
+
+    /// 写入去重后的权限
+    async fn insert_permissions_in<C: ConnectionTrait>(
+        db: &C,
+        token_id: &str,
+        permissions: &[(String, String)],
+    ) -> Result<(), StorageError> {
+        let mut unique: Vec<&(String, String)> = Vec::new();
+        for permission in permissions {
+            if !unique.contains(&permission) {
+                unique.push(permission);
+            }
+        }
+        if unique.is_empty() {
+            return Ok(());
+        }
+        let now = chrono::Utc::now();
+        let models =
+            unique
+                .into_iter()
+                .map(|(resource_type, resource_path)| permission::ActiveModel {
+                    id: Set(Uuid::now_v7().to_string()),
+                    token_id: Set(token_id.to_string()),
+                    resource_type: Set(resource_type.clone()),
+                    resource_path: Set(resource_path.clone()),
+                    allowed: Set(true),
+                    created_at: Set(now.into()),
+                    updated_at: Set(now.into()),
+                });
+        permission::Entity::insert_many(models)
+            .exec(db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to add: {}", e)))?;
+        Ok(())
+    }
+
+    fn token_active_model(token: &Token) -> token::ActiveModel {
         let now = chrono::Utc::now();
 
-        let token_model = token::ActiveModel {
+        token::ActiveModel {
             id: Set(token.id.clone()),
             name: Set(token.name.clone()),
             token: Set(token.value.clone()),
@@ -696,23 +1049,7 @@ impl Storage {
                     .unwrap_or(now)
                     .into()
             })),
-        };
-
-        let token_id = token.id.clone();
-        let models = vec![token_model];
-
-        token::Entity::insert_many(models)
-            .exec(&self.db)
-            .await
-            .map_err(|e| {
-                if e.to_string().contains("UNIQUE constraint failed") {
-                    StorageError::AlreadyExists(format!("Token '{}' already exists", token.name))
-                } else {
-                    StorageError::Database(format!("Failed to create: {}", e))
-                }
-            })?;
-
-        Ok(token_id)
+        }
     }
 
     /// 根据 ID 获取 Token
@@ -794,6 +1131,24 @@ impl Storage {
             .await
             .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
 
+        // 通过 OAuth 授予的访问令牌随 Token 一起失效
+        oauth_grant::Entity::delete_many()
+            .filter(oauth_grant::Column::TokenId.eq(token_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// 删除 Token 的所有权限
+    pub async fn delete_token_permissions(&self, token_id: &str) -> Result<(), StorageError> {
+        permission::Entity::delete_many()
+            .filter(permission::Column::TokenId.eq(token_id))
+            .exec(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
+
         Ok(())
     }
 
@@ -823,6 +1178,38 @@ impl Storage {
         Ok(())
     }
 
+    /// 更新 Token 的描述、启用状态和过期时间
+    pub async fn update_token_metadata(
+        &self,
+        token_id: &str,
+        description: Option<String>,
+        enabled: bool,
+        expires_at: Option<u64>,
+    ) -> Result<(), StorageError> {
+        use sea_orm::Set;
+
+        let token_entity = token::Entity::find_by_id(token_id.to_string())
+            .one(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))?
+            .ok_or_else(|| StorageError::NotFound(format!("Token {} not found", token_id)))?;
+
+        let mut active_model: token::ActiveModel = token_entity.into();
+        active_model.description = Set(description);
+        active_model.enabled = Set(enabled);
+        active_model.expires_at = Set(expires_at
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts as i64, 0))
+            .map(Into::into));
+        active_model.updated_at = Set(chrono::Utc::now().into());
+
+        active_model
+            .update(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to update: {}", e)))?;
+
+        Ok(())
+    }
+
     // ============================================================================
     // 权限管理方法
     // ============================================================================
@@ -1015,6 +1402,333 @@ impl Storage {
         Ok(permission.is_some())
     }
 
+    // ============================================================================
+    // 密钥管理方法
+    // ============================================================================
+
+    /// 获取所有密钥（按名称排序）
+    pub async fn list_secrets(&self) -> Result<Vec<secret::Model>, StorageError> {
+        secret::Entity::find()
+            .order_by_asc(secret::Column::Name)
+            .all(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))
+    }
+
+    /// 根据名称获取密钥
+    pub async fn get_secret(&self, name: &str) -> Result<Option<secret::Model>, StorageError> {
+        secret::Entity::find_by_id(name.to_string())
+            .one(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))
+    }
+
+    /// 新增或覆盖密钥
+    pub async fn upsert_secret(&self, name: &str, ciphertext: &str) -> Result<(), StorageError> {
+        let now = chrono::Utc::now();
+
+        match self.get_secret(name).await? {
+            Some(existing) => {
+                let mut active_model: secret::ActiveModel = existing.into();
+                active_model.ciphertext = Set(ciphertext.to_string());
+                active_model.updated_at = Set(now.into());
+                active_model
+                    .update(&self.db)
+                    .await
+                    .map_err(|e| StorageError::Database(format!("Failed to update: {}", e)))?;
+            }
+            None => {
+                let active_model = secret::ActiveModel {
+                    name: Set(name.to_string()),
+                    ciphertext: Set(ciphertext.to_string()),
+                    created_at: Set(now.into()),
+                    updated_at: Set(now.into()),
+                };
+                active_model
+                    .insert(&self.db)
+                    .await
+                    .map_err(|e| StorageError::Database(format!("Failed to insert: {}", e)))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 删除密钥
+    pub async fn delete_secret(&self, name: &str) -> Result<(), StorageError> {
+        let result = secret::Entity::delete_by_id(name.to_string())
+            .exec(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
+
+        if result.rows_affected == 0 {
+            return Err(StorageError::NotFound(format!("Secret {} not found", name)));
+        }
+
+        Ok(())
+    }
+
+    // ============================================================================
+    // 下游 OAuth 凭据方法
+    // ============================================================================
+
+    /// 获取服务的 OAuth 凭据
+    pub async fn get_oauth_credential(
+        &self,
+        server_name: &str,
+    ) -> Result<Option<oauth_credential::Model>, StorageError> {
+        oauth_credential::Entity::find_by_id(server_name.to_string())
+            .one(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))
+    }
+
+    /// 新增或覆盖服务的 OAuth 凭据
+    pub async fn upsert_oauth_credential(
+        &self,
+        server_name: &str,
+        ciphertext: &str,
+    ) -> Result<(), StorageError> {
+        let now = chrono::Utc::now();
+
+        match self.get_oauth_credential(server_name).await? {
+            Some(existing) => {
+                let mut active_model: oauth_credential::ActiveModel = existing.into();
+                active_model.ciphertext = Set(ciphertext.to_string());
+                active_model.updated_at = Set(now.into());
+                active_model
+                    .update(&self.db)
+                    .await
+                    .map_err(|e| StorageError::Database(format!("Failed to update: {}", e)))?;
+            }
+            None => {
+                let active_model = oauth_credential::ActiveModel {
+                    server_name: Set(server_name.to_string()),
+                    ciphertext: Set(ciphertext.to_string()),
+                    created_at: Set(now.into()),
+                    updated_at: Set(now.into()),
+                };
+                active_model
+                    .insert(&self.db)
+                    .await
+                    .map_err(|e| StorageError::Database(format!("Failed to insert: {}", e)))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 删除服务的 OAuth 凭据，不存在时不报错
+    pub async fn delete_oauth_credential(&self, server_name: &str) -> Result<(), StorageError> {
+        oauth_credential::Entity::delete_by_id(server_name.to_string())
+            .exec(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
+        Ok(())
+    }
+
+    // ============================================================================
+    // OAuth 授权服务器方法
+    // ============================================================================
+
+    /// 注册 OAuth 客户端
+    pub async fn insert_oauth_client(
+        &self,
+        client_id: &str,
+        client_name: &str,
+        redirect_uris: &[String],
+    ) -> Result<(), StorageError> {
+        let redirect_uris = serde_json::to_string(redirect_uris)?;
+
+        let active_model = oauth_client::ActiveModel {
+            client_id: Set(client_id.to_string()),
+            client_name: Set(client_name.to_string()),
+            redirect_uris: Set(redirect_uris),
+            created_at: Set(chrono::Utc::now().into()),
+        };
+        active_model
+            .insert(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to insert: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// 根据 ID 获取 OAuth 客户端
+    pub async fn get_oauth_client(
+        &self,
+        client_id: &str,
+    ) -> Result<Option<oauth_client::Model>, StorageError> {
+        oauth_client::Entity::find_by_id(client_id.to_string())
+            .one(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))
+    }
+
+    /// 获取所有 OAuth 客户端
+    pub async fn list_oauth_clients(&self) -> Result<Vec<oauth_client::Model>, StorageError> {
+        oauth_client::Entity::find()
+            .all(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))
+    }
+
+    /// 统计已注册的 OAuth 客户端数量
+    pub async fn count_oauth_clients(&self) -> Result<u64, StorageError> {
+        oauth_client::Entity::find()
+            .count(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))
+    }
+
+    /// 删除在指定时间之前注册、且从未获得授权的 OAuth 客户端，返回删除数量
+    pub async fn delete_unused_oauth_clients(
+        &self,
+        registered_before: chrono::DateTime<chrono::Utc>,
+    ) -> Result<u64, StorageError> {
+        let used: Vec<String> = oauth_grant::Entity::find()
+            .select_only()
+            .column(oauth_grant::Column::ClientId)
+            .distinct()
+            .into_tuple()
+            .all(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))?;
+
+        let result = oauth_client::Entity::delete_many()
+            .filter(oauth_client::Column::CreatedAt.lt(registered_before.fixed_offset()))
+            .filter(oauth_client::Column::ClientId.is_not_in(used))
+            .exec(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// 创建 OAuth 授权，返回授权 ID
+    pub async fn create_oauth_grant(
+        &self,
+        client_id: &str,
+        token_id: &str,
+        access_token_hash: &str,
+        access_expires_at: chrono::DateTime<chrono::Utc>,
+        refresh_token_hash: &str,
+    ) -> Result<String, StorageError> {
+        let now = chrono::Utc::now();
+        let id = Uuid::now_v7().to_string();
+
+        let active_model = oauth_grant::ActiveModel {
+            id: Set(id.clone()),
+            client_id: Set(client_id.to_string()),
+            token_id: Set(token_id.to_string()),
+            access_token_hash: Set(access_token_hash.to_string()),
+            access_expires_at: Set(access_expires_at.into()),
+            refresh_token_hash: Set(refresh_token_hash.to_string()),
+            previous_refresh_token_hash: Set(None),
+            created_at: Set(now.into()),
+            updated_at: Set(now.into()),
+        };
+        active_model
+            .insert(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to insert: {}", e)))?;
+
+        Ok(id)
+    }
+
+    /// 根据访问令牌摘要获取 OAuth 授权
+    pub async fn get_oauth_grant_by_access_hash(
+        &self,
+        access_token_hash: &str,
+    ) -> Result<Option<oauth_grant::Model>, StorageError> {
+        oauth_grant::Entity::find()
+            .filter(oauth_grant::Column::AccessTokenHash.eq(access_token_hash))
+            .one(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))
+    }
+
+    /// 根据刷新令牌摘要获取 OAuth 授权
+    pub async fn get_oauth_grant_by_refresh_hash(
+        &self,
+        refresh_token_hash: &str,
+    ) -> Result<Option<oauth_grant::Model>, StorageError> {
+        oauth_grant::Entity::find()
+            .filter(oauth_grant::Column::RefreshTokenHash.eq(refresh_token_hash))
+            .one(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))
+    }
+
+    /// 根据上一个刷新令牌的摘要获取 OAuth 授权
+    pub async fn get_oauth_grant_by_previous_refresh_hash(
+        &self,
+        refresh_token_hash: &str,
+    ) -> Result<Option<oauth_grant::Model>, StorageError> {
+        oauth_grant::Entity::find()
+            .filter(oauth_grant::Column::PreviousRefreshTokenHash.eq(refresh_token_hash))
+            .one(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))
+    }
+
+    /// 轮换 OAuth 授权的访问令牌和刷新令牌
+    ///
+    /// 仅当授权的刷新令牌仍是 `current_refresh_hash` 时更新，返回是否轮换成功；
+    /// 并发使用同一个刷新令牌时只有一个请求能成功
+    pub async fn rotate_oauth_grant(
+        &self,
+        grant_id: &str,
+        current_refresh_hash: &str,
+        access_token_hash: &str,
+        access_expires_at: chrono::DateTime<chrono::Utc>,
+        refresh_token_hash: &str,
+    ) -> Result<bool, StorageError> {
+        let active_model = oauth_grant::ActiveModel {
+            access_token_hash: Set(access_token_hash.to_string()),
+            access_expires_at: Set(access_expires_at.into()),
+            refresh_token_hash: Set(refresh_token_hash.to_string()),
+            previous_refresh_token_hash: Set(Some(current_refresh_hash.to_string())),
+            updated_at: Set(chrono::Utc::now().into()),
+            ..Default::default()
+        };
+        let result = oauth_grant::Entity::update_many()
+            .set(active_model)
+            .filter(oauth_grant::Column::Id.eq(grant_id))
+            .filter(oauth_grant::Column::RefreshTokenHash.eq(current_refresh_hash))
+            .exec(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to update: {}", e)))?;
+
+        Ok(result.rows_affected == 1)
+    }
+
+    /// 获取所有 OAuth 授权（按创建时间倒序）
+    pub async fn list_oauth_grants(&self) -> Result<Vec<oauth_grant::Model>, StorageError> {
+        oauth_grant::Entity::find()
+            .order_by_desc(oauth_grant::Column::CreatedAt)
+            .all(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to query: {}", e)))
+    }
+
+    /// 删除 OAuth 授权
+    pub async fn delete_oauth_grant(&self, id: &str) -> Result<(), StorageError> {
+        let result = oauth_grant::Entity::delete_by_id(id.to_string())
+            .exec(&self.db)
+            .await
+            .map_err(|e| StorageError::Database(format!("Failed to delete: {}", e)))?;
+
+        if result.rows_affected == 0 {
+            return Err(StorageError::NotFound(format!(
+                "OAuth grant {} not found",
+                id
+            )));
+        }
+
+        Ok(())
+    }
+
     // ============================================================================
     // 兼容性别名方法（为 McpServerManager 和 TokenManager 提供统一接口）
     // ============================================================================
@@ -0,0 +1,374 @@
+// MCP 服务健康监控与自动重连
+
+use crate::mcp_manager::McpServerManager;
+use crate::process_monitor::ProcessExit;
+use crate::types::{McpServerConfig, RestartPolicy, ServiceTransport};
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// 健康检查间隔
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// ping 请求超时时间
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+/// 检查启用服务列表变化的间隔
+const RECONCILE_INTERVAL: Duration = Duration::from_secs(15);
+/// 重连退避的初始与最大延迟
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// 为每个启用的服务运行一个监控任务
+///
+/// 监控任务定期发送 MCP ping，检测子进程退出或 HTTP 连接失效，
+/// 失败后按带抖动的指数退避重连，重连成功后重新同步清单。
+/// STDIO 子进程退出时立即检查，并按服务的重启策略决定是否重启；
+/// lazy 服务不自动重连，空闲超时后由监控任务停止。
+pub struct ServiceSupervisor {
+    mcp_server_manager: Arc<McpServerManager>,
+    tasks: Mutex<HashMap<String, CancellationToken>>,
+}
+
+impl ServiceSupervisor {
+    pub fn new(mcp_server_manager: Arc<McpServerManager>) -> Self {
+        Self {
+            mcp_server_manager,
+            tasks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 启动监控：周期性对比启用的服务列表，为新服务启动任务、停止已禁用/删除服务的任务
+    pub fn start(self: &Arc<Self>) {
+        let supervisor = Arc::clone(self);
+        tokio::spawn(async move {
+            tracing::info!("🩺 Service supervisor started");
+            loop {
+                supervisor.reconcile().await;
+                tokio::time::sleep(RECONCILE_INTERVAL).await;
+            }
+        });
+    }
+
+    async fn reconcile(self: &Arc<Self>) {
+        let enabled = match self.mcp_server_manager.list_enabled_server_names().await {
+            Ok(names) => names,
+            Err(e) => {
+                tracing::warn!("Supervisor failed to list enabled servers: {}", e);
+                return;
+            }
+        };
+
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+
+        // 停止已禁用或已删除服务的任务
+        tasks.retain(|name, token| {
+            let keep = enabled.contains(name) && !token.is_cancelled();
+            if !keep {
+                token.cancel();
+            }
+            keep
+        });
+
+        for name in enabled {
+            if tasks.contains_key(&name) {
+                continue;
+            }
+            let token = CancellationToken::new();
+            tasks.insert(name.clone(), token.clone());
+
+            let supervisor = Arc::clone(self);
+            tokio::spawn(async move {
+                supervisor.supervise(&name, &token).await;
+                token.cancel();
+            });
+        }
+    }
+
+    /// 单个服务的监控循环
+    async fn supervise(&self, server_name: &str, token: &CancellationToken) {
+        tracing::debug!("Supervising server '{}'", server_name);
+
+        let process_monitor = crate::MCP_CLIENT_MANAGER.process_monitor();
+        process_monitor.reset_restart_count(server_name);
+        let exit_notify = process_monitor.exit_notify(server_name);
+
+        // None 表示尚未观察到状态，避免启动时误报状态变化
+        let mut last_connected: Option<bool> = None;
+        // 重启策略不再允许重启时停止检查，直到服务被重新连接
+        let mut gave_up = false;
+        // 重连后的进程已经退出时跳过等待，立即检查
+        let mut check_now = false;
+
+        loop {
+            if std::mem::take(&mut check_now) {
+                if token.is_cancelled() {
+                    break;
+                }
+            } else {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = tokio::time::sleep(HEALTH_CHECK_INTERVAL) => {}
+                    // 子进程退出时立即检查
+                    _ = exit_notify.notified() => {}
+                }
+            }
+
+            let config = match self.mcp_server_manager.get_server_config(server_name).await {
+                Ok(Some(config)) => config,
+                _ => continue,
+            };
+
+            if gave_up {
+                if !crate::MCP_CLIENT_MANAGER.is_connected(server_name).await {
+                    continue;
+                }
+                gave_up = false;
+            }
+
+            // lazy 服务：未运行时不检查，空闲超时后停止，失败后等下次调用时再启动
+            if config.is_lazy() {
+                if !crate::MCP_CLIENT_MANAGER.is_connected(server_name).await {
+                    last_connected = None;
+                    continue;
+                }
+                let idle = crate::MCP_CLIENT_MANAGER
+                    .idle_duration(server_name)
+                    .unwrap_or(Duration::MAX);
+                if idle >= config.idle_timeout() {
+                    match crate::MCP_CLIENT_MANAGER
+                        .stop_idle(server_name, config.idle_timeout())
+                        .await
+                    {
+                        Ok(true) => {
+                            tracing::info!(
+                                "💤 Stopped idle lazy server '{}' (idle for {}s)",
+                                server_name,
+                                config.idle_timeout().as_secs()
+                            );
+                            last_connected = None;
+                            continue;
+                        }
+                        // 检查期间有新的调用
+                        Ok(false) => {}
+                        Err(e) => {
+                            tracing::warn!("Failed to stop idle server '{}': {}", server_name, e);
+                            last_connected = None;
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            match crate::MCP_CLIENT_MANAGER
+                .check_health(server_name, PING_TIMEOUT)
+                .await
+            {
+                Ok(()) => {
+                    if last_connected == Some(false) {
+                        self.notify_status_change(server_name, true).await;
+                    }
+                    last_connected = Some(true);
+                }
+                Err(e) => {
+                    tracing::warn!("⚠️ Health check failed for server '{}': {}", server_name, e);
+                    crate::MCP_CLIENT_MANAGER
+                        .mark_failed(server_name, e.to_string())
+                        .await;
+                    if last_connected != Some(false) {
+                        self.notify_status_change(server_name, false).await;
+                    }
+                    last_connected = Some(false);
+
+                    if config.is_lazy() {
+                        continue;
+                    }
+                    if self.reconnect_with_backoff(server_name, token).await {
+                        self.notify_status_change(server_name, true).await;
+                        last_connected = Some(true);
+                        // 重连期间退出的进程不一定能被下一次等待观察到
+                        check_now = process_monitor.exited(server_name).is_some();
+                    } else {
+                        gave_up = true;
+                    }
+                }
+            }
+        }
+
+        tracing::debug!("Stopped supervising server '{}'", server_name);
+    }
+
+    /// 按指数退避重连，直到成功、服务被禁用、重启策略不允许或任务被取消
+    async fn reconnect_with_backoff(&self, server_name: &str, token: &CancellationToken) -> bool {
+        let mut attempt: u32 = 0;
+        // 一次故障只计一次重启，之后的重试不再计数
+        let mut restart_recorded = false;
+
+        loop {
+            if token.is_cancelled() {
+                return false;
+            }
+
+            // 每次重连前重新读取配置，以便应用用户的修改
+            let config = match self.mcp_server_manager.get_server_config(server_name).await {
+                Ok(Some(config)) if config.enabled => config,
+                _ => return false,
+            };
+
+            // STDIO 服务按重启策略决定是否重启
+            let process_monitor = crate::MCP_CLIENT_MANAGER.process_monitor();
+            let exit = process_monitor.exited(server_name);
+            let restart_count = process_monitor.restart_count(server_name);
+            // 本次故障已经计数时按计数前的次数判断，重试不会耗尽重启次数
+            let previous_restarts = restart_count.saturating_sub(u32::from(restart_recorded));
+            if !restart_allowed(&config, exit.as_ref(), previous_restarts) {
+                tracing::warn!(
+                    "⛔ Not restarting server '{}' (restart policy: {}, restarts: {}, last exit: {})",
+                    server_name,
+                    config.restart_policy,
+                    restart_count,
+                    exit.map(|e| e.to_string())
+                        .unwrap_or_else(|| "none".to_string())
+                );
+                return false;
+            }
+            if config.transport == ServiceTransport::Stdio && !restart_recorded {
+                process_monitor.record_restart(server_name);
+                restart_recorded = true;
+            }
+
+            attempt += 1;
+            tracing::info!(
+                "🔄 Reconnecting to server '{}' (attempt {})",
+                server_name,
+                attempt
+            );
+
+            // 连接超时由服务配置的 connect_timeout 控制
+            match crate::MCP_CLIENT_MANAGER
+                .ensure_connection(&config, true)
+                .await
+            {
+                Ok(_) => {
+                    tracing::info!(
+                        "✅ Reconnected to server '{}' after {} attempt(s)",
+                        server_name,
+                        attempt
+                    );
+                    return true;
+                }
+                Err(e) => {
+                    tracing::warn!("Reconnect to server '{}' failed: {}", server_name, e);
+                }
+            }
+
+            let delay = backoff_delay(attempt);
+            tracing::debug!(
+                "Next reconnect to server '{}' in {}ms",
+                server_name,
+                delay.as_millis()
+            );
+            tokio::select! {
+                _ = token.cancelled() => return false,
+                _ = tokio::time::sleep(delay) => {}
+            }
+        }
+    }
+
+    /// 通过聚合器报告服务状态变化（连接成功时会刷新清单）
+    async fn notify_status_change(&self, server_name: &str, is_connected: bool) {
+        let aggregator = crate::AGGREGATOR
+            .lock()
+            .ok()
+            .and_then(|guard| guard.as_ref().cloned());
+
+        if let Some(aggregator) = aggregator {
+            aggregator
+                .handle_service_status_change(server_name, is_connected)
+                .await;
+        } else if is_connected {
+            // 聚合器未运行时直接同步清单
+            if let Err(e) = self
+                .mcp_server_manager
+                .sync_server_manifests(server_name)
+                .await
+            {
+                tracing::warn!(
+                    "Failed to refresh manifests for service '{}': {}",
+                    server_name,
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// 根据重启策略判断是否应重启（HTTP 服务始终重连）
+fn restart_allowed(
+    config: &McpServerConfig,
+    exit: Option<&ProcessExit>,
+    restart_count: u32,
+) -> bool {
+    if config.transport != ServiceTransport::Stdio {
+        return true;
+    }
+    match config.restart_policy {
+        RestartPolicy::Never => false,
+        RestartPolicy::OnFailure => {
+            // 进程未退出（例如无响应）也视为失败
+            !exit.is_some_and(|e| e.success()) && restart_count < config.max_restarts()
+        }
+        RestartPolicy::Always => true,
+    }
+}
+
+/// 带 ±20% 抖动的指数退避延迟
+fn backoff_delay(attempt: u32) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(16);
+    let base = INITIAL_BACKOFF
+        .saturating_mul(1u32 << exponent)
+        .min(MAX_BACKOFF);
+    let jitter = rand::rng().random_range(0.8..1.2);
+    base.mul_f64(jitter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exit(code: i32) -> ProcessExit {
+        ProcessExit {
+            code: Some(code),
+            signal: None,
+            exited_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_restart_allowed_by_policy() {
+        let mut config = McpServerConfig::new(String::new(), "demo".to_string());
+        config.max_restarts = Some(2);
+
+        assert!(restart_allowed(&config, Some(&exit(1)), 0));
+        assert!(restart_allowed(&config, None, 1));
+        assert!(!restart_allowed(&config, Some(&exit(1)), 2));
+        assert!(!restart_allowed(&config, Some(&exit(0)), 0));
+
+        config.restart_policy = RestartPolicy::Never;
+        assert!(!restart_allowed(&config, Some(&exit(1)), 0));
+
+        config.restart_policy = RestartPolicy::Always;
+        assert!(restart_allowed(&config, Some(&exit(0)), 100));
+
+        config.restart_policy = RestartPolicy::Never;
+        config.transport = ServiceTransport::Http;
+        assert!(restart_allowed(&config, None, 100));
+    }
+
+    #[test]
+    fn test_backoff_delay_is_capped() {
+        assert!(backoff_delay(1) <= INITIAL_BACKOFF.mul_f64(1.2));
+        assert!(backoff_delay(1) >= INITIAL_BACKOFF.mul_f64(0.8));
+        assert!(backoff_delay(u32::MAX) <= MAX_BACKOFF.mul_f64(1.2));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::error::{McpError, Result};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command as StdCommand;
 use std::time::Duration;
@@ -6,6 +7,251 @@ use tokio::fs;
 use tokio::process::Command as TokioCommand;
 use tokio::sync::RwLock;
 
+/// 默认安装的 Bun 版本
+pub const BUN_VERSION: &str = "1.2.23";
+/// 默认安装的 uv 版本
+pub const UV_VERSION: &str = "0.8.22";
+/// 默认安装的 Node.js 版本
+pub const NODE_VERSION: &str = "22.20.0";
+/// 默认安装的 Python 版本（由 uv 管理）
+pub const PYTHON_VERSION: &str = "3.12";
+/// 默认下载地址，可通过设置中的 tool_mirror_url 替换
+const GITHUB_BASE_URL: &str = "https://github.com";
+/// Node.js 默认下载地址，可通过设置中的 node_mirror_url 替换
+const NODE_DIST_URL: &str = "https://nodejs.org/dist";
+/// 安装新版本前备份旧可执行文件使用的后缀，用于回滚
+const BACKUP_SUFFIX: &str = "previous";
+
+/// 工具安装选项
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+pub struct InstallOptions {
+    /// 安装的版本，未设置时使用内置的固定版本
+    pub version: Option<String>,
+    /// 本地压缩包路径（离线安装），设置后不再下载
+    pub archive_path: Option<String>,
+    /// 期望的 SHA-256，未设置时使用官方发布附带的校验文件（不经过镜像）
+    pub sha256: Option<String>,
+    /// 恢复到上一次安装前的版本
+    #[serde(default)]
+    pub rollback: bool,
+}
+
+/// 通过下载发布包安装的工具
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ManagedTool {
+    Bun,
+    Uv,
+}
+
+impl ManagedTool {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "bun" => Some(Self::Bun),
+            "uv" | "uvx" => Some(Self::Uv),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Bun => "Bun",
+            Self::Uv => "UV",
+        }
+    }
+
+    fn default_version(self) -> &'static str {
+        match self {
+            Self::Bun => BUN_VERSION,
+            Self::Uv => UV_VERSION,
+        }
+    }
+
+    /// 安装后写入 bin 目录的可执行文件
+    fn binaries(self) -> &'static [&'static str] {
+        match self {
+            Self::Bun => &["bun"],
+            Self::Uv => &["uv", "uvx"],
+        }
+    }
+
+    /// 当前平台的发布包文件名
+    fn asset(self, os: &str, arch: &str) -> Result<&'static str> {
+        let asset = match (self, os, arch) {
+            (Self::Bun, "darwin", "x86_64") => "bun-darwin-x64.zip",
+            (Self::Bun, "darwin", "aarch64") => "bun-darwin-aarch64.zip",
+            (Self::Bun, "linux", "x86_64") => "bun-linux-x64.zip",
+            (Self::Bun, "linux", "aarch64") => "bun-linux-aarch64.zip",
+            (Self::Bun, "windows", "x86_64") => "bun-windows-x64.zip",
+            (Self::Uv, "darwin", "x86_64") => "uv-x86_64-apple-darwin.tar.gz",
+            (Self::Uv, "darwin", "aarch64") => "uv-aarch64-apple-darwin.tar.gz",
+            (Self::Uv, "linux", "x86_64") => "uv-x86_64-unknown-linux-gnu.tar.gz",
+            (Self::Uv, "linux", "aarch64") => "uv-aarch64-unknown-linux-gnu.tar.gz",
+            (Self::Uv, "windows", "x86_64") => "uv-x86_64-pc-windows-msvc.zip",
+            _ => return Err(McpError::UnsupportedPlatform(format!("{}-{}", os, arch))),
+        };
+        Ok(asset)
+    }
+
+    /// 发布包路径（相对于 GitHub 或镜像地址）
+    fn release_path(self, version: &str, asset: &str) -> String {
+        match self {
+            Self::Bun => format!(
+                "oven-sh/bun/releases/download/bun-v{}/{}",
+                version, asset
+            ),
+            Self::Uv => format!("astral-sh/uv/releases/download/{}/{}", version, asset),
+        }
+    }
+
+    /// 发布附带的 SHA-256 校验文件地址
+    ///
+    /// 始终从 GitHub 获取而不是镜像，镜像只提供发布包本身，无法同时替换包和校验值
+    fn checksum_url(self, version: &str, asset: &str) -> String {
+        let path = match self {
+            Self::Bun => format!(
+                "oven-sh/bun/releases/download/bun-v{}/SHASUMS256.txt",
+                version
+            ),
+            Self::Uv => format!("{}.sha256", self.release_path(version, asset)),
+        };
+        format!("{}/{}", GITHUB_BASE_URL, path)
+    }
+}
+
+/// 从校验文件中取出指定文件的 SHA-256
+///
+/// 支持 `sha256sum` 格式（`<hash>  <file>`，文件名可带 `*` 前缀）；
+/// 只有一个哈希值的文件（例如 `<asset>.sha256`）直接使用该值。
+fn parse_checksum(content: &str, file_name: &str) -> Option<String> {
+    let entries: Vec<(&str, Option<&str>)> = content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let name = parts.next().map(|n| n.trim_start_matches('*'));
+            Some((hash, name))
+        })
+        .filter(|(hash, _)| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .collect();
+
+    let matched = entries.iter().find(|(_, name)| {
+        name.is_some_and(|n| Path::new(n).file_name().is_some_and(|n| n == file_name))
+    });
+    match (matched, entries.as_slice()) {
+        (Some((hash, _)), _) => Some(hash.to_lowercase()),
+        (None, [(hash, _)]) => Some(hash.to_lowercase()),
+        _ => None,
+    }
+}
+
+/// 从 `--version` 输出中取出版本号，例如 `uv 0.8.22 (ade2bdbd2 2025-09-23)` 中的 `0.8.22`
+fn parse_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .map(|token| token.trim_start_matches('v'))
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()) && token.contains('.'))
+        .map(str::to_string)
+}
+
+/// 版本是否匹配请求的版本（`20` 匹配 `20.19.5`，`3.12` 匹配 `3.12.11`）
+fn version_matches(version: &str, requested: &str) -> bool {
+    version == requested || version.starts_with(&format!("{}.", requested))
+}
+
+/// 是否为完整的发布版本号（`22.20.0`），用作安装目录名前必须校验
+fn is_release_version(version: &str) -> bool {
+    let parts: Vec<&str> = version.split('.').collect();
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// 是否为合法的版本请求：主版本、主次版本或完整版本（`20`、`3.12`、`22.20.0`）
+fn is_version_request(version: &str) -> bool {
+    let parts: Vec<&str> = version.split('.').collect();
+    parts.len() <= 3
+        && parts
+            .iter()
+            .all(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// 按数字比较版本号
+fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parts = |v: &str| -> Vec<u64> { v.split('.').map(|p| p.parse().unwrap_or(0)).collect() };
+    parts(a).cmp(&parts(b))
+}
+
+/// uv 管理的 Python 安装目录名中的版本，例如 `cpython-3.12.11-linux-x86_64-gnu`
+fn managed_python_version(dir_name: &str) -> Option<String> {
+    let mut parts = dir_name.split('-');
+    let implementation = parts.next()?;
+    let version = parts.next()?;
+    (implementation == "cpython" && version.starts_with(|c: char| c.is_ascii_digit()))
+        .then(|| version.to_string())
+}
+
+/// 目录下所有子目录的名称（目录不存在时为空）
+async fn list_dirs(dir: &Path) -> Vec<String> {
+    let mut names = Vec::new();
+    let Ok(mut entries) = fs::read_dir(dir).await else {
+        return names;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if entry.file_type().await.is_ok_and(|t| t.is_dir()) {
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    names
+}
+
+/// 当前平台的 Node.js 发布包文件名
+fn node_asset(version: &str, os: &str, arch: &str) -> Result<String> {
+    let os_name = match os {
+        "darwin" | "linux" => os,
+        "windows" => "win",
+        _ => return Err(McpError::UnsupportedPlatform(format!("{}-{}", os, arch))),
+    };
+    let arch_name = match arch {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        _ => return Err(McpError::UnsupportedPlatform(format!("{}-{}", os, arch))),
+    };
+    let ext = if os == "windows" { "zip" } else { "tar.gz" };
+    Ok(format!("node-v{}-{}-{}.{}", version, os_name, arch_name, ext))
+}
+
+/// 将整个压缩包解压到目录（保留目录结构、符号链接和权限）
+fn extract_archive(bytes: &[u8], is_zip: bool, dest: &Path) -> Result<()> {
+    let cursor = std::io::Cursor::new(bytes);
+    if is_zip {
+        zip::ZipArchive::new(cursor)
+            .and_then(|mut archive| archive.extract(dest))
+            .map_err(|e| McpError::DownloadError(format!("Failed to extract zip: {}", e)))
+    } else {
+        tar::Archive::new(flate2::read::GzDecoder::new(cursor))
+            .unpack(dest)
+            .map_err(|e| McpError::DownloadError(format!("Failed to extract tar.gz: {}", e)))
+    }
+}
+
+/// 校验内容的 SHA-256
+fn verify_sha256(bytes: &[u8], expected: &str, source: &str) -> Result<()> {
+    use sha2::{Digest, Sha256};
+
+    let actual: String = Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    if actual != expected.to_lowercase() {
+        return Err(McpError::DownloadError(format!(
+            "SHA-256 mismatch for {}: expected {}, got {}",
+            source, expected, actual
+        )));
+    }
+    Ok(())
+}
+
 /// Simple tool manager for Bun, UV, and UVX
 #[derive(Debug)]
 pub struct ToolManager {
@@ -71,10 +317,7 @@ impl ToolManager {
         };
 
         if output.status.success() {
-            String::from_utf8_lossy(&output.stdout)
-                .split_whitespace()
-                .last()
-                .map(|s| s.to_string())
+            parse_version(&String::from_utf8_lossy(&output.stdout))
         } else {
             None
         }
@@ -86,30 +329,8 @@ impl ToolManager {
             tracing::info!("Bun already exists");
             return Ok(());
         }
-
-        // Determine platform and download URL
-        let (os, arch) = self.detect_platform();
-        let url = match (os.as_str(), arch.as_str()) {
-            ("darwin", "x86_64") => {
-                "https://github.com/oven-sh/bun/releases/latest/download/bun-darwin-x64.zip"
-            }
-            ("darwin", "aarch64") => {
-                "https://github.com/oven-sh/bun/releases/latest/download/bun-darwin-aarch64.zip"
-            }
-            ("linux", "x86_64") => {
-                "https://github.com/oven-sh/bun/releases/latest/download/bun-linux-x64.zip"
-            }
-            ("linux", "aarch64") => {
-                "https://github.com/oven-sh/bun/releases/latest/download/bun-linux-aarch64.zip"
-            }
-            ("windows", "x86_64") => {
-                "https://github.com/oven-sh/bun/releases/latest/download/bun-windows-x64.zip"
-            }
-            _ => return Err(McpError::UnsupportedPlatform(format!("{}-{}", os, arch))),
-        };
-
-        // Download and extract
-        self.download_and_extract_zip(url, "bun").await
+        self.install_release(ManagedTool::Bun, &InstallOptions::default())
+            .await
     }
 
     /// Download and install UV (includes UVX)
@@ -118,43 +339,474 @@ impl ToolManager {
             tracing::info!("UV already exists (includes UVX)");
             return Ok(());
         }
+        self.install_release(ManagedTool::Uv, &InstallOptions::default())
+            .await?;
 
-        // Determine platform and download URL
-        let (os, arch) = self.detect_platform();
-        let url = match (os.as_str(), arch.as_str()) {
-            ("darwin", "x86_64") => "https://github.com/astral-sh/uv/releases/latest/download/uv-x86_64-apple-darwin.tar.gz",
-            ("darwin", "aarch64") => "https://github.com/astral-sh/uv/releases/latest/download/uv-aarch64-apple-darwin.tar.gz",
-            ("linux", "x86_64") => "https://github.com/astral-sh/uv/releases/latest/download/uv-x86_64-unknown-linux-gnu.tar.gz",
-            ("linux", "aarch64") => "https://github.com/astral-sh/uv/releases/latest/download/uv-aarch64-unknown-linux-gnu.tar.gz",
-            ("windows", "x86_64") => "https://github.com/astral-sh/uv/releases/latest/download/uv-x86_64-pc-windows-msvc.zip",
-            _ => return Err(McpError::UnsupportedPlatform(format!("{}-{}", os, arch))),
-        };
+        tracing::info!("UV installed successfully (includes UVX command)");
+        Ok(())
+    }
+
+    /// 下载地址根路径：设置中的镜像地址或 GitHub
+    fn mirror_base_url() -> String {
+        crate::config::CONFIG_SERVICE
+            .current()
+            .settings
+            .as_ref()
+            .and_then(|s| s.tool_mirror_url.as_deref())
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| GITHUB_BASE_URL.to_string())
+    }
+
+    /// Node.js 下载地址根路径：设置中的 node_mirror_url 或 nodejs.org
+    fn node_dist_url() -> String {
+        crate::config::CONFIG_SERVICE
+            .current()
+            .settings
+            .as_ref()
+            .and_then(|s| s.node_mirror_url.as_deref())
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| NODE_DIST_URL.to_string())
+    }
+
+    /// 安装指定版本的发布包：下载（或读取本地压缩包）、校验 SHA-256、备份旧版本后解压
+    async fn install_release(&self, tool: ManagedTool, options: &InstallOptions) -> Result<()> {
+        self.initialize().await?;
 
-        // Download and extract UV
-        if url.ends_with(".zip") {
-            self.download_and_extract_zip(url, "uv").await?;
+        let version = options
+            .version
+            .as_deref()
+            .map(|v| v.trim().trim_start_matches('v'))
+            .filter(|v| !v.is_empty())
+            .unwrap_or(tool.default_version());
+        let (os, arch) = self.detect_platform();
+        let asset = tool.asset(&os, &arch)?;
+        let base_url = Self::mirror_base_url();
+        let (bytes, file_name) = self
+            .fetch_verified(
+                options,
+                asset,
+                &format!("{}/{}", base_url, tool.release_path(version, asset)),
+                &tool.checksum_url(version, asset),
+            )
+            .await?;
+
+        // 备份当前版本，解压失败时恢复
+        self.backup_binaries(tool).await?;
+        let result = if file_name.ends_with(".zip") {
+            self.extract_zip_from_bytes(&bytes, tool.binaries()[0]).await
         } else {
-            self.download_and_extract_tar(url).await?;
+            self.extract_tar_from_bytes(&bytes).await
+        };
+        if let Err(e) = result {
+            tracing::error!(
+                "Failed to install {}, restoring previous version: {}",
+                tool.name(),
+                e
+            );
+            self.swap_backup(tool).await?;
+            return Err(e);
         }
 
-        tracing::info!("UV installed successfully (includes UVX command)");
+        tracing::info!("{} {} installed", tool.name(), version);
         Ok(())
     }
 
-    /// Download and extract ZIP archive
-    async fn download_and_extract_zip(&self, url: &str, tool_name: &str) -> Result<()> {
-        // Download
-        let response = self.download_client.get(url).send().await.map_err(|e| {
-            McpError::DownloadError(format!("Failed to download {}: {}", tool_name, e))
+    /// 读取本地压缩包（离线安装）或下载发布包，并校验 SHA-256；返回内容和文件名
+    ///
+    /// 校验值依次来自参数、压缩包旁的校验文件或发布附带的校验文件，都没有时拒绝安装。
+    async fn fetch_verified(
+        &self,
+        options: &InstallOptions,
+        asset: &str,
+        url: &str,
+        checksum_url: &str,
+    ) -> Result<(Vec<u8>, String)> {
+        let expected = options
+            .sha256
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_lowercase);
+
+        let archive_path = options
+            .archive_path
+            .as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty());
+        let (bytes, file_name, expected, source) = match archive_path {
+            Some(path) => {
+                let path = PathBuf::from(path);
+                let bytes = fs::read(&path).await.map_err(|e| {
+                    McpError::DownloadError(format!(
+                        "Failed to read archive {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                let file_name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| asset.to_string());
+                let expected = match expected {
+                    Some(expected) => Some(expected),
+                    None => Self::local_checksum(&path, &file_name).await,
+                };
+                (bytes, file_name, expected, path.display().to_string())
+            }
+            None => {
+                tracing::info!("Downloading {}", url);
+                let bytes = self.download(url).await?;
+                let expected = match expected {
+                    Some(expected) => Some(expected),
+                    None => {
+                        let checksums = self.download(checksum_url).await?;
+                        parse_checksum(&String::from_utf8_lossy(&checksums), asset)
+                    }
+                };
+                (bytes, asset.to_string(), expected, url.to_string())
+            }
+        };
+
+        let expected = expected.ok_or_else(|| {
+            McpError::DownloadError(format!(
+                "No SHA-256 checksum available for {}, provide one to install",
+                source
+            ))
         })?;
+        verify_sha256(&bytes, &expected, &source)?;
+        tracing::info!("✅ Verified SHA-256 of {}", source);
+        Ok((bytes, file_name))
+    }
+
+    /// 读取离线压缩包旁的校验文件（`<archive>.sha256` 或 `SHASUMS256.txt`）
+    async fn local_checksum(archive: &Path, file_name: &str) -> Option<String> {
+        let mut candidates = vec![PathBuf::from(format!("{}.sha256", archive.display()))];
+        if let Some(dir) = archive.parent() {
+            candidates.push(dir.join("SHASUMS256.txt"));
+        }
+        for candidate in candidates {
+            if let Ok(content) = fs::read_to_string(&candidate).await {
+                if let Some(checksum) = parse_checksum(&content, file_name) {
+                    return Some(checksum);
+                }
+            }
+        }
+        None
+    }
+
+    async fn download(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self
+            .download_client
+            .get(url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| McpError::DownloadError(format!("Failed to download {}: {}", url, e)))?;
 
         let bytes = response
             .bytes()
             .await
             .map_err(|e| McpError::DownloadError(format!("Failed to read response: {}", e)))?;
+        Ok(bytes.to_vec())
+    }
+
+    fn backup_path(&self, binary: &str) -> PathBuf {
+        self.bin_dir.join(format!("{}.{}", binary, BACKUP_SUFFIX))
+    }
+
+    /// 将当前可执行文件移动为备份
+    async fn backup_binaries(&self, tool: ManagedTool) -> Result<()> {
+        for binary in tool.binaries() {
+            let path = self.get_tool_path(binary);
+            if fs::metadata(&path).await.is_ok() {
+                let backup = self.backup_path(binary);
+                // Windows 上 rename 不能覆盖已有文件
+                if fs::metadata(&backup).await.is_ok() {
+                    fs::remove_file(&backup).await?;
+                }
+                fs::rename(&path, backup).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 交换当前版本和备份版本（回滚后再次回滚即恢复）
+    async fn swap_backup(&self, tool: ManagedTool) -> Result<()> {
+        for binary in tool.binaries() {
+            let path = self.get_tool_path(binary);
+            let backup = self.backup_path(binary);
+            let has_current = fs::metadata(&path).await.is_ok();
+            let has_backup = fs::metadata(&backup).await.is_ok();
+            match (has_current, has_backup) {
+                (true, true) => {
+                    let swap = self.bin_dir.join(format!("{}.swap", binary));
+                    fs::rename(&path, &swap).await?;
+                    fs::rename(&backup, &path).await?;
+                    fs::rename(&swap, &backup).await?;
+                }
+                (false, true) => fs::rename(&backup, &path).await?,
+                (true, false) => fs::rename(&path, &backup).await?,
+                (false, false) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// 是否存在可回滚的备份
+    async fn has_backup(&self, tool: ManagedTool) -> bool {
+        fs::metadata(self.backup_path(tool.binaries()[0]))
+            .await
+            .is_ok()
+    }
+
+    /// 内置 Node.js 的根目录，每个版本安装在其中的 `<version>` 子目录
+    fn node_root(&self) -> PathBuf {
+        self.bin_dir.join("node")
+    }
+
+    /// 内置 Node.js 可执行文件所在目录（需加入 PATH，npx / npm 通过 `env node` 启动）
+    pub fn node_bin_dir(&self, version: &str) -> PathBuf {
+        let dir = self.node_root().join(version);
+        if cfg!(windows) {
+            dir
+        } else {
+            dir.join("bin")
+        }
+    }
+
+    /// 内置 Node.js 中 node / npm / npx 的路径
+    pub fn node_command_path(&self, version: &str, command: &str) -> PathBuf {
+        let file_name = match (cfg!(windows), command) {
+            (true, "node") => "node.exe".to_string(),
+            (true, command) => format!("{}.cmd", command),
+            (false, command) => command.to_string(),
+        };
+        self.node_bin_dir(version).join(file_name)
+    }
 
-        // Extract in a separate function to avoid Send issues
-        self.extract_zip_from_bytes(&bytes, tool_name).await
+    /// 已安装的内置 Node.js 版本（从低到高）
+    pub async fn installed_node_versions(&self) -> Vec<String> {
+        let mut versions: Vec<String> = list_dirs(&self.node_root())
+            .await
+            .into_iter()
+            .filter(|name| is_release_version(name))
+            .collect();
+        versions.sort_by(|a, b| compare_versions(a, b));
+        versions
+    }
+
+    /// 确保请求的 Node.js 版本已安装，返回实际使用的版本
+    ///
+    /// 未指定时使用 [`NODE_VERSION`]；只指定主版本（如 `20`）时优先使用已安装的最高匹配版本，
+    /// 否则安装该主版本的最新发布。
+    pub async fn ensure_node(&self, requested: Option<&str>) -> Result<String> {
+        let requested = requested
+            .map(|v| v.trim().trim_start_matches('v'))
+            .filter(|v| !v.is_empty())
+            .unwrap_or(NODE_VERSION);
+        if let Some(version) = self
+            .installed_node_versions()
+            .await
+            .into_iter()
+            .rev()
+            .find(|v| version_matches(v, requested))
+        {
+            return Ok(version);
+        }
+
+        let options = InstallOptions {
+            version: Some(requested.to_string()),
+            ..Default::default()
+        };
+        self.install_node(&options).await
+    }
+
+    /// 主版本的最新发布版本（从 `latest-v<major>.x/SHASUMS256.txt` 中解析）
+    async fn latest_node_version(&self, major: &str) -> Result<String> {
+        let url = format!("{}/latest-v{}.x/SHASUMS256.txt", Self::node_dist_url(), major);
+        let content = self.download(&url).await?;
+        String::from_utf8_lossy(&content)
+            .split_whitespace()
+            .filter_map(|name| name.strip_prefix("node-v"))
+            .find_map(|rest| rest.split('-').next())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                McpError::DownloadError(format!("No Node.js v{} release found at {}", major, url))
+            })
+    }
+
+    /// 安装内置 Node.js（下载或离线压缩包，校验 SHA-256 后解压到 `node/<version>`），返回安装的版本
+    async fn install_node(&self, options: &InstallOptions) -> Result<String> {
+        self.initialize().await?;
+
+        let requested = options
+            .version
+            .as_deref()
+            .map(|v| v.trim().trim_start_matches('v'))
+            .filter(|v| !v.is_empty());
+        // 离线安装未指定版本时从文件名（node-v<version>-<os>-<arch>）中取版本
+        let archive_version = options
+            .archive_path
+            .as_deref()
+            .and_then(|p| Path::new(p.trim()).file_name())
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix("node-v"))
+            .and_then(|rest| rest.split('-').next())
+            .map(str::to_string);
+        let version = match (requested, archive_version) {
+            (None, Some(version)) => version,
+            (requested, _) => {
+                let requested = requested.unwrap_or(NODE_VERSION);
+                if is_release_version(requested) {
+                    requested.to_string()
+                } else if is_version_request(requested) && !requested.contains('.') {
+                    self.latest_node_version(requested).await?
+                } else {
+                    return Err(McpError::InvalidConfiguration(format!(
+                        "Node.js version '{}' must be a major version or a full version",
+                        requested
+                    )));
+                }
+            }
+        };
+        // 版本会拼接到安装目录中，文件名或镜像返回的版本也必须是纯数字版本号
+        if !is_release_version(&version) {
+            return Err(McpError::InvalidConfiguration(format!(
+                "Invalid Node.js version '{}'",
+                version
+            )));
+        }
+
+        let target = self.node_root().join(&version);
+        if options.archive_path.is_none() && fs::metadata(&target).await.is_ok() {
+            tracing::info!("Node.js {} already installed", version);
+            return Ok(version);
+        }
+
+        let (os, arch) = self.detect_platform();
+        let asset = node_asset(&version, &os, &arch)?;
+        // 校验和始终从 nodejs.org 获取，镜像只提供发布包
+        let (bytes, file_name) = self
+            .fetch_verified(
+                options,
+                &asset,
+                &format!("{}/v{}/{}", Self::node_dist_url(), version, asset),
+                &format!("{}/v{}/SHASUMS256.txt", NODE_DIST_URL, version),
+            )
+            .await?;
+
+        // 先解压到临时目录，完成后再移动到版本目录
+        let staging = self.node_root().join(format!(".v{}.tmp", version));
+        if fs::metadata(&staging).await.is_ok() {
+            fs::remove_dir_all(&staging).await?;
+        }
+        fs::create_dir_all(&staging).await?;
+        let staging_dir = staging.clone();
+        let is_zip = file_name.ends_with(".zip");
+        tokio::task::spawn_blocking(move || extract_archive(&bytes, is_zip, &staging_dir))
+            .await
+            .map_err(|e| McpError::RuntimeError(format!("Extraction task failed: {}", e)))??;
+
+        // 发布包中只有一个顶层目录 node-v<version>-<os>-<arch>
+        let mut entries = fs::read_dir(&staging).await?;
+        let top_level = entries
+            .next_entry()
+            .await?
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .ok_or_else(|| {
+                McpError::DownloadError(format!("Unexpected Node.js archive layout: {}", file_name))
+            })?;
+        if fs::metadata(&target).await.is_ok() {
+            fs::remove_dir_all(&target).await?;
+        }
+        fs::rename(&top_level, &target).await?;
+        fs::remove_dir_all(&staging).await?;
+
+        tracing::info!("Node.js {} installed to {}", version, target.display());
+        Ok(version)
+    }
+
+    /// uv 管理的 Python 安装目录（UV_PYTHON_INSTALL_DIR）
+    pub fn python_dir(&self) -> PathBuf {
+        self.bin_dir.join("python")
+    }
+
+    /// 运行 uv / uvx 时使用的环境变量：Python 安装到内置目录，下载走镜像
+    pub fn uv_python_env(&self) -> Vec<(String, String)> {
+        let mut env = vec![(
+            "UV_PYTHON_INSTALL_DIR".to_string(),
+            self.python_dir().display().to_string(),
+        )];
+        let mirror = Self::mirror_base_url();
+        if mirror != GITHUB_BASE_URL {
+            env.push((
+                "UV_PYTHON_INSTALL_MIRROR".to_string(),
+                format!("{}/astral-sh/python-build-standalone/releases/download", mirror),
+            ));
+        }
+        env
+    }
+
+    /// 已安装的 uv 管理的 Python 版本（从低到高）
+    pub async fn installed_python_versions(&self) -> Vec<String> {
+        let mut versions: Vec<String> = list_dirs(&self.python_dir())
+            .await
+            .iter()
+            .filter_map(|name| managed_python_version(name))
+            .collect();
+        versions.sort_by(|a, b| compare_versions(a, b));
+        versions.dedup();
+        versions
+    }
+
+    /// 确保请求的 Python 版本已由 uv 安装到内置目录
+    pub async fn ensure_python(&self, requested: Option<&str>) -> Result<String> {
+        let requested = requested
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .unwrap_or(PYTHON_VERSION);
+        if !is_version_request(requested) {
+            return Err(McpError::InvalidConfiguration(format!(
+                "Invalid Python version '{}'",
+                requested
+            )));
+        }
+        if let Some(version) = self
+            .installed_python_versions()
+            .await
+            .into_iter()
+            .rev()
+            .find(|v| version_matches(v, requested))
+        {
+            return Ok(version);
+        }
+        self.install_python(requested).await?;
+        Ok(requested.to_string())
+    }
+
+    /// 使用 `uv python install` 安装 Python（uv 会校验下载内容）
+    async fn install_python(&self, version: &str) -> Result<()> {
+        self.install_uv_tools().await?;
+
+        tracing::info!("Installing Python {} with uv", version);
+        let output = TokioCommand::new(self.get_tool_path("uv"))
+            .args(["python", "install", version])
+            .envs(self.uv_python_env())
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(McpError::RuntimeError(format!(
+                "Failed to install Python {}: {}",
+                version,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        tracing::info!("Python {} installed to {}", version, self.python_dir().display());
+        Ok(())
     }
 
     /// Extract ZIP from bytes (separated to avoid Send issues)
@@ -209,22 +861,6 @@ impl ToolManager {
         Ok(())
     }
 
-    /// Download and extract tar.gz archive
-    async fn download_and_extract_tar(&self, url: &str) -> Result<()> {
-        // Download
-        let response = self.download_client.get(url).send().await.map_err(|e| {
-            McpError::DownloadError(format!("Failed to download UV tar.gz: {}", e))
-        })?;
-
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(|e| McpError::DownloadError(format!("Failed to read response: {}", e)))?;
-
-        // Extract in a separate function to avoid Send issues
-        self.extract_tar_from_bytes(&bytes).await
-    }
-
     /// Extract tar.gz from bytes (separated to avoid Send issues)
     async fn extract_tar_from_bytes(&self, bytes: &[u8]) -> Result<()> {
         use flate2::read::GzDecoder;
@@ -376,6 +1012,50 @@ impl ToolManager {
         }
     }
 
+    /// 设置中为命令指定的可执行文件（settings.command_paths），文件不存在时忽略
+    ///
+    /// 由调用方传入当前设置，一次启动只读取一次配置
+    pub fn configured_command_path(
+        command_paths: &HashMap<String, String>,
+        command: &str,
+    ) -> Option<PathBuf> {
+        let path = command_paths
+            .get(command)
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())?;
+        let path = PathBuf::from(path);
+        if path.is_file() {
+            Some(path)
+        } else {
+            tracing::warn!(
+                "Configured path for '{}' does not exist: {}",
+                command,
+                path.display()
+            );
+            None
+        }
+    }
+
+    /// 在 `search_path` 中查找系统命令（不含内置工具目录）
+    pub fn find_system_command(&self, command: &str, search_path: Option<&str>) -> Option<PathBuf> {
+        let search_path = search_path
+            .map(str::to_string)
+            .or_else(|| std::env::var("PATH").ok())?;
+        let separator = if cfg!(windows) { ';' } else { ':' };
+        let search_path = search_path
+            .split(separator)
+            .filter(|dir| Path::new(dir) != self.bin_dir)
+            .collect::<Vec<_>>()
+            .join(&separator.to_string());
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        which::which_in(command, Some(search_path), cwd).ok()
+    }
+
+    /// 是否为内置工具目录中的可执行文件
+    pub fn is_managed(&self, path: &Path) -> bool {
+        path.starts_with(&self.bin_dir)
+    }
+
     /// Get the executable path for a command
     pub async fn get_executable_path(&self, command: &str) -> Result<PathBuf> {
         let first_word = command.split_whitespace().next().unwrap_or("");
@@ -465,13 +1145,17 @@ impl ToolManager {
             name: "Bun".to_string(),
             full_name: "Bun JavaScript Runtime".to_string(),
             path: bun_path.to_string_lossy().to_string(),
+            installed_versions: bun_version.iter().cloned().collect(),
             version: bun_version,
             status: bun_status,
             last_check: Some(chrono::Utc::now().to_rfc3339()),
             python_required: false,
+            pinned_version: Some(BUN_VERSION.to_string()),
+            rollback_available: self.has_backup(ManagedTool::Bun).await,
         });
 
         // UV tool
+        let uv_rollback = self.has_backup(ManagedTool::Uv).await;
         let uv_path = self.get_tool_path("uv");
         let (uv_status, uv_version) = if self.tool_exists("uv").await {
             let version = self.get_tool_version("uv");
@@ -485,9 +1169,12 @@ impl ToolManager {
             full_name: "UV Package Manager".to_string(),
             path: uv_path.to_string_lossy().to_string(),
             version: uv_version.clone(),
+            installed_versions: uv_version.iter().cloned().collect(),
             status: uv_status,
             last_check: Some(chrono::Utc::now().to_rfc3339()),
             python_required: true,
+            pinned_version: Some(UV_VERSION.to_string()),
+            rollback_available: uv_rollback,
         });
 
         // UVX tool (separate executable from UV)
@@ -503,10 +1190,51 @@ impl ToolManager {
             name: "UVX".to_string(),
             full_name: "UVX Package Executor".to_string(),
             path: uvx_path.to_string_lossy().to_string(),
+            installed_versions: uvx_version.iter().cloned().collect(),
             version: uvx_version,
             status: uvx_status,
             last_check: Some(chrono::Utc::now().to_rfc3339()),
             python_required: true,
+            pinned_version: Some(UV_VERSION.to_string()),
+            rollback_available: uv_rollback,
+        });
+
+        // Node.js（按版本安装在 node/<version> 下）
+        let node_versions = self.installed_node_versions().await;
+        tools.push(crate::types::ToolInfo {
+            name: "Node.js".to_string(),
+            full_name: "Node.js JavaScript Runtime".to_string(),
+            path: self.node_root().to_string_lossy().to_string(),
+            version: node_versions.last().cloned(),
+            status: if node_versions.is_empty() {
+                crate::types::ToolStatus::NotInstalled
+            } else {
+                crate::types::ToolStatus::Installed
+            },
+            last_check: Some(chrono::Utc::now().to_rfc3339()),
+            python_required: false,
+            pinned_version: Some(NODE_VERSION.to_string()),
+            rollback_available: false,
+            installed_versions: node_versions,
+        });
+
+        // uv 管理的 Python
+        let python_versions = self.installed_python_versions().await;
+        tools.push(crate::types::ToolInfo {
+            name: "Python".to_string(),
+            full_name: "Python (managed by uv)".to_string(),
+            path: self.python_dir().to_string_lossy().to_string(),
+            version: python_versions.last().cloned(),
+            status: if python_versions.is_empty() {
+                crate::types::ToolStatus::NotInstalled
+            } else {
+                crate::types::ToolStatus::Installed
+            },
+            last_check: Some(chrono::Utc::now().to_rfc3339()),
+            python_required: false,
+            pinned_version: Some(PYTHON_VERSION.to_string()),
+            rollback_available: false,
+            installed_versions: python_versions,
         });
 
         // Update cached tools
@@ -517,6 +1245,11 @@ impl ToolManager {
 
     /// Check Python runtime compatibility
     pub async fn check_python_runtime(&self) -> Result<(bool, Option<String>)> {
+        // uv 管理的 Python 优先于系统 Python
+        if let Some(version) = self.installed_python_versions().await.pop() {
+            return Ok((true, Some(version)));
+        }
+
         let output = TokioCommand::new("python3").arg("--version").output().await;
 
         match output {
@@ -548,7 +1281,7 @@ impl ToolManager {
         // Check Python first
         let (python_available, python_version) = self.check_python_runtime().await?;
         if !python_available {
-            tracing::warn!("Python not found, UV tools will not work properly");
+            tracing::warn!("Python not found, uv will install a managed Python on demand");
         } else {
             tracing::info!("Python found: {:?}", python_version);
         }
@@ -559,46 +1292,54 @@ impl ToolManager {
             return Err(e);
         }
 
-        // Install UV and UVX
-        if python_available {
-            if let Err(e) = self.install_uv_tools().await {
-                tracing::error!("Failed to install UV tools: {}", e);
-                return Err(e);
-            }
-        } else {
-            tracing::warn!("Skipping UV tools installation due to missing Python");
+        // Install UV and UVX（uv 可以自行安装 Python，不依赖系统 Python）
+        if let Err(e) = self.install_uv_tools().await {
+            tracing::error!("Failed to install UV tools: {}", e);
+            return Err(e);
         }
 
         tracing::info!("All tools installed successfully");
         Ok(())
     }
 
-    /// Install a specific tool
-    pub async fn install_tool(&self, tool_name: &str) -> Result<()> {
-        tracing::info!("Installing tool: {}", tool_name);
-
-        // Remove existing tool if it exists
-        let tool_path = self.get_tool_path(tool_name);
-        if tokio::fs::metadata(&tool_path).await.is_ok() {
-            tokio::fs::remove_file(&tool_path).await?;
+    /// Install a specific tool: upgrade / downgrade to a version, install offline or roll back
+    pub async fn install_tool(&self, tool_name: &str, options: &InstallOptions) -> Result<()> {
+        // Node.js / Python 按版本并存安装，不需要回滚
+        let runtime = tool_name.to_lowercase();
+        let is_runtime = matches!(runtime.as_str(), "node" | "nodejs" | "node.js" | "python");
+        if is_runtime && options.rollback {
+            return Err(McpError::RuntimeError(format!(
+                "Rollback is not supported for {}, install the wanted version instead",
+                tool_name
+            )));
         }
-
-        // Install based on tool type (case-insensitive)
-        match tool_name.to_lowercase().as_str() {
-            "bun" => {
-                self.install_bun().await?;
-                Ok(())
+        match runtime.as_str() {
+            "node" | "nodejs" | "node.js" => return self.install_node(options).await.map(|_| ()),
+            "python" => {
+                return self
+                    .install_python(options.version.as_deref().unwrap_or(PYTHON_VERSION))
+                    .await
             }
-            "uv" | "uvx" => {
-                if let Ok((true, _)) = self.check_python_runtime().await {
-                    self.install_uv_tools().await?;
-                    Ok(())
-                } else {
-                    Err(McpError::RuntimeError("Python not available".to_string()))
-                }
+            _ => {}
+        }
+
+        let tool = ManagedTool::from_name(tool_name)
+            .ok_or_else(|| McpError::InvalidTool(tool_name.to_string()))?;
+
+        if options.rollback {
+            if !self.has_backup(tool).await {
+                return Err(McpError::RuntimeError(format!(
+                    "No previous version of {} to roll back to",
+                    tool.name()
+                )));
             }
-            _ => Err(McpError::InvalidTool(tool_name.to_string())),
+            self.swap_backup(tool).await?;
+            tracing::info!("Rolled back {} to the previous version", tool.name());
+            return Ok(());
         }
+
+        tracing::info!("Installing tool: {}", tool_name);
+        self.install_release(tool, options).await
     }
 
     /// Get a summary of tool status for startup check
@@ -624,6 +1365,8 @@ impl ToolManager {
             python_available,
             missing_tools: tools_info
                 .into_iter()
+                // Node.js / Python 在服务固定版本时按需安装，不算缺失
+                .filter(|t| !matches!(t.name.as_str(), "Node.js" | "Python"))
                 .filter(|t| t.status != crate::types::ToolStatus::Installed)
                 .map(|t| t.name)
                 .collect(),
@@ -633,7 +1376,10 @@ impl ToolManager {
 
 #[cfg(test)]
 mod tests {
-    use crate::tool_manager::ToolManager;
+    use crate::tool_manager::{
+        managed_python_version, node_asset, parse_checksum, parse_version, verify_sha256,
+        version_matches, ManagedTool, ToolManager,
+    };
 
     #[test]
     fn test_convert_npx_to_bun_x() {
@@ -844,5 +1590,82 @@ mod tests {
         assert_eq!(tool_manager.convert_command("uvx run my-script"), "uvx run my-script");
         assert_eq!(tool_manager.convert_command("npm install"), "npm install");
     }
-}
 
+    #[test]
+    fn test_parse_checksum() {
+        let bun_hash = "a".repeat(64);
+        let other_hash = "b".repeat(64);
+        let shasums = format!(
+            "{}  bun-darwin-x64.zip\n{} *bun-linux-x64.zip\n",
+            other_hash, bun_hash
+        );
+        assert_eq!(parse_checksum(&shasums, "bun-linux-x64.zip"), Some(bun_hash.clone()));
+        assert_eq!(parse_checksum(&shasums, "bun-windows-x64.zip"), None);
+
+        // 单个哈希值的校验文件（uv 的 <asset>.sha256）
+        let single = format!("{}  uv-x86_64-unknown-linux-gnu.tar.gz\n", bun_hash.to_uppercase());
+        assert_eq!(parse_checksum(&single, "local.tar.gz"), Some(bun_hash));
+        assert_eq!(parse_checksum("not a checksum", "bun-linux-x64.zip"), None);
+    }
+
+    #[test]
+    fn test_checksum_url_ignores_mirror() {
+        let url = ManagedTool::Uv.checksum_url("0.8.22", "uv-x86_64-unknown-linux-gnu.tar.gz");
+        assert_eq!(
+            url,
+            "https://github.com/astral-sh/uv/releases/download/0.8.22/uv-x86_64-unknown-linux-gnu.tar.gz.sha256"
+        );
+        assert!(ManagedTool::Bun
+            .checksum_url("1.2.23", "bun-linux-x64.zip")
+            .starts_with("https://github.com/oven-sh/bun/releases/download/bun-v1.2.23/"));
+    }
+
+    #[test]
+    fn test_verify_sha256() {
+        let expected = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert!(verify_sha256(b"hello", expected, "test").is_ok());
+        assert!(verify_sha256(b"hello", &expected.to_uppercase(), "test").is_ok());
+        assert!(verify_sha256(b"world", expected, "test").is_err());
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            parse_version("uv 0.8.22 (ade2bdbd2 2025-09-23)").as_deref(),
+            Some("0.8.22")
+        );
+        assert_eq!(parse_version("1.2.23\n").as_deref(), Some("1.2.23"));
+        assert_eq!(parse_version("v22.20.0").as_deref(), Some("22.20.0"));
+        assert_eq!(parse_version("Python 3.12.11").as_deref(), Some("3.12.11"));
+        assert_eq!(parse_version("unknown"), None);
+    }
+
+    #[test]
+    fn test_managed_runtime_versions() {
+        assert_eq!(
+            managed_python_version("cpython-3.12.11-linux-x86_64-gnu").as_deref(),
+            Some("3.12.11")
+        );
+        assert_eq!(managed_python_version(".lock"), None);
+        assert!(version_matches("20.19.5", "20"));
+        assert!(version_matches("3.12.11", "3.12"));
+        assert!(!version_matches("3.120.1", "3.12"));
+        assert!(is_release_version("22.20.0"));
+        assert!(!is_release_version("22"));
+        assert!(!is_release_version("../../x.1.2"));
+        assert!(!is_release_version("22.20.0/.."));
+        assert!(is_version_request("20"));
+        assert!(is_version_request("3.12"));
+        assert!(!is_version_request(""));
+        assert!(!is_version_request("3..12"));
+        assert!(!is_version_request("--help"));
+        assert_eq!(
+            node_asset("22.20.0", "linux", "x86_64").unwrap(),
+            "node-v22.20.0-linux-x64.tar.gz"
+        );
+        assert_eq!(
+            node_asset("22.20.0", "windows", "aarch64").unwrap(),
+            "node-v22.20.0-win-arm64.zip"
+        );
+    }
+}
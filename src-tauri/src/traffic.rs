@@ -0,0 +1,386 @@
+// 聚合接口的连接数限制、请求体大小限制与流量统计
+
+use axum::body::Body;
+use axum::extract::connect_info::{ConnectInfo, Connected};
+use axum::extract::Request;
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::serve::IncomingStream;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{Instant, Sleep};
+
+/// 空闲连接的最短超时时间
+const MIN_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 聚合接口的实时流量统计与限制
+pub struct AggregatorTraffic {
+    max_connections: usize,
+    max_body_size: usize,
+    idle_timeout: Duration,
+    connection_permits: Arc<Semaphore>,
+    active_connections: AtomicUsize,
+    in_flight_requests: AtomicUsize,
+    rejected_requests: AtomicU64,
+}
+
+impl AggregatorTraffic {
+    /// `keep_alive` 为 SSE 心跳间隔，空闲超时至少是它的两倍，避免关闭仍在推送的流
+    pub fn new(max_connections: usize, max_body_size: usize, keep_alive: Duration) -> Self {
+        // max_connections 为 0 时视为 1，避免监听器永久阻塞；配置校验会拒绝过小的请求体上限
+        let max_connections = max_connections.max(1);
+        Self {
+            max_connections,
+            max_body_size: max_body_size.max(crate::types::MIN_BODY_SIZE),
+            idle_timeout: keep_alive.saturating_mul(2).max(MIN_IDLE_TIMEOUT),
+            connection_permits: Arc::new(Semaphore::new(max_connections)),
+            active_connections: AtomicUsize::new(0),
+            in_flight_requests: AtomicUsize::new(0),
+            rejected_requests: AtomicU64::new(0),
+        }
+    }
+
+    pub fn active_connections(&self) -> usize {
+        self.active_connections.load(Ordering::Relaxed)
+    }
+
+    pub fn in_flight_requests(&self) -> usize {
+        self.in_flight_requests.load(Ordering::Relaxed)
+    }
+
+    pub fn rejected_requests(&self) -> u64 {
+        self.rejected_requests.load(Ordering::Relaxed)
+    }
+
+    pub fn max_connections(&self) -> usize {
+        self.max_connections
+    }
+
+    fn reject(&self, status: StatusCode, message: String) -> Response {
+        self.rejected_requests.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!("Rejected aggregator request ({}): {}", status, message);
+        (status, message).into_response()
+    }
+}
+
+/// 单个连接的活动状态，由连接和其上处理中的请求共享
+#[derive(Debug)]
+pub struct ConnectionActivity {
+    in_flight: AtomicUsize,
+    last_active: Mutex<Instant>,
+    idle_timeout: Duration,
+    /// 请求处理期间挂起的读操作，请求结束后唤醒以启动空闲计时
+    read_waker: Mutex<Option<Waker>>,
+}
+
+impl ConnectionActivity {
+    fn new(idle_timeout: Duration) -> Self {
+        Self {
+            in_flight: AtomicUsize::new(0),
+            last_active: Mutex::new(Instant::now()),
+            idle_timeout,
+            read_waker: Mutex::new(None),
+        }
+    }
+
+    fn touch(&self) {
+        *self.last_active.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+    }
+
+    /// 没有处理中的请求时，连接在该时间点之后视为空闲
+    fn idle_deadline(&self) -> Option<Instant> {
+        if self.in_flight.load(Ordering::Acquire) > 0 {
+            return None;
+        }
+        let last_active = *self.last_active.lock().unwrap_or_else(|e| e.into_inner());
+        Some(last_active + self.idle_timeout)
+    }
+}
+
+/// 通过 `ConnectInfo` 传给请求的连接句柄
+#[derive(Debug, Clone)]
+pub struct ConnectionHandle(Arc<ConnectionActivity>);
+
+impl Connected<IncomingStream<'_, LimitedListener>> for ConnectionHandle {
+    fn connect_info(stream: IncomingStream<'_, LimitedListener>) -> Self {
+        Self(Arc::clone(&stream.io().activity))
+    }
+}
+
+/// 连接上处理中的请求，drop 时结束
+struct RequestActivity(Arc<ConnectionActivity>);
+
+impl RequestActivity {
+    fn begin(activity: Arc<ConnectionActivity>) -> Self {
+        activity.in_flight.fetch_add(1, Ordering::AcqRel);
+        Self(activity)
+    }
+}
+
+impl Drop for RequestActivity {
+    fn drop(&mut self) {
+        self.0.touch();
+        if self.0.in_flight.fetch_sub(1, Ordering::AcqRel) == 1 {
+            let waker = self
+                .0
+                .read_waker
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .take();
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+}
+
+/// 限制并发连接数的 TCP 监听器
+///
+/// 连接数达到上限时暂停 accept，新连接在内核队列中等待（背压），
+/// 直到已有连接关闭。没有处理中的请求、也没有读写的 keep-alive 连接
+/// 超过空闲超时后会被关闭，释放连接名额。
+pub struct LimitedListener {
+    inner: TcpListener,
+    traffic: Arc<AggregatorTraffic>,
+}
+
+impl LimitedListener {
+    pub fn new(inner: TcpListener, traffic: Arc<AggregatorTraffic>) -> Self {
+        Self { inner, traffic }
+    }
+}
+
+impl axum::serve::Listener for LimitedListener {
+    type Io = CountedStream;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        let permits = Arc::clone(&self.traffic.connection_permits);
+        let permit = match permits.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                tracing::warn!(
+                    "Aggregator reached max_connections ({}), waiting for a free slot",
+                    self.traffic.max_connections
+                );
+                permits
+                    .acquire_owned()
+                    .await
+                    .expect("connection semaphore is never closed")
+            }
+        };
+
+        let (stream, addr) = axum::serve::Listener::accept(&mut self.inner).await;
+        self.traffic
+            .active_connections
+            .fetch_add(1, Ordering::Relaxed);
+
+        let idle_timeout = self.traffic.idle_timeout;
+        (
+            CountedStream {
+                inner: stream,
+                traffic: Arc::clone(&self.traffic),
+                activity: Arc::new(ConnectionActivity::new(idle_timeout)),
+                idle_timer: Box::pin(tokio::time::sleep(idle_timeout)),
+                _permit: permit,
+            },
+            addr,
+        )
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        self.inner.local_addr()
+    }
+}
+
+/// 关闭时自动释放连接名额的 TCP 连接
+pub struct CountedStream {
+    inner: TcpStream,
+    traffic: Arc<AggregatorTraffic>,
+    activity: Arc<ConnectionActivity>,
+    idle_timer: Pin<Box<Sleep>>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl CountedStream {
+    /// 连接空闲超时后返回 true，需在读操作挂起时调用以注册定时器
+    fn poll_idle(&mut self, cx: &mut Context<'_>) -> bool {
+        if self.activity.idle_deadline().is_none() {
+            *self
+                .activity
+                .read_waker
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()) = Some(cx.waker().clone());
+        }
+        // 保存唤醒器之后再检查一次，请求可能恰好在此期间结束
+        let Some(deadline) = self.activity.idle_deadline() else {
+            return false;
+        };
+        if self.idle_timer.deadline() != deadline {
+            self.idle_timer.as_mut().reset(deadline);
+        }
+        self.idle_timer.as_mut().poll(cx).is_ready()
+    }
+}
+
+impl Drop for CountedStream {
+    fn drop(&mut self) {
+        self.traffic
+            .active_connections
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl AsyncRead for CountedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let filled = buf.filled().len();
+        match Pin::new(&mut self.inner).poll_read(cx, buf) {
+            Poll::Ready(result) => {
+                if buf.filled().len() > filled {
+                    self.activity.touch();
+                }
+                Poll::Ready(result)
+            }
+            // 空闲超时后返回 EOF，由 hyper 关闭连接
+            Poll::Pending if self.poll_idle(cx) => {
+                tracing::debug!("Closing idle aggregator connection");
+                Poll::Ready(Ok(()))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+impl AsyncWrite for CountedStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let result = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if matches!(result, Poll::Ready(Ok(n)) if n > 0) {
+            self.activity.touch();
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        let result = Pin::new(&mut self.inner).poll_write_vectored(cx, bufs);
+        if matches!(result, Poll::Ready(Ok(n)) if n > 0) {
+            self.activity.touch();
+        }
+        result
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+}
+
+/// 在途请求计数，drop 时自动减一
+struct InFlightGuard {
+    traffic: Arc<AggregatorTraffic>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.traffic
+            .in_flight_requests
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// 请求限制中间件：限制请求体大小和在途请求数，并统计流量
+///
+/// - 请求体超过 `max_body_size` 返回 413
+/// - 在途请求数超过 `max_connections`（例如 HTTP/2 多路复用）返回 503
+/// - 处理期间连接不会因空闲超时被关闭
+pub async fn traffic_limit_middleware(
+    req: Request,
+    next: Next,
+    traffic: Arc<AggregatorTraffic>,
+) -> Response {
+    let _activity = req
+        .extensions()
+        .get::<ConnectInfo<ConnectionHandle>>()
+        .map(|ConnectInfo(handle)| RequestActivity::begin(Arc::clone(&handle.0)));
+
+    // 先根据 Content-Length 快速拒绝
+    let content_length = req
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if let Some(length) = content_length {
+        if length > traffic.max_body_size {
+            return traffic.reject(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "Request body too large: {} bytes (limit {} bytes)",
+                    length, traffic.max_body_size
+                ),
+            );
+        }
+    }
+
+    let in_flight = traffic.in_flight_requests.fetch_add(1, Ordering::Relaxed) + 1;
+    let _guard = InFlightGuard {
+        traffic: Arc::clone(&traffic),
+    };
+    if in_flight > traffic.max_connections {
+        let mut response = traffic.reject(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!(
+                "Too many in-flight requests (limit {})",
+                traffic.max_connections
+            ),
+        );
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, header::HeaderValue::from_static("1"));
+        return response;
+    }
+
+    // 分块传输没有 Content-Length，读取时限制大小
+    let (parts, body) = req.into_parts();
+    let bytes = match axum::body::to_bytes(body, traffic.max_body_size).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            return traffic.reject(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!(
+                    "Request body too large (limit {} bytes)",
+                    traffic.max_body_size
+                ),
+            );
+        }
+    };
+
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
@@ -29,6 +29,55 @@ pub struct McpServerConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<HashMap<String, String>>,
     pub enabled: bool,
+    /// 单次请求（call_tool / get_prompt / read_resource）超时秒数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_timeout: Option<u64>,
+    /// 建立连接超时秒数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+    /// 最大并发调用数，未设置时不限制
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_calls: Option<u32>,
+    /// 生命周期：eager 在启动时连接，lazy 在首次调用时启动
+    #[serde(default)]
+    pub lifecycle: ServerLifecycle,
+    /// lazy 服务空闲多少秒后停止
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idle_timeout: Option<u64>,
+    /// STDIO 子进程退出后的重启策略
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
+    /// on-failure 策略下的最大重启次数
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_restarts: Option<u32>,
+    /// STDIO 进程树内存上限（MB），超出后重启服务
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_limit_mb: Option<u64>,
+    /// STDIO 服务沙箱配置，未设置时不隔离
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<crate::sandbox::SandboxProfile>,
+    /// STDIO 进程工作目录，支持 `~/` 开头
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// 从 Shell 环境继承变量的策略
+    #[serde(default)]
+    pub env_inheritance: EnvInheritance,
+    /// allowlist 策略下继承的变量名，以 `*` 结尾表示前缀（如 `NODE_*`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env_allowlist: Option<Vec<String>>,
+    /// 在 Docker / Podman 容器中运行，设置后不需要 command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<crate::container::ContainerConfig>,
+    /// npx / npm / node 命令使用的运行时
+    #[serde(default)]
+    pub runtime: ServerRuntime,
+    /// 固定的运行时版本：npx / npm / node 命令使用该版本的内置 Node.js，
+    /// uv / uvx 命令使用该版本的 uv 管理的 Python
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime_version: Option<String>,
+    /// HTTP 服务的凭据助手，输出的令牌作为请求头注入
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_helper: Option<crate::credential_helper::CredentialHelper>,
 }
 
 impl McpServerConfig {
@@ -77,10 +126,131 @@ impl McpServerConfig {
             url: None,
             headers: None,
             enabled: true,
+            request_timeout: None,
+            connect_timeout: None,
+            max_concurrent_calls: None,
+            lifecycle: ServerLifecycle::default(),
+            idle_timeout: None,
+            restart_policy: RestartPolicy::default(),
+            max_restarts: None,
+            memory_limit_mb: None,
+            sandbox: None,
+            cwd: None,
+            env_inheritance: EnvInheritance::default(),
+            env_allowlist: None,
+            container: None,
+            runtime: ServerRuntime::default(),
+            runtime_version: None,
+            credential_helper: None,
         }
     }
+
+    /// 请求超时，未配置时使用默认值
+    pub fn request_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.request_timeout
+                .filter(|v| *v > 0)
+                .unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS),
+        )
+    }
+
+    /// 连接超时，未配置时使用默认值
+    pub fn connect_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.connect_timeout
+                .filter(|v| *v > 0)
+                .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS),
+        )
+    }
+
+    /// 空闲超时，未配置时使用默认值
+    pub fn idle_timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.idle_timeout
+                .filter(|v| *v > 0)
+                .unwrap_or(DEFAULT_IDLE_TIMEOUT_SECS),
+        )
+    }
+
+    pub fn is_lazy(&self) -> bool {
+        self.lifecycle == ServerLifecycle::Lazy
+    }
+
+    /// on-failure 策略的最大重启次数，未配置时使用默认值
+    pub fn max_restarts(&self) -> u32 {
+        self.max_restarts.unwrap_or(DEFAULT_MAX_RESTARTS)
+    }
+
+    /// 工作目录，展开开头的 `~/`
+    pub fn resolved_cwd(&self) -> Option<std::path::PathBuf> {
+        let cwd = self
+            .cwd
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())?;
+        match (cwd.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(rest), Some(home)) => Some(home.join(rest)),
+            _ => Some(std::path::PathBuf::from(cwd)),
+        }
+    }
+
+    /// 校验工作目录：展开 `~/` 后必须是已存在目录的绝对路径
+    pub fn validated_cwd(&self) -> crate::error::Result<Option<std::path::PathBuf>> {
+        let Some(dir) = self.resolved_cwd() else {
+            return Ok(None);
+        };
+        if !dir.is_absolute() {
+            return Err(crate::error::McpError::InvalidConfiguration(format!(
+                "Working directory '{}' must be an absolute path",
+                dir.display()
+            )));
+        }
+        if !dir.is_dir() {
+            return Err(crate::error::McpError::InvalidConfiguration(format!(
+                "Working directory '{}' does not exist",
+                dir.display()
+            )));
+        }
+        Ok(Some(dir))
+    }
+
+    /// 是否从 Shell 环境继承该变量
+    pub fn inherits_env(&self, key: &str) -> bool {
+        match self.env_inheritance {
+            EnvInheritance::InheritAll => true,
+            EnvInheritance::None => BASE_ENV_VARS.contains(&key),
+            EnvInheritance::Allowlist => BASE_ENV_VARS.contains(&key) || self.env_allowlisted(key),
+        }
+    }
+
+    /// 变量是否在 env_allowlist 中，HTTP 服务的 url / headers 只能引用这些变量
+    pub fn env_allowlisted(&self, key: &str) -> bool {
+        self.env_allowlist
+            .iter()
+            .flatten()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => key.starts_with(prefix),
+                None => key == pattern,
+            })
+    }
+
+    /// 内存上限（字节），未配置或为 0 时不限制
+    pub fn memory_limit_bytes(&self) -> Option<u64> {
+        self.memory_limit_mb
+            .filter(|v| *v > 0)
+            .map(|v| v * 1024 * 1024)
+    }
 }
 
+/// 默认请求超时（秒）
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 60;
+/// 默认连接超时（秒）
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 30;
+/// lazy 服务默认空闲超时（秒）
+pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 600;
+/// on-failure 策略默认最大重启次数
+pub const DEFAULT_MAX_RESTARTS: u32 = 5;
+
 // Conditional serialization helpers removed
 
 #[derive(
@@ -93,6 +263,141 @@ pub enum ServiceTransport {
     Http,
 }
 
+/// 服务生命周期
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ServerLifecycle {
+    /// 应用启动时连接并保持运行
+    #[default]
+    Eager,
+    /// 首次调用时启动，空闲超时后停止
+    Lazy,
+}
+
+/// 运行所需的基础环境变量，none / allowlist 策略下也会继承
+pub const BASE_ENV_VARS: &[&str] = &[
+    "PATH", "HOME", "USER", "LOGNAME", "LANG", "LC_ALL", "LC_CTYPE", "TZ", "TMPDIR", "TERM",
+];
+
+/// STDIO 服务从 Shell 环境继承变量的策略
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum EnvInheritance {
+    /// 继承全部 Shell 环境变量
+    #[default]
+    InheritAll,
+    /// 只继承基础变量和 env_allowlist 中的变量
+    Allowlist,
+    /// 只继承基础变量
+    None,
+}
+
+/// npx / npm / node 命令使用的运行时，序列化为 `auto`、`bun`、`node`、`system-node` 或可执行文件路径
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(from = "String", into = "String")]
+pub enum ServerRuntime {
+    /// npx / npm 使用内置 Bun，node 优先使用系统 Node.js；设置中指定了命令路径时使用该路径，
+    /// 内置 Bun 不可用时退回系统命令
+    #[default]
+    Auto,
+    /// 使用内置 Bun（npx 转换为 `bun x`）
+    Bun,
+    /// 使用内置 Node.js（安装在 ~/.mcprouter/bin/node 下）
+    Node,
+    /// 使用系统 Node.js，不转换命令
+    SystemNode,
+    /// 使用指定的可执行文件替换命令
+    Path(String),
+}
+
+impl From<String> for ServerRuntime {
+    fn from(value: String) -> Self {
+        match value.trim() {
+            "" | "auto" => Self::Auto,
+            "bun" => Self::Bun,
+            "node" => Self::Node,
+            "system-node" => Self::SystemNode,
+            path => Self::Path(path.to_string()),
+        }
+    }
+}
+
+impl From<ServerRuntime> for String {
+    fn from(value: ServerRuntime) -> Self {
+        value.to_string()
+    }
+}
+
+impl std::fmt::Display for ServerRuntime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Bun => write!(f, "bun"),
+            Self::Node => write!(f, "node"),
+            Self::SystemNode => write!(f, "system-node"),
+            Self::Path(path) => write!(f, "{}", path),
+        }
+    }
+}
+
+/// 实际启动 STDIO 服务的运行时
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LaunchedRuntime {
+    /// bun | node | system-node | path | managed | system | container
+    pub kind: String,
+    /// 实际执行的可执行文件
+    pub executable: String,
+}
+
+/// STDIO 子进程重启策略
+#[derive(
+    Debug,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum RestartPolicy {
+    /// 进程退出后不重启
+    Never,
+    /// 非正常退出（或无响应）时重启，最多 max_restarts 次
+    #[default]
+    OnFailure,
+    /// 无论退出码如何都重启
+    Always,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct AppConfig {
@@ -135,6 +440,24 @@ pub struct Settings {
     pub npm_registry: Option<String>,
     #[serde(default)]
     pub command_paths: HashMap<String, String>,
+    /// 加载环境变量使用的登录 Shell，未设置时使用 $SHELL
+    #[serde(default)]
+    pub shell_path: Option<String>,
+    /// Shell 环境缓存时间（秒）
+    #[serde(default)]
+    pub shell_env_ttl: Option<u64>,
+    /// Bun / uv 下载镜像地址，替换 https://github.com
+    #[serde(default)]
+    pub tool_mirror_url: Option<String>,
+    /// Node.js 下载镜像地址，替换 https://nodejs.org/dist
+    #[serde(default)]
+    pub node_mirror_url: Option<String>,
+    /// 声明式配置文件（JSON / TOML / YAML），描述服务、工具开关和 Token 授权
+    #[serde(default)]
+    pub config_file: Option<String>,
+    /// 服务配置中 `${workspace}` 占位符对应的目录
+    #[serde(default)]
+    pub workspace: Option<String>,
 }
 
 fn default_theme() -> Option<String> {
@@ -154,19 +477,78 @@ pub struct ServerConfig {
     pub timeout_seconds: u64,
     #[serde(default)]
     pub auth: bool, // Controls whether authentication is enabled
+    /// 是否启用内置 OAuth 2.1 授权服务器（需同时开启 auth）
+    #[serde(default)]
+    pub oauth: bool,
+    /// 聚合接口允许的最大请求体大小（字节）
+    #[serde(default = "default_max_body_size")]
+    pub max_body_size: usize,
 }
 
+fn default_max_body_size() -> usize {
+    4 * 1024 * 1024
+}
+
+/// 聚合接口请求体大小上限的允许范围（字节）
+pub const MIN_BODY_SIZE: usize = 1024;
+pub const MAX_BODY_SIZE: usize = 1024 * 1024 * 1024;
+
 impl ServerConfig {
     /// Validate server configuration
     pub fn validate(&self) -> Result<(), crate::config::ConfigError> {
-        // No specific validation needed for now
+        use crate::config::ConfigError;
+
+        // 与聚合服务启动时解析监听地址的方式一致
+        format!("{}:{}", self.host, self.port)
+            .parse::<std::net::SocketAddr>()
+            .map_err(|_| {
+                ConfigError::Invalid(format!(
+                    "server.host '{}' is not a valid IP address",
+                    self.host
+                ))
+            })?;
+        if self.port == 0 {
+            return Err(ConfigError::Invalid(
+                "server.port must be between 1 and 65535".to_string(),
+            ));
+        }
+        if self.max_connections == 0 {
+            return Err(ConfigError::Invalid(
+                "server.max_connections must be greater than 0".to_string(),
+            ));
+        }
+        if self.timeout_seconds == 0 {
+            return Err(ConfigError::Invalid(
+                "server.timeout_seconds must be greater than 0".to_string(),
+            ));
+        }
+        if !(MIN_BODY_SIZE..=MAX_BODY_SIZE).contains(&self.max_body_size) {
+            return Err(ConfigError::Invalid(format!(
+                "server.max_body_size must be between {} and {} bytes",
+                MIN_BODY_SIZE, MAX_BODY_SIZE
+            )));
+        }
         Ok(())
     }
 
+    /// 修改后需要重新监听的字段（auth 可以在运行时切换）
+    pub fn requires_rebind(&self, other: &ServerConfig) -> bool {
+        self.host != other.host
+            || self.port != other.port
+            || self.max_connections != other.max_connections
+            || self.timeout_seconds != other.timeout_seconds
+            || self.max_body_size != other.max_body_size
+    }
+
     /// Check if authentication is enabled
     pub fn is_auth_enabled(&self) -> bool {
         self.auth
     }
+
+    /// Check if the built-in OAuth authorization server is enabled
+    pub fn is_oauth_enabled(&self) -> bool {
+        self.auth && self.oauth
+    }
 }
 
 // ============================================================================
@@ -264,6 +646,10 @@ pub struct ServiceStatus {
     pub version: Option<String>,
     pub start_time: Option<chrono::DateTime<chrono::Utc>>,
     pub error_message: Option<String>,
+    pub restart_count: u32,
+    pub last_exit_reason: Option<String>,
+    pub resource_usage: Option<crate::resource_monitor::ProcessUsage>,
+    pub runtime: Option<LaunchedRuntime>,
 }
 
 // 合并后的响应结构体，包含状态和配置信息
@@ -299,6 +685,46 @@ pub struct McpServerInfo {
     pub prompt_count: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prompt_template_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub circuit_breaker: Option<crate::circuit_breaker::CircuitBreakerSnapshot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_calls: Option<u32>,
+    pub lifecycle: ServerLifecycle,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idle_timeout: Option<u64>,
+    pub restart_policy: RestartPolicy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_restarts: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_limit_mb: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<crate::sandbox::SandboxProfile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    pub env_inheritance: EnvInheritance,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env_allowlist: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<crate::container::ContainerConfig>,
+    pub runtime: ServerRuntime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_helper: Option<crate::credential_helper::CredentialHelper>,
+    /// 当前进程实际使用的运行时
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_runtime: Option<LaunchedRuntime>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_usage: Option<crate::resource_monitor::ProcessUsage>,
+    /// 当前机器上无法解析的占位符
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unresolved_placeholders: Option<Vec<String>>,
+    /// 已保存下游 OAuth 授权
+    pub oauth_authorized: bool,
 }
 
 /// MCP服务器列表分页结果
@@ -421,15 +847,39 @@ pub struct McpConnection {
 }
 
 impl AppConfig {
-    /// 从文件加载配置
-    pub fn load() -> Result<Self, crate::config::ConfigError> {
+    /// 配置文件路径 ~/.mcprouter/config.json
+    pub fn config_path() -> std::path::PathBuf {
         // Resolve home directory cross-platform
         let home_dir = std::env::var("HOME")
             .or_else(|_| std::env::var("USERPROFILE"))
             .unwrap_or_else(|_| ".".to_string());
-        let app_data_dir = std::path::PathBuf::from(format!("{}/.mcprouter", home_dir));
+        std::path::PathBuf::from(format!("{}/.mcprouter", home_dir)).join("config.json")
+    }
 
-        let config_path = app_data_dir.join("config.json");
+    /// 校验配置，热加载时拒绝无效的修改
+    pub fn validate(&self) -> Result<(), crate::config::ConfigError> {
+        self.server.validate()?;
+        if let Some(logging) = &self.logging {
+            if !matches!(
+                logging.level.to_ascii_lowercase().as_str(),
+                "trace" | "debug" | "info" | "warn" | "error"
+            ) {
+                return Err(crate::config::ConfigError::Invalid(format!(
+                    "logging.level '{}' must be one of trace, debug, info, warn, error",
+                    logging.level
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// 从文件加载配置
+    pub fn load() -> Result<Self, crate::config::ConfigError> {
+        let config_path = Self::config_path();
+        let app_data_dir = config_path
+            .parent()
+            .map(std::path::Path::to_path_buf)
+            .unwrap_or_default();
 
         // Migration: read old path if new path not exists
         if !config_path.exists() {
@@ -474,12 +924,7 @@ impl AppConfig {
 
     /// 保存配置到文件
     pub fn save(&self) -> Result<(), crate::config::ConfigError> {
-        let home_dir = std::env::var("HOME")
-            .or_else(|_| std::env::var("USERPROFILE"))
-            .unwrap_or_else(|_| ".".to_string());
-        let app_data_dir = std::path::PathBuf::from(format!("{}/.mcprouter", home_dir));
-
-        let config_path = app_data_dir.join("config.json");
+        let config_path = Self::config_path();
 
         if let Some(parent) = config_path.parent() {
             let _ = std::fs::create_dir_all(parent);
@@ -497,6 +942,8 @@ impl Default for AppConfig {
                 max_connections: 100,
                 timeout_seconds: 30,
                 auth: false, // Default to false for backward compatibility
+                oauth: false,
+                max_body_size: default_max_body_size(),
             },
             logging: Some(crate::types::LoggingSettings {
                 level: "info".to_string(),
@@ -515,6 +962,12 @@ impl Default for AppConfig {
                 uv_index_url: None,
                 npm_registry: None,
                 command_paths: Default::default(),
+                shell_path: None,
+                shell_env_ttl: None,
+                tool_mirror_url: None,
+                node_mirror_url: None,
+                config_file: None,
+                workspace: None,
             }),
         }
     }
@@ -1007,8 +1460,11 @@ pub struct ToolInfo {
     pub path: String,      // Path to the tool executable
     pub version: Option<String>, // Auto-detected version
     pub status: ToolStatus,
-    pub last_check: Option<String>, // ISO timestamp
-    pub python_required: bool,      // Whether Python runtime is required
+    pub last_check: Option<String>,      // ISO timestamp
+    pub python_required: bool,           // Whether Python runtime is required
+    pub pinned_version: Option<String>,  // 默认安装的固定版本
+    pub installed_versions: Vec<String>, // 已安装的版本（Node.js / Python 可并存多个版本）
+    pub rollback_available: bool,        // 是否存在可回滚的上一版本
 }
 
 /// Python 运行时信息
@@ -1027,3 +1483,66 @@ pub struct ToolStartupStatus {
     pub python_available: bool,
     pub missing_tools: Vec<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stdio_config() -> McpServerConfig {
+        McpServerConfig::new(String::new(), "demo".to_string())
+    }
+
+    #[test]
+    fn test_inherits_env_by_policy() {
+        let mut config = stdio_config();
+        assert!(config.inherits_env("AWS_SECRET_ACCESS_KEY"));
+
+        config.env_inheritance = EnvInheritance::None;
+        assert!(config.inherits_env("PATH"));
+        assert!(!config.inherits_env("AWS_SECRET_ACCESS_KEY"));
+
+        config.env_inheritance = EnvInheritance::Allowlist;
+        config.env_allowlist = Some(vec!["GITHUB_TOKEN".to_string(), "NODE_*".to_string()]);
+        assert!(config.inherits_env("HOME"));
+        assert!(config.inherits_env("GITHUB_TOKEN"));
+        assert!(config.inherits_env("NODE_OPTIONS"));
+        assert!(!config.inherits_env("GITHUB_TOKEN_2"));
+        assert!(!config.inherits_env("AWS_SECRET_ACCESS_KEY"));
+    }
+
+    #[test]
+    fn test_resolved_cwd() {
+        let mut config = stdio_config();
+        assert_eq!(config.resolved_cwd(), None);
+
+        config.cwd = Some("  ".to_string());
+        assert_eq!(config.resolved_cwd(), None);
+
+        config.cwd = Some(" /srv/project ".to_string());
+        assert_eq!(
+            config.resolved_cwd(),
+            Some(std::path::PathBuf::from("/srv/project"))
+        );
+
+        if let Some(home) = dirs::home_dir() {
+            config.cwd = Some("~/project".to_string());
+            assert_eq!(config.resolved_cwd(), Some(home.join("project")));
+        }
+    }
+
+    #[test]
+    fn test_validated_cwd() {
+        let mut config = stdio_config();
+        assert!(config.validated_cwd().unwrap().is_none());
+
+        let dir = std::env::temp_dir();
+        config.cwd = Some(dir.display().to_string());
+        assert_eq!(config.validated_cwd().unwrap(), Some(dir.clone()));
+
+        config.cwd = Some("relative/dir".to_string());
+        assert!(config.validated_cwd().is_err());
+
+        config.cwd = Some(dir.join("mcprouter-missing-cwd").display().to_string());
+        assert!(config.validated_cwd().is_err());
+    }
+}
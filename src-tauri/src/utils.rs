@@ -0,0 +1,31 @@
+// 通用的小工具函数
+
+/// 把任意名称编码为只包含字母、数字、`-` 和 `_` 的字符串，可直接用作文件名或密钥名
+///
+/// 其余字节（包括 `_` 本身）编码为 `_xx`，不同的名称不会得到相同的结果，
+/// 也不会出现路径分隔符或以 `.` 开头。
+pub fn encode_name(name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("_{:02x}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_name_is_unambiguous() {
+        assert_eq!(encode_name("GitHub-Server"), "GitHub-Server");
+        assert_eq!(encode_name("a/b"), "a_2fb");
+        assert_eq!(encode_name("../x"), "_2e_2e_2fx");
+        assert_ne!(encode_name("a_b"), encode_name("a b"));
+        assert_ne!(encode_name("a_2fb"), encode_name("a/b"));
+    }
+}
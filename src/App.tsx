@@ -13,7 +13,9 @@ import { useTranslation } from 'react-i18next'
 import './App.css'
 import AboutModal from './components/AboutModal'
 import AntdConfigProvider from './components/AntdConfigProvider'
+import ConfigReloadListener from './components/ConfigReloadListener'
 import LanguageSelector from './components/LanguageSelector'
+import OAuthConsentListener from './components/OAuthConsentListener'
 import ThemeSelector from './components/ThemeSelector'
 import { AppProvider, useAppContext } from './contexts/AppContext'
 import Dashboard from './pages/Dashboard'
@@ -38,6 +40,8 @@ const AppContent = memo(() => {
   return (
     <AntdConfigProvider>
       <AntdApp>
+        <ConfigReloadListener />
+        <OAuthConsentListener />
         <div
           className={`h-screen overflow-hidden ${
             state.isDarkMode
@@ -0,0 +1,251 @@
+import {
+  App,
+  Button,
+  Card,
+  Flex,
+  Input,
+  Select,
+  Switch,
+  Table,
+  Tag,
+  Typography,
+} from 'antd'
+import { memo, useState } from 'react'
+import { useTranslation } from 'react-i18next'
+import { ConfigService } from '../services/config-service'
+import type {
+  BundleImportReport,
+  ConflictStrategy,
+  ImportResult,
+} from '../types'
+
+const { Text } = Typography
+
+const DEFAULT_BUNDLE_PATH = '~/mcprouter-bundle.zip'
+
+const RESULT_COLORS: Record<ImportResult['status'], string> = {
+  added: 'success',
+  replaced: 'processing',
+  renamed: 'processing',
+  skipped: 'default',
+  failed: 'error',
+}
+
+const BundleManager: React.FC = memo(() => {
+  const { t } = useTranslation()
+  const { message } = App.useApp()
+
+  const [exportPath, setExportPath] = useState(DEFAULT_BUNDLE_PATH)
+  const [redactSecrets, setRedactSecrets] = useState(true)
+  const [includeSettings, setIncludeSettings] = useState(true)
+  const [exporting, setExporting] = useState(false)
+
+  const [importPath, setImportPath] = useState(DEFAULT_BUNDLE_PATH)
+  const [strategy, setStrategy] = useState<ConflictStrategy>('skip')
+  const [importSettings, setImportSettings] = useState(false)
+  const [importing, setImporting] = useState(false)
+  const [report, setReport] = useState<BundleImportReport | null>(null)
+
+  const handleExport = async () => {
+    setExporting(true)
+    try {
+      const summary = await ConfigService.exportRouterBundle(
+        exportPath.trim(),
+        redactSecrets,
+        includeSettings,
+      )
+      message.success(
+        t('settings.backup.export_success', {
+          path: summary.path,
+          servers: summary.servers,
+          tokens: summary.tokens,
+        }),
+      )
+    } catch (error) {
+      console.error('Failed to export router bundle:', error)
+      message.error(`${t('settings.backup.export_failed')}: ${error}`)
+    } finally {
+      setExporting(false)
+    }
+  }
+
+  const handleImport = async () => {
+    setImporting(true)
+    setReport(null)
+    try {
+      const result = await ConfigService.importRouterBundle(
+        importPath.trim(),
+        strategy,
+        importSettings,
+      )
+      setReport(result)
+      message.success(t('settings.backup.import_success'))
+    } catch (error) {
+      console.error('Failed to import router bundle:', error)
+      message.error(`${t('settings.backup.import_failed')}: ${error}`)
+    } finally {
+      setImporting(false)
+    }
+  }
+
+  const switchRow = (
+    label: string,
+    description: string,
+    checked: boolean,
+    onChange: (checked: boolean) => void,
+  ) => (
+    <Flex justify='space-between' align='center'>
+      <div>
+        <Text strong>{label}</Text>
+        <Text
+          type='secondary'
+          style={{ fontSize: '14px', display: 'block', marginTop: '2px' }}>
+          {description}
+        </Text>
+      </div>
+      <Switch checked={checked} onChange={onChange} />
+    </Flex>
+  )
+
+  const resultColumns = (titleKey: string) => [
+    { title: t(titleKey), dataIndex: 'name', key: 'name' },
+    {
+      title: t('mcp_server.import.result'),
+      dataIndex: 'status',
+      key: 'status',
+      render: (status: ImportResult['status']) => (
+        <Tag color={RESULT_COLORS[status]}>
+          {t(`mcp_server.import.status.${status}`)}
+        </Tag>
+      ),
+    },
+    {
+      title: t('mcp_server.import.message'),
+      dataIndex: 'message',
+      key: 'message',
+    },
+  ]
+
+  return (
+    <Flex vertical gap='middle'>
+      <Card title={t('settings.backup.export_title')}>
+        <Flex vertical gap='middle'>
+          <Text type='secondary'>
+            {t('settings.backup.export_description')}
+          </Text>
+          <div>
+            <Text strong>{t('settings.backup.path')}</Text>
+            <Input
+              value={exportPath}
+              onChange={(e) => setExportPath(e.target.value)}
+              placeholder={DEFAULT_BUNDLE_PATH}
+              style={{ marginTop: '4px' }}
+            />
+            <Text type='secondary' style={{ fontSize: '12px' }}>
+              {t('settings.backup.path_description')}
+            </Text>
+          </div>
+          {switchRow(
+            t('settings.backup.redact_secrets'),
+            t('settings.backup.redact_secrets_description'),
+            redactSecrets,
+            setRedactSecrets,
+          )}
+          {switchRow(
+            t('settings.backup.include_settings'),
+            t('settings.backup.include_settings_description'),
+            includeSettings,
+            setIncludeSettings,
+          )}
+          <Flex justify='flex-end'>
+            <Button
+              type='primary'
+              loading={exporting}
+              disabled={!exportPath.trim()}
+              onClick={handleExport}>
+              {t('settings.backup.export')}
+            </Button>
+          </Flex>
+        </Flex>
+      </Card>
+
+      <Card title={t('settings.backup.import_title')}>
+        <Flex vertical gap='middle'>
+          <Text type='secondary'>
+            {t('settings.backup.import_description')}
+          </Text>
+          <div>
+            <Text strong>{t('settings.backup.path')}</Text>
+            <Input
+              value={importPath}
+              onChange={(e) => setImportPath(e.target.value)}
+              placeholder={DEFAULT_BUNDLE_PATH}
+              style={{ marginTop: '4px' }}
+            />
+          </div>
+          <div>
+            <Text strong>{t('settings.backup.strategy')}</Text>
+            <Select
+              value={strategy}
+              onChange={setStrategy}
+              style={{ width: '100%', marginTop: '4px' }}
+              options={(['skip', 'overwrite', 'rename'] as const).map(
+                (value) => ({
+                  value,
+                  label: t(`settings.backup.strategies.${value}`),
+                }),
+              )}
+            />
+          </div>
+          {switchRow(
+            t('settings.backup.import_settings'),
+            t('settings.backup.import_settings_description'),
+            importSettings,
+            setImportSettings,
+          )}
+          <Flex justify='flex-end'>
+            <Button
+              type='primary'
+              loading={importing}
+              disabled={!importPath.trim()}
+              onClick={handleImport}>
+              {t('settings.backup.import')}
+            </Button>
+          </Flex>
+
+          {report && (
+            <Flex vertical gap='small'>
+              <Text>
+                {t('settings.backup.report_summary', {
+                  tool_flags: report.tool_flags,
+                })}
+                {report.settings_imported &&
+                  ` ${t('settings.backup.report_settings')}`}
+              </Text>
+              {report.servers.length > 0 && (
+                <Table
+                  size='small'
+                  rowKey='name'
+                  pagination={false}
+                  dataSource={report.servers}
+                  columns={resultColumns('mcp_server.table.service_name')}
+                />
+              )}
+              {report.tokens.length > 0 && (
+                <Table
+                  size='small'
+                  rowKey='name'
+                  pagination={false}
+                  dataSource={report.tokens}
+                  columns={resultColumns('settings.backup.token_name')}
+                />
+              )}
+            </Flex>
+          )}
+        </Flex>
+      </Card>
+    </Flex>
+  )
+})
+
+export default BundleManager
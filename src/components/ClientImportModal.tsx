@@ -0,0 +1,392 @@
+import {
+  App,
+  Button,
+  Checkbox,
+  Collapse,
+  Empty,
+  Flex,
+  Input,
+  Modal,
+  Select,
+  Spin,
+  Table,
+  Tag,
+  Tooltip,
+  Typography,
+} from 'antd'
+import { AlertTriangle, RotateCcw } from 'lucide-react'
+import React, { useEffect, useMemo, useState } from 'react'
+import { useTranslation } from 'react-i18next'
+import { McpServerService } from '../services/mcp-server-service'
+import type {
+  ClientConfigPreview,
+  ClientInput,
+  ConflictStrategy,
+  ImportCandidate,
+  ImportResult,
+} from '../types'
+
+const { Text } = Typography
+const { TextArea } = Input
+
+interface ClientImportModalProps {
+  open: boolean
+  onClose: () => void
+  onImported?: () => void
+}
+
+const candidateKey = (sourceIndex: number, name: string) =>
+  `${sourceIndex}:${name}`
+
+const RESULT_COLORS: Record<ImportResult['status'], string> = {
+  added: 'success',
+  replaced: 'processing',
+  renamed: 'processing',
+  skipped: 'default',
+  failed: 'error',
+}
+
+const ClientImportModal: React.FC<ClientImportModalProps> = ({
+  open,
+  onClose,
+  onImported,
+}) => {
+  const { t } = useTranslation()
+  const { message } = App.useApp()
+  const [sources, setSources] = useState<ClientConfigPreview[]>([])
+  const [loading, setLoading] = useState(false)
+  const [pasted, setPasted] = useState('')
+  const [selected, setSelected] = useState<string[]>([])
+  const [inputValues, setInputValues] = useState<Record<string, string>>({})
+  const [strategy, setStrategy] = useState<ConflictStrategy>('skip')
+  const [importing, setImporting] = useState(false)
+  const [results, setResults] = useState<ImportResult[] | null>(null)
+
+  // 默认选中不重复的服务
+  const selectNew = (previews: ClientConfigPreview[]) =>
+    previews.flatMap((preview, index) =>
+      preview.servers
+        .filter((server) => !server.duplicate && !server.error)
+        .map((server) => candidateKey(index, server.name)),
+    )
+
+  const discover = async () => {
+    setLoading(true)
+    setResults(null)
+    try {
+      const previews = await McpServerService.discoverClientConfigs()
+      setSources(previews)
+      setSelected(selectNew(previews))
+    } catch (error) {
+      console.error('Failed to discover client configs:', error)
+      message.error(`${t('mcp_server.import.discover_failed')}: ${error}`)
+    } finally {
+      setLoading(false)
+    }
+  }
+
+  useEffect(() => {
+    if (open) {
+      setPasted('')
+      setInputValues({})
+      setStrategy('skip')
+      discover()
+    }
+  }, [open])
+
+  const handlePreviewPasted = async () => {
+    if (!pasted.trim()) return
+    try {
+      const preview = await McpServerService.previewMcpServersImport(pasted)
+      // 粘贴的配置放在最前面，替换上一次的粘贴结果
+      const discovered = sources.filter((source) => source.client !== 'other')
+      const previews = [preview, ...discovered]
+      setSources(previews)
+      setSelected(selectNew(previews))
+      setResults(null)
+    } catch (error) {
+      message.error(`${t('mcp_server.import.preview_failed')}: ${error}`)
+    }
+  }
+
+  const selectedCandidates = useMemo(
+    () =>
+      sources.flatMap((source, index) =>
+        source.servers.filter((server) =>
+          selected.includes(candidateKey(index, server.name)),
+        ),
+      ),
+    [sources, selected],
+  )
+
+  // 选中服务引用的 VS Code inputs
+  const requiredInputs = useMemo(() => {
+    const declared = new Map<string, ClientInput>()
+    sources.forEach((source) =>
+      source.inputs.forEach((input) => declared.set(input.id, input)),
+    )
+    const ids = Array.from(
+      new Set(selectedCandidates.flatMap((c) => c.required_inputs)),
+    )
+    return ids.map((id) => declared.get(id) ?? { id, password: false })
+  }, [sources, selectedCandidates])
+
+  const toggle = (key: string, checked: boolean) => {
+    setSelected((prev) =>
+      checked ? [...prev, key] : prev.filter((item) => item !== key),
+    )
+  }
+
+  const handleImport = async () => {
+    const missing = requiredInputs.filter((input) => !inputValues[input.id])
+    if (missing.length > 0) {
+      message.warning(
+        t('mcp_server.import.inputs_required', {
+          inputs: missing.map((input) => input.id).join(', '),
+        }),
+      )
+      return
+    }
+    setImporting(true)
+    try {
+      const importResults = await McpServerService.importMcpServers(
+        selectedCandidates.map((candidate) => candidate.config),
+        strategy,
+        inputValues,
+      )
+      setResults(importResults)
+      const imported = importResults.filter(
+        (r) =>
+          r.status === 'added' ||
+          r.status === 'replaced' ||
+          r.status === 'renamed',
+      ).length
+      if (imported > 0) {
+        message.success(t('mcp_server.import.import_success', { imported }))
+        onImported?.()
+      }
+    } catch (error) {
+      message.error(`${t('mcp_server.import.import_failed')}: ${error}`)
+    } finally {
+      setImporting(false)
+    }
+  }
+
+  const renderCandidate = (sourceIndex: number, candidate: ImportCandidate) => {
+    const key = candidateKey(sourceIndex, candidate.name)
+    const target =
+      candidate.config.type === 'http'
+        ? candidate.config.url
+        : [candidate.config.command, ...(candidate.config.args ?? [])]
+            .filter(Boolean)
+            .join(' ')
+    return (
+      <Flex key={key} align='flex-start' gap={8}>
+        <Checkbox
+          checked={selected.includes(key)}
+          disabled={!!candidate.error}
+          onChange={(e) => toggle(key, e.target.checked)}
+        />
+        <Flex vertical style={{ minWidth: 0, flex: 1 }}>
+          <Flex align='center' gap={6} wrap='wrap'>
+            <Text strong>{candidate.name}</Text>
+            <Tag>{candidate.config.type}</Tag>
+            {!candidate.config.enabled && (
+              <Tag>{t('mcp_server.import.disabled')}</Tag>
+            )}
+            {candidate.duplicate && (
+              <Tag color='warning'>
+                {candidate.duplicate.reason === 'name'
+                  ? t('mcp_server.import.duplicate_name')
+                  : t('mcp_server.import.duplicate_definition', {
+                      name: candidate.duplicate.existing,
+                    })}
+              </Tag>
+            )}
+            {candidate.warnings.length > 0 && (
+              <Tooltip title={candidate.warnings.join('\n')}>
+                <AlertTriangle size={14} color='#faad14' />
+              </Tooltip>
+            )}
+          </Flex>
+          {candidate.error ? (
+            <Text type='danger' style={{ fontSize: '12px' }}>
+              {candidate.error}
+            </Text>
+          ) : (
+            <Text type='secondary' ellipsis style={{ fontSize: '12px' }}>
+              {target}
+            </Text>
+          )}
+        </Flex>
+      </Flex>
+    )
+  }
+
+  return (
+    <Modal
+      title={t('mcp_server.import.title')}
+      open={open}
+      onCancel={onClose}
+      width={720}
+      footer={[
+        <Button key='close' onClick={onClose}>
+          {t('mcp_server.actions.cancel')}
+        </Button>,
+        <Button
+          key='import'
+          type='primary'
+          loading={importing}
+          disabled={selectedCandidates.length === 0}
+          onClick={handleImport}>
+          {t('mcp_server.import.import_selected', {
+            count: selectedCandidates.length,
+          })}
+        </Button>,
+      ]}>
+      <Flex vertical gap='middle'>
+        <Flex vertical gap={4}>
+          <Text strong>{t('mcp_server.import.paste_title')}</Text>
+          <TextArea
+            value={pasted}
+            onChange={(e) => setPasted(e.target.value)}
+            placeholder={t('mcp_server.import.paste_placeholder')}
+            rows={4}
+            style={{ fontFamily: 'monospace' }}
+          />
+          <Flex justify='flex-end'>
+            <Button disabled={!pasted.trim()} onClick={handlePreviewPasted}>
+              {t('mcp_server.import.preview')}
+            </Button>
+          </Flex>
+        </Flex>
+
+        <Flex justify='space-between' align='center'>
+          <Text strong>{t('mcp_server.import.discovered_title')}</Text>
+          <Button
+            size='small'
+            icon={<RotateCcw size={14} />}
+            loading={loading}
+            onClick={discover}>
+            {t('mcp_server.actions.refresh')}
+          </Button>
+        </Flex>
+
+        <Spin spinning={loading}>
+          {sources.length === 0 ? (
+            <Empty description={t('mcp_server.import.no_configs_found')} />
+          ) : (
+            <Collapse
+              defaultActiveKey={sources.map((_, index) => index)}
+              items={sources.map((source, index) => ({
+                key: index,
+                label: (
+                  <Flex vertical>
+                    <Text strong>
+                      {t(`mcp_server.import.clients.${source.client}`)}
+                    </Text>
+                    {source.path && (
+                      <Text type='secondary' style={{ fontSize: '12px' }}>
+                        {source.path}
+                      </Text>
+                    )}
+                  </Flex>
+                ),
+                children: source.error ? (
+                  <Text type='danger'>{source.error}</Text>
+                ) : source.servers.length === 0 ? (
+                  <Text type='secondary'>
+                    {t('mcp_server.import.no_servers')}
+                  </Text>
+                ) : (
+                  <Flex vertical gap={8}>
+                    {source.servers.map((candidate) =>
+                      renderCandidate(index, candidate),
+                    )}
+                  </Flex>
+                ),
+              }))}
+            />
+          )}
+        </Spin>
+
+        {requiredInputs.length > 0 && (
+          <Flex vertical gap={8}>
+            <Text strong>{t('mcp_server.import.inputs_title')}</Text>
+            {requiredInputs.map((input) => {
+              const InputComponent = input.password ? Input.Password : Input
+              return (
+                <Flex key={input.id} vertical gap={4}>
+                  <Text>
+                    {input.id}
+                    {input.description && (
+                      <Text type='secondary'> — {input.description}</Text>
+                    )}
+                  </Text>
+                  <InputComponent
+                    value={inputValues[input.id] ?? ''}
+                    onChange={(e) =>
+                      setInputValues({
+                        ...inputValues,
+                        [input.id]: e.target.value,
+                      })
+                    }
+                  />
+                </Flex>
+              )
+            })}
+          </Flex>
+        )}
+
+        <Flex align='center' gap={8}>
+          <Text>{t('mcp_server.import.strategy')}</Text>
+          <Select
+            size='small'
+            value={strategy}
+            onChange={setStrategy}
+            style={{ minWidth: 200 }}
+            options={(['skip', 'overwrite', 'rename'] as const).map(
+              (value) => ({
+                value,
+                label: t(`settings.backup.strategies.${value}`),
+              }),
+            )}
+          />
+        </Flex>
+
+        {results && (
+          <Table
+            size='small'
+            rowKey='name'
+            pagination={false}
+            dataSource={results}
+            columns={[
+              {
+                title: t('mcp_server.table.service_name'),
+                dataIndex: 'name',
+                key: 'name',
+              },
+              {
+                title: t('mcp_server.import.result'),
+                dataIndex: 'status',
+                key: 'status',
+                render: (status: ImportResult['status']) => (
+                  <Tag color={RESULT_COLORS[status]}>
+                    {t(`mcp_server.import.status.${status}`)}
+                  </Tag>
+                ),
+              },
+              {
+                title: t('mcp_server.import.message'),
+                dataIndex: 'message',
+                key: 'message',
+              },
+            ]}
+          />
+        )}
+      </Flex>
+    </Modal>
+  )
+}
+
+export default ClientImportModal
@@ -0,0 +1,148 @@
+import {
+  App,
+  Button,
+  Empty,
+  Flex,
+  Popconfirm,
+  Table,
+  Tag,
+  Typography,
+} from 'antd'
+import { memo, useState } from 'react'
+import { useTranslation } from 'react-i18next'
+import { ConfigService } from '../services/config-service'
+import type { ConfigChange, ReconcileReport } from '../types'
+
+const { Text } = Typography
+
+const ACTION_COLORS: Record<ConfigChange['action'], string> = {
+  add: 'success',
+  update: 'processing',
+  remove: 'error',
+}
+
+interface ConfigFileSyncProps {
+  path?: string | null
+}
+
+const ConfigFileSync: React.FC<ConfigFileSyncProps> = memo(({ path }) => {
+  const { t } = useTranslation()
+  const { message } = App.useApp()
+  const [checking, setChecking] = useState(false)
+  const [applying, setApplying] = useState(false)
+  const [report, setReport] = useState<ReconcileReport | null>(null)
+
+  const reconcile = async (dryRun: boolean) => {
+    const setBusy = dryRun ? setChecking : setApplying
+    setBusy(true)
+    try {
+      const result = await ConfigService.reconcileConfigFile(
+        path?.trim() || undefined,
+        dryRun,
+      )
+      setReport(result)
+      if (!dryRun) {
+        const failed = result.changes.filter((c) => c.error).length
+        if (failed > 0) {
+          message.warning(t('settings.config_file.apply_partial', { failed }))
+        } else {
+          message.success(
+            t('settings.config_file.apply_success', {
+              count: result.changes.length,
+            }),
+          )
+        }
+      }
+    } catch (error) {
+      console.error('Failed to reconcile config file:', error)
+      message.error(`${t('settings.config_file.reconcile_failed')}: ${error}`)
+    } finally {
+      setBusy(false)
+    }
+  }
+
+  return (
+    <Flex vertical gap='middle' style={{ marginTop: '16px' }}>
+      <Flex justify='flex-end' gap='small'>
+        <Button loading={checking} onClick={() => reconcile(true)}>
+          {t('settings.config_file.check_drift')}
+        </Button>
+        <Popconfirm
+          title={t('settings.config_file.apply_confirm_title')}
+          description={t('settings.config_file.apply_confirm')}
+          onConfirm={() => reconcile(false)}>
+          <Button type='primary' loading={applying}>
+            {t('settings.config_file.apply')}
+          </Button>
+        </Popconfirm>
+      </Flex>
+
+      {report &&
+        (report.changes.length === 0 ? (
+          <Empty description={t('settings.config_file.in_sync')} />
+        ) : (
+          <Flex vertical gap='small'>
+            <Text type='secondary'>
+              {report.dry_run
+                ? t('settings.config_file.drift_summary', {
+                    count: report.changes.length,
+                    path: report.path,
+                  })
+                : t('settings.config_file.applied_summary', {
+                    path: report.path,
+                  })}
+            </Text>
+            <Table
+              size='small'
+              rowKey={(change) => `${change.kind}:${change.name}`}
+              pagination={false}
+              dataSource={report.changes}
+              columns={[
+                {
+                  title: t('settings.config_file.kind'),
+                  dataIndex: 'kind',
+                  key: 'kind',
+                  render: (kind: ConfigChange['kind']) =>
+                    t(`settings.config_file.kinds.${kind}`),
+                },
+                {
+                  title: t('settings.config_file.name'),
+                  dataIndex: 'name',
+                  key: 'name',
+                },
+                {
+                  title: t('settings.config_file.action'),
+                  dataIndex: 'action',
+                  key: 'action',
+                  render: (action: ConfigChange['action']) => (
+                    <Tag color={ACTION_COLORS[action]}>
+                      {t(`settings.config_file.actions.${action}`)}
+                    </Tag>
+                  ),
+                },
+                {
+                  title: t('settings.config_file.detail'),
+                  key: 'detail',
+                  render: (_, change: ConfigChange) =>
+                    change.error ? (
+                      <Text type='danger'>{change.error}</Text>
+                    ) : change.token_value ? (
+                      <Flex vertical gap={4}>
+                        <Text type='warning'>{t('settings.config_file.token_generated')}</Text>
+                        <Text code copyable>
+                          {change.token_value}
+                        </Text>
+                      </Flex>
+                    ) : (
+                      change.detail
+                    ),
+                },
+              ]}
+            />
+          </Flex>
+        ))}
+    </Flex>
+  )
+})
+
+export default ConfigFileSync
@@ -0,0 +1,74 @@
+import { listen } from '@tauri-apps/api/event'
+import { App, Flex, Typography } from 'antd'
+import { memo, useEffect } from 'react'
+import { useTranslation } from 'react-i18next'
+import type { ReconcileReport } from '../types'
+
+// 监听后端配置热加载事件并提示用户
+const ConfigReloadListener: React.FC = memo(() => {
+  const { t } = useTranslation()
+  const { message, notification } = App.useApp()
+
+  useEffect(() => {
+    const unlistenReloaded = listen('config-reloaded', () => {
+      message.success(t('settings.config_reload.reloaded'))
+    })
+    const unlistenError = listen<string>('config-error', (event) => {
+      notification.error({
+        message: t('settings.config_reload.rejected'),
+        description: event.payload,
+        duration: 0,
+      })
+    })
+    // 声明式配置文件修改后自动同步的结果
+    const unlistenReconciled = listen<ReconcileReport>('config-file-reconciled', (event) => {
+      const failed = event.payload.changes.filter((change) => change.error).length
+      if (failed > 0) {
+        notification.warning({
+          message: t('settings.config_file.auto_applied', {
+            count: event.payload.changes.length - failed,
+          }),
+          description: t('settings.config_file.auto_partial', { failed }),
+        })
+      } else {
+        message.success(
+          t('settings.config_file.auto_applied', { count: event.payload.changes.length }),
+        )
+      }
+      // 新建的 Token 只在这里出现一次
+      for (const change of event.payload.changes) {
+        if (change.token_value) {
+          notification.info({
+            message: change.name,
+            description: (
+              <Flex vertical gap={4}>
+                {t('settings.config_file.token_generated')}
+                <Typography.Text code copyable>
+                  {change.token_value}
+                </Typography.Text>
+              </Flex>
+            ),
+            duration: 0,
+          })
+        }
+      }
+    })
+    const unlistenFileError = listen<string>('config-file-error', (event) => {
+      notification.error({
+        message: t('settings.config_file.reconcile_failed'),
+        description: event.payload,
+      })
+    })
+
+    return () => {
+      unlistenReloaded.then((unlisten) => unlisten())
+      unlistenError.then((unlisten) => unlisten())
+      unlistenReconciled.then((unlisten) => unlisten())
+      unlistenFileError.then((unlisten) => unlisten())
+    }
+  }, [message, notification, t])
+
+  return null
+})
+
+export default ConfigReloadListener
@@ -0,0 +1,140 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import { App, Button, Flex, Modal, Select, Typography } from 'antd'
+import { memo, useEffect, useState } from 'react'
+import { useTranslation } from 'react-i18next'
+import { OAuthService } from '../services/oauth-service'
+import type { OAuthConsentRequest, Token } from '../types'
+
+const { Text } = Typography
+
+// 展示 MCP 客户端通过 OAuth 发起的授权请求，由用户选择授予哪个 Token 的权限
+const OAuthConsentListener: React.FC = memo(() => {
+  const { t } = useTranslation()
+  const { message } = App.useApp()
+  const [requests, setRequests] = useState<OAuthConsentRequest[]>([])
+  const [tokens, setTokens] = useState<Token[]>([])
+  const [tokenId, setTokenId] = useState<string>()
+  const [responding, setResponding] = useState(false)
+
+  const current = requests[0]
+
+  useEffect(() => {
+    OAuthService.listConsentRequests()
+      .then(setRequests)
+      .catch((error) => {
+        console.error('Failed to load OAuth consent requests:', error)
+      })
+
+    const unlisten = listen<OAuthConsentRequest>(
+      'oauth-consent-request',
+      (event) => {
+        setRequests((prev) =>
+          prev.some((request) => request.id === event.payload.id)
+            ? prev
+            : [...prev, event.payload],
+        )
+      },
+    )
+
+    return () => {
+      unlisten.then((unlisten) => unlisten())
+    }
+  }, [])
+
+  useEffect(() => {
+    if (!current) {
+      return
+    }
+    setTokenId(undefined)
+    invoke<Token[]>('list_tokens')
+      .then((list) =>
+        setTokens(list.filter((token) => token.enabled && !token.is_expired)),
+      )
+      .catch((error) => {
+        console.error('Failed to load tokens:', error)
+      })
+  }, [current])
+
+  const handleRespond = async (approve: boolean) => {
+    if (!current) {
+      return
+    }
+    setResponding(true)
+    try {
+      await OAuthService.respondConsent(current.id, approve, tokenId)
+      message.success(
+        approve
+          ? t('oauth_consent.approved', { client: current.client_name })
+          : t('oauth_consent.denied', { client: current.client_name }),
+      )
+    } catch (error) {
+      console.error('Failed to respond to OAuth consent request:', error)
+      message.error(`${t('oauth_consent.respond_failed')}: ${error}`)
+    } finally {
+      setRequests((prev) => prev.filter((request) => request !== current))
+      setResponding(false)
+    }
+  }
+
+  return (
+    <Modal
+      open={!!current}
+      title={t('oauth_consent.title')}
+      closable={false}
+      maskClosable={false}
+      keyboard={false}
+      footer={[
+        <Button
+          key='deny'
+          danger
+          disabled={responding}
+          onClick={() => handleRespond(false)}>
+          {t('oauth_consent.deny')}
+        </Button>,
+        <Button
+          key='approve'
+          type='primary'
+          loading={responding}
+          disabled={!tokenId}
+          onClick={() => handleRespond(true)}>
+          {t('oauth_consent.approve')}
+        </Button>,
+      ]}>
+      {current && (
+        <Flex vertical gap='middle'>
+          <Text>
+            {t('oauth_consent.description', { client: current.client_name })}
+          </Text>
+          <div>
+            <Text type='secondary'>{t('oauth_consent.redirect_uri')}</Text>
+            <div>
+              <Text code>{current.redirect_uri}</Text>
+            </div>
+          </div>
+          <div>
+            <Text strong>{t('oauth_consent.token')}</Text>
+            <Select
+              value={tokenId}
+              onChange={setTokenId}
+              placeholder={t('oauth_consent.token_placeholder')}
+              notFoundContent={t('oauth_consent.no_tokens')}
+              options={tokens.map((token) => ({
+                value: token.id,
+                label: token.name,
+              }))}
+              style={{ width: '100%', marginTop: '4px' }}
+            />
+            <Text
+              type='secondary'
+              style={{ fontSize: '12px', display: 'block', marginTop: '4px' }}>
+              {t('oauth_consent.token_help')}
+            </Text>
+          </div>
+        </Flex>
+      )}
+    </Modal>
+  )
+})
+
+export default OAuthConsentListener
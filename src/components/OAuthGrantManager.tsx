@@ -0,0 +1,110 @@
+import { App, Button, Card, Popconfirm, Table, Typography } from 'antd'
+import type { ColumnsType } from 'antd/es/table'
+import { memo, useCallback, useEffect, useState } from 'react'
+import { useTranslation } from 'react-i18next'
+import { OAuthService } from '../services/oauth-service'
+import type { OAuthGrant } from '../types'
+
+const { Text } = Typography
+
+// 通过 OAuth 授权接入聚合接口的客户端列表
+const OAuthGrantManager: React.FC = memo(() => {
+  const { t } = useTranslation()
+  const { message } = App.useApp()
+  const [grants, setGrants] = useState<OAuthGrant[]>([])
+  const [loading, setLoading] = useState(false)
+
+  const loadGrants = useCallback(async () => {
+    setLoading(true)
+    try {
+      setGrants(await OAuthService.listGrants())
+    } catch (error) {
+      console.error('Failed to load OAuth grants:', error)
+    } finally {
+      setLoading(false)
+    }
+  }, [])
+
+  useEffect(() => {
+    loadGrants()
+  }, [loadGrants])
+
+  const handleRevoke = async (grant: OAuthGrant) => {
+    try {
+      await OAuthService.revokeGrant(grant.id)
+      message.success(
+        t('token.oauth.revoke_success', { client: grant.client_name }),
+      )
+      await loadGrants()
+    } catch (error) {
+      console.error('Failed to revoke OAuth grant:', error)
+      message.error(`${t('token.oauth.revoke_failed')}: ${error}`)
+    }
+  }
+
+  const columns: ColumnsType<OAuthGrant> = [
+    {
+      title: t('token.oauth.client'),
+      dataIndex: 'client_name',
+      key: 'client_name',
+    },
+    {
+      title: t('token.oauth.token'),
+      dataIndex: 'token_name',
+      key: 'token_name',
+      render: (name?: string) => name ?? <Text type='secondary'>-</Text>,
+    },
+    {
+      title: t('token.table.created'),
+      dataIndex: 'created_at',
+      key: 'created_at',
+      render: (timestamp: number) =>
+        new Date(timestamp * 1000).toLocaleString(),
+    },
+    {
+      title: t('token.oauth.last_issued'),
+      dataIndex: 'updated_at',
+      key: 'updated_at',
+      render: (timestamp: number) =>
+        new Date(timestamp * 1000).toLocaleString(),
+    },
+    {
+      title: t('token.table.actions'),
+      key: 'actions',
+      render: (_, grant) => (
+        <Popconfirm
+          title={t('token.oauth.revoke_confirm')}
+          onConfirm={() => handleRevoke(grant)}>
+          <Button size='small' danger>
+            {t('token.oauth.revoke')}
+          </Button>
+        </Popconfirm>
+      ),
+    },
+  ]
+
+  return (
+    <Card
+      title={t('token.oauth.title')}
+      extra={
+        <Button size='small' onClick={loadGrants} loading={loading}>
+          {t('token.actions.refresh')}
+        </Button>
+      }
+      style={{ marginTop: 24 }}>
+      <Text type='secondary' style={{ display: 'block', marginBottom: 16 }}>
+        {t('token.oauth.description')}
+      </Text>
+      <Table
+        columns={columns}
+        dataSource={grants}
+        rowKey='id'
+        loading={loading}
+        size='small'
+        pagination={false}
+      />
+    </Card>
+  )
+})
+
+export default OAuthGrantManager
@@ -0,0 +1,178 @@
+import {
+  App,
+  Button,
+  Card,
+  Flex,
+  Form,
+  Input,
+  Popconfirm,
+  Table,
+  Typography,
+} from 'antd'
+import { memo, useCallback, useEffect, useState } from 'react'
+import { useTranslation } from 'react-i18next'
+import { ConfigService } from '../services/config-service'
+import type { SecretInfo } from '../types'
+
+const { Text } = Typography
+
+interface SecretFormValues {
+  name: string
+  value: string
+}
+
+const SecretManager: React.FC = memo(() => {
+  const { t } = useTranslation()
+  const { message } = App.useApp()
+  const [form] = Form.useForm<SecretFormValues>()
+  const [secrets, setSecrets] = useState<SecretInfo[]>([])
+  const [loading, setLoading] = useState(false)
+  const [saving, setSaving] = useState(false)
+  const [migrating, setMigrating] = useState(false)
+
+  const loadSecrets = useCallback(async () => {
+    setLoading(true)
+    try {
+      setSecrets(await ConfigService.listSecrets())
+    } catch (error) {
+      console.error('Failed to load secrets:', error)
+      message.error(`${t('settings.secrets.load_failed')}: ${error}`)
+    } finally {
+      setLoading(false)
+    }
+  }, [message, t])
+
+  useEffect(() => {
+    loadSecrets()
+  }, [loadSecrets])
+
+  const handleSave = async (values: SecretFormValues) => {
+    setSaving(true)
+    try {
+      const reference = await ConfigService.setSecret(
+        values.name.trim(),
+        values.value,
+      )
+      message.success(t('settings.secrets.save_success', { reference }))
+      form.resetFields()
+      await loadSecrets()
+    } catch (error) {
+      console.error('Failed to save secret:', error)
+      message.error(`${t('settings.secrets.save_failed')}: ${error}`)
+    } finally {
+      setSaving(false)
+    }
+  }
+
+  const handleDelete = async (name: string) => {
+    try {
+      await ConfigService.deleteSecret(name)
+      message.success(t('settings.secrets.delete_success', { name }))
+      await loadSecrets()
+    } catch (error) {
+      console.error('Failed to delete secret:', error)
+      message.error(`${t('settings.secrets.delete_failed')}: ${error}`)
+    }
+  }
+
+  const handleMigrate = async () => {
+    setMigrating(true)
+    try {
+      const count = await ConfigService.migrateSecrets()
+      message.success(t('settings.secrets.migrate_success', { count }))
+      await loadSecrets()
+    } catch (error) {
+      console.error('Failed to migrate secrets:', error)
+      message.error(`${t('settings.secrets.migrate_failed')}: ${error}`)
+    } finally {
+      setMigrating(false)
+    }
+  }
+
+  return (
+    <Card>
+      <Flex vertical gap='middle'>
+        <Text type='secondary'>{t('settings.secrets.description')}</Text>
+
+        <Form form={form} layout='inline' onFinish={handleSave}>
+          <Form.Item
+            name='name'
+            rules={[
+              {
+                required: true,
+                pattern: /^[A-Za-z0-9_.-]+$/,
+                message: t('settings.secrets.name_invalid'),
+              },
+            ]}>
+            <Input placeholder={t('settings.secrets.name')} />
+          </Form.Item>
+          <Form.Item name='value' rules={[{ required: true }]}>
+            <Input.Password
+              placeholder={t('settings.secrets.value')}
+              autoComplete='new-password'
+            />
+          </Form.Item>
+          <Form.Item>
+            <Button type='primary' htmlType='submit' loading={saving}>
+              {t('settings.secrets.save')}
+            </Button>
+          </Form.Item>
+        </Form>
+
+        <Table
+          size='small'
+          rowKey='name'
+          loading={loading}
+          pagination={false}
+          dataSource={secrets}
+          columns={[
+            {
+              title: t('settings.secrets.name'),
+              dataIndex: 'name',
+              key: 'name',
+            },
+            {
+              title: t('settings.secrets.reference'),
+              key: 'reference',
+              render: (_, secret: SecretInfo) => (
+                <Text code copyable>{`\${secret:${secret.name}}`}</Text>
+              ),
+            },
+            {
+              title: t('settings.secrets.updated_at'),
+              dataIndex: 'updated_at',
+              key: 'updated_at',
+              render: (value: string) => new Date(value).toLocaleString(),
+            },
+            {
+              title: t('settings.secrets.actions'),
+              key: 'actions',
+              render: (_, secret: SecretInfo) => (
+                <Popconfirm
+                  title={t('settings.secrets.delete_confirm', {
+                    name: secret.name,
+                  })}
+                  onConfirm={() => handleDelete(secret.name)}>
+                  <Button danger size='small'>
+                    {t('settings.secrets.delete')}
+                  </Button>
+                </Popconfirm>
+              ),
+            },
+          ]}
+        />
+
+        <Flex justify='space-between' align='center' gap='small'>
+          <Text type='secondary'>
+            {t('settings.secrets.migrate_description')}
+          </Text>
+          <Button loading={migrating} onClick={handleMigrate}>
+            {t('settings.secrets.migrate')}
+          </Button>
+        </Flex>
+      </Flex>
+    </Card>
+  )
+})
+
+export default SecretManager
@@ -7,6 +7,8 @@ import {
   Typography,
   Progress,
   Tooltip,
+  Modal,
+  Input,
   App
 } from 'antd'
 import {
@@ -15,11 +17,13 @@ import {
   DownloadOutlined,
   ReloadOutlined,
   InfoCircleOutlined,
-  WarningOutlined
+  WarningOutlined,
+  RollbackOutlined,
+  SettingOutlined
 } from '@ant-design/icons'
 import { memo, useCallback, useEffect, useState } from 'react'
 import { useTranslation } from 'react-i18next'
-import type { ToolInfo, PythonRuntimeInfo } from '../types'
+import type { ToolInfo, PythonRuntimeInfo, InstallToolOptions } from '../types'
 import { ToolStatus } from '../types'
 import { ToolManagerService } from '../services/tool-manager-service'
 
@@ -37,6 +41,9 @@ const SystemToolManager: React.FC<SystemToolManagerProps> = memo(({ className })
   const [pythonRuntime, setPythonRuntime] = useState<PythonRuntimeInfo>({ available: false })
   const [loading, setLoading] = useState(false)
   const [installing, setInstalling] = useState<{ [key: string]: boolean }>({})
+  // 指定版本 / 离线安装
+  const [installTarget, setInstallTarget] = useState<ToolInfo | null>(null)
+  const [installOptions, setInstallOptions] = useState<InstallToolOptions>({})
 
   // 加载工具信息
   const loadToolsInfo = useCallback(async () => {
@@ -73,21 +80,53 @@ const SystemToolManager: React.FC<SystemToolManagerProps> = memo(({ className })
   }, [antMessage, loadToolsInfo])
 
   // 安装特定工具
-  const handleInstallTool = useCallback(async (toolName: string) => {
+  const handleInstallTool = useCallback(async (toolName: string, options?: InstallToolOptions) => {
     setInstalling(prev => ({ ...prev, [toolName]: true }))
     try {
-      await ToolManagerService.installTool(toolName)
+      await ToolManagerService.installTool(toolName, options)
       antMessage.success(t('tool_manager.messages.install_success', { tool: toolName }))
       // 重新加载工具信息
       await loadToolsInfo()
     } catch (error) {
       console.error(`Failed to install ${toolName}:`, error)
-      antMessage.error(t('tool_manager.errors.install_failed', { tool: toolName }))
+      antMessage.error(t('tool_manager.errors.install_failed', { tool: toolName }) + `: ${error}`)
     } finally {
       setInstalling(prev => ({ ...prev, [toolName]: false }))
     }
   }, [antMessage, loadToolsInfo])
 
+  // 回滚到上一版本
+  const handleRollbackTool = useCallback(async (toolName: string) => {
+    setInstalling(prev => ({ ...prev, [toolName]: true }))
+    try {
+      await ToolManagerService.installTool(toolName, { rollback: true })
+      antMessage.success(t('tool_manager.messages.rollback_success', { tool: toolName }))
+      await loadToolsInfo()
+    } catch (error) {
+      console.error(`Failed to roll back ${toolName}:`, error)
+      antMessage.error(t('tool_manager.errors.rollback_failed', { tool: toolName }) + `: ${error}`)
+    } finally {
+      setInstalling(prev => ({ ...prev, [toolName]: false }))
+    }
+  }, [antMessage, loadToolsInfo])
+
+  const openInstallModal = (tool: ToolInfo) => {
+    setInstallOptions({ version: tool.pinned_version })
+    setInstallTarget(tool)
+  }
+
+  const handleInstallWithOptions = () => {
+    if (!installTarget) return
+    const options: InstallToolOptions = {
+      version: installOptions.version?.trim() || null,
+      archive_path: installOptions.archive_path?.trim() || null,
+      sha256: installOptions.sha256?.trim() || null
+    }
+    const toolName = installTarget.name
+    setInstallTarget(null)
+    handleInstallTool(toolName, options)
+  }
+
   // 获取状态图标和颜色
   const getStatusConfig = (status: ToolStatus) => {
     switch (status) {
@@ -224,6 +263,14 @@ const SystemToolManager: React.FC<SystemToolManagerProps> = memo(({ className })
                     {tool.version && (
                       <Text type="secondary" style={{ fontSize: '12px', display: 'block', marginBottom: '4px' }}>
                         {t('tool_manager.version')}: {tool.version}
+                        {tool.pinned_version &&
+                          ` (${t('tool_manager.pinned_version')}: ${tool.pinned_version})`}
+                      </Text>
+                    )}
+
+                    {tool.installed_versions.length > 1 && (
+                      <Text type="secondary" style={{ fontSize: '12px', display: 'block', marginBottom: '4px' }}>
+                        {t('tool_manager.installed_versions')}: {tool.installed_versions.join(', ')}
                       </Text>
                     )}
 
@@ -256,6 +303,26 @@ const SystemToolManager: React.FC<SystemToolManagerProps> = memo(({ className })
                       </Button>
                     )}
 
+                    {!isInstalling && (
+                      <Tooltip title={t('tool_manager.actions.install_version')}>
+                        <Button
+                          size="small"
+                          icon={<SettingOutlined />}
+                          onClick={() => openInstallModal(tool)}
+                        />
+                      </Tooltip>
+                    )}
+
+                    {tool.rollback_available && !isInstalling && (
+                      <Button
+                        size="small"
+                        icon={<RollbackOutlined />}
+                        onClick={() => handleRollbackTool(tool.name)}
+                      >
+                        {t('tool_manager.actions.rollback')}
+                      </Button>
+                    )}
+
                     {isInstalling && installing[tool.name] && (
                       <Progress
                         type="circle"
@@ -272,6 +339,55 @@ const SystemToolManager: React.FC<SystemToolManagerProps> = memo(({ className })
           })}
         </div>
       </Flex>
+
+      {/* 指定版本 / 离线安装 */}
+      <Modal
+        title={t('tool_manager.install_modal.title', { tool: installTarget?.name })}
+        open={!!installTarget}
+        onOk={handleInstallWithOptions}
+        onCancel={() => setInstallTarget(null)}
+        okText={t('tool_manager.actions.install')}
+      >
+        <Flex vertical gap="middle">
+          <div>
+            <Text strong>{t('tool_manager.install_modal.version')}</Text>
+            <Input
+              value={installOptions.version ?? ''}
+              onChange={(e) => setInstallOptions(prev => ({ ...prev, version: e.target.value }))}
+              placeholder={installTarget?.pinned_version}
+              style={{ marginTop: '4px' }}
+            />
+          </div>
+          {/* Python 由 uv 下载安装，不支持离线压缩包 */}
+          {installTarget?.name !== 'Python' && (
+            <>
+              <div>
+                <Text strong>{t('tool_manager.install_modal.archive_path')}</Text>
+                <Input
+                  value={installOptions.archive_path ?? ''}
+                  onChange={(e) => setInstallOptions(prev => ({ ...prev, archive_path: e.target.value }))}
+                  placeholder="/path/to/bun-linux-x64.zip"
+                  style={{ marginTop: '4px' }}
+                />
+                <Text type="secondary" style={{ fontSize: '12px' }}>
+                  {t('tool_manager.install_modal.archive_path_help')}
+                </Text>
+              </div>
+              <div>
+                <Text strong>{t('tool_manager.install_modal.sha256')}</Text>
+                <Input
+                  value={installOptions.sha256 ?? ''}
+                  onChange={(e) => setInstallOptions(prev => ({ ...prev, sha256: e.target.value }))}
+                  style={{ marginTop: '4px', fontFamily: 'monospace' }}
+                />
+                <Text type="secondary" style={{ fontSize: '12px' }}>
+                  {t('tool_manager.install_modal.sha256_help')}
+                </Text>
+              </div>
+            </>
+          )}
+        </Flex>
+      </Modal>
     </div>
   )
 })
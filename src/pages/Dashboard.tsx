@@ -3,6 +3,7 @@ import { openUrl } from '@tauri-apps/plugin-opener'
 import {
   App,
   Button,
+  Popconfirm,
   Select,
   Space,
   Statistic,
@@ -21,10 +22,30 @@ import {
 import React, { useEffect, useState } from 'react'
 import { useTranslation } from 'react-i18next'
 import { useAppContext } from '../contexts/AppContext'
-import type { DashboardStats, TokenForDashboard } from '../types'
+import { ConfigService } from '../services/config-service'
+import type {
+  ClientConfigSnippet,
+  ClientTarget,
+  DashboardStats,
+  TokenForDashboard,
+} from '../types'
 
 const { Text } = Typography
 
+const CLIENT_TARGETS: ClientTarget[] = [
+  'streamable-http',
+  'claude-desktop',
+  'cursor',
+  'vs-code',
+  'continue',
+  'stdio-bridge',
+]
+
+const formatMemory = (bytes: number) => {
+  const mb = bytes / 1024 / 1024
+  return mb >= 1024 ? `${(mb / 1024).toFixed(1)} GB` : `${mb.toFixed(0)} MB`
+}
+
 const Dashboard: React.FC = () => {
   const { t } = useTranslation()
   const { state } = useAppContext()
@@ -65,6 +86,13 @@ const Dashboard: React.FC = () => {
   const [isTokensLoading, setIsTokensLoading] = useState(false)
   const [tokenDropdownOpen, setTokenDropdownOpen] = useState(false)
 
+  // 客户端配置导出
+  const [exportClient, setExportClient] =
+    useState<ClientTarget>('streamable-http')
+  const [clientSnippet, setClientSnippet] =
+    useState<ClientConfigSnippet | null>(null)
+  const [writingConfig, setWritingConfig] = useState(false)
+
   // 加载仪表板数据
   const loadDashboardData = async () => {
     try {
@@ -264,8 +292,53 @@ const Dashboard: React.FC = () => {
     }
   }
 
+  const configText =
+    clientSnippet?.text ?? JSON.stringify(generatedConfig, null, 2)
+
+  useEffect(() => {
+    ConfigService.generateClientConfig(exportClient, selectedTokenId)
+      .then(setClientSnippet)
+      .catch((error) => {
+        console.error('Failed to generate client config:', error)
+        setClientSnippet(null)
+      })
+  }, [
+    exportClient,
+    selectedTokenId,
+    settings.server.auth,
+    settings.server.host,
+    settings.server.port,
+  ])
+
+  const writeClientConfig = async () => {
+    setWritingConfig(true)
+    try {
+      const result = await ConfigService.writeClientConfig(
+        exportClient,
+        selectedTokenId,
+      )
+      message.success(
+        result.backup_path
+          ? t('dashboard.client_config.write_success_backup', {
+              path: result.path,
+              backup: result.backup_path,
+            })
+          : t('dashboard.client_config.write_success', { path: result.path }),
+      )
+    } catch (error: any) {
+      console.error('Failed to write client config:', error)
+      message.error(
+        `${t('dashboard.client_config.write_failed')}: ${
+          error?.cause?.message ?? error
+        }`,
+      )
+    } finally {
+      setWritingConfig(false)
+    }
+  }
+
   const copyMcpServersJson = async () => {
-    const json = JSON.stringify(generatedConfig, null, 2)
+    const json = configText
     try {
       await navigator.clipboard.writeText(json)
       message.success(t('dashboard.client_config.copy_config'))
@@ -491,6 +564,33 @@ const Dashboard: React.FC = () => {
             </div>
           )}
 
+          <div className='flex items-center gap-3'>
+            <Text className='text-sm'>
+              {t('dashboard.client_config.target')}
+            </Text>
+            <Select
+              value={exportClient}
+              onChange={setExportClient}
+              style={{ width: 220 }}
+              options={CLIENT_TARGETS.map((target) => ({
+                value: target,
+                label: t(`dashboard.client_config.targets.${target}`),
+              }))}
+            />
+            {clientSnippet?.path && (
+              <Popconfirm
+                title={t('dashboard.client_config.write_confirm_title')}
+                description={t('dashboard.client_config.write_confirm', {
+                  path: clientSnippet.path,
+                })}
+                onConfirm={writeClientConfig}>
+                <Button size='small' loading={writingConfig}>
+                  {t('dashboard.client_config.write_to_file')}
+                </Button>
+              </Popconfirm>
+            )}
+          </div>
+
           <div className='relative'>
             <div
               className='rounded p-3 overflow-auto max-h-64 border'
@@ -504,7 +604,7 @@ const Dashboard: React.FC = () => {
                   color: state.isDarkMode ? '#f9fafb' : '#111827',
                   backgroundColor: 'transparent',
                 }}>
-                {JSON.stringify(generatedConfig, null, 2)}
+                {configText}
               </pre>
             </div>
             <button
@@ -684,6 +784,94 @@ const Dashboard: React.FC = () => {
                 </Text>
                 <Text strong>{stats.active_clients}</Text>
               </div>
+              <div className='flex justify-between items-center'>
+                <Text type='secondary'>
+                  {t('dashboard.system_info.aggregator_connections')}:
+                </Text>
+                <Text strong>
+                  {stats.connections?.active_services ?? 0}
+                  {stats.aggregator?.max_connections
+                    ? ` / ${stats.aggregator.max_connections}`
+                    : ''}
+                </Text>
+              </div>
+              <div className='flex justify-between items-center'>
+                <Text type='secondary'>
+                  {t('dashboard.system_info.in_flight_requests')}:
+                </Text>
+                <Text strong>
+                  {stats.connections?.in_flight_requests ?? 0}
+                  {stats.connections?.rejected_requests
+                    ? ` (${t('dashboard.system_info.rejected_requests')}: ${stats.connections.rejected_requests})`
+                    : ''}
+                </Text>
+              </div>
+              <div className='flex justify-between items-center'>
+                <Text type='secondary'>
+                  {t('dashboard.system_info.circuit_breakers')}:
+                </Text>
+                {stats.circuit_breakers && stats.circuit_breakers.length > 0 ? (
+                  <Tooltip
+                    title={stats.circuit_breakers
+                      .map(
+                        (b) =>
+                          `${b.name}: ${t(
+                            `dashboard.circuit_breaker.${b.state}`,
+                          )} (${Math.round(b.failure_rate * 100)}%)`,
+                      )
+                      .join(', ')}>
+                    <Text strong style={{ color: '#ff4d4f' }}>
+                      {stats.circuit_breakers.map((b) => b.name).join(', ')}
+                    </Text>
+                  </Tooltip>
+                ) : (
+                  <Text style={{ color: '#52c41a' }}>
+                    {t('dashboard.circuit_breaker.all_closed')}
+                  </Text>
+                )}
+              </div>
+              {stats.resource_usage && stats.resource_usage.length > 0 && (
+                <div className='flex justify-between items-center'>
+                  <Text type='secondary'>
+                    {t('dashboard.system_info.resource_usage')}:
+                  </Text>
+                  <Tooltip
+                    title={stats.resource_usage
+                      .map(
+                        (u) =>
+                          `${u.server_name}: ${formatMemory(u.memory_bytes)}, CPU ${u.cpu_percent.toFixed(1)}% (${t('dashboard.system_info.process_count')}: ${u.process_count})`,
+                      )
+                      .join(', ')}>
+                    <Text strong>
+                      {stats.resource_usage
+                        .slice(0, 3)
+                        .map(
+                          (u) =>
+                            `${u.server_name} ${formatMemory(u.memory_bytes)}`,
+                        )
+                        .join(', ')}
+                    </Text>
+                  </Tooltip>
+                </div>
+              )}
+              {stats.runtimes && stats.runtimes.length > 0 && (
+                <div className='flex justify-between items-center'>
+                  <Text type='secondary'>
+                    {t('dashboard.system_info.runtimes')}:
+                  </Text>
+                  <Tooltip
+                    title={stats.runtimes
+                      .map((r) => `${r.name}: ${r.kind} (${r.executable})`)
+                      .join(', ')}>
+                    <Text strong>
+                      {stats.runtimes
+                        .slice(0, 3)
+                        .map((r) => `${r.name} ${r.kind}`)
+                        .join(', ')}
+                    </Text>
+                  </Tooltip>
+                </div>
+              )}
             </Space>
           </div>
         </div>
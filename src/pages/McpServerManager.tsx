@@ -4,6 +4,7 @@ import {
   Button,
   Flex,
   Input,
+  InputNumber,
   Modal,
   Popconfirm,
   Select,
@@ -18,7 +19,10 @@ import {
 import {
   AlertCircle,
   CheckCircle,
+  Download,
   Edit3,
+  KeyRound,
+  Moon,
   Plus,
   RotateCcw,
   Trash2,
@@ -28,15 +32,82 @@ import {
 import React, { useEffect, useState } from 'react'
 import { useTranslation } from 'react-i18next'
 import { useAntdConfig } from '../components/AntdConfigProvider'
+import ClientImportModal from '../components/ClientImportModal'
 import ToolManager from '../components/ToolManager'
 import { useAppContext } from '../contexts/AppContext'
 import { McpServerService } from '../services/mcp-server-service'
 import { ToolManagerService } from '../services/tool-manager-service'
-import type { McpServerInfo, ToolStartupStatus } from '../types'
+import type {
+  ContainerConfig,
+  ContainerRuntime,
+  CredentialHelper,
+  EnvInheritance,
+  McpServerInfo,
+  PullPolicy,
+  RestartPolicy,
+  SandboxProfile,
+  ServerLifecycle,
+  ToolStartupStatus,
+} from '../types'
 
 const { TextArea } = Input
 const { Title, Text } = Typography
 
+const DEFAULT_SANDBOX: SandboxProfile = {
+  allowed_paths: [],
+  read_only_paths: [],
+  network: true,
+  drop_env: true,
+  env_allowlist: [],
+}
+
+const RUNTIME_PRESETS = ['auto', 'bun', 'node', 'system-node']
+
+const DEFAULT_CONTAINER: ContainerConfig = {
+  runtime: 'auto',
+  image: '',
+  mounts: [],
+  pull_policy: 'missing',
+  extra_args: [],
+}
+
+// 去除空值后提交
+const normalizeContainer = (container: ContainerConfig): ContainerConfig => ({
+  ...container,
+  image: container.image.trim(),
+  tag: container.tag?.trim() || null,
+  network: container.network?.trim() || null,
+  mounts: container.mounts.map((m) => m.trim()).filter(Boolean),
+})
+
+const DEFAULT_CREDENTIAL_HELPER: CredentialHelper = {
+  command: '',
+  args: [],
+  header: 'Authorization',
+  format: 'Bearer {token}',
+}
+
+// 去除空值后提交
+const normalizeCredentialHelper = (
+  helper: CredentialHelper,
+): CredentialHelper => ({
+  ...helper,
+  command: helper.command.trim(),
+  args: helper.args.map((arg) => arg.trim()).filter(Boolean),
+  header: helper.header.trim() || DEFAULT_CREDENTIAL_HELPER.header,
+  format: helper.format.trim() || DEFAULT_CREDENTIAL_HELPER.format,
+})
+
+// 去除空行后提交
+const normalizeSandbox = (sandbox: SandboxProfile): SandboxProfile => ({
+  ...sandbox,
+  allowed_paths: sandbox.allowed_paths.map((p) => p.trim()).filter(Boolean),
+  read_only_paths: sandbox.read_only_paths
+    .map((p) => p.trim())
+    .filter(Boolean),
+  env_allowlist: sandbox.env_allowlist.map((k) => k.trim()).filter(Boolean),
+})
+
 interface McpServerManagerProps {
   onServiceChange?: () => void
 }
@@ -50,6 +121,7 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
   const { setActiveTab } = useAppContext()
   const [mcpServers, setMcpServers] = useState<McpServerInfo[]>([])
   const [showAddService, setShowAddService] = useState(false)
+  const [showClientImport, setShowClientImport] = useState(false)
   const [showEditService, setShowEditService] = useState(false)
   const [editingService, setEditingService] = useState<McpServerInfo | null>(
     null,
@@ -67,9 +139,29 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
     url: '',
     env: '',
     headers: '',
+    request_timeout: null as number | null,
+    connect_timeout: null as number | null,
+    max_concurrent_calls: null as number | null,
+    lifecycle: 'eager' as ServerLifecycle,
+    idle_timeout: null as number | null,
+    restart_policy: 'on-failure' as RestartPolicy,
+    max_restarts: null as number | null,
+    memory_limit_mb: null as number | null,
+    sandbox: null as SandboxProfile | null,
+    cwd: '',
+    env_inheritance: 'inherit-all' as EnvInheritance,
+    env_allowlist: '',
+    container: null as ContainerConfig | null,
+    credential_helper: null as CredentialHelper | null,
+    runtime_mode: 'auto',
+    runtime_path: '',
+    runtime_version: '',
   })
   const [loading, setLoading] = useState(false)
   const [togglingServers, setTogglingServers] = useState<Set<string>>(new Set())
+  const [authorizingServer, setAuthorizingServer] = useState<string | null>(
+    null,
+  )
   const [addingService, setAddingService] = useState(false)
 
   // Add service mode: 'form' or 'json'
@@ -183,6 +275,36 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
     }
   }
 
+  const handleAuthorizeServer = async (serverName: string) => {
+    setAuthorizingServer(serverName)
+    message.info(t('mcp_server.oauth.browser_opened'))
+    try {
+      await McpServerService.authorizeMcpServer(serverName)
+      await fetchMcpServers()
+      onServiceChange?.()
+      message.success(t('mcp_server.oauth.authorize_success'))
+    } catch (error) {
+      console.error('Failed to authorize server:', error)
+      message.error(
+        t('mcp_server.oauth.authorize_failed', { error: String(error) }),
+      )
+    } finally {
+      setAuthorizingServer(null)
+    }
+  }
+
+  const handleClearAuthorization = async (serverName: string) => {
+    try {
+      await McpServerService.clearMcpServerAuthorization(serverName)
+      await fetchMcpServers()
+      onServiceChange?.()
+      message.success(t('mcp_server.oauth.sign_out_success'))
+    } catch (error) {
+      console.error('Failed to clear authorization:', error)
+      message.error(t('mcp_server.oauth.sign_out_failed'))
+    }
+  }
+
   // Convert key-value pairs format to JSON object
   const keyValuePairsToJson = (pairsString: string): Record<string, string> => {
     const result: Record<string, string> = {}
@@ -220,6 +342,27 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
       url: server.url || '',
       env: server.env ? jsonToKeyValuePairs(server.env) : '',
       headers: server.headers ? jsonToKeyValuePairs(server.headers) : '',
+      request_timeout: server.request_timeout ?? null,
+      connect_timeout: server.connect_timeout ?? null,
+      max_concurrent_calls: server.max_concurrent_calls ?? null,
+      lifecycle: server.lifecycle ?? 'eager',
+      idle_timeout: server.idle_timeout ?? null,
+      restart_policy: server.restart_policy ?? 'on-failure',
+      max_restarts: server.max_restarts ?? null,
+      memory_limit_mb: server.memory_limit_mb ?? null,
+      sandbox: server.sandbox ?? null,
+      cwd: server.cwd ?? '',
+      env_inheritance: server.env_inheritance ?? 'inherit-all',
+      env_allowlist: server.env_allowlist?.join(',') ?? '',
+      container: server.container ?? null,
+      credential_helper: server.credential_helper ?? null,
+      runtime_mode: RUNTIME_PRESETS.includes(server.runtime ?? 'auto')
+        ? (server.runtime ?? 'auto')
+        : 'path',
+      runtime_path: RUNTIME_PRESETS.includes(server.runtime ?? 'auto')
+        ? ''
+        : (server.runtime ?? ''),
+      runtime_version: server.runtime_version ?? '',
     })
     setShowEditService(true)
   }
@@ -231,11 +374,12 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
         try {
           const configData = JSON.parse(jsonConfig)
 
-          // Validate JSON structure
-          if (
-            !configData.mcpServers ||
-            typeof configData.mcpServers !== 'object'
-          ) {
+          // Validate JSON structure (Claude Desktop / VS Code / Zed formats)
+          const servers =
+            configData.mcpServers ??
+            configData.servers ??
+            configData.context_servers
+          if (!servers || typeof servers !== 'object') {
             setJsonError(
               t('mcp_server.messages.config_format_error_mcp_servers_missing'),
             )
@@ -243,7 +387,7 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
             return
           }
 
-          if (Object.keys(configData.mcpServers).length === 0) {
+          if (Object.keys(servers).length === 0) {
             setJsonError(
               t('mcp_server.messages.config_format_error_mcp_servers_empty'),
             )
@@ -345,6 +489,40 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
         env.length > 0 ? env : null,
         headers.length > 0 ? headers : null,
         editingService.enabled,
+        {
+          request_timeout: newServiceConfig.request_timeout,
+          connect_timeout: newServiceConfig.connect_timeout,
+          max_concurrent_calls: newServiceConfig.max_concurrent_calls,
+          lifecycle: newServiceConfig.lifecycle,
+          idle_timeout: newServiceConfig.idle_timeout,
+          restart_policy: newServiceConfig.restart_policy,
+          max_restarts: newServiceConfig.max_restarts,
+          memory_limit_mb: newServiceConfig.memory_limit_mb,
+          sandbox: newServiceConfig.sandbox
+            ? normalizeSandbox(newServiceConfig.sandbox)
+            : null,
+          cwd: newServiceConfig.cwd.trim() || null,
+          env_inheritance: newServiceConfig.env_inheritance,
+          env_allowlist: newServiceConfig.env_allowlist
+            ? newServiceConfig.env_allowlist
+                .split(',')
+                .map((name) => name.trim())
+                .filter(Boolean)
+            : null,
+          container: newServiceConfig.container
+            ? normalizeContainer(newServiceConfig.container)
+            : null,
+          credential_helper:
+            newServiceConfig.type === 'http' &&
+            newServiceConfig.credential_helper
+              ? normalizeCredentialHelper(newServiceConfig.credential_helper)
+              : null,
+          runtime:
+            newServiceConfig.runtime_mode === 'path'
+              ? newServiceConfig.runtime_path.trim() || 'auto'
+              : newServiceConfig.runtime_mode,
+          runtime_version: newServiceConfig.runtime_version.trim() || null,
+        },
       )
 
       message.success(t('mcp_server.messages.update_service_success'))
@@ -369,6 +547,23 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
       url: '',
       env: '',
       headers: '',
+      request_timeout: null,
+      connect_timeout: null,
+      max_concurrent_calls: null,
+      lifecycle: 'eager',
+      idle_timeout: null,
+      restart_policy: 'on-failure',
+      max_restarts: null,
+      memory_limit_mb: null,
+      sandbox: null,
+      cwd: '',
+      env_inheritance: 'inherit-all',
+      env_allowlist: '',
+      container: null,
+      credential_helper: null,
+      runtime_mode: 'auto',
+      runtime_path: '',
+      runtime_version: '',
     })
     setJsonConfig('')
     setJsonError('')
@@ -440,6 +635,7 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
         { text: t('mcp_server.status.connected'), value: 'connected' },
         { text: t('mcp_server.status.connecting'), value: 'connecting' },
         { text: t('mcp_server.status.disconnected'), value: 'disconnected' },
+        { text: t('mcp_server.status.idle'), value: 'idle' },
         { text: t('mcp_server.status.failed'), value: 'failed' },
       ],
       onFilter: (value, record: McpServerInfo) => record.status === value,
@@ -464,6 +660,12 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
                 icon: <AlertCircle size={12} />,
                 text: t('mcp_server.status.failed'),
               }
+            case 'idle':
+              return {
+                color: 'default',
+                icon: <Moon size={12} />,
+                text: t('mcp_server.status.idle'),
+              }
             default:
               return {
                 color: 'default',
@@ -484,15 +686,37 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
         )
 
         // 如果是连接失败状态且有错误信息，显示错误详情 Tooltip
-        if (status === 'failed' && record.error_message) {
-          return (
+        const statusWithDetail =
+          status === 'failed' && record.error_message ? (
             <Tooltip title={record.error_message} placement='topLeft'>
               {statusElement}
             </Tooltip>
+          ) : (
+            statusElement
+          )
+
+        // 当前机器上无法解析的占位符
+        if (record.unresolved_placeholders?.length) {
+          return (
+            <Flex gap={4} wrap>
+              {statusWithDetail}
+              <Tooltip
+                title={t('mcp_server.placeholders.unresolved_tooltip', {
+                  placeholders: record.unresolved_placeholders.join(', '),
+                })}
+                placement='topLeft'>
+                <Tag color='warning' style={{ fontSize: '12px', margin: 0 }}>
+                  <Flex align='center' gap={4}>
+                    <AlertCircle size={12} />
+                    {t('mcp_server.placeholders.unresolved')}
+                  </Flex>
+                </Tag>
+              </Tooltip>
+            </Flex>
           )
         }
 
-        return statusElement
+        return statusWithDetail
       },
     },
     {
@@ -571,7 +795,7 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
     {
       title: t('mcp_server.table.actions'),
       key: 'actions',
-      width: 120,
+      width: 150,
       fixed: 'right',
       render: (_, record: McpServerInfo) => (
         <Space size='small'>
@@ -596,6 +820,33 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
             icon={<Edit3 size={12} />}
             onClick={() => handleEditServer(record)}
           />
+          {record.type === 'http' &&
+            (record.oauth_authorized ? (
+              <Popconfirm
+                title={t('mcp_server.oauth.sign_out_confirm')}
+                description={t('mcp_server.oauth.sign_out_description', {
+                  name: record.name,
+                })}
+                onConfirm={() => handleClearAuthorization(record.name)}>
+                <Tooltip title={t('mcp_server.oauth.authorized')}>
+                  <Button
+                    size='small'
+                    type='text'
+                    icon={<KeyRound size={12} color='#52c41a' />}
+                  />
+                </Tooltip>
+              </Popconfirm>
+            ) : (
+              <Tooltip title={t('mcp_server.oauth.authorize')}>
+                <Button
+                  size='small'
+                  type='text'
+                  loading={authorizingServer === record.name}
+                  icon={<KeyRound size={12} />}
+                  onClick={() => handleAuthorizeServer(record.name)}
+                />
+              </Tooltip>
+            ))}
           <Popconfirm
             title={t('mcp_server.modals.delete_service_confirm')}
             description={t('mcp_server.modals.delete_service_description', {
@@ -694,6 +945,11 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
               onClick={() => fetchMcpServers()}>
               {t('mcp_server.actions.refresh')}
             </Button>
+            <Button
+              icon={<Download size={16} />}
+              onClick={() => setShowClientImport(true)}>
+              {t('mcp_server.import.title')}
+            </Button>
             <Button
               type='primary'
               icon={<Plus size={16} />}
@@ -739,6 +995,15 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
         />
       </Flex>
 
+      <ClientImportModal
+        open={showClientImport}
+        onClose={() => setShowClientImport(false)}
+        onImported={async () => {
+          onServiceChange?.()
+          await fetchMcpServers()
+        }}
+      />
+
       {/* Add Service Modal */}
       <Modal
         title={t('mcp_server.modals.add_service_title')}
@@ -1086,6 +1351,81 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
                 />
               </div>
 
+              <div>
+                <Text strong>{t('mcp_server.form.runtime')}</Text>
+                <Flex gap='small' style={{ marginTop: '4px' }}>
+                  <Select
+                    value={newServiceConfig.runtime_mode}
+                    onChange={(value: string) =>
+                      setNewServiceConfig({
+                        ...newServiceConfig,
+                        runtime_mode: value,
+                      })
+                    }
+                    options={[
+                      {
+                        value: 'auto',
+                        label: t('mcp_server.form.runtime_auto'),
+                      },
+                      { value: 'bun', label: t('mcp_server.form.runtime_bun') },
+                      {
+                        value: 'node',
+                        label: t('mcp_server.form.runtime_node'),
+                      },
+                      {
+                        value: 'system-node',
+                        label: t('mcp_server.form.runtime_system_node'),
+                      },
+                      {
+                        value: 'path',
+                        label: t('mcp_server.form.runtime_path'),
+                      },
+                    ]}
+                    style={{ flex: 1 }}
+                  />
+                  <Input
+                    value={newServiceConfig.runtime_path}
+                    disabled={newServiceConfig.runtime_mode !== 'path'}
+                    onChange={(e) =>
+                      setNewServiceConfig({
+                        ...newServiceConfig,
+                        runtime_path: e.target.value,
+                      })
+                    }
+                    placeholder={t('mcp_server.form.runtime_path_placeholder')}
+                    style={{ flex: 2 }}
+                  />
+                  <Input
+                    value={newServiceConfig.runtime_version}
+                    onChange={(e) =>
+                      setNewServiceConfig({
+                        ...newServiceConfig,
+                        runtime_version: e.target.value,
+                      })
+                    }
+                    placeholder={t(
+                      'mcp_server.form.runtime_version_placeholder',
+                    )}
+                    style={{ flex: 1 }}
+                  />
+                </Flex>
+                <Text
+                  type='secondary'
+                  style={{
+                    fontSize: '12px',
+                    marginTop: '4px',
+                    display: 'block',
+                  }}>
+                  {editingService?.active_runtime
+                    ? t('mcp_server.form.runtime_active', {
+                        kind: editingService.active_runtime.kind,
+                        executable: editingService.active_runtime.executable,
+                      })
+                    : t('mcp_server.form.runtime_help')}{' '}
+                  {t('mcp_server.form.runtime_version_help')}
+                </Text>
+              </div>
+
               <div>
                 <Text strong>{t('mcp_server.form.env_vars')}</Text>
                 <TextArea
@@ -1110,6 +1450,72 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
                   {t('mcp_server.form.env_vars_help')}
                 </Text>
               </div>
+
+              <div>
+                <Text strong>{t('mcp_server.form.env_inheritance')}</Text>
+                <Flex gap='small' style={{ marginTop: '4px' }}>
+                  <Select
+                    value={newServiceConfig.env_inheritance}
+                    onChange={(value: EnvInheritance) =>
+                      setNewServiceConfig({
+                        ...newServiceConfig,
+                        env_inheritance: value,
+                      })
+                    }
+                    options={[
+                      {
+                        value: 'inherit-all',
+                        label: t('mcp_server.form.env_inheritance_inherit_all'),
+                      },
+                      {
+                        value: 'allowlist',
+                        label: t('mcp_server.form.env_inheritance_allowlist'),
+                      },
+                      {
+                        value: 'none',
+                        label: t('mcp_server.form.env_inheritance_none'),
+                      },
+                    ]}
+                    style={{ width: 160 }}
+                  />
+                  <Input
+                    disabled={newServiceConfig.env_inheritance !== 'allowlist'}
+                    value={newServiceConfig.env_allowlist}
+                    onChange={(e) =>
+                      setNewServiceConfig({
+                        ...newServiceConfig,
+                        env_allowlist: e.target.value,
+                      })
+                    }
+                    placeholder='NODE_*,GITHUB_TOKEN'
+                    style={{ flex: 1 }}
+                  />
+                </Flex>
+                <Text
+                  type='secondary'
+                  style={{
+                    fontSize: '12px',
+                    marginTop: '4px',
+                    display: 'block',
+                  }}>
+                  {t('mcp_server.form.env_inheritance_help')}
+                </Text>
+              </div>
+
+              <div>
+                <Text strong>{t('mcp_server.form.cwd')}</Text>
+                <Input
+                  value={newServiceConfig.cwd}
+                  onChange={(e) =>
+                    setNewServiceConfig({
+                      ...newServiceConfig,
+                      cwd: e.target.value,
+                    })
+                  }
+                  placeholder={t('mcp_server.form.cwd_placeholder')}
+                  style={{ marginTop: '4px' }}
+                />
+              </div>
             </>
           )}
 
@@ -1154,8 +1560,624 @@ const McpServerManager: React.FC<McpServerManagerProps> = ({
                   {t('mcp_server.form.headers_help')}
                 </Text>
               </div>
+
+              <div>
+                <Text strong>{t('mcp_server.form.remote_env_allowlist')}</Text>
+                <Input
+                  value={newServiceConfig.env_allowlist}
+                  onChange={(e) =>
+                    setNewServiceConfig({
+                      ...newServiceConfig,
+                      env_allowlist: e.target.value,
+                    })
+                  }
+                  placeholder='GITHUB_TOKEN,API_*'
+                  style={{ marginTop: '4px' }}
+                />
+                <Text
+                  type='secondary'
+                  style={{
+                    fontSize: '12px',
+                    marginTop: '4px',
+                    display: 'block',
+                  }}>
+                  {t('mcp_server.form.remote_env_allowlist_help')}
+                </Text>
+              </div>
             </>
           )}
+
+          <div>
+            <Text strong>{t('mcp_server.form.call_limits')}</Text>
+            <Flex gap='small' style={{ marginTop: '4px' }}>
+              <InputNumber
+                min={1}
+                value={newServiceConfig.request_timeout}
+                onChange={(value) =>
+                  setNewServiceConfig({
+                    ...newServiceConfig,
+                    request_timeout: value,
+                  })
+                }
+                addonBefore={t('mcp_server.form.request_timeout')}
+                addonAfter='s'
+                placeholder='60'
+                style={{ flex: 1 }}
+              />
+              <InputNumber
+                min={1}
+                value={newServiceConfig.connect_timeout}
+                onChange={(value) =>
+                  setNewServiceConfig({
+                    ...newServiceConfig,
+                    connect_timeout: value,
+                  })
+                }
+                addonBefore={t('mcp_server.form.connect_timeout')}
+                addonAfter='s'
+                placeholder='30'
+                style={{ flex: 1 }}
+              />
+              <InputNumber
+                min={1}
+                value={newServiceConfig.max_concurrent_calls}
+                onChange={(value) =>
+                  setNewServiceConfig({
+                    ...newServiceConfig,
+                    max_concurrent_calls: value,
+                  })
+                }
+                addonBefore={t('mcp_server.form.max_concurrent_calls')}
+                placeholder='∞'
+                style={{ flex: 1 }}
+              />
+            </Flex>
+            <Text
+              type='secondary'
+              style={{
+                fontSize: '12px',
+                marginTop: '4px',
+                display: 'block',
+              }}>
+              {t('mcp_server.form.call_limits_help')}
+            </Text>
+          </div>
+
+          <div>
+            <Text strong>{t('mcp_server.form.lifecycle')}</Text>
+            <Flex gap='small' style={{ marginTop: '4px' }}>
+              <Select
+                value={newServiceConfig.lifecycle}
+                onChange={(value: ServerLifecycle) =>
+                  setNewServiceConfig({
+                    ...newServiceConfig,
+                    lifecycle: value,
+                  })
+                }
+                options={[
+                  {
+                    value: 'eager',
+                    label: t('mcp_server.form.lifecycle_eager'),
+                  },
+                  {
+                    value: 'lazy',
+                    label: t('mcp_server.form.lifecycle_lazy'),
+                  },
+                ]}
+                style={{ flex: 1 }}
+              />
+              <InputNumber
+                min={1}
+                disabled={newServiceConfig.lifecycle !== 'lazy'}
+                value={newServiceConfig.idle_timeout}
+                onChange={(value) =>
+                  setNewServiceConfig({
+                    ...newServiceConfig,
+                    idle_timeout: value,
+                  })
+                }
+                addonBefore={t('mcp_server.form.idle_timeout')}
+                addonAfter='s'
+                placeholder='600'
+                style={{ flex: 1 }}
+              />
+            </Flex>
+            <Text
+              type='secondary'
+              style={{
+                fontSize: '12px',
+                marginTop: '4px',
+                display: 'block',
+              }}>
+              {t('mcp_server.form.lifecycle_help')}
+            </Text>
+          </div>
+
+          {newServiceConfig.type === 'stdio' && (
+            <div>
+              <Text strong>{t('mcp_server.form.restart_policy')}</Text>
+              <Flex gap='small' style={{ marginTop: '4px' }}>
+                <Select
+                  value={newServiceConfig.restart_policy}
+                  onChange={(value: RestartPolicy) =>
+                    setNewServiceConfig({
+                      ...newServiceConfig,
+                      restart_policy: value,
+                    })
+                  }
+                  options={[
+                    {
+                      value: 'never',
+                      label: t('mcp_server.form.restart_policy_never'),
+                    },
+                    {
+                      value: 'on-failure',
+                      label: t('mcp_server.form.restart_policy_on_failure'),
+                    },
+                    {
+                      value: 'always',
+                      label: t('mcp_server.form.restart_policy_always'),
+                    },
+                  ]}
+                  style={{ flex: 1 }}
+                />
+                <InputNumber
+                  min={0}
+                  disabled={newServiceConfig.restart_policy !== 'on-failure'}
+                  value={newServiceConfig.max_restarts}
+                  onChange={(value) =>
+                    setNewServiceConfig({
+                      ...newServiceConfig,
+                      max_restarts: value,
+                    })
+                  }
+                  addonBefore={t('mcp_server.form.max_restarts')}
+                  placeholder='5'
+                  style={{ flex: 1 }}
+                />
+              </Flex>
+              <InputNumber
+                min={0}
+                value={newServiceConfig.memory_limit_mb}
+                onChange={(value) =>
+                  setNewServiceConfig({
+                    ...newServiceConfig,
+                    memory_limit_mb: value,
+                  })
+                }
+                addonBefore={t('mcp_server.form.memory_limit')}
+                addonAfter='MB'
+                placeholder={t('mcp_server.form.memory_limit_placeholder')}
+                style={{ width: '100%', marginTop: '8px' }}
+              />
+            </div>
+          )}
+
+          {newServiceConfig.type === 'stdio' && (
+            <div>
+              <Flex justify='space-between' align='center'>
+                <Text strong>{t('mcp_server.form.sandbox')}</Text>
+                <Switch
+                  size='small'
+                  checked={!!newServiceConfig.sandbox}
+                  onChange={(checked) =>
+                    setNewServiceConfig({
+                      ...newServiceConfig,
+                      sandbox: checked ? { ...DEFAULT_SANDBOX } : null,
+                    })
+                  }
+                />
+              </Flex>
+              {newServiceConfig.sandbox && (
+                <Flex vertical gap='small' style={{ marginTop: '8px' }}>
+                  <TextArea
+                    value={newServiceConfig.sandbox.allowed_paths.join('\n')}
+                    onChange={(e) =>
+                      setNewServiceConfig({
+                        ...newServiceConfig,
+                        sandbox: {
+                          ...newServiceConfig.sandbox!,
+                          allowed_paths: e.target.value.split('\n'),
+                        },
+                      })
+                    }
+                    placeholder={t('mcp_server.form.sandbox_allowed_paths')}
+                    autoSize={{ minRows: 2, maxRows: 4 }}
+                  />
+                  <TextArea
+                    value={newServiceConfig.sandbox.read_only_paths.join('\n')}
+                    onChange={(e) =>
+                      setNewServiceConfig({
+                        ...newServiceConfig,
+                        sandbox: {
+                          ...newServiceConfig.sandbox!,
+                          read_only_paths: e.target.value.split('\n'),
+                        },
+                      })
+                    }
+                    placeholder={t('mcp_server.form.sandbox_read_only_paths')}
+                    autoSize={{ minRows: 1, maxRows: 4 }}
+                  />
+                  <Flex gap='middle' align='center'>
+                    <Space>
+                      <Switch
+                        size='small'
+                        checked={newServiceConfig.sandbox.network}
+                        onChange={(checked) =>
+                          setNewServiceConfig({
+                            ...newServiceConfig,
+                            sandbox: {
+                              ...newServiceConfig.sandbox!,
+                              network: checked,
+                            },
+                          })
+                        }
+                      />
+                      <Text>{t('mcp_server.form.sandbox_network')}</Text>
+                    </Space>
+                    <Space>
+                      <Switch
+                        size='small'
+                        checked={newServiceConfig.sandbox.drop_env}
+                        onChange={(checked) =>
+                          setNewServiceConfig({
+                            ...newServiceConfig,
+                            sandbox: {
+                              ...newServiceConfig.sandbox!,
+                              drop_env: checked,
+                            },
+                          })
+                        }
+                      />
+                      <Text>{t('mcp_server.form.sandbox_drop_env')}</Text>
+                    </Space>
+                  </Flex>
+                  {newServiceConfig.sandbox.drop_env && (
+                    <Input
+                      value={newServiceConfig.sandbox.env_allowlist.join(',')}
+                      onChange={(e) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          sandbox: {
+                            ...newServiceConfig.sandbox!,
+                            env_allowlist: e.target.value.split(','),
+                          },
+                        })
+                      }
+                      placeholder={t('mcp_server.form.sandbox_env_allowlist')}
+                    />
+                  )}
+                  <Flex gap='small'>
+                    <InputNumber
+                      min={1}
+                      value={newServiceConfig.sandbox.max_memory_mb}
+                      onChange={(value) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          sandbox: {
+                            ...newServiceConfig.sandbox!,
+                            max_memory_mb: value,
+                          },
+                        })
+                      }
+                      addonBefore={t('mcp_server.form.sandbox_max_memory')}
+                      addonAfter='MB'
+                      style={{ flex: 1 }}
+                    />
+                    <InputNumber
+                      min={1}
+                      value={newServiceConfig.sandbox.max_cpu_seconds}
+                      onChange={(value) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          sandbox: {
+                            ...newServiceConfig.sandbox!,
+                            max_cpu_seconds: value,
+                          },
+                        })
+                      }
+                      addonBefore={t('mcp_server.form.sandbox_max_cpu')}
+                      addonAfter='s'
+                      style={{ flex: 1 }}
+                    />
+                  </Flex>
+                  <Flex gap='small'>
+                    <InputNumber
+                      min={1}
+                      value={newServiceConfig.sandbox.max_open_files}
+                      onChange={(value) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          sandbox: {
+                            ...newServiceConfig.sandbox!,
+                            max_open_files: value,
+                          },
+                        })
+                      }
+                      addonBefore={t('mcp_server.form.sandbox_max_open_files')}
+                      style={{ flex: 1 }}
+                    />
+                    <InputNumber
+                      min={1}
+                      value={newServiceConfig.sandbox.max_processes}
+                      onChange={(value) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          sandbox: {
+                            ...newServiceConfig.sandbox!,
+                            max_processes: value,
+                          },
+                        })
+                      }
+                      addonBefore={t('mcp_server.form.sandbox_max_processes')}
+                      style={{ flex: 1 }}
+                    />
+                  </Flex>
+                </Flex>
+              )}
+              <Text
+                type='secondary'
+                style={{
+                  fontSize: '12px',
+                  marginTop: '4px',
+                  display: 'block',
+                }}>
+                {t('mcp_server.form.sandbox_help')}
+              </Text>
+            </div>
+          )}
+
+          {newServiceConfig.type === 'stdio' && (
+            <div>
+              <Flex justify='space-between' align='center'>
+                <Text strong>{t('mcp_server.form.container')}</Text>
+                <Switch
+                  size='small'
+                  checked={!!newServiceConfig.container}
+                  onChange={(checked) =>
+                    setNewServiceConfig({
+                      ...newServiceConfig,
+                      container: checked ? { ...DEFAULT_CONTAINER } : null,
+                    })
+                  }
+                />
+              </Flex>
+              {newServiceConfig.container && (
+                <Flex vertical gap='small' style={{ marginTop: '8px' }}>
+                  <Flex gap='small'>
+                    <Input
+                      value={newServiceConfig.container.image}
+                      onChange={(e) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          container: {
+                            ...newServiceConfig.container!,
+                            image: e.target.value,
+                          },
+                        })
+                      }
+                      addonBefore={t('mcp_server.form.container_image')}
+                      placeholder='mcp/fetch'
+                      style={{ flex: 2 }}
+                    />
+                    <Input
+                      value={newServiceConfig.container.tag ?? ''}
+                      onChange={(e) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          container: {
+                            ...newServiceConfig.container!,
+                            tag: e.target.value,
+                          },
+                        })
+                      }
+                      addonBefore={t('mcp_server.form.container_tag')}
+                      placeholder='latest'
+                      style={{ flex: 1 }}
+                    />
+                  </Flex>
+                  <Flex gap='small'>
+                    <Select
+                      value={newServiceConfig.container.runtime}
+                      onChange={(value: ContainerRuntime) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          container: {
+                            ...newServiceConfig.container!,
+                            runtime: value,
+                          },
+                        })
+                      }
+                      options={[
+                        {
+                          value: 'auto',
+                          label: t('mcp_server.form.container_runtime_auto'),
+                        },
+                        { value: 'docker', label: 'Docker' },
+                        { value: 'podman', label: 'Podman' },
+                      ]}
+                      style={{ flex: 1 }}
+                    />
+                    <Select
+                      value={newServiceConfig.container.pull_policy}
+                      onChange={(value: PullPolicy) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          container: {
+                            ...newServiceConfig.container!,
+                            pull_policy: value,
+                          },
+                        })
+                      }
+                      options={[
+                        {
+                          value: 'missing',
+                          label: t('mcp_server.form.container_pull_missing'),
+                        },
+                        {
+                          value: 'always',
+                          label: t('mcp_server.form.container_pull_always'),
+                        },
+                        {
+                          value: 'never',
+                          label: t('mcp_server.form.container_pull_never'),
+                        },
+                      ]}
+                      style={{ flex: 1 }}
+                    />
+                    <Input
+                      value={newServiceConfig.container.network ?? ''}
+                      onChange={(e) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          container: {
+                            ...newServiceConfig.container!,
+                            network: e.target.value,
+                          },
+                        })
+                      }
+                      placeholder={t('mcp_server.form.container_network')}
+                      style={{ flex: 1 }}
+                    />
+                  </Flex>
+                  <TextArea
+                    value={newServiceConfig.container.mounts.join('\n')}
+                    onChange={(e) =>
+                      setNewServiceConfig({
+                        ...newServiceConfig,
+                        container: {
+                          ...newServiceConfig.container!,
+                          mounts: e.target.value.split('\n'),
+                        },
+                      })
+                    }
+                    placeholder={t('mcp_server.form.container_mounts')}
+                    rows={2}
+                  />
+                </Flex>
+              )}
+              <Text
+                type='secondary'
+                style={{
+                  fontSize: '12px',
+                  marginTop: '4px',
+                  display: 'block',
+                }}>
+                {t('mcp_server.form.container_help')}
+              </Text>
+            </div>
+          )}
+
+          {newServiceConfig.type === 'http' && (
+            <div>
+              <Flex justify='space-between' align='center'>
+                <Text strong>{t('mcp_server.form.credential_helper')}</Text>
+                <Switch
+                  size='small'
+                  checked={!!newServiceConfig.credential_helper}
+                  onChange={(checked) =>
+                    setNewServiceConfig({
+                      ...newServiceConfig,
+                      credential_helper: checked
+                        ? { ...DEFAULT_CREDENTIAL_HELPER }
+                        : null,
+                    })
+                  }
+                />
+              </Flex>
+              {newServiceConfig.credential_helper && (
+                <Flex vertical gap='small' style={{ marginTop: '8px' }}>
+                  <Flex gap='small'>
+                    <Input
+                      value={newServiceConfig.credential_helper.command}
+                      onChange={(e) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          credential_helper: {
+                            ...newServiceConfig.credential_helper!,
+                            command: e.target.value,
+                          },
+                        })
+                      }
+                      addonBefore={t('mcp_server.form.credential_command')}
+                      placeholder='gcloud'
+                      style={{ flex: 1 }}
+                    />
+                    <Input
+                      value={newServiceConfig.credential_helper.args.join(' ')}
+                      onChange={(e) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          credential_helper: {
+                            ...newServiceConfig.credential_helper!,
+                            args: e.target.value.split(' '),
+                          },
+                        })
+                      }
+                      addonBefore={t('mcp_server.form.credential_args')}
+                      placeholder='auth print-access-token'
+                      style={{ flex: 2 }}
+                    />
+                  </Flex>
+                  <Flex gap='small'>
+                    <Input
+                      value={newServiceConfig.credential_helper.header}
+                      onChange={(e) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          credential_helper: {
+                            ...newServiceConfig.credential_helper!,
+                            header: e.target.value,
+                          },
+                        })
+                      }
+                      addonBefore={t('mcp_server.form.credential_header')}
+                      style={{ flex: 1 }}
+                    />
+                    <Input
+                      value={newServiceConfig.credential_helper.format}
+                      onChange={(e) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          credential_helper: {
+                            ...newServiceConfig.credential_helper!,
+                            format: e.target.value,
+                          },
+                        })
+                      }
+                      addonBefore={t('mcp_server.form.credential_format')}
+                      style={{ flex: 1 }}
+                    />
+                    <InputNumber
+                      min={1}
+                      value={newServiceConfig.credential_helper.ttl_seconds}
+                      onChange={(value) =>
+                        setNewServiceConfig({
+                          ...newServiceConfig,
+                          credential_helper: {
+                            ...newServiceConfig.credential_helper!,
+                            ttl_seconds: value,
+                          },
+                        })
+                      }
+                      addonBefore={t('mcp_server.form.credential_ttl')}
+                      addonAfter='s'
+                      style={{ flex: 1 }}
+                    />
+                  </Flex>
+                </Flex>
+              )}
+              <Text
+                type='secondary'
+                style={{
+                  fontSize: '12px',
+                  marginTop: '4px',
+                  display: 'block',
+                }}>
+                {t('mcp_server.form.credential_helper_help')}
+              </Text>
+            </div>
+          )}
         </Flex>
       </Modal>
 
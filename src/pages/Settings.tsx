@@ -4,6 +4,7 @@ import {
   Button,
   Col,
   Flex,
+  Input,
   InputNumber,
   Row,
   Select,
@@ -16,10 +17,14 @@ import {
 import Card from 'antd/es/card'
 import { memo, useCallback, useEffect, useState } from 'react'
 import { useTranslation } from 'react-i18next'
+import BundleManager from '../components/BundleManager'
+import ConfigFileSync from '../components/ConfigFileSync'
+import SecretManager from '../components/SecretManager'
 import SystemToolManager from '../components/SystemToolManager'
-import type { SystemSettings } from '../types'
+import type { ShellEnvironmentSnapshot, SystemSettings } from '../types'
 
 const { Title, Text } = Typography
+const { TextArea } = Input
 
 const Settings: React.FC = memo(() => {
   const { t } = useTranslation()
@@ -32,6 +37,7 @@ const Settings: React.FC = memo(() => {
       port: 8850,
       max_connections: 100,
       timeout_seconds: 30,
+      max_body_size: 4 * 1024 * 1024,
       auth: false,
     },
     logging: {
@@ -55,6 +61,26 @@ const Settings: React.FC = memo(() => {
   const [autostartEnabled, setAutostartEnabled] = useState(false)
   const [localIPs, setLocalIPs] = useState<string[]>([])
   const [loadingIPs, setLoadingIPs] = useState(false)
+  const [shellEnv, setShellEnv] = useState<ShellEnvironmentSnapshot | null>(
+    null,
+  )
+  const [loadingShellEnv, setLoadingShellEnv] = useState(false)
+
+  const loadShellEnvironment = useCallback(
+    async (refresh: boolean) => {
+      setLoadingShellEnv(true)
+      try {
+        const { ConfigService } = await import('../services/config-service')
+        setShellEnv(await ConfigService.getShellEnvironment(refresh))
+      } catch (error) {
+        console.error('Failed to load shell environment:', error)
+        message.error(t('settings.errors.load_shell_env_failed'))
+      } finally {
+        setLoadingShellEnv(false)
+      }
+    },
+    [message.error],
+  )
 
   const loadLocalIPs = useCallback(async () => {
     setLoadingIPs(true)
@@ -146,6 +172,31 @@ const Settings: React.FC = memo(() => {
     [],
   )
 
+  // 鉴权开关修改后立即保存，失败时恢复原来的状态
+  const handleServerToggle = async (
+    key: 'auth' | 'oauth',
+    checked: boolean,
+  ) => {
+    const newSettings = {
+      ...settings,
+      server: {
+        ...settings.server,
+        [key]: checked,
+      },
+    }
+    setSettings(newSettings)
+
+    try {
+      const { ConfigService } = await import('../services/config-service')
+      await ConfigService.saveSystemSettings(newSettings)
+      message.success(t('settings.messages.auth_settings_saved'))
+    } catch (error) {
+      console.error(`Failed to save ${key} setting:`, error)
+      message.error(t('settings.errors.save_auth_settings_failed'))
+      setSettings(settings)
+    }
+  }
+
   const handleLoggingSettingChange = useCallback(
     (key: string, value: string | number | boolean) => {
       setSettings((prev) => ({
@@ -159,6 +210,19 @@ const Settings: React.FC = memo(() => {
     [],
   )
 
+  const handleAppSettingChange = useCallback(
+    (key: string, value: string | number | null) => {
+      setSettings((prev) => ({
+        ...prev,
+        settings: {
+          ...prev.settings,
+          [key]: value,
+        },
+      }))
+    },
+    [],
+  )
+
   // security removed
 
   const handleSystemTraySettingChange = useCallback(
@@ -295,6 +359,24 @@ const Settings: React.FC = memo(() => {
                         style={{ width: '100%', marginTop: '4px' }}
                       />
                     </Col>
+                    <Col xs={24} md={12}>
+                      <Text strong>{t('settings.server.max_body_size')}</Text>
+                      <InputNumber
+                        value={Math.round(
+                          (settings.server.max_body_size ?? 4 * 1024 * 1024) /
+                            (1024 * 1024),
+                        )}
+                        onChange={(value: number | null) =>
+                          handleServerSettingChange(
+                            'max_body_size',
+                            (value || 1) * 1024 * 1024,
+                          )
+                        }
+                        min={1}
+                        max={1024}
+                        style={{ width: '100%', marginTop: '4px' }}
+                      />
+                    </Col>
                     <Col xs={24}>
                       <Flex justify='space-between' align='center'>
                         <div>
@@ -311,40 +393,32 @@ const Settings: React.FC = memo(() => {
                         </div>
                         <Switch
                           checked={settings.server.auth || false}
-                          onChange={async (checked: boolean) => {
-                            // 先更新本地状态
-                            const newSettings = {
-                              ...settings,
-                              server: {
-                                ...settings.server,
-                                auth: checked,
-                              },
-                            }
-                            setSettings(newSettings)
-
-                            // 立即保存设置
-                            try {
-                              const { ConfigService } = await import(
-                                '../services/config-service'
-                              )
-                              await ConfigService.saveSystemSettings(
-                                newSettings,
-                              )
-                              message.success(
-                                t('settings.messages.auth_settings_saved'),
-                              )
-                            } catch (error) {
-                              console.error(
-                                'Failed to save auth setting:',
-                                error,
-                              )
-                              message.error(
-                                t('settings.errors.save_auth_settings_failed'),
-                              )
-                              // 如果保存失败，恢复原来的状态
-                              setSettings(settings)
-                            }
-                          }}
+                          onChange={(checked: boolean) =>
+                            handleServerToggle('auth', checked)
+                          }
+                        />
+                      </Flex>
+                    </Col>
+                    <Col xs={24}>
+                      <Flex justify='space-between' align='center'>
+                        <div>
+                          <Text strong>{t('settings.server.oauth.title')}</Text>
+                          <Text
+                            type='secondary'
+                            style={{
+                              fontSize: '14px',
+                              display: 'block',
+                              marginTop: '2px',
+                            }}>
+                            {t('settings.server.oauth.description')}
+                          </Text>
+                        </div>
+                        <Switch
+                          checked={settings.server.oauth || false}
+                          disabled={!settings.server.auth}
+                          onChange={(checked: boolean) =>
+                            handleServerToggle('oauth', checked)
+                          }
                         />
                       </Flex>
                     </Col>
@@ -391,6 +465,138 @@ const Settings: React.FC = memo(() => {
                     </Col>
                   </Row>
                 </Card>
+
+                {/* Shell Environment Settings */}
+                <Card
+                  title={t('settings.shell_env.title')}
+                  style={{ marginTop: '16px' }}>
+                  <Row gutter={[16, 16]}>
+                    <Col xs={24} md={12}>
+                      <Text strong>{t('settings.shell_env.shell_path')}</Text>
+                      <Input
+                        value={settings.settings?.shell_path ?? ''}
+                        onChange={(e) =>
+                          handleAppSettingChange(
+                            'shell_path',
+                            e.target.value || null,
+                          )
+                        }
+                        placeholder='$SHELL'
+                        style={{ marginTop: '4px' }}
+                      />
+                    </Col>
+                    <Col xs={24} md={12}>
+                      <Text strong>{t('settings.shell_env.ttl')}</Text>
+                      <InputNumber
+                        value={settings.settings?.shell_env_ttl}
+                        onChange={(value: number | null) =>
+                          handleAppSettingChange('shell_env_ttl', value)
+                        }
+                        min={0}
+                        placeholder='300'
+                        addonAfter='s'
+                        style={{ width: '100%', marginTop: '4px' }}
+                      />
+                    </Col>
+                    <Col xs={24}>
+                      <Text strong>{t('settings.shell_env.workspace')}</Text>
+                      <Input
+                        value={settings.settings?.workspace ?? ''}
+                        onChange={(e) =>
+                          handleAppSettingChange(
+                            'workspace',
+                            e.target.value || null,
+                          )
+                        }
+                        placeholder='~/workspace'
+                        style={{ marginTop: '4px' }}
+                      />
+                      <Text type='secondary' style={{ fontSize: '12px' }}>
+                        {t('settings.shell_env.workspace_description')}
+                      </Text>
+                    </Col>
+                    <Col xs={24}>
+                      <Flex justify='space-between' align='center'>
+                        <Text type='secondary' style={{ fontSize: '12px' }}>
+                          {shellEnv
+                            ? t('settings.shell_env.summary', {
+                                shell: shellEnv.shell,
+                                count: Object.keys(shellEnv.variables).length,
+                                duration: shellEnv.load_duration_ms,
+                                time: new Date(
+                                  shellEnv.loaded_at,
+                                ).toLocaleString(),
+                              })
+                            : t('settings.shell_env.description')}
+                        </Text>
+                        <Space>
+                          <Button
+                            loading={loadingShellEnv}
+                            onClick={() => loadShellEnvironment(false)}>
+                            {t('settings.shell_env.inspect')}
+                          </Button>
+                          <Button
+                            loading={loadingShellEnv}
+                            onClick={() => loadShellEnvironment(true)}>
+                            {t('settings.shell_env.refresh')}
+                          </Button>
+                        </Space>
+                      </Flex>
+                      {shellEnv && (
+                        <TextArea
+                          readOnly
+                          value={Object.entries(shellEnv.variables)
+                            .sort(([a], [b]) => a.localeCompare(b))
+                            .map(([key, value]) => `${key}=${value}`)
+                            .join('\n')}
+                          autoSize={{ minRows: 4, maxRows: 12 }}
+                          style={{
+                            marginTop: '8px',
+                            fontFamily: 'monospace',
+                            fontSize: '12px',
+                          }}
+                        />
+                      )}
+                    </Col>
+                  </Row>
+                </Card>
+
+                {/* Tool Download Settings */}
+                <Card
+                  title={t('settings.tool_download.title')}
+                  style={{ marginTop: '16px' }}>
+                  <Text strong>{t('settings.tool_download.mirror_url')}</Text>
+                  <Input
+                    value={settings.settings?.tool_mirror_url ?? ''}
+                    onChange={(e) =>
+                      handleAppSettingChange(
+                        'tool_mirror_url',
+                        e.target.value || null,
+                      )
+                    }
+                    placeholder='https://github.com'
+                    style={{ marginTop: '4px' }}
+                  />
+                  <Text type='secondary' style={{ fontSize: '12px' }}>
+                    {t('settings.tool_download.description')}
+                  </Text>
+                  <div style={{ marginTop: '16px' }}>
+                    <Text strong>
+                      {t('settings.tool_download.node_mirror_url')}
+                    </Text>
+                  </div>
+                  <Input
+                    value={settings.settings?.node_mirror_url ?? ''}
+                    onChange={(e) =>
+                      handleAppSettingChange(
+                        'node_mirror_url',
+                        e.target.value || null,
+                      )
+                    }
+                    placeholder='https://nodejs.org/dist'
+                    style={{ marginTop: '4px' }}
+                  />
+                </Card>
               </div>
             ),
           },
@@ -500,11 +706,45 @@ const Settings: React.FC = memo(() => {
               </Card>
             ),
           },
+          {
+            key: 'config_file',
+            label: t('settings.config_file.title'),
+            children: (
+              <Card>
+                <Text strong>{t('settings.config_file.path')}</Text>
+                <Input
+                  value={settings.settings?.config_file ?? ''}
+                  onChange={(e) =>
+                    handleAppSettingChange(
+                      'config_file',
+                      e.target.value || null,
+                    )
+                  }
+                  placeholder='~/.mcprouter/mcprouter.yaml'
+                  style={{ marginTop: '4px' }}
+                />
+                <Text type='secondary' style={{ fontSize: '12px' }}>
+                  {t('settings.config_file.description')}
+                </Text>
+                <ConfigFileSync path={settings.settings?.config_file} />
+              </Card>
+            ),
+          },
+          {
+            key: 'secrets',
+            label: t('settings.secrets.title'),
+            children: <SecretManager />,
+          },
+          {
+            key: 'backup',
+            label: t('settings.backup.title'),
+            children: <BundleManager />,
+          },
         ]}
       />
 
       {/* Bottom Save Button */}
-      {['server'].includes(activeTab) && (
+      {['server', 'config_file'].includes(activeTab) && (
         <div
           style={{
             marginTop: 'auto',
@@ -35,6 +35,7 @@ import {
 import type { ColumnsType } from 'antd/es/table'
 import React, { useEffect, useState } from 'react'
 import { useTranslation } from 'react-i18next'
+import OAuthGrantManager from '../components/OAuthGrantManager'
 import PermissionSelector from '../components/PermissionSelector'
 import { permissionService } from '../services/permissionService'
 import { AvailablePermissions, Token, TokenStats } from '../types'
@@ -941,6 +942,9 @@ const TokenManagement: React.FC = () => {
         }}
       />
 
+      {/* OAuth Clients */}
+      <OAuthGrantManager />
+
       {/* Edit Token Drawer */}
       <Drawer
         title={
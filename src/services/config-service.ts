@@ -1,5 +1,17 @@
 import { invoke } from '@tauri-apps/api/core'
-import type { AppConfig, SystemSettings } from '../types'
+import type {
+  AppConfig,
+  BundleImportReport,
+  BundleSummary,
+  ClientConfigSnippet,
+  ClientTarget,
+  ConflictStrategy,
+  ReconcileReport,
+  SecretInfo,
+  ShellEnvironmentSnapshot,
+  SystemSettings,
+  WriteClientConfigResult,
+} from '../types'
 
 class ServiceError extends Error {
   constructor(
@@ -128,4 +140,204 @@ export class ConfigService {
       )
     }
   }
+
+  // Login shell environment
+  static async getShellEnvironment(
+    refresh: boolean = false,
+  ): Promise<ShellEnvironmentSnapshot> {
+    try {
+      return await withTimeout(
+        invoke('get_shell_environment', { refresh }),
+        15000,
+        'Get shell environment',
+      )
+    } catch (error) {
+      throw new ServiceError(
+        'Failed to load shell environment',
+        'getShellEnvironment',
+        error instanceof Error ? error : new Error(String(error)),
+      )
+    }
+  }
+
+  // Client configuration pointing at the aggregator
+  static async generateClientConfig(
+    client: ClientTarget,
+    tokenId?: string,
+    serverName?: string,
+  ): Promise<ClientConfigSnippet> {
+    try {
+      return await withTimeout(
+        invoke('generate_client_config', {
+          client,
+          token_id: tokenId,
+          server_name: serverName,
+        }),
+        10000,
+        'Generate client config',
+      )
+    } catch (error) {
+      throw new ServiceError(
+        'Failed to generate client config',
+        'generateClientConfig',
+        error instanceof Error ? error : new Error(String(error)),
+      )
+    }
+  }
+
+  static async writeClientConfig(
+    client: ClientTarget,
+    tokenId?: string,
+    serverName?: string,
+    path?: string,
+  ): Promise<WriteClientConfigResult> {
+    try {
+      return await withTimeout(
+        invoke('write_client_config', {
+          client,
+          token_id: tokenId,
+          server_name: serverName,
+          path,
+        }),
+        15000,
+        'Write client config',
+      )
+    } catch (error) {
+      throw new ServiceError(
+        'Failed to write client config',
+        'writeClientConfig',
+        error instanceof Error ? error : new Error(String(error)),
+      )
+    }
+  }
+
+  // Router state bundle (servers, tool flags, tokens, settings)
+  static async exportRouterBundle(
+    path: string,
+    redactSecrets: boolean,
+    includeSettings: boolean,
+  ): Promise<BundleSummary> {
+    try {
+      return await withTimeout(
+        invoke('export_router_bundle', {
+          path,
+          redact_secrets: redactSecrets,
+          include_settings: includeSettings,
+        }),
+        30000,
+        'Export router bundle',
+      )
+    } catch (error) {
+      throw new ServiceError(
+        'Failed to export router bundle',
+        'exportRouterBundle',
+        error instanceof Error ? error : new Error(String(error)),
+      )
+    }
+  }
+
+  static async importRouterBundle(
+    path: string,
+    strategy: ConflictStrategy,
+    importSettings: boolean,
+  ): Promise<BundleImportReport> {
+    try {
+      return await withTimeout(
+        invoke('import_router_bundle', {
+          path,
+          strategy,
+          import_settings: importSettings,
+        }),
+        60000,
+        'Import router bundle',
+      )
+    } catch (error) {
+      throw new ServiceError(
+        'Failed to import router bundle',
+        'importRouterBundle',
+        error instanceof Error ? error : new Error(String(error)),
+      )
+    }
+  }
+
+  // Declarative config file; without a path the one from settings is used
+  static async reconcileConfigFile(
+    path?: string,
+    dryRun?: boolean,
+  ): Promise<ReconcileReport> {
+    try {
+      return await withTimeout(
+        invoke('reconcile_config_file', { path, dry_run: dryRun }),
+        60000,
+        'Reconcile config file',
+      )
+    } catch (error) {
+      throw new ServiceError(
+        'Failed to reconcile config file',
+        'reconcileConfigFile',
+        error instanceof Error ? error : new Error(String(error)),
+      )
+    }
+  }
+
+  // Encrypted secret store; values are referenced as ${secret:name}
+  static async listSecrets(): Promise<SecretInfo[]> {
+    try {
+      return await withTimeout(invoke('list_secrets'), 10000, 'List secrets')
+    } catch (error) {
+      throw new ServiceError(
+        'Failed to list secrets',
+        'listSecrets',
+        error instanceof Error ? error : new Error(String(error)),
+      )
+    }
+  }
+
+  static async setSecret(name: string, value: string): Promise<string> {
+    try {
+      return await withTimeout(
+        invoke('set_secret', { name, value }),
+        10000,
+        'Set secret',
+      )
+    } catch (error) {
+      throw new ServiceError(
+        'Failed to set secret',
+        'setSecret',
+        error instanceof Error ? error : new Error(String(error)),
+      )
+    }
+  }
+
+  static async deleteSecret(name: string): Promise<void> {
+    try {
+      await withTimeout(
+        invoke('delete_secret', { name }),
+        10000,
+        'Delete secret',
+      )
+    } catch (error) {
+      throw new ServiceError(
+        'Failed to delete secret',
+        'deleteSecret',
+        error instanceof Error ? error : new Error(String(error)),
+      )
+    }
+  }
+
+  static async migrateSecrets(): Promise<number> {
+    try {
+      return await withTimeout(
+        invoke('migrate_secrets'),
+        30000,
+        'Migrate secrets',
+      )
+    } catch (error) {
+      throw new ServiceError(
+        'Failed to migrate secrets',
+        'migrateSecrets',
+        error instanceof Error ? error : new Error(String(error)),
+      )
+    }
+  }
 }
@@ -1,5 +1,15 @@
 import { invoke } from '@tauri-apps/api/core'
-import type { McpResourceInfo, McpServerResult } from '../types'
+import type {
+  ClientConfigPreview,
+  ConflictStrategy,
+  ImportResult,
+  McpResourceInfo,
+  McpServerConfig,
+  McpServerOptions,
+  McpServerResult,
+  ProcessUsage,
+  ServiceStatus,
+} from '../types'
 
 export class McpServerService {
   // MCP Server Management
@@ -12,6 +22,7 @@ export class McpServerService {
     description?: string,
     env?: [string, string][],
     headers?: [string, string][],
+    options?: McpServerOptions,
   ): Promise<string> {
     // Create request object to match backend structure
     const request = {
@@ -23,6 +34,7 @@ export class McpServerService {
       description,
       env,
       headers,
+      ...options,
     }
 
     return invoke('add_mcp_server', { request })
@@ -38,6 +50,7 @@ export class McpServerService {
     env?: [string, string][] | null,
     headers?: [string, string][] | null,
     enabled?: boolean,
+    options?: McpServerOptions,
   ): Promise<string> {
     // Create request object to match backend structure
     const request = {
@@ -50,6 +63,7 @@ export class McpServerService {
       env,
       headers,
       enabled: enabled ?? true,
+      ...options,
     }
 
     return invoke('update_mcp_server', { request })
@@ -59,6 +73,14 @@ export class McpServerService {
     return invoke('delete_mcp_server', { name })
   }
 
+  static async authorizeMcpServer(name: string): Promise<string> {
+    return invoke('authorize_mcp_server', { name })
+  }
+
+  static async clearMcpServerAuthorization(name: string): Promise<string> {
+    return invoke('clear_mcp_server_authorization', { name })
+  }
+
   static async checkMcpServerConnectivity(name: string): Promise<string> {
     return invoke('check_mcp_server_connectivity', { name })
   }
@@ -75,6 +97,48 @@ export class McpServerService {
     return invoke('import_mcp_servers_config', { configJson })
   }
 
+  static async discoverClientConfigs(): Promise<ClientConfigPreview[]> {
+    return invoke('discover_client_configs')
+  }
+
+  static async previewMcpServersImport(
+    configJson: string,
+  ): Promise<ClientConfigPreview> {
+    return invoke('preview_mcp_servers_import', { config_json: configJson })
+  }
+
+  static async importMcpServers(
+    servers: McpServerConfig[],
+    strategy?: ConflictStrategy,
+    inputValues?: Record<string, string>,
+  ): Promise<ImportResult[]> {
+    return invoke('import_mcp_servers', {
+      servers,
+      strategy,
+      input_values: inputValues,
+    })
+  }
+
+  static async getMcpServerLogs(
+    serverName: string,
+    lines?: number,
+    fromFile?: boolean,
+  ): Promise<string[]> {
+    return invoke('get_mcp_server_logs', {
+      server_name: serverName,
+      lines,
+      from_file: fromFile,
+    })
+  }
+
+  static async getMcpServerStatus(serverName: string): Promise<ServiceStatus> {
+    return invoke('get_mcp_server_status', { server_name: serverName })
+  }
+
+  static async getMcpServersResourceUsage(): Promise<ProcessUsage[]> {
+    return invoke('get_mcp_servers_resource_usage')
+  }
+
   // MCP Resources Management
   static async listMcpServerResources(
     serverName: string,
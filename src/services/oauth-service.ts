@@ -0,0 +1,29 @@
+import { invoke } from '@tauri-apps/api/core'
+import type { OAuthConsentRequest, OAuthGrant } from '../types'
+
+// Built-in OAuth authorization server of the aggregator endpoint
+export class OAuthService {
+  static async listConsentRequests(): Promise<OAuthConsentRequest[]> {
+    return invoke('list_oauth_consent_requests')
+  }
+
+  static async respondConsent(
+    requestId: string,
+    approve: boolean,
+    tokenId?: string,
+  ): Promise<void> {
+    return invoke('respond_oauth_consent', {
+      request_id: requestId,
+      approve,
+      token_id: tokenId ?? null,
+    })
+  }
+
+  static async listGrants(): Promise<OAuthGrant[]> {
+    return invoke('list_oauth_grants')
+  }
+
+  static async revokeGrant(id: string): Promise<void> {
+    return invoke('revoke_oauth_grant', { id })
+  }
+}
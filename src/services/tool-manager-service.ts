@@ -1,5 +1,10 @@
 import { invoke } from '@tauri-apps/api/core'
-import type { ToolInfo, PythonRuntimeInfo, ToolStartupStatus } from '../types'
+import type {
+  ToolInfo,
+  PythonRuntimeInfo,
+  ToolStartupStatus,
+  InstallToolOptions
+} from '../types'
 
 export class ToolManagerService {
   /**
@@ -26,8 +31,11 @@ export class ToolManagerService {
   /**
    * 安装特定工具
    */
-  static async installTool(toolName: string): Promise<void> {
-    return await invoke('install_tool', { tool_name: toolName })
+  static async installTool(
+    toolName: string,
+    options?: InstallToolOptions
+  ): Promise<void> {
+    return await invoke('install_tool', { tool_name: toolName, options })
   }
 
   /**
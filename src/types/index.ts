@@ -10,20 +10,150 @@ export interface McpServerConfig {
   enabled: boolean
 }
 
+// Client config import
+export type McpClient =
+  | 'claude-desktop'
+  | 'cursor'
+  | 'vs-code'
+  | 'windsurf'
+  | 'zed'
+  | 'continue'
+  | 'other'
+
+export interface ClientInput {
+  id: string
+  description?: string
+  password: boolean
+}
+
+export interface ImportCandidate {
+  name: string
+  config: McpServerConfig
+  duplicate?: {
+    reason: 'name' | 'definition'
+    existing: string
+  }
+  required_inputs: string[]
+  warnings: string[]
+  error?: string
+}
+
+export interface ClientConfigPreview {
+  client: McpClient
+  path?: string
+  servers: ImportCandidate[]
+  inputs: ClientInput[]
+  error?: string
+}
+
+export interface ImportResult {
+  name: string
+  status: 'added' | 'replaced' | 'renamed' | 'skipped' | 'failed'
+  message?: string
+}
+
+// Client config export
+export type ClientTarget =
+  | 'claude-desktop'
+  | 'cursor'
+  | 'vs-code'
+  | 'continue'
+  | 'streamable-http'
+  | 'stdio-bridge'
+
+export interface ClientConfigSnippet {
+  client: ClientTarget
+  endpoint: string
+  snippet: Record<string, unknown>
+  text: string
+  path?: string
+}
+
+export interface WriteClientConfigResult {
+  path: string
+  backup_path?: string
+}
+
+// Router state bundle
+export type ConflictStrategy = 'skip' | 'overwrite' | 'rename'
+
+export interface BundleSummary {
+  path: string
+  servers: number
+  tool_flags: number
+  tokens: number
+  settings: boolean
+  redacted: boolean
+}
+
+export interface BundleImportReport {
+  servers: ImportResult[]
+  tokens: ImportResult[]
+  tool_flags: number
+  settings_imported: boolean
+}
+
+// Declarative config file
+export interface ConfigChange {
+  kind: 'server' | 'tool' | 'token'
+  name: string
+  action: 'add' | 'update' | 'remove'
+  detail?: string
+  error?: string
+  token_value?: string
+}
+
+export interface ReconcileReport {
+  path: string
+  dry_run: boolean
+  changes: ConfigChange[]
+}
+
+export interface SecretInfo {
+  name: string
+  created_at: string
+  updated_at: string
+}
+
+// Authorization request from an OAuth client waiting for consent
+export interface OAuthConsentRequest {
+  id: string
+  client_id: string
+  client_name: string
+  redirect_uri: string
+  created_at: number
+}
+
+// Access granted to an OAuth client, backed by an existing token
+export interface OAuthGrant {
+  id: string
+  client_id: string
+  client_name: string
+  token_id: string
+  token_name?: string
+  created_at: number
+  updated_at: number
+}
+
 export interface ServiceStatus {
   name: string
   enabled: boolean
-  status: 'connecting' | 'connected' | 'disconnected' | 'failed'
+  status: 'connecting' | 'connected' | 'disconnected' | 'failed' | 'idle'
+  pid?: number
   version?: string
   start_time?: string
   last_error?: string
   error_message?: string
+  restart_count: number
+  last_exit_reason?: string
+  resource_usage?: ProcessUsage
+  runtime?: LaunchedRuntime
 }
 
 export interface McpServerInfo {
   name: string
   enabled: boolean
-  status: 'connecting' | 'connected' | 'disconnected' | 'failed'
+  status: 'connecting' | 'connected' | 'disconnected' | 'failed' | 'idle'
   version?: string
   last_error?: string
   error_message?: string
@@ -35,6 +165,118 @@ export interface McpServerInfo {
   command?: string
   args?: string[]
   tool_count?: number
+  circuit_breaker?: CircuitBreakerSnapshot
+  request_timeout?: number
+  connect_timeout?: number
+  max_concurrent_calls?: number
+  lifecycle?: ServerLifecycle
+  idle_timeout?: number
+  restart_policy?: RestartPolicy
+  max_restarts?: number
+  memory_limit_mb?: number
+  sandbox?: SandboxProfile
+  cwd?: string
+  env_inheritance?: EnvInheritance
+  env_allowlist?: string[]
+  container?: ContainerConfig
+  credential_helper?: CredentialHelper
+  runtime?: ServerRuntime
+  runtime_version?: string
+  active_runtime?: LaunchedRuntime
+  resource_usage?: ProcessUsage
+  unresolved_placeholders?: string[]
+  oauth_authorized?: boolean
+}
+
+export type ServerLifecycle = 'eager' | 'lazy'
+
+export type RestartPolicy = 'never' | 'on-failure' | 'always'
+
+export type EnvInheritance = 'inherit-all' | 'allowlist' | 'none'
+
+// 'auto' | 'bun' | 'node' | 'system-node' 或可执行文件路径
+export type ServerRuntime = string
+
+export interface LaunchedRuntime {
+  kind:
+    | 'bun'
+    | 'node'
+    | 'system-node'
+    | 'path'
+    | 'managed'
+    | 'system'
+    | 'container'
+  executable: string
+}
+
+export interface McpServerOptions {
+  request_timeout?: number | null
+  connect_timeout?: number | null
+  max_concurrent_calls?: number | null
+  lifecycle?: ServerLifecycle
+  idle_timeout?: number | null
+  restart_policy?: RestartPolicy
+  max_restarts?: number | null
+  memory_limit_mb?: number | null
+  sandbox?: SandboxProfile | null
+  cwd?: string | null
+  env_inheritance?: EnvInheritance
+  env_allowlist?: string[] | null
+  container?: ContainerConfig | null
+  credential_helper?: CredentialHelper | null
+  runtime?: ServerRuntime
+  runtime_version?: string | null
+}
+
+export type ContainerRuntime = 'auto' | 'docker' | 'podman'
+
+export type PullPolicy = 'always' | 'missing' | 'never'
+
+export interface ContainerConfig {
+  runtime: ContainerRuntime
+  image: string
+  tag?: string | null
+  mounts: string[]
+  network?: string | null
+  pull_policy: PullPolicy
+  extra_args: string[]
+}
+
+export interface CredentialHelper {
+  command: string
+  args: string[]
+  header: string
+  format: string
+  ttl_seconds?: number | null
+}
+
+export interface SandboxProfile {
+  allowed_paths: string[]
+  read_only_paths: string[]
+  network: boolean
+  max_memory_mb?: number | null
+  max_open_files?: number | null
+  max_processes?: number | null
+  max_cpu_seconds?: number | null
+  drop_env: boolean
+  env_allowlist: string[]
+}
+
+export interface ProcessUsage {
+  server_name: string
+  pid: number
+  process_count: number
+  cpu_percent: number
+  memory_bytes: number
+  memory_limit_bytes?: number
+  sampled_at: string
+}
+
+export interface CircuitBreakerSnapshot {
+  state: 'closed' | 'open' | 'half_open'
+  failure_rate: number
+  recent_calls: number
+  retry_after_seconds?: number
 }
 
 export interface McpTool {
@@ -217,7 +459,9 @@ export interface SystemSettings {
     port: number
     max_connections: number
     timeout_seconds: number
+    max_body_size?: number
     auth?: boolean
+    oauth?: boolean
   }
   logging: {
     level: 'trace' | 'debug' | 'info' | 'warn' | 'error'
@@ -235,9 +479,23 @@ export interface SystemSettings {
     }
     uv_index_url?: string
     npm_registry?: string
+    shell_path?: string | null
+    shell_env_ttl?: number | null
+    tool_mirror_url?: string | null
+    node_mirror_url?: string | null
+    config_file?: string | null
+    workspace?: string | null
   }
 }
 
+export interface ShellEnvironmentSnapshot {
+  shell: string
+  variables: Record<string, string>
+  loaded_at: string
+  load_duration_ms: number
+  ttl_seconds: number
+}
+
 export interface DashboardStats {
   total_servers: number
   enabled_servers: number
@@ -249,6 +507,14 @@ export interface DashboardStats {
   total_prompt_templates: number
   active_clients: number
   startup_time: string
+  circuit_breakers?: {
+    name: string
+    state: 'closed' | 'open' | 'half_open'
+    failure_rate: number
+    retry_after_seconds?: number
+  }[]
+  resource_usage?: ProcessUsage[]
+  runtimes?: ({ name: string } & LaunchedRuntime)[]
   aggregator?: {
     endpoint: string
     max_connections?: number
@@ -258,6 +524,8 @@ export interface DashboardStats {
   connections?: {
     active_clients: number
     active_services: number
+    in_flight_requests?: number
+    rejected_requests?: number
     total_connections?: number
   }
 }
@@ -373,6 +641,16 @@ export interface ToolInfo {
   status: ToolStatus
   last_check?: string // ISO timestamp
   python_required: boolean // Whether Python runtime is required
+  pinned_version?: string // 默认安装的固定版本
+  installed_versions: string[] // 已安装的版本（Node.js / Python 可并存多个版本）
+  rollback_available: boolean // 是否存在可回滚的上一版本
+}
+
+export interface InstallToolOptions {
+  version?: string | null
+  archive_path?: string | null
+  sha256?: string | null
+  rollback?: boolean
 }
 
 export type ToolStatus =